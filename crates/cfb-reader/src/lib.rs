@@ -40,6 +40,23 @@ impl<F: Read + Seek> Ole2Container<F> {
         Ok(buf)
     }
 
+    /// Open a stream for random-access reading: callers can `seek` and read
+    /// only the bytes they need (e.g. a single `ScanIndexEntry`) instead of
+    /// buffering the whole stream up front via [`read_stream`](Self::read_stream).
+    pub fn open_stream(&mut self, path: &str) -> io::Result<cfb::Stream<&mut F>> {
+        self.cf.open_stream(path)
+    }
+
+    /// Read `len` bytes at `offset` within a stream without loading the rest
+    /// of it into memory.
+    pub fn read_stream_range(&mut self, path: &str, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let mut stream = self.open_stream(path)?;
+        stream.seek(io::SeekFrom::Start(offset))?;
+        let mut buf = vec![0u8; len];
+        stream.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
     /// Get the size of a stream in bytes.
     pub fn stream_len(&self, path: &str) -> Option<u64> {
         self.cf.entry(path).ok().map(|e| e.len())
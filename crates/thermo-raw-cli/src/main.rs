@@ -67,6 +67,56 @@ enum Commands {
         truth_dir: PathBuf,
     },
 
+    /// Compare two spectra sources (RAW or mzML) scan-by-scan, peak-by-peak.
+    Diff {
+        /// First source: a RAW file or an mzML file.
+        a: PathBuf,
+        /// Second source: a RAW file or an mzML file.
+        b: PathBuf,
+        /// m/z matching tolerance in ppm.
+        #[arg(long, default_value = "10.0")]
+        ppm: f64,
+        /// Maximum allowed relative intensity deviation between matched peaks.
+        #[arg(long, default_value = "0.1")]
+        intensity_rtol: f64,
+    },
+
+    /// Byte-faithful regression comparison of a candidate conversion against
+    /// a reference, for CI gating.
+    ///
+    /// Unlike `Diff` (which sorts and peak-matches, tolerant of reordering),
+    /// `Verify` assumes `reference` and `candidate` store each scan's peaks
+    /// in the same order -- the expected case when comparing two runs of
+    /// this crate's own converter, or a new build against a checked-in
+    /// known-good mzML.
+    Verify {
+        /// Known-good reference: a RAW file or an mzML file.
+        reference: PathBuf,
+        /// Candidate output to check: a RAW file or an mzML file.
+        candidate: PathBuf,
+        /// m/z tolerance in ppm (0 = exact match required).
+        #[arg(long, default_value = "0.1")]
+        mz_tolerance_ppm: f64,
+        /// Intensity relative tolerance (0 = exact match required).
+        #[arg(long, default_value = "1e-6")]
+        intensity_rtol: f64,
+        /// Stop printing individual mismatches after this many.
+        #[arg(long, default_value = "20")]
+        max_mismatches: usize,
+    },
+
+    /// Check data-stream integrity: CRC-32 over the scan data region plus a
+    /// cross-check of the header's claimed scan count against the scan
+    /// index, optionally matched against a manifest of expected hashes
+    /// (redump-style batch verification).
+    Integrity {
+        file: PathBuf,
+        /// JSON manifest of `{"file_name": "...", "crc32": ...}` entries to
+        /// match `file`'s computed CRC-32 against, by file name.
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+    },
+
     /// Benchmark: read all scans (performance test).
     Benchmark {
         file: PathBuf,
@@ -77,6 +127,21 @@ enum Commands {
         /// Also benchmark XIC extraction (internally timed).
         #[arg(long)]
         xic: bool,
+        /// Repeated measurements per operation, after the warmup run.
+        /// Reported as mean/std/min/median rather than a single timing.
+        #[arg(long, default_value = "5")]
+        iterations: usize,
+        /// Print scans/sec, MB/sec, and latency percentiles from live
+        /// per-scan metrics instead of (or alongside) the iteration stats.
+        #[arg(long)]
+        metrics: bool,
+        /// Enable the decoded-centroid LRU cache (see
+        /// `RawFile::with_cache_bytes`) with this many megabytes of budget,
+        /// and print its hit rate after the timed iterations. Only affects
+        /// `--xic`: each iteration re-runs the same XIC extraction, so after
+        /// the first iteration every scan is served from cache.
+        #[arg(long)]
+        cache_mb: Option<u64>,
     },
 
     /// Debug: dump internal addresses and sanity checks.
@@ -98,14 +163,35 @@ enum Commands {
         /// Intensity precision: 32 or 64 (default: 32).
         #[arg(long, default_value = "32")]
         intensity_bits: u8,
-        /// Compression: none, zlib (default).
+        /// Compression: none, zlib (default), numpress-linear, numpress-pic,
+        /// numpress-slof, numpress-linear-zlib, numpress-pic-zlib,
+        /// numpress-slof-zlib, zstd, or auto (picks the smallest codec per
+        /// array).
         #[arg(long, default_value = "zlib")]
         compression: String,
         /// Skip index generation (plain mzML instead of indexed).
         #[arg(long)]
         no_index: bool,
+        /// Folder conversion: number of worker threads (default: 1, i.e.
+        /// sequential). Ignored for single-file conversion.
+        #[arg(long, default_value = "1")]
+        threads: usize,
+        /// Gzip the output mzML file(s), naming them `.mzML.gz`. Combine with
+        /// `--compression` for per-array zlib/numpress compression too.
+        #[arg(long)]
+        gzip: bool,
+        /// Single-file conversion only: print scans/sec, MB/sec, and latency
+        /// percentiles on completion. The percentiles come from one
+        /// whole-conversion latency sample, not a per-scan histogram; with
+        /// `--gzip` only scans/sec and MB/sec of gzip output are printed.
+        /// Folder conversion prints no metrics.
+        #[arg(long)]
+        metrics: bool,
     },
 
+    /// Per-MS-level summary statistics over all scans.
+    Stats { file: PathBuf },
+
     /// Batch EIC extraction across multiple RAW files.
     ///
     /// Extracts chromatograms for target m/z values from multiple files,
@@ -129,6 +215,16 @@ enum Commands {
         /// Output file (CSV). Defaults to stdout.
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Treat `files` as replicates and bootstrap-resample them N times to
+        /// report per-timepoint intensity uncertainty as extra `<target>_mean`
+        /// / `<target>_sd` columns (0 = disabled). Only applies when more
+        /// than one file is given; single-file output has no such columns.
+        #[arg(long, default_value = "0")]
+        bootstrap: usize,
+        /// Seed the bootstrap RNG for reproducible resampling (default: seeded
+        /// from system entropy).
+        #[arg(long)]
+        seed: Option<u64>,
     },
 }
 
@@ -141,6 +237,211 @@ fn ms_level_str(level: &thermo_raw::MsLevel) -> &'static str {
     }
 }
 
+/// One scan's centroid data loaded for `Diff`, regardless of whether it came
+/// from a RAW file or an mzML file.
+struct DiffScan {
+    scan_number: u32,
+    rt: f64,
+    mz: Vec<f64>,
+    intensity: Vec<f64>,
+}
+
+/// RT fallback tolerance (minutes) used by [`match_diff_scans`] when a scan
+/// number in `a` has no equal scan number in `b` -- e.g. comparing a RAW file
+/// against an mzML re-numbered by a different converter.
+const DIFF_RT_FALLBACK_TOLERANCE_MIN: f64 = 0.05;
+
+/// Load every scan's `(scan_number, rt, centroid_mz, centroid_intensity)`
+/// from `path`, detecting RAW vs. mzML by extension. Mirrors the two-source
+/// model behind [`thermo_raw::validation::GroundTruthSource`] used by
+/// `Validate`, minus the "ground truth" framing -- here neither side is
+/// privileged.
+fn load_diff_scans(path: &std::path::Path) -> anyhow::Result<Vec<DiffScan>> {
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("mzml")) {
+        use thermo_raw::validation::GroundTruthSource;
+        let source = thermo_raw_mzml::MzmlGroundTruthSource::open(path)?;
+        source
+            .scan_index()?
+            .into_iter()
+            .map(|idx| {
+                let data = source.scan_data(idx.scan_number)?;
+                Ok(DiffScan {
+                    scan_number: idx.scan_number,
+                    rt: idx.rt,
+                    mz: data.centroid_mz.unwrap_or_default(),
+                    intensity: data.centroid_intensity.unwrap_or_default(),
+                })
+            })
+            .collect()
+    } else {
+        let raw = RawFile::open_mmap(path)?;
+        (raw.first_scan()..=raw.last_scan())
+            .map(|i| {
+                let scan = raw.scan(i)?;
+                Ok(DiffScan {
+                    scan_number: scan.scan_number,
+                    rt: scan.rt,
+                    mz: scan.centroid_mz,
+                    intensity: scan.centroid_intensity,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Match each scan in `a` to a scan in `b`: first by equal scan number,
+/// falling back to the nearest RT in `b` within `rt_tolerance_min`. Scans in
+/// `a` with no match in `b` are dropped.
+fn match_diff_scans<'a>(
+    a: &'a [DiffScan],
+    b: &'a [DiffScan],
+    rt_tolerance_min: f64,
+) -> Vec<(&'a DiffScan, &'a DiffScan)> {
+    let by_scan_number: std::collections::HashMap<u32, &DiffScan> =
+        b.iter().map(|s| (s.scan_number, s)).collect();
+
+    a.iter()
+        .filter_map(|sa| {
+            if let Some(&sb) = by_scan_number.get(&sa.scan_number) {
+                return Some((sa, sb));
+            }
+            b.iter()
+                .min_by(|x, y| (x.rt - sa.rt).abs().partial_cmp(&(y.rt - sa.rt).abs()).unwrap())
+                .filter(|sb| (sb.rt - sa.rt).abs() <= rt_tolerance_min)
+                .map(|sb| (sa, sb))
+        })
+        .collect()
+}
+
+/// Per-scan result of comparing two matched [`DiffScan`]s.
+struct ScanDiff {
+    scan_number: u32,
+    only_in_a: usize,
+    only_in_b: usize,
+    worst_mz_error_ppm: f64,
+    worst_intensity_rel_error: f64,
+}
+
+/// Sort both centroid lists by m/z and walk them in parallel (a sort-then-zip
+/// merge, same strategy used to diff sorted alignment/read files), pairing
+/// peaks within `ppm` and tracking the worst relative intensity error among
+/// paired peaks; anything left over on either side is unmatched. The caller
+/// decides pass/fail by comparing `worst_intensity_rel_error` against its own
+/// `intensity_rtol` threshold.
+fn diff_scan(a: &DiffScan, b: &DiffScan, ppm: f64) -> ScanDiff {
+    let mut ia: Vec<usize> = (0..a.mz.len()).collect();
+    ia.sort_by(|&i, &j| a.mz[i].partial_cmp(&a.mz[j]).unwrap());
+    let mut ib: Vec<usize> = (0..b.mz.len()).collect();
+    ib.sort_by(|&i, &j| b.mz[i].partial_cmp(&b.mz[j]).unwrap());
+
+    let mut only_in_a = 0usize;
+    let mut only_in_b = 0usize;
+    let mut worst_mz_error_ppm = 0.0f64;
+    let mut worst_intensity_rel_error = 0.0f64;
+
+    let (mut pa, mut pb) = (0usize, 0usize);
+    while pa < ia.len() && pb < ib.len() {
+        let mz_a = a.mz[ia[pa]];
+        let mz_b = b.mz[ib[pb]];
+        let error_ppm = if mz_a != 0.0 { ((mz_a - mz_b) / mz_a).abs() * 1e6 } else { 0.0 };
+
+        if error_ppm <= ppm {
+            worst_mz_error_ppm = worst_mz_error_ppm.max(error_ppm);
+            let int_a = a.intensity[ia[pa]];
+            let int_b = b.intensity[ib[pb]];
+            let rel_error = if int_a != 0.0 {
+                ((int_a - int_b) / int_a).abs()
+            } else if int_b != 0.0 {
+                f64::INFINITY
+            } else {
+                0.0
+            };
+            worst_intensity_rel_error = worst_intensity_rel_error.max(rel_error);
+            pa += 1;
+            pb += 1;
+        } else if mz_a < mz_b {
+            only_in_a += 1;
+            pa += 1;
+        } else {
+            only_in_b += 1;
+            pb += 1;
+        }
+    }
+    only_in_a += ia.len() - pa;
+    only_in_b += ib.len() - pb;
+
+    ScanDiff {
+        scan_number: a.scan_number,
+        only_in_a,
+        only_in_b,
+        worst_mz_error_ppm,
+        worst_intensity_rel_error,
+    }
+}
+
+/// Arithmetic mean of `values`, or `None` if empty.
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Population standard deviation of `values` (divided by `n`, not `n - 1`),
+/// or `None` if empty.
+fn std_deviation(values: &[f64]) -> Option<f64> {
+    let m = mean(values)?;
+    let variance = values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / values.len() as f64;
+    Some(variance.sqrt())
+}
+
+/// Summary statistics over a set of repeated timing measurements (milliseconds).
+struct SampleStats {
+    mean: f64,
+    std: f64,
+    min: f64,
+    median: f64,
+}
+
+impl std::fmt::Display for SampleStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mean={:>7.1}ms std={:>6.1}ms min={:>7.1}ms median={:>7.1}ms",
+            self.mean, self.std, self.min, self.median
+        )
+    }
+}
+
+/// Mean/std/min/median over `samples`, or `None` if empty.
+fn sample_stats(samples: &[f64]) -> Option<SampleStats> {
+    let mean = mean(samples)?;
+    let std = std_deviation(samples)?;
+    let min = samples.iter().cloned().fold(f64::INFINITY, f64::min);
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = sorted.len() / 2;
+    let median = if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    };
+    Some(SampleStats { mean, std, min, median })
+}
+
+/// Format an integer with `,` thousands separators, e.g. `1234567` -> `1,234,567`.
+fn format_thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
 fn polarity_str(p: &thermo_raw::Polarity) -> &'static str {
     match p {
         thermo_raw::Polarity::Positive => "Positive",
@@ -149,6 +450,70 @@ fn polarity_str(p: &thermo_raw::Polarity) -> &'static str {
     }
 }
 
+/// A small xorshift64* PRNG, used for `BatchXic --bootstrap` resampling.
+///
+/// No `rand` dependency is available in this tree (no `Cargo.toml` to
+/// declare it in), and bootstrap resampling only needs a fast, reproducible
+/// stream of indices -- not cryptographic quality.
+struct Xorshift64 {
+    state: u64,
+}
+
+impl Xorshift64 {
+    fn new(seed: u64) -> Self {
+        // xorshift64* is undefined for a zero state; fall back to a fixed
+        // non-zero seed (golden-ratio constant) if the caller passes 0.
+        Self {
+            state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed },
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A uniformly distributed index in `0..n`. Panics if `n == 0`.
+    fn next_index(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Bootstrap-resample `n_samples` replicates `bootstrap_n` times and return
+/// `(mean, sd)` of the resample means, per the procedure in
+/// `EstrellaXD/RawFileReaderS#chunk17-2`: each resample draws `n_samples`
+/// indices with replacement and averages `value_at(idx)` over them; the
+/// reported mean/sd summarize those `bootstrap_n` resample means (population
+/// SD, i.e. divided by `bootstrap_n` not `bootstrap_n - 1`).
+///
+/// Short-circuits to `(value_at(0), 0.0)` for a single replicate, since every
+/// resample of one sample is identical.
+fn bootstrap_mean_sd(
+    n_samples: usize,
+    bootstrap_n: usize,
+    rng: &mut Xorshift64,
+    value_at: impl Fn(usize) -> f64,
+) -> (f64, f64) {
+    if n_samples <= 1 {
+        return (value_at(0), 0.0);
+    }
+
+    let resample_means: Vec<f64> = (0..bootstrap_n)
+        .map(|_| {
+            let sum: f64 = (0..n_samples).map(|_| value_at(rng.next_index(n_samples))).sum();
+            sum / n_samples as f64
+        })
+        .collect();
+
+    let mean = resample_means.iter().sum::<f64>() / bootstrap_n as f64;
+    let variance = resample_means.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / bootstrap_n as f64;
+    (mean, variance.sqrt())
+}
+
 /// Spawn a progress bar backed by an atomic counter.
 ///
 /// Returns `(counter, done_flag)`. The caller increments `counter` from worker
@@ -356,7 +721,8 @@ fn main() -> anyhow::Result<()> {
         Commands::Validate { file, truth_dir } => {
             let raw = RawFile::open(&file)?;
             let criteria = thermo_raw::validation::ValidationCriteria::default();
-            let report = thermo_raw::validation::validate_file(&raw, &truth_dir, &criteria)?;
+            let source = thermo_raw::validation::JsonGroundTruthSource::new(truth_dir);
+            let report = thermo_raw::validation::validate_file(&raw, &source, &criteria)?;
 
             println!("Validation Report");
             println!("=================");
@@ -372,6 +738,19 @@ fn main() -> anyhow::Result<()> {
                 "Worst intensity error: {:.2e}",
                 report.worst_intensity_error
             );
+            println!(
+                "m/z error (ppm): median={:.4} p95={:.4} p99={:.4} signed_mean={:.4}",
+                report.overall_stats.mz_error_median_ppm,
+                report.overall_stats.mz_error_p95_ppm,
+                report.overall_stats.mz_error_p99_ppm,
+                report.overall_stats.mz_mean_signed_error_ppm,
+            );
+            println!(
+                "intensity error (relative): median={:.2e} p95={:.2e} p99={:.2e}",
+                report.overall_stats.intensity_error_median,
+                report.overall_stats.intensity_error_p95,
+                report.overall_stats.intensity_error_p99,
+            );
 
             if !report.failures.is_empty() {
                 println!("\nFailed scans:");
@@ -390,6 +769,130 @@ fn main() -> anyhow::Result<()> {
             }
         }
 
+        Commands::Diff { a, b, ppm, intensity_rtol } => {
+            let scans_a = load_diff_scans(&a)?;
+            let scans_b = load_diff_scans(&b)?;
+            let pairs = match_diff_scans(&scans_a, &scans_b, DIFF_RT_FALLBACK_TOLERANCE_MIN);
+
+            println!("Diff: {} vs {}", a.display(), b.display());
+            println!("Matched {} of {} scans in A", pairs.len(), scans_a.len());
+            println!();
+
+            let mut passed = 0usize;
+            for (sa, sb) in &pairs {
+                let d = diff_scan(sa, sb, ppm);
+                let scan_passed =
+                    d.only_in_a == 0 && d.only_in_b == 0 && d.worst_intensity_rel_error <= intensity_rtol;
+                if scan_passed {
+                    passed += 1;
+                } else {
+                    println!(
+                        "  scan {}: only_in_a={} only_in_b={} worst_mz_err={:.4}ppm worst_intensity_rel_err={:.2e}",
+                        d.scan_number, d.only_in_a, d.only_in_b, d.worst_mz_error_ppm, d.worst_intensity_rel_error
+                    );
+                }
+            }
+
+            let pass_rate = if pairs.is_empty() {
+                0.0
+            } else {
+                passed as f64 / pairs.len() as f64 * 100.0
+            };
+            println!("\nPass rate: {}/{} ({:.1}%)", passed, pairs.len(), pass_rate);
+        }
+
+        Commands::Verify { reference, candidate, mz_tolerance_ppm, intensity_rtol, max_mismatches } => {
+            use thermo_raw::validation::{validate_intensity_arrays, validate_mz_arrays};
+
+            let ref_scans = load_diff_scans(&reference)?;
+            let cand_scans = load_diff_scans(&candidate)?;
+            let cand_by_scan: std::collections::HashMap<u32, &DiffScan> =
+                cand_scans.iter().map(|s| (s.scan_number, s)).collect();
+
+            println!("Verify: reference={} candidate={}", reference.display(), candidate.display());
+            println!();
+
+            let mut missing = 0usize;
+            let mut mismatched = 0usize;
+            let mut passed = 0usize;
+            let mut printed = 0usize;
+
+            for sr in &ref_scans {
+                let Some(&sc) = cand_by_scan.get(&sr.scan_number) else {
+                    missing += 1;
+                    if printed < max_mismatches {
+                        println!("  scan {}: missing from candidate", sr.scan_number);
+                        printed += 1;
+                    }
+                    continue;
+                };
+
+                let (max_mz_err, _mean_mz_err, mz_errors) =
+                    validate_mz_arrays(&sc.mz, &sr.mz, mz_tolerance_ppm);
+                let (max_int_err, int_errors) =
+                    validate_intensity_arrays(&sc.intensity, &sr.intensity, intensity_rtol);
+
+                if mz_errors.is_empty() && int_errors.is_empty() {
+                    passed += 1;
+                } else {
+                    mismatched += 1;
+                    if printed < max_mismatches {
+                        println!(
+                            "  scan {}: max_mz_err={:.4}ppm max_intensity_rel_err={:.2e}",
+                            sr.scan_number, max_mz_err, max_int_err
+                        );
+                        for err in mz_errors.iter().chain(int_errors.iter()).take(3) {
+                            println!("    {}", err);
+                        }
+                        printed += 1;
+                    }
+                }
+            }
+
+            println!();
+            println!(
+                "Verify summary: {} passed, {} mismatched, {} missing (of {} reference scans)",
+                passed, mismatched, missing, ref_scans.len()
+            );
+        }
+
+        Commands::Integrity { file, manifest } => {
+            let raw = RawFile::open_mmap(&file)?;
+            let report = raw.integrity_report();
+
+            println!("Integrity: {}", file.display());
+            println!("CRC-32:    {:08x}", report.crc32);
+            println!(
+                "Scans:     {} (header) / {} (scan index)",
+                report.n_scans_header, report.n_scans_index
+            );
+
+            let mut ok = true;
+            if let Some(problem) = report.problem() {
+                ok = false;
+                println!("MISMATCH:  {}", problem);
+            }
+
+            if let Some(manifest_path) = manifest {
+                let entries = thermo_raw::checksum::load_manifest(&manifest_path)?;
+                let file_name = file
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                match thermo_raw::checksum::check_against_manifest(&entries, &file_name, report.crc32) {
+                    Some(true) => println!("Manifest:  matches expected CRC-32"),
+                    Some(false) => {
+                        ok = false;
+                        println!("MISMATCH:  CRC-32 does not match manifest entry for {file_name}");
+                    }
+                    None => println!("Manifest:  no entry for {file_name}"),
+                }
+            }
+
+            println!();
+            println!("Result: {}", if ok { "OK" } else { "FAILED" });
+        }
+
         Commands::Debug { file } => {
             let raw = RawFile::open(&file)?;
             let info = raw.debug_info();
@@ -507,6 +1010,8 @@ fn main() -> anyhow::Result<()> {
             rt_resolution,
             rt_range,
             output,
+            bootstrap,
+            seed,
         } => {
             let targets: Vec<(f64, f64)> = mz.iter().map(|&m| (m, ppm)).collect();
             let paths: Vec<&std::path::Path> = files.iter().map(|p| p.as_path()).collect();
@@ -584,15 +1089,29 @@ fn main() -> anyhow::Result<()> {
                 Box::new(std::io::stdout())
             };
 
-            // CSV header: rt, then sample_mz columns
+            // CSV header: rt, then sample_mz columns, then (if bootstrapping)
+            // a mean/sd column pair per target.
             let mut header = vec!["rt".to_string()];
             for name in &result.sample_names {
                 for m in &mz {
                     header.push(format!("{}_{:.4}", name, m));
                 }
             }
+            if bootstrap > 0 {
+                for m in &mz {
+                    header.push(format!("{:.4}_mean", m));
+                    header.push(format!("{:.4}_sd", m));
+                }
+            }
             writeln!(writer, "{}", header.join(","))?;
 
+            let mut rng = Xorshift64::new(seed.unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos() as u64)
+                    .unwrap_or(0)
+            }));
+
             // Data rows
             for (i, &rt) in result.rt_grid.iter().enumerate() {
                 write!(writer, "{:.6}", rt)?;
@@ -602,6 +1121,14 @@ fn main() -> anyhow::Result<()> {
                         write!(writer, ",{:.2}", val)?;
                     }
                 }
+                if bootstrap > 0 {
+                    for t in 0..result.n_targets {
+                        let (mean, sd) = bootstrap_mean_sd(result.n_samples, bootstrap, &mut rng, |s| {
+                            result.get(s, t)[i]
+                        });
+                        write!(writer, ",{:.2},{:.2}", mean, sd)?;
+                    }
+                }
                 writeln!(writer)?;
             }
             } // else (multi-file)
@@ -614,6 +1141,9 @@ fn main() -> anyhow::Result<()> {
             intensity_bits,
             compression,
             no_index,
+            threads,
+            gzip,
+            metrics,
         } => {
             let mz_precision = match mz_bits {
                 32 => thermo_raw_mzml::Precision::F32,
@@ -623,8 +1153,19 @@ fn main() -> anyhow::Result<()> {
                 64 => thermo_raw_mzml::Precision::F64,
                 _ => thermo_raw_mzml::Precision::F32,
             };
+            // Just string-to-enum mapping: the numpress codecs' own
+            // round-trip correctness is covered by thermo_raw_mzml's tests,
+            // re-verified there after the chunk2-1 sign-inference fix.
             let comp = match compression.to_lowercase().as_str() {
                 "none" => thermo_raw_mzml::Compression::None,
+                "numpress-linear" => thermo_raw_mzml::Compression::NumpressLinear,
+                "numpress-pic" => thermo_raw_mzml::Compression::NumpressPic,
+                "numpress-slof" => thermo_raw_mzml::Compression::NumpressSlof,
+                "numpress-linear-zlib" => thermo_raw_mzml::Compression::NumpressLinearZlib,
+                "numpress-pic-zlib" => thermo_raw_mzml::Compression::NumpressPicZlib,
+                "numpress-slof-zlib" => thermo_raw_mzml::Compression::NumpressSlofZlib,
+                "zstd" => thermo_raw_mzml::Compression::Zstd,
+                "auto" => thermo_raw_mzml::Compression::Auto,
                 _ => thermo_raw_mzml::Compression::Zlib,
             };
             let config = thermo_raw_mzml::MzmlConfig {
@@ -636,25 +1177,65 @@ fn main() -> anyhow::Result<()> {
 
             if input.is_dir() {
                 let out_dir = output.unwrap_or_else(|| input.clone());
-                let file_count = std::fs::read_dir(&input)?
+                std::fs::create_dir_all(&out_dir)?;
+                let raw_paths: Vec<PathBuf> = std::fs::read_dir(&input)?
                     .filter_map(|e| e.ok())
-                    .filter(|e| {
-                        e.path()
-                            .extension()
-                            .is_some_and(|ext| ext.eq_ignore_ascii_case("raw"))
-                    })
-                    .count() as u64;
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("raw")))
+                    .collect();
+                let file_count = raw_paths.len() as u64;
+                let n_threads = threads.max(1);
+
                 let (counter, done, handle) = spawn_progress_bar(file_count, "Converting folder");
                 let start = std::time::Instant::now();
-                let files = thermo_raw_mzml::convert_folder_with_progress(
-                    &input, &out_dir, &config, &counter,
-                )?;
+
+                // Bounded work queue (std `sync_channel` instead of a new
+                // crossbeam dependency, since no Cargo.toml here declares
+                // one): a fixed pool of workers pulls the next RAW path,
+                // converts it, and ticks the shared counter. The channel's
+                // bound caps in-flight paths even if one worker stalls.
+                let (tx, rx) = std::sync::mpsc::sync_channel::<PathBuf>(n_threads * 2);
+                let rx = std::sync::Mutex::new(rx);
+                let converted = std::sync::Mutex::new(Vec::new());
+
+                std::thread::scope(|scope| {
+                    for _ in 0..n_threads {
+                        scope.spawn(|| loop {
+                            let raw_path = match rx.lock().unwrap().recv() {
+                                Ok(p) => p,
+                                Err(_) => break,
+                            };
+                            let stem = raw_path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+                            let out_path = if gzip {
+                                out_dir.join(format!("{}.mzML.gz", stem))
+                            } else {
+                                out_dir.join(format!("{}.mzML", stem))
+                            };
+                            let result = if gzip {
+                                thermo_raw_mzml::convert_file_gzip(&raw_path, &out_path, &config)
+                            } else {
+                                thermo_raw_mzml::convert_file(&raw_path, &out_path, &config)
+                            };
+                            if result.is_ok() {
+                                converted.lock().unwrap().push(out_path);
+                            }
+                            counter.fetch_add(1, Ordering::Relaxed);
+                        });
+                    }
+
+                    for raw_path in raw_paths {
+                        tx.send(raw_path).expect("workers outlive the sender");
+                    }
+                    drop(tx);
+                });
+
                 done.store(true, Ordering::Relaxed);
                 handle.join().unwrap();
                 let elapsed = start.elapsed();
+                let files = converted.into_inner().unwrap();
                 println!(
                     "Converted {} files in {:.1}s",
-                    files.len(),
+                    format_thousands(files.len() as u64),
                     elapsed.as_secs_f64()
                 );
                 for f in &files {
@@ -667,18 +1248,143 @@ fn main() -> anyhow::Result<()> {
                 drop(raw_for_count);
 
                 let (counter, done, handle) = spawn_progress_bar(n_scans, "Converting");
-                let out_path = output.unwrap_or_else(|| input.with_extension("mzML"));
+                let out_path = output.unwrap_or_else(|| {
+                    if gzip {
+                        input.with_extension("mzML.gz")
+                    } else {
+                        input.with_extension("mzML")
+                    }
+                });
                 let start = std::time::Instant::now();
-                thermo_raw_mzml::convert_file_with_progress(&input, &out_path, &config, &counter)?;
-                done.store(true, Ordering::Relaxed);
-                handle.join().unwrap();
-                let elapsed = start.elapsed();
+
+                if gzip {
+                    thermo_raw_mzml::convert_file_gzip(&input, &out_path, &config)?;
+                    done.store(true, Ordering::Relaxed);
+                    handle.join().unwrap();
+                    let elapsed = start.elapsed();
+                    println!(
+                        "Converted {} -> {} in {:.1}s",
+                        input.display(),
+                        out_path.display(),
+                        elapsed.as_secs_f64()
+                    );
+                    if metrics {
+                        let bytes = std::fs::metadata(&out_path).map(|m| m.len()).unwrap_or(0);
+                        println!(
+                            "  {:.1} scans/sec, {:.2} MB/sec (gzip output; no per-scan latency histogram)",
+                            n_scans as f64 / elapsed.as_secs_f64(),
+                            (bytes as f64 / 1_000_000.0) / elapsed.as_secs_f64()
+                        );
+                    }
+                } else if metrics {
+                    let m = thermo_raw::new_metrics();
+                    thermo_raw_mzml::convert_file_with_metrics(&input, &out_path, &config, &m)?;
+                    done.store(true, Ordering::Relaxed);
+                    handle.join().unwrap();
+                    let elapsed = start.elapsed();
+                    let snap = m.snapshot();
+                    println!(
+                        "Converted {} -> {} in {:.1}s",
+                        input.display(),
+                        out_path.display(),
+                        elapsed.as_secs_f64()
+                    );
+                    println!(
+                        "  {:.1} scans/sec, {:.2} MB/sec, p50={}us p99={}us",
+                        snap.scans_per_sec(elapsed),
+                        snap.mb_per_sec(elapsed),
+                        snap.latency_percentile_micros(50.0),
+                        snap.latency_percentile_micros(99.0),
+                    );
+                } else {
+                    thermo_raw_mzml::convert_file_with_progress(&input, &out_path, &config, &counter)?;
+                    done.store(true, Ordering::Relaxed);
+                    handle.join().unwrap();
+                    let elapsed = start.elapsed();
+                    println!(
+                        "Converted {} -> {} in {:.1}s",
+                        input.display(),
+                        out_path.display(),
+                        elapsed.as_secs_f64()
+                    );
+                }
+            }
+        }
+
+        Commands::Stats { file } => {
+            let raw = RawFile::open_mmap(&file)?;
+            let n_scans = raw.n_scans() as u64;
+            let (counter, done, handle) = spawn_progress_bar(n_scans, "Computing stats");
+
+            #[derive(Default)]
+            struct LevelStats {
+                tic: Vec<f64>,
+                peaks: Vec<f64>,
+                base_peak_intensity: Vec<f64>,
+                rt_min: f64,
+                rt_max: f64,
+            }
+
+            impl LevelStats {
+                fn record(&mut self, tic: f64, peaks: f64, base_peak_intensity: f64, rt: f64) {
+                    if self.tic.is_empty() {
+                        self.rt_min = rt;
+                        self.rt_max = rt;
+                    } else {
+                        self.rt_min = self.rt_min.min(rt);
+                        self.rt_max = self.rt_max.max(rt);
+                    }
+                    self.tic.push(tic);
+                    self.peaks.push(peaks);
+                    self.base_peak_intensity.push(base_peak_intensity);
+                }
+            }
+
+            let mut by_level: [LevelStats; 4] = Default::default();
+            let level_index = |level: &thermo_raw::MsLevel| -> usize {
+                match level {
+                    thermo_raw::MsLevel::Ms1 => 0,
+                    thermo_raw::MsLevel::Ms2 => 1,
+                    thermo_raw::MsLevel::Ms3 => 2,
+                    thermo_raw::MsLevel::Other(_) => 3,
+                }
+            };
+
+            for i in raw.first_scan()..=raw.last_scan() {
+                let scan = raw.scan(i)?;
+                let idx = level_index(&scan.ms_level);
+                by_level[idx].record(
+                    scan.tic,
+                    scan.centroid_mz.len() as f64,
+                    scan.base_peak_intensity,
+                    scan.rt,
+                );
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+            done.store(true, Ordering::Relaxed);
+            handle.join().unwrap();
+
+            for (label, stats) in [("MS1", &by_level[0]), ("MS2", &by_level[1]), ("MS3", &by_level[2]), ("Other", &by_level[3])] {
+                if stats.tic.is_empty() {
+                    continue;
+                }
+                println!("{}:", label);
+                println!("  scans:                {}", format_thousands(stats.tic.len() as u64));
                 println!(
-                    "Converted {} -> {} in {:.1}s",
-                    input.display(),
-                    out_path.display(),
-                    elapsed.as_secs_f64()
+                    "  TIC:                  mean {:.3e}  sd {:.3e}",
+                    mean(&stats.tic).unwrap_or(0.0),
+                    std_deviation(&stats.tic).unwrap_or(0.0)
+                );
+                println!(
+                    "  peaks/scan:           mean {:.1}  sd {:.1}",
+                    mean(&stats.peaks).unwrap_or(0.0),
+                    std_deviation(&stats.peaks).unwrap_or(0.0)
                 );
+                println!(
+                    "  base peak intensity:  mean {:.3e}",
+                    mean(&stats.base_peak_intensity).unwrap_or(0.0)
+                );
+                println!("  RT span:              {:.3} - {:.3} min", stats.rt_min, stats.rt_max);
             }
         }
 
@@ -693,13 +1399,36 @@ fn main() -> anyhow::Result<()> {
             parallel,
             mmap,
             xic,
+            iterations,
+            metrics,
+            cache_mb,
         } => {
             let raw = if mmap {
                 RawFile::open_mmap(&file)?
             } else {
                 RawFile::open(&file)?
             };
+            let raw = match cache_mb {
+                Some(mb) => raw.with_cache_bytes(mb * 1_000_000),
+                None => raw,
+            };
             let mode = if mmap { "mmap" } else { "read" };
+            let iterations = iterations.max(1);
+
+            // Runs `f` `iterations` times, timing each call, and reduces the
+            // timings to `SampleStats`. The warmup run the caller already did
+            // before calling this is never included.
+            let run_timed = |mut f: Box<dyn FnMut() -> anyhow::Result<usize>>| -> anyhow::Result<(SampleStats, usize)> {
+                let mut samples = Vec::with_capacity(iterations);
+                let mut last_n = 0;
+                for _ in 0..iterations {
+                    let start = std::time::Instant::now();
+                    last_n = f()?;
+                    samples.push(start.elapsed().as_secs_f64() * 1000.0);
+                }
+                let stats = sample_stats(&samples).expect("iterations.max(1) guarantees at least one sample");
+                Ok((stats, last_n))
+            };
 
             if xic {
                 // File info
@@ -729,94 +1458,103 @@ fn main() -> anyhow::Result<()> {
                     .map(|i| (mz_start + i as f64 * step, ppm))
                     .collect();
 
-                // Warmup run (populates caches)
+                // Warmup run (populates caches); excluded from the aggregates below.
                 let _ = raw.xic_ms1(targets_2000[0].0, ppm)?;
 
+                println!("({} iterations per measurement, warmup discarded)", iterations);
+
                 // --- Single target XIC ---
-                let start = std::time::Instant::now();
-                let chrom = raw.xic_ms1(targets_2000[0].0, ppm)?;
-                let t_single = start.elapsed();
-                println!(
-                    "XIC MS1 single:      {:>8.1}ms  ({} points)",
-                    t_single.as_secs_f64() * 1000.0,
-                    chrom.rt.len()
-                );
+                let (stats, n_points) = run_timed(Box::new(|| Ok(raw.xic_ms1(targets_2000[0].0, ppm)?.rt.len())))?;
+                println!("XIC MS1 single:      {}  ({} points)", stats, n_points);
 
                 // --- 10-target batch XIC ---
-                let start = std::time::Instant::now();
-                let chroms = raw.xic_batch_ms1(&targets_2000[..10])?;
-                let t_10 = start.elapsed();
-                println!(
-                    "XIC MS1 batch 10:    {:>8.1}ms  ({} chroms)",
-                    t_10.as_secs_f64() * 1000.0,
-                    chroms.len()
-                );
+                let (stats_10, n_10) = run_timed(Box::new(|| Ok(raw.xic_batch_ms1(&targets_2000[..10])?.len())))?;
+                println!("XIC MS1 batch 10:    {}  ({} chroms)", stats_10, n_10);
 
                 // --- 100-target batch XIC ---
-                let start = std::time::Instant::now();
-                let chroms = raw.xic_batch_ms1(&targets_2000[..100])?;
-                let t_100 = start.elapsed();
-                println!(
-                    "XIC MS1 batch 100:   {:>8.1}ms  ({} chroms)",
-                    t_100.as_secs_f64() * 1000.0,
-                    chroms.len()
-                );
+                let (stats_100, n_100) = run_timed(Box::new(|| Ok(raw.xic_batch_ms1(&targets_2000[..100])?.len())))?;
+                println!("XIC MS1 batch 100:   {}  ({} chroms)", stats_100, n_100);
 
                 // --- 500-target batch XIC ---
-                let start = std::time::Instant::now();
-                let chroms = raw.xic_batch_ms1(&targets_2000[..500])?;
-                let t_500 = start.elapsed();
-                println!(
-                    "XIC MS1 batch 500:   {:>8.1}ms  ({} chroms)",
-                    t_500.as_secs_f64() * 1000.0,
-                    chroms.len()
-                );
+                let (stats_500, n_500) = run_timed(Box::new(|| Ok(raw.xic_batch_ms1(&targets_2000[..500])?.len())))?;
+                println!("XIC MS1 batch 500:   {}  ({} chroms)", stats_500, n_500);
 
                 // --- 2000-target batch XIC ---
-                let start = std::time::Instant::now();
-                let chroms = raw.xic_batch_ms1(&targets_2000)?;
-                let t_2000 = start.elapsed();
-                println!(
-                    "XIC MS1 batch 2000:  {:>8.1}ms  ({} chroms)",
-                    t_2000.as_secs_f64() * 1000.0,
-                    chroms.len()
-                );
+                let (stats_2000, n_2000) = run_timed(Box::new(|| Ok(raw.xic_batch_ms1(&targets_2000)?.len())))?;
+                println!("XIC MS1 batch 2000:  {}  ({} chroms)", stats_2000, n_2000);
 
-                // Summary
+                // Summary (derived from the batch-2000 mean)
                 println!("\n--- Summary ---");
                 println!(
                     "Per-target cost (batch 2000): {:.2}ms/target",
-                    t_2000.as_secs_f64() * 1000.0 / 2000.0
+                    stats_2000.mean / 2000.0
                 );
                 println!(
                     "Throughput (batch 2000): {:.0} targets/sec",
-                    2000.0 / t_2000.as_secs_f64()
+                    2000.0 / (stats_2000.mean / 1000.0)
                 );
-            } else {
-                let start = std::time::Instant::now();
-                if parallel {
-                    let scans = raw.scans_parallel(raw.first_scan()..raw.last_scan() + 1)?;
-                    let elapsed = start.elapsed();
+
+                if cache_mb.is_some() {
+                    let hits = raw.cache_hits();
+                    let misses = raw.cache_misses();
+                    let total = hits + misses;
+                    let hit_rate = if total == 0 { 0.0 } else { hits as f64 / total as f64 * 100.0 };
                     println!(
-                        "{} scans read in {:.1}ms ({:.1} scans/sec) [parallel, {}]",
-                        scans.len(),
-                        elapsed.as_secs_f64() * 1000.0,
-                        scans.len() as f64 / elapsed.as_secs_f64(),
-                        mode
+                        "Centroid cache: {} hits, {} misses ({:.1}% hit rate)",
+                        hits, misses, hit_rate
                     );
+                }
+            } else {
+                // Warmup run; excluded from the aggregates below.
+                if parallel {
+                    let _ = raw.scans_parallel(raw.first_scan()..raw.last_scan() + 1)?;
                 } else {
-                    let mut count = 0u32;
                     for i in raw.first_scan()..=raw.last_scan() {
                         let _ = raw.scan(i)?;
-                        count += 1;
                     }
+                }
+
+                println!("({} iterations, warmup discarded)", iterations);
+
+                let (stats, n_scans) = if parallel {
+                    run_timed(Box::new(|| Ok(raw.scans_parallel(raw.first_scan()..raw.last_scan() + 1)?.len())))?
+                } else {
+                    run_timed(Box::new(|| {
+                        let mut count = 0usize;
+                        for i in raw.first_scan()..=raw.last_scan() {
+                            let _ = raw.scan(i)?;
+                            count += 1;
+                        }
+                        Ok(count)
+                    }))?
+                };
+
+                println!(
+                    "{} scans read: {}  ({:.1} scans/sec, mean) [{}, {}]",
+                    n_scans,
+                    stats,
+                    n_scans as f64 / (stats.mean / 1000.0),
+                    if parallel { "parallel" } else { "sequential" },
+                    mode
+                );
+
+                if metrics {
+                    let m = thermo_raw::new_metrics();
+                    let start = std::time::Instant::now();
+                    let scans = raw.scans_parallel_with_metrics(raw.first_scan()..raw.last_scan() + 1, &m)?;
                     let elapsed = start.elapsed();
+                    let snap = m.snapshot();
+                    println!(
+                        "\nLive metrics: {} scans, {:.1} scans/sec, {:.2} MB/sec",
+                        scans.len(),
+                        snap.scans_per_sec(elapsed),
+                        snap.mb_per_sec(elapsed)
+                    );
                     println!(
-                        "{} scans read in {:.1}ms ({:.1} scans/sec) [sequential, {}]",
-                        count,
-                        elapsed.as_secs_f64() * 1000.0,
-                        count as f64 / elapsed.as_secs_f64(),
-                        mode
+                        "  latency: p50={}us p95={}us p99={}us",
+                        snap.latency_percentile_micros(50.0),
+                        snap.latency_percentile_micros(95.0),
+                        snap.latency_percentile_micros(99.0)
                     );
                 }
             }
@@ -15,7 +15,18 @@ use gpui_component::{
 };
 
 use crate::conversion;
-use crate::file_list::{FileEntry, FileStatus};
+use crate::engine;
+use crate::file_list::{self, FileEntry, FileStatus};
+use crate::preview;
+use crate::scan;
+use crate::settings;
+use crate::theme;
+use crate::verify;
+use crate::watch;
+
+/// How long a watched `.raw` file's size must be unchanged before it's
+/// considered fully written and handed to the scan pipeline.
+const WATCH_STABLE_FOR: std::time::Duration = std::time::Duration::from_secs(3);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppPhase {
@@ -32,66 +43,198 @@ pub struct AppState {
     mz_select: Entity<SelectState<Vec<&'static str>>>,
     intensity_select: Entity<SelectState<Vec<&'static str>>>,
     compression_select: Entity<SelectState<Vec<&'static str>>>,
+    disposition_select: Entity<SelectState<Vec<&'static str>>>,
     write_index: bool,
     include_ms2: bool,
     threshold_input: Entity<InputState>,
 
+    // Named presets over the options above
+    presets: Vec<settings::Preset>,
+    preset_select: Entity<SelectState<Vec<String>>>,
+    preset_name_input: Entity<InputState>,
+
+    // Theme
+    theme_pref: settings::ThemePreference,
+    theme_select: Entity<SelectState<Vec<&'static str>>>,
+
     // Conversion state
     phase: AppPhase,
-    progress_counter: Option<thermo_raw::ProgressCounter>,
+    progress_rx: Option<crossbeam_channel::Receiver<conversion::ProgressData>>,
+    latest_progress: Option<conversion::ProgressData>,
+    files_total: usize,
     cancel_flag: Option<Arc<AtomicBool>>,
-    total_scans: u64,
     convert_handle: Option<std::thread::JoinHandle<Vec<conversion::ConversionResult>>>,
     start_time: Option<Instant>,
     messages: Vec<(String, bool)>, // (text, is_error)
+
+    // Watch-folder mode
+    watch_dir: Option<PathBuf>,
+    watch_handle: Option<watch::WatchHandle>,
+    watch_tracker: watch::DebounceTracker,
+    auto_convert: bool,
+
+    // Scan-phase state
+    scan_rx: Option<crossbeam_channel::Receiver<scan::ScanResult>>,
+    scan_handle: Option<std::thread::JoinHandle<()>>,
+    scan_cancel: Option<Arc<AtomicBool>>,
+
+    // Verify-phase state (user-triggered CRC-32 + scan-count integrity check)
+    verify_rx: Option<crossbeam_channel::Receiver<verify::VerifyResult>>,
+    verify_handle: Option<std::thread::JoinHandle<()>>,
+    verify_cancel: Option<Arc<AtomicBool>>,
+
+    // Preview pane
+    selected_index: Option<usize>,
+    preview: Option<preview::PreviewData>,
 }
 
 impl AppState {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let settings = settings::Settings::load();
+
         let mz_items = vec!["64-bit", "32-bit"];
+        let mz_row = match settings.mz_precision {
+            settings::PrecisionSetting::F64 => 0,
+            settings::PrecisionSetting::F32 => 1,
+        };
         let mz_select = cx.new(|cx| {
-            SelectState::new(mz_items, Some(IndexPath::default().row(0)), window, cx)
+            SelectState::new(mz_items, Some(IndexPath::default().row(mz_row)), window, cx)
         });
 
         let intensity_items = vec!["32-bit", "64-bit"];
+        let intensity_row = match settings.intensity_precision {
+            settings::PrecisionSetting::F32 => 0,
+            settings::PrecisionSetting::F64 => 1,
+        };
         let intensity_select = cx.new(|cx| {
-            SelectState::new(intensity_items, Some(IndexPath::default().row(0)), window, cx)
+            SelectState::new(
+                intensity_items,
+                Some(IndexPath::default().row(intensity_row)),
+                window,
+                cx,
+            )
         });
 
         let compression_items = vec!["Zlib", "None"];
+        let compression_row = match settings.compression {
+            settings::CompressionSetting::Zlib => 0,
+            settings::CompressionSetting::None => 1,
+        };
         let compression_select = cx.new(|cx| {
             SelectState::new(
                 compression_items,
-                Some(IndexPath::default().row(0)),
+                Some(IndexPath::default().row(compression_row)),
+                window,
+                cx,
+            )
+        });
+
+        let disposition_items = vec!["Keep", "Move to Trash", "Archive"];
+        let disposition_row = match settings.disposition {
+            settings::DispositionSetting::Keep => 0,
+            settings::DispositionSetting::Trash => 1,
+            settings::DispositionSetting::Archive => 2,
+        };
+        let disposition_select = cx.new(|cx| {
+            SelectState::new(
+                disposition_items,
+                Some(IndexPath::default().row(disposition_row)),
                 window,
                 cx,
             )
         });
 
         let threshold_input = cx.new(|cx| {
-            InputState::new(window, cx).placeholder("0")
+            InputState::new(window, cx)
+                .placeholder("0")
+                .default_value(format!("{}", settings.intensity_threshold))
         });
 
+        let preset_names: Vec<String> = settings.presets.iter().map(|p| p.name.clone()).collect();
+        let preset_select = cx.new(|cx| SelectState::new(preset_names, None, window, cx));
+        let preset_name_input = cx.new(|cx| InputState::new(window, cx).placeholder("Preset name"));
+
+        let theme_items = vec!["Light", "Dark", "Auto"];
+        let theme_row = match settings.theme {
+            settings::ThemePreference::Light => 0,
+            settings::ThemePreference::Dark => 1,
+            settings::ThemePreference::Auto => 2,
+        };
+        let theme_select = cx.new(|cx| {
+            SelectState::new(theme_items, Some(IndexPath::default().row(theme_row)), window, cx)
+        });
+        theme::apply(settings.theme, window, cx);
+
+        // Re-resolve the theme whenever the OS appearance changes, as long
+        // as the user hasn't pinned it to Light or Dark explicitly.
+        cx.spawn_in(window, async move |this, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(std::time::Duration::from_secs(1))
+                    .await;
+
+                let alive = this
+                    .update_in(cx, |this, window, cx| {
+                        if this.theme_pref == settings::ThemePreference::Auto
+                            && theme::changed_since_last_apply(window, cx)
+                        {
+                            theme::apply(this.theme_pref, window, cx);
+                            cx.notify();
+                        }
+                    })
+                    .is_ok();
+
+                if !alive {
+                    break;
+                }
+            }
+        })
+        .detach();
+
         Self {
             files: Vec::new(),
-            output_dir: None,
+            output_dir: settings.output_dir.clone(),
             mz_select,
             intensity_select,
             compression_select,
-            write_index: true,
-            include_ms2: true,
+            disposition_select,
+            write_index: settings.write_index,
+            include_ms2: settings.include_ms2,
             threshold_input,
+            presets: settings.presets.clone(),
+            preset_select,
+            preset_name_input,
+            theme_pref: settings.theme,
+            theme_select,
             phase: AppPhase::Idle,
-            progress_counter: None,
+            progress_rx: None,
+            latest_progress: None,
+            files_total: 0,
             cancel_flag: None,
-            total_scans: 0,
             convert_handle: None,
             start_time: None,
             messages: Vec::new(),
+            watch_dir: None,
+            watch_handle: None,
+            watch_tracker: watch::DebounceTracker::new(),
+            auto_convert: false,
+            scan_rx: None,
+            scan_handle: None,
+            scan_cancel: None,
+
+            verify_rx: None,
+            verify_handle: None,
+            verify_cancel: None,
+            selected_index: None,
+            preview: None,
         }
     }
 
-    fn build_config(&self, cx: &App) -> thermo_raw_mzml::MzmlConfig {
+    /// Collect the engine-facing conversion settings out of the option
+    /// widgets. Shared by [`Self::start_conversion_inner`]; the `--no-gui`
+    /// CLI (`crate::cli`) builds the same [`engine::EngineOptions`] from
+    /// flags instead of widgets.
+    fn build_engine_options(&self, cx: &App) -> engine::EngineOptions {
         let mz_precision = match self.mz_select.read(cx).selected_value() {
             Some(&"32-bit") => thermo_raw_mzml::Precision::F32,
             _ => thermo_raw_mzml::Precision::F64,
@@ -110,16 +253,116 @@ impl AppState {
             .parse::<f64>()
             .unwrap_or(0.0)
             .max(0.0);
-        thermo_raw_mzml::MzmlConfig {
+        let disposition = match self.disposition_select.read(cx).selected_value() {
+            Some(&"Move to Trash") => conversion::SourceDisposition::Trash,
+            Some(&"Archive") => conversion::SourceDisposition::Archive,
+            _ => conversion::SourceDisposition::Keep,
+        };
+
+        engine::EngineOptions {
             mz_precision,
             intensity_precision,
             compression,
             write_index: self.write_index,
             include_ms2: self.include_ms2,
             intensity_threshold,
+            disposition,
         }
     }
 
+    /// Snapshot the current widget state into a [`settings::Settings`] and
+    /// write it out, so the next launch of `AppState::new` picks up right
+    /// where this session left off.
+    fn persist_settings(&self, cx: &App) {
+        let options = self.build_engine_options(cx);
+        settings::Settings {
+            mz_precision: options.mz_precision.into(),
+            intensity_precision: options.intensity_precision.into(),
+            compression: options.compression.into(),
+            write_index: options.write_index,
+            include_ms2: options.include_ms2,
+            intensity_threshold: options.intensity_threshold,
+            disposition: options.disposition.into(),
+            output_dir: self.output_dir.clone(),
+            presets: self.presets.clone(),
+            theme: self.theme_pref,
+        }
+        .save();
+    }
+
+    /// Save the current compression/indexed/MS2/threshold combination as a
+    /// named preset (replacing any existing preset with the same name), add
+    /// it to the header picker, and persist it.
+    fn save_preset(&mut self, name: String, cx: &mut Context<Self>) {
+        if name.trim().is_empty() {
+            return;
+        }
+        let options = self.build_engine_options(cx);
+        let preset = settings::Preset {
+            name: name.clone(),
+            compression: options.compression.into(),
+            write_index: options.write_index,
+            include_ms2: options.include_ms2,
+            intensity_threshold: options.intensity_threshold,
+        };
+        if let Some(existing) = self.presets.iter_mut().find(|p| p.name == name) {
+            *existing = preset;
+        } else {
+            self.presets.push(preset);
+        }
+
+        let preset_names: Vec<String> = self.presets.iter().map(|p| p.name.clone()).collect();
+        let selected_row = preset_names.iter().position(|n| n == &name);
+        self.preset_select.update(cx, |state, cx| {
+            state.set_items(preset_names, cx);
+            if let Some(row) = selected_row {
+                state.set_selected_index(Some(IndexPath::default().row(row)), cx);
+            }
+        });
+
+        self.persist_settings(cx);
+        cx.notify();
+    }
+
+    /// Apply a preset selected from the header picker: repopulate
+    /// `compression_select`, `write_index`, `include_ms2`, and
+    /// `threshold_input` so the rest of the conversion options match the
+    /// saved combination in one click.
+    fn apply_preset(&mut self, index: IndexPath, cx: &mut Context<Self>) {
+        let Some(preset) = self.presets.get(index.row).cloned() else {
+            return;
+        };
+
+        let compression_row = match preset.compression {
+            settings::CompressionSetting::Zlib => 0,
+            settings::CompressionSetting::None => 1,
+        };
+        self.compression_select.update(cx, |state, cx| {
+            state.set_selected_index(Some(IndexPath::default().row(compression_row)), cx);
+        });
+        self.threshold_input.update(cx, |state, cx| {
+            state.set_value(format!("{}", preset.intensity_threshold), cx);
+        });
+        self.write_index = preset.write_index;
+        self.include_ms2 = preset.include_ms2;
+
+        self.persist_settings(cx);
+        cx.notify();
+    }
+
+    /// Apply a theme picked from the title-bar `Select`, persisting the
+    /// preference so the next launch starts in the same mode.
+    fn change_theme(&mut self, index: IndexPath, window: &mut Window, cx: &mut Context<Self>) {
+        self.theme_pref = match index.row {
+            0 => settings::ThemePreference::Light,
+            1 => settings::ThemePreference::Dark,
+            _ => settings::ThemePreference::Auto,
+        };
+        theme::apply(self.theme_pref, window, cx);
+        self.persist_settings(cx);
+        cx.notify();
+    }
+
     fn add_files_action(&mut self, _: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
         cx.spawn(async move |this, cx| {
             let dialog = rfd::AsyncFileDialog::new()
@@ -160,8 +403,12 @@ impl AppState {
                             .filter_map(|e| e.ok())
                             .map(|e| e.path())
                             .filter(|p| {
-                                p.extension()
-                                    .is_some_and(|ext| ext.eq_ignore_ascii_case("raw"))
+                                let has_raw_ext = p
+                                    .extension()
+                                    .is_some_and(|ext| ext.eq_ignore_ascii_case("raw"));
+                                // Also pick up extension-less files that are
+                                // valid RAW by signature.
+                                has_raw_ext || (p.extension().is_none() && file_list::has_raw_signature(p))
                             })
                             .collect()
                     })
@@ -192,45 +439,266 @@ impl AppState {
         cx.notify();
     }
 
-    /// Open each file on the background executor to read scan counts,
-    /// then update the matching entries on the main thread.
+    /// Scan headers for `paths` with bounded concurrency, streaming each
+    /// entry's `n_scans` back as soon as it's ready instead of waiting for
+    /// the whole batch. Cancellable via [`Self::stop_scan`].
     fn scan_files_background(&mut self, paths: Vec<PathBuf>, cx: &mut Context<Self>) {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let handle = scan::spawn_scan(paths, tx, Arc::clone(&cancel));
+        self.scan_rx = Some(rx);
+        self.scan_handle = Some(handle);
+        self.scan_cancel = Some(cancel);
+
         cx.spawn(async move |this, cx| {
-            // Heavy I/O on background thread
-            let results: Vec<(PathBuf, Result<u32, String>)> = cx
-                .background_executor()
-                .spawn(async move {
-                    paths
-                        .into_iter()
-                        .map(|p| {
-                            let result = thermo_raw::RawFile::open_mmap(&p)
-                                .map(|raw| raw.n_scans())
-                                .map_err(|e| format!("Cannot read: {e}"));
-                            (p, result)
-                        })
-                        .collect()
-                })
-                .await;
+            loop {
+                cx.background_executor()
+                    .timer(std::time::Duration::from_millis(50))
+                    .await;
 
-            // Apply results back on the main thread
-            this.update(cx, |this, cx| {
-                for (path, result) in results {
-                    if let Some(entry) = this.files.iter_mut().find(|f| f.path == path) {
-                        match result {
-                            Ok(n) => {
-                                entry.n_scans = Some(n);
-                                entry.status = FileStatus::Pending;
-                                if this.output_dir.is_none() {
-                                    if let Some(parent) = path.parent() {
-                                        this.output_dir = Some(parent.to_path_buf());
-                                    }
+                let finished = this
+                    .update(cx, |this, cx| {
+                        this.apply_scan_results(cx);
+
+                        let finished = this
+                            .scan_handle
+                            .as_ref()
+                            .is_some_and(|h| h.is_finished());
+                        if finished {
+                            this.apply_scan_results(cx);
+                            this.scan_rx = None;
+                            this.scan_handle = None;
+                            this.scan_cancel = None;
+                            // Candidates never reached before cancellation stay Scanning forever otherwise.
+                            for f in &mut this.files {
+                                if f.status == FileStatus::Scanning {
+                                    f.status = FileStatus::Failed;
+                                    f.error = Some("Scan cancelled".into());
                                 }
                             }
-                            Err(e) => {
-                                entry.status = FileStatus::Failed;
-                                entry.error = Some(e);
+                            if this.auto_convert
+                                && this.phase != AppPhase::Converting
+                                && this.can_convert()
+                            {
+                                this.start_conversion_inner(cx);
                             }
                         }
+                        cx.notify();
+                        finished
+                    })
+                    .unwrap_or(true);
+
+                if finished {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Drain any buffered [`scan::ScanResult`]s and apply them to the
+    /// matching file rows.
+    fn apply_scan_results(&mut self, cx: &mut Context<Self>) {
+        let Some(rx) = &self.scan_rx else { return };
+        while let Ok(scan::ScanResult { path, result }) = rx.try_recv() {
+            if let Some(entry) = self.files.iter_mut().find(|f| f.path == path) {
+                match result {
+                    Ok(n) => {
+                        entry.n_scans = Some(n);
+                        entry.status = FileStatus::Pending;
+                        if self.output_dir.is_none() {
+                            if let Some(parent) = path.parent() {
+                                self.output_dir = Some(parent.to_path_buf());
+                            }
+                        }
+                    }
+                    Err(e) if e == "Not a Thermo RAW file" => {
+                        entry.status = FileStatus::WrongType;
+                        entry.error = Some(e);
+                    }
+                    Err(e) => {
+                        entry.status = FileStatus::Failed;
+                        entry.error = Some(e);
+                    }
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    fn stop_scan(&mut self, _: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(cancel) = &self.scan_cancel {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        cx.notify();
+    }
+
+    /// Run a CRC-32 + scan-count integrity check over every file currently
+    /// `Pending`, moving each to `Verifying` and back. Unlike the header-only
+    /// scan phase, this reads the whole data stream, so it's only ever run
+    /// on demand.
+    fn verify_files_action(&mut self, _: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        let paths: Vec<PathBuf> = self
+            .files
+            .iter_mut()
+            .filter(|f| f.status == FileStatus::Pending)
+            .map(|f| {
+                f.status = FileStatus::Verifying;
+                f.path.clone()
+            })
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let handle = verify::spawn_verify(paths, tx, Arc::clone(&cancel));
+        self.verify_rx = Some(rx);
+        self.verify_handle = Some(handle);
+        self.verify_cancel = Some(cancel);
+
+        cx.spawn(async move |this, cx| loop {
+            cx.background_executor()
+                .timer(std::time::Duration::from_millis(50))
+                .await;
+
+            let finished = this
+                .update(cx, |this, cx| {
+                    this.apply_verify_results(cx);
+                    let finished = this
+                        .verify_handle
+                        .as_ref()
+                        .is_some_and(|h| h.is_finished());
+                    if finished {
+                        this.apply_verify_results(cx);
+                        this.verify_rx = None;
+                        this.verify_handle = None;
+                        this.verify_cancel = None;
+                    }
+                    cx.notify();
+                    finished
+                })
+                .unwrap_or(true);
+
+            if finished {
+                break;
+            }
+        })
+        .detach();
+    }
+
+    /// Drain any buffered [`verify::VerifyResult`]s and apply them to the
+    /// matching file rows, flagging a scan-count mismatch (or a manifest
+    /// mismatch, once one is supplied) as a `Failed` error rather than
+    /// silently returning to `Pending`.
+    fn apply_verify_results(&mut self, cx: &mut Context<Self>) {
+        let Some(rx) = &self.verify_rx else { return };
+        while let Ok(verify::VerifyResult { path, result }) = rx.try_recv() {
+            if let Some(entry) = self.files.iter_mut().find(|f| f.path == path) {
+                match result {
+                    Ok(report) => {
+                        entry.crc32 = Some(report.crc32);
+                        if let Some(problem) = report.problem() {
+                            entry.status = FileStatus::Failed;
+                            entry.error = Some(problem);
+                        } else {
+                            entry.status = FileStatus::Pending;
+                        }
+                    }
+                    Err(e) => {
+                        entry.status = FileStatus::Failed;
+                        entry.error = Some(e);
+                    }
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    /// Select a file row and kick off an async preview computation for it.
+    fn select_file(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.selected_index = Some(index);
+        self.preview = None;
+        cx.notify();
+
+        let Some(entry) = self.files.get(index) else {
+            return;
+        };
+        if entry.status == FileStatus::WrongType {
+            return;
+        }
+        let path = entry.path.clone();
+
+        cx.spawn(async move |this, cx| {
+            let result = cx
+                .background_executor()
+                .spawn({
+                    let path = path.clone();
+                    async move { preview::build_preview(&path) }
+                })
+                .await;
+
+            this.update(cx, |this, cx| {
+                // The selection may have moved on while this was computing.
+                if this.selected_index != Some(index) {
+                    return;
+                }
+                match result {
+                    Ok(data) => this.preview = Some(data),
+                    Err(e) => {
+                        this.messages.push((format!("Preview failed: {e}"), true));
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn toggle_watch_folder(
+        &mut self,
+        _: &ClickEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.watch_handle.is_some() {
+            self.watch_handle = None;
+            self.watch_dir = None;
+            self.messages.push(("Stopped watching folder.".into(), false));
+            cx.notify();
+            return;
+        }
+
+        cx.spawn(async move |this, cx| {
+            let dialog = rfd::AsyncFileDialog::new().set_title("Select folder to watch");
+            let Some(handle) = dialog.pick_folder().await else {
+                return;
+            };
+            let dir = handle.path().to_path_buf();
+
+            let watch_result = cx
+                .background_executor()
+                .spawn({
+                    let dir = dir.clone();
+                    async move { watch::watch_folder(&dir) }
+                })
+                .await;
+
+            this.update(cx, |this, cx| {
+                match watch_result {
+                    Ok(watch_handle) => {
+                        this.watch_dir = Some(dir);
+                        this.watch_handle = Some(watch_handle);
+                        this.watch_tracker = watch::DebounceTracker::new();
+                        this.messages
+                            .push(("Watching folder for new RAW files...".into(), false));
+                        this.start_watch_poll_loop(cx);
+                    }
+                    Err(e) => {
+                        this.messages
+                            .push((format!("Cannot watch folder: {e}"), true));
                     }
                 }
                 cx.notify();
@@ -240,10 +708,49 @@ impl AppState {
         .detach();
     }
 
+    /// Poll the active filesystem watcher every 500ms, handing any RAW file
+    /// that has been size-stable for [`WATCH_STABLE_FOR`] to the scan
+    /// pipeline. Exits once watching is turned off.
+    fn start_watch_poll_loop(&self, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(std::time::Duration::from_millis(500))
+                    .await;
+
+                let still_watching = this
+                    .update(cx, |this, cx| {
+                        let Some(handle) = this.watch_handle.as_ref() else {
+                            return false;
+                        };
+                        this.watch_tracker.observe(handle);
+                        let ready = this.watch_tracker.take_stable(WATCH_STABLE_FOR);
+                        if !ready.is_empty() {
+                            this.add_paths(&ready, cx);
+                            this.scan_files_background(ready, cx);
+                        }
+                        cx.notify();
+                        true
+                    })
+                    .unwrap_or(false);
+
+                if !still_watching {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
     fn clear_files(&mut self, _: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(cancel) = &self.scan_cancel {
+            cancel.store(true, Ordering::Relaxed);
+        }
         self.files.clear();
         self.phase = AppPhase::Idle;
         self.messages.clear();
+        self.selected_index = None;
+        self.preview = None;
         cx.notify();
     }
 
@@ -258,6 +765,7 @@ impl AppState {
             if let Some(handle) = dialog.pick_folder().await {
                 this.update(cx, |this, cx| {
                     this.output_dir = Some(handle.path().to_path_buf());
+                    this.persist_settings(cx);
                     cx.notify();
                 }).ok();
             }
@@ -271,6 +779,12 @@ impl AppState {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.start_conversion_inner(cx);
+    }
+
+    /// Core of [`Self::start_conversion`], split out so watch-folder
+    /// auto-convert can trigger it without a `ClickEvent`.
+    fn start_conversion_inner(&mut self, cx: &mut Context<Self>) {
         let Some(output_dir) = self.output_dir.clone() else {
             self.messages
                 .push(("No output directory selected.".into(), true));
@@ -279,12 +793,12 @@ impl AppState {
         };
 
         // Collect files to convert
-        let convertible: Vec<(usize, PathBuf)> = self
+        let convertible: Vec<(usize, PathBuf, u64)> = self
             .files
             .iter()
             .enumerate()
             .filter(|(_, f)| f.status == FileStatus::Pending && f.n_scans.is_some())
-            .map(|(i, f)| (i, f.path.clone()))
+            .map(|(i, f)| (i, f.path.clone(), f.n_scans.unwrap_or(0) as u64))
             .collect();
 
         if convertible.is_empty() {
@@ -293,31 +807,25 @@ impl AppState {
             return;
         }
 
-        // Calculate total scans
-        self.total_scans = self
-            .files
-            .iter()
-            .filter(|f| f.status == FileStatus::Pending && f.n_scans.is_some())
-            .map(|f| f.n_scans.unwrap_or(0) as u64)
-            .sum();
+        self.files_total = convertible.len();
 
         // Mark files as converting
-        for (i, _) in &convertible {
+        for (i, _, _) in &convertible {
             self.files[*i].status = FileStatus::Converting;
+            self.files[*i].scans_progress = (0, 0);
         }
 
-        let config = self.build_config(cx);
-        let counter = thermo_raw::progress::new_counter();
+        self.persist_settings(cx);
+        let engine = engine::Engine::new(self.build_engine_options(cx));
         let cancel = Arc::new(AtomicBool::new(false));
+        let (handle, progress_rx) = engine.spawn(convertible, output_dir, Arc::clone(&cancel));
 
-        self.progress_counter = Some(Arc::clone(&counter));
-        self.cancel_flag = Some(Arc::clone(&cancel));
+        self.progress_rx = Some(progress_rx);
+        self.latest_progress = None;
+        self.cancel_flag = Some(cancel);
         self.phase = AppPhase::Converting;
         self.start_time = Some(Instant::now());
         self.messages.clear();
-
-        let handle =
-            conversion::spawn_conversion(convertible, output_dir, config, counter, cancel);
         self.convert_handle = Some(handle);
 
         // Start polling loop
@@ -329,6 +837,16 @@ impl AppState {
 
                 let should_stop = this
                     .update(cx, |this, cx| {
+                        if let Some(rx) = &this.progress_rx {
+                            while let Ok(update) = rx.try_recv() {
+                                if let Some(f) = this.files.get_mut(update.file_index) {
+                                    f.scans_progress =
+                                        (update.scans_done_in_file, update.scans_total_in_file);
+                                }
+                                this.latest_progress = Some(update);
+                            }
+                        }
+
                         let handle = this.convert_handle.as_ref();
                         let finished = handle.is_some_and(|h| h.is_finished());
 
@@ -342,6 +860,8 @@ impl AppState {
 
                             let mut success = 0usize;
                             let mut failed = 0usize;
+                            let mut disposed = 0usize;
+                            let mut disposal_failures = 0usize;
                             for r in &results {
                                 match &r.result {
                                     Ok(()) => {
@@ -349,6 +869,12 @@ impl AppState {
                                             f.status = FileStatus::Done;
                                         }
                                         success += 1;
+                                        if r.disposed {
+                                            match &r.disposition_error {
+                                                None => disposed += 1,
+                                                Some(_) => disposal_failures += 1,
+                                            }
+                                        }
                                     }
                                     Err(e) => {
                                         if let Some(f) = this.files.get_mut(r.index) {
@@ -373,8 +899,24 @@ impl AppState {
                                 msg.push_str(&format!(" {failed} failed."));
                             }
                             this.messages.push((msg, false));
+                            if disposed > 0 {
+                                let action = match this.build_engine_options(cx).disposition {
+                                    conversion::SourceDisposition::Trash => "Trash",
+                                    conversion::SourceDisposition::Archive => "the archive folder",
+                                    conversion::SourceDisposition::Keep => "",
+                                };
+                                this.messages
+                                    .push((format!("Moved {disposed} source file(s) to {action}."), false));
+                            }
+                            if disposal_failures > 0 {
+                                this.messages.push((
+                                    format!("Could not move {disposal_failures} source file(s)."),
+                                    true,
+                                ));
+                            }
                             this.phase = AppPhase::Done;
-                            this.progress_counter = None;
+                            this.progress_rx = None;
+                            this.latest_progress = None;
                             this.cancel_flag = None;
                             cx.notify();
                             return true;
@@ -405,20 +947,55 @@ impl AppState {
             flag.store(true, Ordering::Relaxed);
         }
         self.messages
-            .push(("Cancelling after current file...".into(), false));
+            .push(("Cancelling after in-flight files finish...".into(), false));
         cx.notify();
     }
 
-    fn progress_fraction(&self) -> f32 {
-        if self.total_scans == 0 {
+    /// Overall progress as 0.0..=1.0, combining whole files already done with
+    /// the current file's sub-progress.
+    fn progress_unit(&self) -> f64 {
+        if self.files_total == 0 {
             return 0.0;
         }
-        let done = self
-            .progress_counter
-            .as_ref()
-            .map(|c| c.load(Ordering::Relaxed))
-            .unwrap_or(0);
-        (done as f64 / self.total_scans as f64 * 100.0).min(100.0) as f32
+        let Some(progress) = &self.latest_progress else {
+            return 0.0;
+        };
+        let in_file_fraction = if progress.scans_total_in_file == 0 {
+            0.0
+        } else {
+            (progress.scans_done_in_file as f64 / progress.scans_total_in_file as f64).min(1.0)
+        };
+        ((progress.files_done as f64 + in_file_fraction) / self.files_total as f64).min(1.0)
+    }
+
+    fn progress_fraction(&self) -> f32 {
+        (self.progress_unit() * 100.0) as f32
+    }
+
+    /// Estimated seconds remaining, extrapolated from elapsed time and the
+    /// fraction of work done so far.
+    fn eta_seconds(&self) -> Option<f64> {
+        let fraction = self.progress_unit();
+        if fraction <= 0.0 {
+            return None;
+        }
+        let elapsed = self.start_time?.elapsed().as_secs_f64();
+        Some((elapsed / fraction * (1.0 - fraction)).max(0.0))
+    }
+
+    fn current_file_label(&self) -> Option<String> {
+        let progress = self.latest_progress.as_ref()?;
+        let stage = match progress.stage {
+            conversion::ConversionStage::Scanning => "scanning",
+            conversion::ConversionStage::Reading => "reading",
+            conversion::ConversionStage::Writing => "writing",
+        };
+        Some(format!(
+            "{} ({stage}, {}/{} files)",
+            progress.file_name,
+            progress.files_done,
+            self.files_total
+        ))
     }
 
     fn can_convert(&self) -> bool {
@@ -451,6 +1028,40 @@ impl AppState {
                     .text_color(cx.theme().muted_foreground)
                     .child(format!("v{}", env!("CARGO_PKG_VERSION"))),
             )
+            .child(div().flex_1())
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Preset:"),
+            )
+            .child(
+                Select::new(&self.preset_select)
+                    .xsmall()
+                    .w(px(120.))
+                    .placeholder("None")
+                    .on_change(cx.listener(|this, index: &IndexPath, _window, cx| {
+                        this.apply_preset(*index, cx);
+                    })),
+            )
+            .child(Input::new(&self.preset_name_input).xsmall().w(px(110.)))
+            .child(
+                Button::new("save-preset")
+                    .xsmall()
+                    .outline()
+                    .label("Save Preset")
+                    .on_click(cx.listener(Self::save_preset_click)),
+            )
+    }
+
+    fn save_preset_click(
+        &mut self,
+        _: &ClickEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let name = self.preset_name_input.read(cx).value().to_string();
+        self.save_preset(name, cx);
     }
 
     fn render_file_list(&self, cx: &Context<Self>) -> impl IntoElement {
@@ -475,12 +1086,15 @@ impl AppState {
         let status_color = match entry.status {
             FileStatus::Scanning => cx.theme().muted_foreground,
             FileStatus::Pending => cx.theme().muted_foreground,
+            FileStatus::Verifying => cx.theme().muted_foreground,
             FileStatus::Converting => cx.theme().blue,
             FileStatus::Done => cx.theme().green,
             FileStatus::Failed => cx.theme().red,
+            FileStatus::WrongType => cx.theme().yellow,
         };
 
         let error_text = entry.error.clone();
+        let is_selected = self.selected_index == Some(index);
 
         h_flex()
             .id(("file-row", index))
@@ -490,7 +1104,11 @@ impl AppState {
             .items_center()
             .border_b_1()
             .border_color(cx.theme().border.opacity(0.5))
+            .when(is_selected, |this| this.bg(cx.theme().muted.opacity(0.5)))
             .hover(|s| s.bg(cx.theme().muted.opacity(0.3)))
+            .on_click(cx.listener(move |this, _event, _window, cx| {
+                this.select_file(index, cx);
+            }))
             .child(div().w_4().h_4().rounded(px(2.)).bg(status_color))
             .child(
                 v_flex()
@@ -539,6 +1157,84 @@ impl AppState {
                     .text_color(status_color)
                     .child(entry.status_label()),
             )
+            .when(entry.status == FileStatus::Converting, |this| {
+                this.child(
+                    Progress::new()
+                        .w(px(60.))
+                        .value(entry.progress_fraction() * 100.0),
+                )
+            })
+    }
+
+    fn render_preview(&self, cx: &Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("preview-pane")
+            .w(px(220.))
+            .h_full()
+            .px_3()
+            .py_2()
+            .gap_2()
+            .border_l_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(cx.theme().foreground)
+                    .child("Preview"),
+            )
+            .map(|this| match &self.preview {
+                None => this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(if self.selected_index.is_some() {
+                            "Loading preview..."
+                        } else {
+                            "Select a file to preview its TIC and a representative MS1 spectrum."
+                        }),
+                ),
+                Some(preview) => this
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("TIC"),
+                    )
+                    .child(Self::render_sparkline(&preview.tic_intensity, cx))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("MS1 spectrum (scan {})", preview.spectrum_scan_number)),
+                    )
+                    .child(Self::render_sparkline(&preview.spectrum_intensity, cx)),
+            })
+    }
+
+    /// Minimal max-in-bucket sparkline, drawn as a row of bars rather than a
+    /// true line plot (no canvas/path primitives needed).
+    fn render_sparkline(values: &[f64], cx: &Context<Self>) -> impl IntoElement {
+        const BARS: usize = 40;
+        let max = values.iter().cloned().fold(0.0_f64, f64::max).max(1.0);
+        let bucket = (values.len() / BARS).max(1);
+        let bars: Vec<f32> = values
+            .chunks(bucket)
+            .take(BARS)
+            .map(|chunk| {
+                let peak = chunk.iter().cloned().fold(0.0_f64, f64::max);
+                ((peak / max) * 48.0).max(1.0) as f32
+            })
+            .collect();
+
+        h_flex()
+            .gap(px(1.))
+            .items_end()
+            .h(px(48.))
+            .children(
+                bars.into_iter()
+                    .map(|height| div().w(px(3.)).h(px(height)).bg(cx.theme().blue)),
+            )
     }
 
     fn render_action_buttons(&self, cx: &Context<Self>) -> impl IntoElement {
@@ -573,6 +1269,27 @@ impl AppState {
                     .disabled(is_converting || self.files.is_empty())
                     .on_click(cx.listener(Self::clear_files)),
             )
+            .when(self.scan_handle.is_some(), |this| {
+                this.child(
+                    Button::new("stop-scan")
+                        .small()
+                        .outline()
+                        .label("Stop Scan")
+                        .on_click(cx.listener(Self::stop_scan)),
+                )
+            })
+            .child(
+                Button::new("verify")
+                    .small()
+                    .outline()
+                    .label("Verify")
+                    .disabled(
+                        is_converting
+                            || self.verify_handle.is_some()
+                            || !self.files.iter().any(|f| f.status == FileStatus::Pending),
+                    )
+                    .on_click(cx.listener(Self::verify_files_action)),
+            )
             .child(
                 div()
                     .flex_1()
@@ -620,6 +1337,67 @@ impl AppState {
                     .disabled(self.phase == AppPhase::Converting)
                     .on_click(cx.listener(Self::change_output_dir)),
             )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("After conversion:"),
+            )
+            .child(
+                Select::new(&self.disposition_select)
+                    .xsmall()
+                    .w(px(110.))
+                    .disabled(self.phase == AppPhase::Converting),
+            )
+    }
+
+    fn render_watch_row(&self, cx: &Context<Self>) -> impl IntoElement {
+        let is_watching = self.watch_handle.is_some();
+        let watch_label = match &self.watch_dir {
+            Some(dir) => format!("Watching: {}", dir.to_string_lossy()),
+            None => "Not watching any folder".into(),
+        };
+        let auto_convert = self.auto_convert;
+
+        h_flex()
+            .px_4()
+            .py_2()
+            .gap_3()
+            .items_center()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().foreground)
+                    .font_weight(FontWeight::MEDIUM)
+                    .child("Watch Folder:"),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .truncate()
+                    .child(watch_label),
+            )
+            .child(
+                Checkbox::new("auto-convert")
+                    .small()
+                    .label("Auto-convert")
+                    .checked(auto_convert)
+                    .on_click(cx.listener(move |this, checked: &bool, _window, cx| {
+                        this.auto_convert = *checked;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("toggle-watch")
+                    .xsmall()
+                    .outline()
+                    .label(if is_watching { "Stop Watching" } else { "Watch Folder..." })
+                    .on_click(cx.listener(Self::toggle_watch_folder)),
+            )
     }
 
     fn render_options_row(&self, cx: &Context<Self>) -> impl IntoElement {
@@ -688,6 +1466,7 @@ impl AppState {
                             .disabled(is_converting)
                             .on_click(cx.listener(move |this, checked: &bool, _window, cx| {
                                 this.write_index = *checked;
+                                this.persist_settings(cx);
                                 cx.notify();
                             }))
                     }),
@@ -707,6 +1486,7 @@ impl AppState {
                             .disabled(is_converting)
                             .on_click(cx.listener(move |this, checked: &bool, _window, cx| {
                                 this.include_ms2 = *checked;
+                                this.persist_settings(cx);
                                 cx.notify();
                             }))
                     })
@@ -742,13 +1522,17 @@ impl AppState {
             .border_color(cx.theme().border)
             .bg(cx.theme().tab_bar)
             .children(self.messages.iter().map(|(msg, is_err)| {
+                // A flat `red`/`green` text color on `tab_bar` reads fine in
+                // dark mode but can wash out once `tab_bar` turns light; a
+                // faint same-color chip behind the text keeps it legible in
+                // both.
+                let color = if *is_err { cx.theme().red } else { cx.theme().green };
                 div()
                     .text_xs()
-                    .text_color(if *is_err {
-                        cx.theme().red
-                    } else {
-                        cx.theme().green
-                    })
+                    .px_1()
+                    .rounded(px(2.))
+                    .bg(color.opacity(0.15))
+                    .text_color(color)
                     .child(msg.clone())
             }))
             .child(
@@ -780,6 +1564,29 @@ impl AppState {
                             )
                     }),
             )
+            .when(is_converting, |this| {
+                this.child(
+                    h_flex()
+                        .gap_3()
+                        .items_center()
+                        .child(
+                            div()
+                                .flex_1()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .truncate()
+                                .children(self.current_file_label()),
+                        )
+                        .when_some(self.eta_seconds(), |this, eta| {
+                            this.child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(format!("~{:.0}s remaining", eta)),
+                            )
+                        }),
+                )
+            })
     }
 }
 
@@ -792,11 +1599,40 @@ impl Render for AppState {
             .font_family(cx.theme().font_family.clone())
             .bg(cx.theme().background)
             .text_color(cx.theme().foreground)
-            .child(TitleBar::new().child("RAW to mzML Converter"))
+            .child(
+                TitleBar::new()
+                    .child("RAW to mzML Converter")
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child("Theme:"),
+                            )
+                            .child(
+                                Select::new(&self.theme_select)
+                                    .xsmall()
+                                    .w(px(70.))
+                                    .on_change(cx.listener(|this, index: &IndexPath, window, cx| {
+                                        this.change_theme(*index, window, cx);
+                                    })),
+                            ),
+                    ),
+            )
             .child(self.render_header(cx))
             .child(self.render_action_buttons(cx))
-            .child(self.render_file_list(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(self.render_file_list(cx))
+                    .child(self.render_preview(cx)),
+            )
             .child(self.render_output_row(cx))
+            .child(self.render_watch_row(cx))
             .child(self.render_options_row(cx))
             .child(self.render_bottom_bar(cx))
             .children(dialog_layer)
@@ -0,0 +1,94 @@
+//! `--no-gui` entry point: drives [`crate::engine::Engine`] directly so the
+//! converter can run on a headless compute node or inside a pipeline,
+//! without opening a window.
+//!
+//! Flags map onto the GUI options in `render_options_row`; m/z and
+//! intensity precision have no flag and stay at 64-bit / 32-bit. Indexed
+//! output and MS2 inclusion are off unless their flag is given:
+//!
+//! ```text
+//! thermo-raw-gui --no-gui --output OUT_DIR [--compression zlib|none]
+//!     [--indexed] [--include-ms2] [--min-intensity N] INPUT.raw [INPUT2.raw ...]
+//! ```
+//!
+//! This module is invoked from `main` when `--no-gui` is present in
+//! `std::env::args()`, before the GUI's `Application::new()` is built.
+
+use std::path::PathBuf;
+
+use crate::engine::{Engine, EngineOptions};
+
+/// Parse `--no-gui` CLI args (excluding the flag itself and the binary
+/// name) and run the conversion, returning a process exit code.
+pub fn run(args: &[String]) -> i32 {
+    let mut options = EngineOptions {
+        write_index: false,
+        include_ms2: false,
+        ..EngineOptions::default()
+    };
+    let mut output_dir: Option<PathBuf> = None;
+    let mut inputs = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--output" => {
+                i += 1;
+                let Some(dir) = args.get(i) else {
+                    eprintln!("--output requires a path");
+                    return 2;
+                };
+                output_dir = Some(PathBuf::from(dir));
+            }
+            "--compression" => {
+                i += 1;
+                options.compression = match args.get(i).map(String::as_str) {
+                    Some("none") => thermo_raw_mzml::Compression::None,
+                    Some("zlib") | None => thermo_raw_mzml::Compression::Zlib,
+                    Some(other) => {
+                        eprintln!("Unknown --compression value: {other}");
+                        return 2;
+                    }
+                };
+            }
+            "--indexed" => options.write_index = true,
+            "--include-ms2" => options.include_ms2 = true,
+            "--min-intensity" => {
+                i += 1;
+                let Some(value) = args.get(i).and_then(|s| s.parse::<f64>().ok()) else {
+                    eprintln!("--min-intensity requires a number");
+                    return 2;
+                };
+                options.intensity_threshold = value.max(0.0);
+            }
+            glob => inputs.push(PathBuf::from(glob)),
+        }
+        i += 1;
+    }
+
+    let Some(output_dir) = output_dir else {
+        eprintln!("--no-gui requires --output OUT_DIR");
+        return 2;
+    };
+    if inputs.is_empty() {
+        eprintln!("--no-gui requires at least one input RAW file");
+        return 2;
+    }
+
+    let engine = Engine::new(options);
+    let results = engine.run_to_completion(&inputs, output_dir);
+
+    let failed = results.iter().filter(|r| r.result.is_err()).count();
+    for r in &results {
+        if let Err(e) = &r.result {
+            eprintln!("{}: {e}", inputs[r.index].display());
+        }
+    }
+    eprintln!(
+        "Converted {}/{} file(s).",
+        results.len() - failed,
+        results.len()
+    );
+
+    if failed > 0 { 1 } else { 0 }
+}
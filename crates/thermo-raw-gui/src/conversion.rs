@@ -1,50 +1,262 @@
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 
+use crossbeam_channel::Sender;
 use thermo_raw::ProgressCounter;
 use thermo_raw_mzml::MzmlConfig;
 
+/// What to do with a source RAW file after it converts successfully.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceDisposition {
+    Keep,
+    Trash,
+    Archive,
+}
+
+impl SourceDisposition {
+    /// Apply this disposition to a source file that just converted
+    /// successfully. Trashed files go through the OS recycle bin (via the
+    /// `trash` crate) so they stay recoverable; archived files are moved
+    /// into an `archive` subfolder beside the source file.
+    fn apply(self, raw_path: &Path) -> Result<(), String> {
+        match self {
+            SourceDisposition::Keep => Ok(()),
+            SourceDisposition::Trash => {
+                trash::delete(raw_path).map_err(|e| format!("Trash failed: {e}"))
+            }
+            SourceDisposition::Archive => {
+                let parent = raw_path.parent().unwrap_or_else(|| Path::new("."));
+                let archive_dir = parent.join("archive");
+                std::fs::create_dir_all(&archive_dir)
+                    .map_err(|e| format!("Cannot create archive folder: {e}"))?;
+                let file_name = raw_path
+                    .file_name()
+                    .ok_or_else(|| "Invalid source path".to_string())?;
+                std::fs::rename(raw_path, archive_dir.join(file_name))
+                    .map_err(|e| format!("Cannot archive file: {e}"))
+            }
+        }
+    }
+}
+
+/// Which phase of a single file's conversion a [`ProgressData`] snapshot
+/// reflects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionStage {
+    /// File picked up, not yet opened.
+    Scanning,
+    /// Decoding RAW scan data and building mzML spectra.
+    Reading,
+    /// The conversion call has returned and the mzML (and index) is on
+    /// disk; sent once per file, with `files_done` already counting it.
+    Writing,
+}
+
+/// A progress snapshot sent from the conversion thread to the UI over a
+/// channel (modeled on czkawka's progress sender pattern), rich enough to
+/// render per-file sub-progress and an ETA without the UI thread polling a
+/// bare scan counter.
+#[derive(Debug, Clone)]
+pub struct ProgressData {
+    pub file_index: usize,
+    pub file_name: String,
+    pub scans_done_in_file: u64,
+    pub scans_total_in_file: u64,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub stage: ConversionStage,
+}
+
 pub struct ConversionResult {
     pub index: usize,
     pub result: Result<(), String>,
+    /// Set when a non-`Keep` disposition was attempted on this file's
+    /// source after a successful conversion.
+    pub disposed: bool,
+    /// Set when the disposition above was attempted but failed.
+    pub disposition_error: Option<String>,
 }
 
-/// Run conversion of multiple files on a background thread.
-/// Returns a JoinHandle that yields per-file results.
+/// Minimum scan-count delta between `ProgressData` sends, so a fast file
+/// doesn't flood the channel with one message per scan.
+const SCANS_PER_UPDATE: u64 = 25;
+
+/// Run conversion of multiple files concurrently on a bounded pool of
+/// background threads (sized like `scan::spawn_scan`'s header-scan pool),
+/// reporting rich per-file progress over `progress_tx` as it goes.
+///
+/// `files` is `(original file-list index, path, scan count)`. Returns a
+/// JoinHandle that yields per-file results (in completion order, not input
+/// order — callers match a result back to its file via `ConversionResult::index`).
 pub fn spawn_conversion(
-    files: Vec<(usize, PathBuf)>,
+    files: Vec<(usize, PathBuf, u64)>,
     output_dir: PathBuf,
     config: MzmlConfig,
-    counter: ProgressCounter,
+    disposition: SourceDisposition,
+    progress_tx: Sender<ProgressData>,
     cancel: Arc<AtomicBool>,
 ) -> std::thread::JoinHandle<Vec<ConversionResult>> {
+    let files_total = files.len();
+    let concurrency = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+
     std::thread::spawn(move || {
-        let mut results = Vec::with_capacity(files.len());
-        for (index, raw_path) in files {
-            if cancel.load(Ordering::Relaxed) {
-                break;
-            }
+        let (work_tx, work_rx) = crossbeam_channel::unbounded::<(usize, PathBuf, u64)>();
+        for file in files {
+            work_tx.send(file).ok();
+        }
+        drop(work_tx);
+
+        let files_done = Arc::new(AtomicUsize::new(0));
+        let results = Arc::new(std::sync::Mutex::new(Vec::with_capacity(files_total)));
 
-            let stem = raw_path
-                .file_stem()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .into_owned();
-            let out_path = output_dir.join(format!("{stem}.mzML"));
-
-            let result = thermo_raw_mzml::convert_file_with_progress(
-                &raw_path,
-                &out_path,
-                &config,
-                &counter,
-            );
-
-            results.push(ConversionResult {
-                index,
-                result: result.map_err(|e| e.to_string()),
-            });
+        let workers: Vec<_> = (0..concurrency)
+            .map(|_| {
+                let work_rx = work_rx.clone();
+                let progress_tx = progress_tx.clone();
+                let cancel = Arc::clone(&cancel);
+                let files_done = Arc::clone(&files_done);
+                let results = Arc::clone(&results);
+                let config = config.clone();
+                let output_dir = output_dir.clone();
+                std::thread::spawn(move || {
+                    while let Ok((index, raw_path, scans_total_in_file)) = work_rx.recv() {
+                        if cancel.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        let result = convert_one(
+                            index,
+                            &raw_path,
+                            scans_total_in_file,
+                            &output_dir,
+                            &config,
+                            disposition,
+                            &progress_tx,
+                            files_total,
+                            &files_done,
+                        );
+                        results.lock().unwrap().push(result);
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            worker.join().ok();
         }
-        results
+
+        Arc::try_unwrap(results)
+            .map(|m| m.into_inner().unwrap_or_default())
+            .unwrap_or_default()
     })
 }
+
+/// Convert a single file end to end: scan/reading/writing progress updates,
+/// the blocking mzML conversion itself, and any post-success disposition.
+/// Pulled out of `spawn_conversion` so each pool worker can call it for
+/// whichever file it dequeues next.
+#[allow(clippy::too_many_arguments)]
+fn convert_one(
+    index: usize,
+    raw_path: &Path,
+    scans_total_in_file: u64,
+    output_dir: &Path,
+    config: &MzmlConfig,
+    disposition: SourceDisposition,
+    progress_tx: &Sender<ProgressData>,
+    files_total: usize,
+    files_done: &Arc<AtomicUsize>,
+) -> ConversionResult {
+    let file_name = raw_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = raw_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    let out_path = output_dir.join(format!("{stem}.mzML"));
+
+    progress_tx
+        .try_send(ProgressData {
+            file_index: index,
+            file_name: file_name.clone(),
+            scans_done_in_file: 0,
+            scans_total_in_file,
+            files_done: files_done.load(Ordering::Relaxed),
+            files_total,
+            stage: ConversionStage::Scanning,
+        })
+        .ok();
+
+    // Fresh per-file counter (`convert_file_with_progress` ticks it once per
+    // scan written); a watcher thread samples it so this worker's blocking
+    // conversion call still yields live sub-progress.
+    let file_counter: ProgressCounter = thermo_raw::progress::new_counter();
+    let watcher_done = Arc::new(AtomicBool::new(false));
+    let watcher = {
+        let watcher_counter = Arc::clone(&file_counter);
+        let watcher_done = Arc::clone(&watcher_done);
+        let watcher_tx = progress_tx.clone();
+        let file_name = file_name.clone();
+        let files_done = Arc::clone(files_done);
+        std::thread::spawn(move || {
+            let mut last_sent = 0u64;
+            while !watcher_done.load(Ordering::Relaxed) {
+                std::thread::sleep(std::time::Duration::from_millis(25));
+                let done = watcher_counter.load(Ordering::Relaxed);
+                if done.saturating_sub(last_sent) >= SCANS_PER_UPDATE
+                    || (scans_total_in_file > 0 && done >= scans_total_in_file)
+                {
+                    last_sent = done;
+                    watcher_tx
+                        .try_send(ProgressData {
+                            file_index: index,
+                            file_name: file_name.clone(),
+                            scans_done_in_file: done,
+                            scans_total_in_file,
+                            files_done: files_done.load(Ordering::Relaxed),
+                            files_total,
+                            stage: ConversionStage::Reading,
+                        })
+                        .ok();
+                }
+            }
+        })
+    };
+
+    let result = thermo_raw_mzml::convert_file_with_progress(raw_path, &out_path, config, &file_counter);
+
+    watcher_done.store(true, Ordering::Relaxed);
+    watcher.join().ok();
+
+    let done_count = files_done.fetch_add(1, Ordering::Relaxed) + 1;
+    progress_tx
+        .try_send(ProgressData {
+            file_index: index,
+            file_name,
+            scans_done_in_file: scans_total_in_file,
+            scans_total_in_file,
+            files_done: done_count,
+            files_total,
+            stage: ConversionStage::Writing,
+        })
+        .ok();
+
+    let disposed = result.is_ok() && disposition != SourceDisposition::Keep;
+    let disposition_error = if disposed {
+        disposition.apply(raw_path).err()
+    } else {
+        None
+    };
+
+    ConversionResult {
+        index,
+        result: result.map_err(|e| e.to_string()),
+        disposed,
+        disposition_error,
+    }
+}
@@ -0,0 +1,144 @@
+//! Headless conversion engine shared by the GUI and the `--no-gui` CLI
+//! entry point ([`crate::cli`]).
+//!
+//! `AppState` used to build a [`thermo_raw_mzml::MzmlConfig`] and call
+//! [`conversion::spawn_conversion`] directly from its click handlers. That
+//! logic didn't depend on gpui at all, so it's pulled out here: the GUI now
+//! builds an [`EngineOptions`] from its widgets and hands it to the same
+//! `Engine` the CLI uses, with progress going to a channel either way (a
+//! polling loop for the GUI, stderr for the CLI).
+
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::conversion::{self, ConversionResult, ProgressData, SourceDisposition};
+use crate::file_list;
+
+/// Conversion settings, independent of how they were collected (GUI widgets
+/// or CLI flags).
+#[derive(Debug, Clone)]
+pub struct EngineOptions {
+    pub mz_precision: thermo_raw_mzml::Precision,
+    pub intensity_precision: thermo_raw_mzml::Precision,
+    pub compression: thermo_raw_mzml::Compression,
+    pub write_index: bool,
+    pub include_ms2: bool,
+    pub intensity_threshold: f64,
+    pub disposition: SourceDisposition,
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        Self {
+            mz_precision: thermo_raw_mzml::Precision::F64,
+            intensity_precision: thermo_raw_mzml::Precision::F32,
+            compression: thermo_raw_mzml::Compression::Zlib,
+            write_index: true,
+            include_ms2: true,
+            intensity_threshold: 0.0,
+            disposition: SourceDisposition::Keep,
+        }
+    }
+}
+
+impl EngineOptions {
+    fn to_mzml_config(&self) -> thermo_raw_mzml::MzmlConfig {
+        thermo_raw_mzml::MzmlConfig {
+            mz_precision: self.mz_precision,
+            intensity_precision: self.intensity_precision,
+            compression: self.compression,
+            write_index: self.write_index,
+            include_ms2: self.include_ms2,
+            intensity_threshold: self.intensity_threshold,
+            write_manifest: false,
+            manifest_path: None,
+            srm_mz_tolerance: None,
+            ..thermo_raw_mzml::MzmlConfig::default()
+        }
+    }
+}
+
+/// The conversion engine: fixed options plus the ability to dispatch a batch
+/// of files, either from a GUI polling loop or a blocking CLI call.
+pub struct Engine {
+    pub options: EngineOptions,
+}
+
+impl Engine {
+    pub fn new(options: EngineOptions) -> Self {
+        Self { options }
+    }
+
+    /// Open each path to read its scan count, skipping anything that isn't a
+    /// Thermo RAW file by signature. Used by the CLI, which has no GUI scan
+    /// phase to populate `n_scans` ahead of time.
+    pub fn scan_inputs(&self, paths: &[PathBuf]) -> Vec<(usize, PathBuf, u64)> {
+        paths
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| {
+                if !file_list::has_raw_signature(p) {
+                    return None;
+                }
+                let n_scans = thermo_raw::RawFile::scan_count_only(p).ok()?;
+                Some((i, p.clone(), n_scans as u64))
+            })
+            .collect()
+    }
+
+    /// Dispatch conversion of `files` on a background thread, streaming
+    /// progress over a freshly created channel. Non-blocking: callers (GUI
+    /// and CLI alike) poll or block on the returned receiver/handle as
+    /// appropriate.
+    pub fn spawn(
+        &self,
+        files: Vec<(usize, PathBuf, u64)>,
+        output_dir: PathBuf,
+        cancel: Arc<AtomicBool>,
+    ) -> (
+        std::thread::JoinHandle<Vec<ConversionResult>>,
+        Receiver<ProgressData>,
+    ) {
+        let (tx, rx): (Sender<ProgressData>, Receiver<ProgressData>) = crossbeam_channel::unbounded();
+        let config = self.options.to_mzml_config();
+        let handle = conversion::spawn_conversion(
+            files,
+            output_dir,
+            config,
+            self.options.disposition,
+            tx,
+            cancel,
+        );
+        (handle, rx)
+    }
+
+    /// Convenience for the CLI: scan, spawn, and block until done, printing
+    /// each progress update to stderr instead of driving a `Progress`
+    /// widget.
+    pub fn run_to_completion(
+        &self,
+        paths: &[PathBuf],
+        output_dir: PathBuf,
+    ) -> Vec<ConversionResult> {
+        let files = self.scan_inputs(paths);
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (handle, rx) = self.spawn(files, output_dir, cancel);
+
+        while let Ok(update) = rx.recv() {
+            eprintln!(
+                "[{}/{}] {} ({:?}): {}/{}",
+                update.files_done,
+                update.files_total,
+                update.file_name,
+                update.stage,
+                update.scans_done_in_file,
+                update.scans_total_in_file
+            );
+        }
+
+        handle.join().unwrap_or_default()
+    }
+}
@@ -4,9 +4,17 @@ use std::path::PathBuf;
 pub enum FileStatus {
     Scanning,
     Pending,
+    /// Computing a CRC-32 over the data stream (and cross-checking the
+    /// scan count) after a user-triggered "Verify" action. Distinct from
+    /// `Scanning`, which only reads the header and never touches this much
+    /// of the file.
+    Verifying,
     Converting,
     Done,
     Failed,
+    /// Extension says `.raw`, but the leading bytes don't match the Thermo
+    /// Finnigan signature (or vice versa for an extension-less pickup).
+    WrongType,
 }
 
 #[derive(Debug, Clone)]
@@ -17,6 +25,17 @@ pub struct FileEntry {
     pub n_scans: Option<u32>,
     pub status: FileStatus,
     pub error: Option<String>,
+    /// (scans converted, scans total) for this file's own conversion
+    /// progress bar, updated as `ConversionStage::Reading` updates arrive
+    /// for this file's index. `(0, 0)` until conversion starts.
+    pub scans_progress: (u64, u64),
+    /// CRC-32 over the data stream, set once a "Verify" pass completes for
+    /// this file. `None` until then.
+    pub crc32: Option<u32>,
+    /// MD5 digest of the data stream. Always `None` -- this crate has no
+    /// cryptographic hash dependency to compute one with; see
+    /// `thermo_raw::checksum` for why only CRC-32 is implemented.
+    pub md5: Option<String>,
 }
 
 impl FileEntry {
@@ -33,6 +52,9 @@ impl FileEntry {
             n_scans: None,
             status: FileStatus::Pending,
             error: None,
+            scans_progress: (0, 0),
+            crc32: None,
+            md5: None,
         }
     }
 
@@ -46,13 +68,45 @@ impl FileEntry {
         }
     }
 
+    /// Fraction complete for this file's own progress bar, or 0 when no
+    /// progress has been reported yet (e.g. still queued behind other
+    /// workers in the bounded conversion pool).
+    pub fn progress_fraction(&self) -> f32 {
+        let (done, total) = self.scans_progress;
+        if total == 0 {
+            0.0
+        } else {
+            (done as f32 / total as f32).min(1.0)
+        }
+    }
+
     pub fn status_label(&self) -> &'static str {
         match self.status {
             FileStatus::Scanning => "Scanning...",
             FileStatus::Pending => "Pending",
+            FileStatus::Verifying => "Verifying...",
             FileStatus::Converting => "Converting...",
             FileStatus::Done => "Done",
             FileStatus::Failed => "Failed",
+            FileStatus::WrongType => "Not a Thermo RAW file",
         }
     }
 }
+
+/// Check the leading bytes of a file against the Thermo Finnigan magic
+/// number, independent of its extension.
+///
+/// Reads only the first two bytes, so it's cheap to run on every candidate
+/// file before committing to a full `n_scans()` open.
+pub fn has_raw_signature(path: &std::path::Path) -> bool {
+    use std::io::Read;
+
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut magic = [0u8; 2];
+    if file.read_exact(&mut magic).is_err() {
+        return false;
+    }
+    u16::from_le_bytes(magic) == thermo_raw::version::FINNIGAN_MAGIC
+}
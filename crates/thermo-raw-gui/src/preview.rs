@@ -0,0 +1,54 @@
+//! Background computation of a quick-look TIC + representative MS1 spectrum
+//! for the file selected in the list, so users can sanity-check an
+//! acquisition before committing to a full batch conversion.
+
+use std::path::{Path, PathBuf};
+
+use thermo_raw::MsLevel;
+
+/// Everything [`crate::app::AppState::render_preview`] needs to draw a
+/// preview for one file.
+pub struct PreviewData {
+    pub path: PathBuf,
+    pub tic_rt: Vec<f64>,
+    pub tic_intensity: Vec<f64>,
+    pub spectrum_scan_number: u32,
+    pub spectrum_mz: Vec<f64>,
+    pub spectrum_intensity: Vec<f64>,
+}
+
+/// Build a preview for `path`: the full TIC, plus the centroid spectrum of
+/// the first MS1 scan at or after the midpoint of the acquisition (searching
+/// backwards from the midpoint only if none follows it; a DDA run's first
+/// scan is sometimes a near-empty lockspray/calibration scan, so the
+/// midpoint is a more representative sample than `first_scan()`).
+pub fn build_preview(path: &Path) -> Result<PreviewData, String> {
+    let raw = thermo_raw::RawFile::open_mmap(path).map_err(|e| e.to_string())?;
+
+    let tic = raw.tic();
+
+    let first = raw.first_scan();
+    let last = raw.last_scan();
+    let mid = first + (last.saturating_sub(first)) / 2;
+
+    let spectrum_scan_number = (mid..=last)
+        .chain((first..mid).rev())
+        .find(|&n| {
+            raw.scan(n)
+                .is_ok_and(|scan| scan.ms_level == MsLevel::Ms1)
+        })
+        .unwrap_or(first);
+
+    let spectrum = raw
+        .scan(spectrum_scan_number)
+        .map_err(|e| format!("Cannot read scan {spectrum_scan_number}: {e}"))?;
+
+    Ok(PreviewData {
+        path: path.to_path_buf(),
+        tic_rt: tic.rt,
+        tic_intensity: tic.intensity,
+        spectrum_scan_number,
+        spectrum_mz: spectrum.centroid_mz,
+        spectrum_intensity: spectrum.centroid_intensity,
+    })
+}
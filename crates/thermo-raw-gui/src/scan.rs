@@ -0,0 +1,71 @@
+//! Bounded-concurrency header scanning for candidate RAW files.
+//!
+//! `RawFile::scan_count_only` only needs to read the file header, but on a
+//! network drive even that can be slow, and a folder pick can hand over
+//! hundreds of candidates at once. Scanning them one at a time on a single
+//! background task would block every row behind the slowest file with no way
+//! to abort; this dispatches a bounded pool of worker threads instead and
+//! streams each result back as it lands.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crossbeam_channel::Sender;
+
+use crate::file_list;
+
+/// Outcome of scanning one candidate file's header.
+pub struct ScanResult {
+    pub path: PathBuf,
+    pub result: Result<u32, String>,
+}
+
+/// Scan file headers with concurrency capped at the number of CPUs,
+/// streaming each [`ScanResult`] over `tx` as it completes. Stops dispatching
+/// new work as soon as `cancel` is set; in-flight files still finish.
+pub fn spawn_scan(
+    paths: Vec<PathBuf>,
+    tx: Sender<ScanResult>,
+    cancel: Arc<AtomicBool>,
+) -> std::thread::JoinHandle<()> {
+    let concurrency = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+
+    std::thread::spawn(move || {
+        let (work_tx, work_rx) = crossbeam_channel::unbounded::<PathBuf>();
+        for path in paths {
+            work_tx.send(path).ok();
+        }
+        drop(work_tx);
+
+        let workers: Vec<_> = (0..concurrency)
+            .map(|_| {
+                let work_rx = work_rx.clone();
+                let tx = tx.clone();
+                let cancel = Arc::clone(&cancel);
+                std::thread::spawn(move || {
+                    while let Ok(path) = work_rx.recv() {
+                        if cancel.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        let result = scan_one(&path);
+                        tx.send(ScanResult { path, result }).ok();
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            worker.join().ok();
+        }
+    })
+}
+
+fn scan_one(path: &PathBuf) -> Result<u32, String> {
+    if !file_list::has_raw_signature(path) {
+        return Err("Not a Thermo RAW file".to_string());
+    }
+    thermo_raw::RawFile::scan_count_only(path).map_err(|e| format!("Cannot read: {e}"))
+}
@@ -0,0 +1,178 @@
+//! Persisted conversion settings.
+//!
+//! Mirrors the layered config approach common in editor tooling: a hardcoded
+//! `Settings::default()` layer, overridden by whatever's in the user's JSON
+//! file (if any field is missing there, e.g. after an upgrade adds one, the
+//! default fills the gap via `#[serde(default)]`), in turn overridable by
+//! whatever the user changes in the GUI this session.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::conversion::SourceDisposition;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub mz_precision: PrecisionSetting,
+    pub intensity_precision: PrecisionSetting,
+    pub compression: CompressionSetting,
+    pub write_index: bool,
+    pub include_ms2: bool,
+    pub intensity_threshold: f64,
+    pub disposition: DispositionSetting,
+    pub output_dir: Option<PathBuf>,
+    pub presets: Vec<Preset>,
+    pub theme: ThemePreference,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mz_precision: PrecisionSetting::F64,
+            intensity_precision: PrecisionSetting::F32,
+            compression: CompressionSetting::Zlib,
+            write_index: true,
+            include_ms2: true,
+            intensity_threshold: 0.0,
+            disposition: DispositionSetting::Keep,
+            output_dir: None,
+            presets: Vec::new(),
+            theme: ThemePreference::Auto,
+        }
+    }
+}
+
+/// Which theme to render. `Auto` re-resolves to light or dark from the OS
+/// appearance (see `crate::theme`) instead of pinning one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemePreference {
+    Light,
+    Dark,
+    Auto,
+}
+
+/// A named snapshot of the compression/indexed/MS2/threshold combination,
+/// e.g. "small archival" (high compression, indexed, MS1-only, high
+/// threshold) kept distinct from "full fidelity" so switching between them
+/// doesn't mean re-toggling every option by hand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub compression: CompressionSetting,
+    pub write_index: bool,
+    pub include_ms2: bool,
+    pub intensity_threshold: f64,
+}
+
+/// Serializable mirrors of the `thermo_raw_mzml`/`conversion` enums: those
+/// types don't derive `Serialize`/`Deserialize`, and adding that as a public
+/// dependency-crate requirement isn't ours to impose, so settings round-trip
+/// through these instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrecisionSetting {
+    F32,
+    F64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompressionSetting {
+    None,
+    Zlib,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DispositionSetting {
+    Keep,
+    Trash,
+    Archive,
+}
+
+impl From<PrecisionSetting> for thermo_raw_mzml::Precision {
+    fn from(value: PrecisionSetting) -> Self {
+        match value {
+            PrecisionSetting::F32 => thermo_raw_mzml::Precision::F32,
+            PrecisionSetting::F64 => thermo_raw_mzml::Precision::F64,
+        }
+    }
+}
+
+impl From<thermo_raw_mzml::Precision> for PrecisionSetting {
+    fn from(value: thermo_raw_mzml::Precision) -> Self {
+        match value {
+            thermo_raw_mzml::Precision::F32 => PrecisionSetting::F32,
+            thermo_raw_mzml::Precision::F64 => PrecisionSetting::F64,
+        }
+    }
+}
+
+impl From<CompressionSetting> for thermo_raw_mzml::Compression {
+    fn from(value: CompressionSetting) -> Self {
+        match value {
+            CompressionSetting::None => thermo_raw_mzml::Compression::None,
+            CompressionSetting::Zlib => thermo_raw_mzml::Compression::Zlib,
+        }
+    }
+}
+
+impl From<thermo_raw_mzml::Compression> for CompressionSetting {
+    fn from(value: thermo_raw_mzml::Compression) -> Self {
+        match value {
+            thermo_raw_mzml::Compression::None => CompressionSetting::None,
+            thermo_raw_mzml::Compression::Zlib => CompressionSetting::Zlib,
+            // Any future compression mode not yet mirrored here settles on
+            // the safest default rather than failing to load.
+            _ => CompressionSetting::Zlib,
+        }
+    }
+}
+
+impl From<DispositionSetting> for SourceDisposition {
+    fn from(value: DispositionSetting) -> Self {
+        match value {
+            DispositionSetting::Keep => SourceDisposition::Keep,
+            DispositionSetting::Trash => SourceDisposition::Trash,
+            DispositionSetting::Archive => SourceDisposition::Archive,
+        }
+    }
+}
+
+impl From<SourceDisposition> for DispositionSetting {
+    fn from(value: SourceDisposition) -> Self {
+        match value {
+            SourceDisposition::Keep => DispositionSetting::Keep,
+            SourceDisposition::Trash => DispositionSetting::Trash,
+            SourceDisposition::Archive => DispositionSetting::Archive,
+        }
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("thermo-raw-gui").join("settings.json"))
+}
+
+impl Settings {
+    /// Load settings from the OS config dir, falling back to defaults if
+    /// the file is missing, unreadable, or fails to parse.
+    pub fn load() -> Self {
+        settings_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write settings back to the OS config dir, creating the parent
+    /// directory if needed. Errors are non-fatal: a failed save just means
+    /// next launch starts from defaults (or the last successful save).
+    pub fn save(&self) {
+        let Some(path) = settings_path() else { return };
+        let Some(parent) = path.parent() else { return };
+        if std::fs::create_dir_all(parent).is_err() {
+            return;
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            std::fs::write(path, json).ok();
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Resolves a persisted [`crate::settings::ThemePreference`] to a concrete
+//! light/dark theme and applies it globally, including the "Auto" case
+//! where the app should track the OS appearance without a restart.
+
+use gpui::{App, Window};
+use gpui_component::{Theme, ThemeMode};
+
+use crate::settings::ThemePreference;
+
+/// Resolve `Auto` against the window's current OS appearance; `Light`/`Dark`
+/// pass straight through.
+fn resolve(pref: ThemePreference, window: &Window) -> ThemeMode {
+    match pref {
+        ThemePreference::Light => ThemeMode::Light,
+        ThemePreference::Dark => ThemeMode::Dark,
+        ThemePreference::Auto => {
+            if window.appearance().is_dark() {
+                ThemeMode::Dark
+            } else {
+                ThemeMode::Light
+            }
+        }
+    }
+}
+
+/// Apply `pref` as the active theme. Called on startup, whenever the user
+/// changes the picker, and whenever the OS appearance changes while `pref`
+/// is `Auto`.
+pub fn apply(pref: ThemePreference, window: &mut Window, cx: &mut App) {
+    Theme::change(resolve(pref, window), Some(window), cx);
+}
+
+/// Whether the OS appearance now disagrees with the currently active theme,
+/// so the Auto-mode poll loop in `AppState::new` only re-applies (and
+/// notifies) on an actual change instead of every tick.
+pub fn changed_since_last_apply(window: &Window, cx: &App) -> bool {
+    resolve(ThemePreference::Auto, window) != Theme::global(cx).mode
+}
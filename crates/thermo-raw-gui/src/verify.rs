@@ -0,0 +1,68 @@
+//! Bounded-concurrency integrity verification for already-scanned RAW files.
+//!
+//! Unlike [`crate::scan::spawn_scan`], which only reads the file header,
+//! this fully opens each file to CRC-32 its data stream and cross-check the
+//! header's claimed scan count against the scan index -- real I/O, so it's
+//! an explicit user-triggered pass rather than something run automatically
+//! during the initial (deliberately cheap) scan.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crossbeam_channel::Sender;
+use thermo_raw::checksum::IntegrityReport;
+
+/// Outcome of verifying one candidate file's data stream.
+pub struct VerifyResult {
+    pub path: PathBuf,
+    pub result: Result<IntegrityReport, String>,
+}
+
+/// Verify file integrity with concurrency capped at the number of CPUs,
+/// streaming each [`VerifyResult`] over `tx` as it completes. Stops
+/// dispatching new work as soon as `cancel` is set; in-flight files still
+/// finish.
+pub fn spawn_verify(
+    paths: Vec<PathBuf>,
+    tx: Sender<VerifyResult>,
+    cancel: Arc<AtomicBool>,
+) -> std::thread::JoinHandle<()> {
+    let concurrency = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+
+    std::thread::spawn(move || {
+        let (work_tx, work_rx) = crossbeam_channel::unbounded::<PathBuf>();
+        for path in paths {
+            work_tx.send(path).ok();
+        }
+        drop(work_tx);
+
+        let workers: Vec<_> = (0..concurrency)
+            .map(|_| {
+                let work_rx = work_rx.clone();
+                let tx = tx.clone();
+                let cancel = Arc::clone(&cancel);
+                std::thread::spawn(move || {
+                    while let Ok(path) = work_rx.recv() {
+                        if cancel.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        let result = verify_one(&path);
+                        tx.send(VerifyResult { path, result }).ok();
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            worker.join().ok();
+        }
+    })
+}
+
+fn verify_one(path: &PathBuf) -> Result<IntegrityReport, String> {
+    let raw = thermo_raw::RawFile::open_mmap(path).map_err(|e| format!("Cannot read: {e}"))?;
+    Ok(raw.integrity_report())
+}
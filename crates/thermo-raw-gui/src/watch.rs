@@ -0,0 +1,91 @@
+//! Watch-folder support: notice new RAW files dropped into a directory and
+//! wait for them to stop growing before handing them to the scan pipeline.
+//!
+//! Acquisition software writes RAW files incrementally, so a bare
+//! filesystem-event callback would hand over a half-written file. Instead we
+//! track each candidate's size across polls and only surface it once it has
+//! been unchanged for `stable_for`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+pub struct WatchHandle {
+    // Kept alive only to keep the OS watch registered; never read directly.
+    _watcher: RecommendedWatcher,
+    rx: Receiver<notify::Result<notify::Event>>,
+}
+
+/// Start watching `dir` (non-recursively) for filesystem events.
+pub fn watch_folder(dir: &Path) -> notify::Result<WatchHandle> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        tx.send(event).ok();
+    })?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok(WatchHandle {
+        _watcher: watcher,
+        rx,
+    })
+}
+
+/// Tracks candidate `.raw` files seen via filesystem events until their size
+/// stops changing, to avoid scanning a file still being written.
+#[derive(Default)]
+pub struct DebounceTracker {
+    pending: HashMap<PathBuf, PendingFile>,
+}
+
+struct PendingFile {
+    size: u64,
+    last_changed: Instant,
+}
+
+impl DebounceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drain pending filesystem events from `handle`, updating (or starting)
+    /// the debounce timer for any `.raw` path whose size changed.
+    pub fn observe(&mut self, handle: &WatchHandle) {
+        while let Ok(event) = handle.rx.try_recv() {
+            let Ok(event) = event else { continue };
+            for path in event.paths {
+                if !path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("raw"))
+                {
+                    continue;
+                }
+                let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                let entry = self.pending.entry(path).or_insert(PendingFile {
+                    size,
+                    last_changed: Instant::now(),
+                });
+                if entry.size != size {
+                    entry.size = size;
+                    entry.last_changed = Instant::now();
+                }
+            }
+        }
+    }
+
+    /// Remove and return paths that have been unchanged for at least
+    /// `stable_for`, i.e. are ready to be picked up.
+    pub fn take_stable(&mut self, stable_for: Duration) -> Vec<PathBuf> {
+        let ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, f)| f.last_changed.elapsed() >= stable_for)
+            .map(|(p, _)| p.clone())
+            .collect();
+        for path in &ready {
+            self.pending.remove(path);
+        }
+        ready
+    }
+}
@@ -0,0 +1,151 @@
+//! Columnar spectrum/chromatogram export backend, alongside the mzML/XML
+//! writer in [`crate::writer`].
+//!
+//! Builds the same per-spectrum model [`crate::writer`] renders into XML as
+//! row-oriented structs that mirror the columnar table an Arrow IPC/Parquet
+//! writer would emit: scalar columns (`index`, `id`, `ms_level`,
+//! `retention_time`, `precursor_mz`, `charge`, `collision_energy`,
+//! `activation_type`) plus `mz`/`intensity` columns that map onto Arrow
+//! `List<Float64>` columns. Chromatograms (TIC/BPC) get their own
+//! time+intensity tables.
+//!
+//! [`write_arrow`] and [`write_parquet`] are the intended entry points
+//! parallel to [`crate::writer::write_mzml`], but actually framing these rows
+//! as Arrow IPC or Parquet bytes needs the `arrow`/`parquet` crates, and this
+//! tree has no `Cargo.toml` to declare them as dependencies in. They're left
+//! unimplemented (returning [`MzmlError::Conversion`]) rather than
+//! hand-rolling a from-scratch reimplementation of either wire format; the
+//! row-building side below is fully wired up so a build that does add those
+//! crates only needs to fill in the two writer bodies.
+
+use crate::MzmlError;
+use std::io::Write;
+use thermo_raw::RawFile;
+
+/// One row of the spectrum columnar table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectrumRow {
+    pub index: usize,
+    pub id: String,
+    pub ms_level: u8,
+    pub retention_time: f64,
+    pub precursor_mz: Option<f64>,
+    pub charge: Option<i32>,
+    pub collision_energy: Option<f64>,
+    pub activation_type: Option<String>,
+    /// Maps onto an Arrow `List<Float64>` column.
+    pub mz: Vec<f64>,
+    /// Maps onto an Arrow `List<Float64>` column.
+    pub intensity: Vec<f64>,
+}
+
+/// A chromatogram table (TIC or BPC): one row per data point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChromatogramTable {
+    pub id: String,
+    pub time: Vec<f64>,
+    pub intensity: Vec<f64>,
+}
+
+/// Build one [`SpectrumRow`] per scan number in `scan_numbers`, skipping
+/// scans that fail to decode (matching the XML writer's own filtering;
+/// there's no XML-style "empty spectrum" placeholder concept in a columnar
+/// table).
+pub fn build_spectrum_rows(raw: &RawFile, scan_numbers: &[u32]) -> Vec<SpectrumRow> {
+    scan_numbers
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &scan_num)| {
+            let scan = raw.scan(scan_num).ok()?;
+            Some(SpectrumRow {
+                index,
+                id: format!("scan={}", scan_num),
+                ms_level: crate::cv::ms_level_as_u8(&scan.ms_level),
+                retention_time: scan.rt,
+                precursor_mz: scan.precursor.as_ref().map(|p| p.mz),
+                charge: scan.precursor.as_ref().and_then(|p| p.charge),
+                collision_energy: scan.precursor.as_ref().and_then(|p| p.collision_energy),
+                activation_type: scan.precursor.as_ref().and_then(|p| p.activation_type.clone()),
+                mz: scan.centroid_mz.clone(),
+                intensity: scan.centroid_intensity.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Build the TIC and BPC chromatogram tables for `raw`.
+pub fn build_chromatogram_tables(raw: &RawFile) -> Vec<ChromatogramTable> {
+    let tic = raw.tic();
+    let bpc = raw.bpc();
+    vec![
+        ChromatogramTable {
+            id: "TIC".to_string(),
+            time: tic.rt,
+            intensity: tic.intensity,
+        },
+        ChromatogramTable {
+            id: "BPC".to_string(),
+            time: bpc.rt,
+            intensity: bpc.intensity,
+        },
+    ]
+}
+
+/// Write `rows`/`chromatograms` as an Arrow IPC file.
+///
+/// Not implemented: see the module-level doc for why (no `arrow` dependency
+/// available in this tree).
+pub fn write_arrow<W: Write>(
+    _rows: &[SpectrumRow],
+    _chromatograms: &[ChromatogramTable],
+    _output: W,
+) -> Result<(), MzmlError> {
+    Err(MzmlError::Conversion(
+        "Arrow IPC export requires the `arrow` crate, which this build does not depend on"
+            .to_string(),
+    ))
+}
+
+/// Write `rows`/`chromatograms` as a Parquet file.
+///
+/// Not implemented: see the module-level doc for why (no `parquet`
+/// dependency available in this tree).
+pub fn write_parquet<W: Write>(
+    _rows: &[SpectrumRow],
+    _chromatograms: &[ChromatogramTable],
+    _output: W,
+) -> Result<(), MzmlError> {
+    Err(MzmlError::Conversion(
+        "Parquet export requires the `parquet` crate, which this build does not depend on"
+            .to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_arrow_reports_missing_dependency() {
+        let rows = vec![SpectrumRow {
+            index: 0,
+            id: "scan=1".to_string(),
+            ms_level: 1,
+            retention_time: 0.1,
+            precursor_mz: None,
+            charge: None,
+            collision_energy: None,
+            activation_type: None,
+            mz: vec![100.0],
+            intensity: vec![1000.0],
+        }];
+        let err = write_arrow(&rows, &[], Vec::new()).unwrap_err();
+        assert!(matches!(err, MzmlError::Conversion(_)));
+    }
+
+    #[test]
+    fn write_parquet_reports_missing_dependency() {
+        let err = write_parquet(&[], &[], Vec::new()).unwrap_err();
+        assert!(matches!(err, MzmlError::Conversion(_)));
+    }
+}
@@ -3,46 +3,430 @@
 //! Encodes numeric arrays through the standard mzML pipeline:
 //! `f64 array -> precision cast (f32/f64) -> LE bytes -> compress -> base64`
 
-use crate::{Compression, Precision};
+use crate::numpress;
+use crate::zstd_codec;
+use crate::{Compression, MzmlError, Precision};
+use base64::write::EncoderWriter;
 use base64::Engine;
 use flate2::write::ZlibEncoder;
 use std::io::Write;
 
+/// Reusable scratch space for encoding binary data arrays, so a long-running
+/// conversion doesn't allocate a fresh LE-byte buffer, compression buffer,
+/// and base64 `String` for every m/z/intensity array of every spectrum.
+/// Create one per conversion (not per spectrum) and pass it through
+/// [`encode_array_resolved_scratch`]; each call clears and reuses the same
+/// three buffers instead of returning new allocations.
+#[derive(Debug, Default)]
+pub struct ScratchBuffers {
+    /// Little-endian-packed `f32`/`f64` bytes, pre-compression.
+    raw: Vec<u8>,
+    /// zlib- or zstd-compressed (or numpress-then-zlib) bytes.
+    compressed: Vec<u8>,
+    /// Final base64 text, borrowed out by [`encode_array_resolved_scratch`].
+    base64: String,
+}
+
+impl ScratchBuffers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 /// Encode a floating-point array to a base64 string for mzML binary data.
 ///
-/// The pipeline is:
-/// 1. Cast to target precision (f32 or f64)
-/// 2. Convert to little-endian bytes
-/// 3. Optionally compress with zlib
-/// 4. Encode as base64
+/// Thin wrapper around [`encode_array_to_writer`] for callers that just want
+/// a `String` (e.g. small arrays, or call sites not yet on the streaming
+/// path).
 pub fn encode_array(data: &[f64], precision: Precision, compression: Compression) -> String {
-    let raw_bytes = match precision {
-        Precision::F64 => {
-            let mut buf = Vec::with_capacity(data.len() * 8);
-            for &val in data {
-                buf.extend_from_slice(&val.to_le_bytes());
+    let mut buf = Vec::new();
+    encode_array_to_writer(data, precision, compression, &mut buf).expect("in-memory write failed");
+    String::from_utf8(buf).expect("base64 output is always valid UTF-8")
+}
+
+/// Encode a floating-point array directly into `out`, avoiding the
+/// intermediate LE-byte buffer, compressed buffer, and base64 `String` that
+/// [`encode_array`] would otherwise allocate.
+///
+/// The pipeline is:
+/// 1. Cast to target precision (f32 or f64) and feed little-endian bytes
+///    straight into the compressor.
+/// 2. Optionally zlib-compress, writing straight into the base64 encoder.
+/// 3. Base64-encode into `out`.
+///
+/// MS-Numpress schemes need the array's max value up front to pick a scale
+/// factor, so they can't be streamed the same way; for those, this still
+/// computes the encoding once and writes the result in a single call.
+pub fn encode_array_to_writer(
+    data: &[f64],
+    precision: Precision,
+    compression: Compression,
+    out: &mut impl Write,
+) -> Result<(), MzmlError> {
+    match compression {
+        Compression::NumpressLinear | Compression::NumpressPic | Compression::NumpressSlof => {
+            let compressed = match compression {
+                Compression::NumpressLinear => numpress::encode_linear(data),
+                Compression::NumpressPic => numpress::encode_pic(data),
+                Compression::NumpressSlof => numpress::encode_slof(data),
+                _ => unreachable!(),
+            };
+            let mut b64 = EncoderWriter::new(out, &base64::engine::general_purpose::STANDARD);
+            b64.write_all(&compressed)?;
+            b64.finish()?;
+            Ok(())
+        }
+        Compression::NumpressLinearZlib
+        | Compression::NumpressPicZlib
+        | Compression::NumpressSlofZlib => {
+            let numpressed = match compression {
+                Compression::NumpressLinearZlib => numpress::encode_linear(data),
+                Compression::NumpressPicZlib => numpress::encode_pic(data),
+                Compression::NumpressSlofZlib => numpress::encode_slof(data),
+                _ => unreachable!(),
+            };
+            let mut b64 = EncoderWriter::new(out, &base64::engine::general_purpose::STANDARD);
+            let mut zlib = ZlibEncoder::new(&mut b64, flate2::Compression::default());
+            zlib.write_all(&numpressed)?;
+            zlib.finish()?;
+            b64.finish()?;
+            Ok(())
+        }
+        Compression::None => {
+            let mut b64 = EncoderWriter::new(out, &base64::engine::general_purpose::STANDARD);
+            write_le_bytes(data, precision, &mut b64)?;
+            b64.finish()?;
+            Ok(())
+        }
+        Compression::Zlib => {
+            let mut b64 = EncoderWriter::new(out, &base64::engine::general_purpose::STANDARD);
+            let mut zlib = ZlibEncoder::new(&mut b64, flate2::Compression::default());
+            write_le_bytes(data, precision, &mut zlib)?;
+            zlib.finish()?;
+            b64.finish()?;
+            Ok(())
+        }
+        Compression::Zstd => {
+            // zstd_codec frames the whole buffer up front, so there's no
+            // streaming win here; build the LE bytes, frame them, base64 the result.
+            let mut raw = Vec::new();
+            write_le_bytes(data, precision, &mut raw)?;
+            let compressed = zstd_codec::encode(&raw);
+            let mut b64 = EncoderWriter::new(out, &base64::engine::general_purpose::STANDARD);
+            b64.write_all(&compressed)?;
+            b64.finish()?;
+            Ok(())
+        }
+        Compression::Auto => Err(MzmlError::Conversion(
+            "Compression::Auto must be resolved via encode_array_auto before reaching the writer pipeline".to_string(),
+        )),
+    }
+}
+
+/// Like [`encode_array`], but for the pure (non-zlib-combined) MS-Numpress
+/// schemes, falls back to plain zlib when numpress would make the array
+/// *larger* than zlib would -- this happens on data that doesn't fit
+/// numpress's assumptions (e.g. non-monotonic or highly irregular arrays).
+/// Returns the compression actually used alongside the encoded string, since
+/// callers need it to write a matching `cvParam`.
+pub fn encode_array_resolved(
+    data: &[f64],
+    precision: Precision,
+    compression: Compression,
+) -> (String, Compression) {
+    let numpress = match compression {
+        Compression::NumpressLinear => Some(numpress::encode_linear(data)),
+        Compression::NumpressPic => Some(numpress::encode_pic(data)),
+        Compression::NumpressSlof => Some(numpress::encode_slof(data)),
+        _ => None,
+    };
+
+    let Some(numpress) = numpress else {
+        return (encode_array(data, precision, compression), compression);
+    };
+
+    let mut raw = Vec::new();
+    write_le_bytes(data, precision, &mut raw).expect("in-memory write failed");
+    let mut zlib_encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    zlib_encoder
+        .write_all(&raw)
+        .expect("in-memory write failed");
+    let zlibbed = zlib_encoder.finish().expect("in-memory write failed");
+
+    if numpress.len() > zlibbed.len() {
+        (
+            base64::engine::general_purpose::STANDARD.encode(&zlibbed),
+            Compression::Zlib,
+        )
+    } else {
+        (
+            base64::engine::general_purpose::STANDARD.encode(&numpress),
+            compression,
+        )
+    }
+}
+
+/// Like [`encode_array_resolved`], but writes through `scratch` instead of
+/// allocating a fresh LE-byte buffer, compression buffer, and base64
+/// `String` on every call. Returns a `&str` borrowed from `scratch`'s base64
+/// buffer, valid until the next call that reuses `scratch`.
+pub fn encode_array_resolved_scratch<'s>(
+    data: &[f64],
+    precision: Precision,
+    compression: Compression,
+    scratch: &'s mut ScratchBuffers,
+) -> Result<(&'s str, Compression), MzmlError> {
+    let numpress = match compression {
+        Compression::NumpressLinear => Some(numpress::encode_linear(data)),
+        Compression::NumpressPic => Some(numpress::encode_pic(data)),
+        Compression::NumpressSlof => Some(numpress::encode_slof(data)),
+        _ => None,
+    };
+
+    let Some(numpress) = numpress else {
+        return encode_array_to_scratch(data, precision, compression, scratch)
+            .map(|s| (s, compression));
+    };
+
+    scratch.raw.clear();
+    write_le_bytes(data, precision, &mut scratch.raw).expect("in-memory write failed");
+    scratch.compressed.clear();
+    {
+        let mut zlib_encoder = ZlibEncoder::new(&mut scratch.compressed, flate2::Compression::default());
+        zlib_encoder
+            .write_all(&scratch.raw)
+            .expect("in-memory write failed");
+        zlib_encoder.finish().expect("in-memory write failed");
+    }
+
+    scratch.base64.clear();
+    if numpress.len() > scratch.compressed.len() {
+        base64::engine::general_purpose::STANDARD.encode_string(&scratch.compressed, &mut scratch.base64);
+        Ok((scratch.base64.as_str(), Compression::Zlib))
+    } else {
+        base64::engine::general_purpose::STANDARD.encode_string(&numpress, &mut scratch.base64);
+        Ok((scratch.base64.as_str(), compression))
+    }
+}
+
+/// Core of [`encode_array_resolved_scratch`] for everything except the
+/// "maybe fall back to zlib" numpress schemes: packs/compresses `data`
+/// through `scratch.raw`/`scratch.compressed` and base64-encodes the result
+/// into `scratch.base64`, reusing all three buffers' existing capacity.
+fn encode_array_to_scratch<'s>(
+    data: &[f64],
+    precision: Precision,
+    compression: Compression,
+    scratch: &'s mut ScratchBuffers,
+) -> Result<&'s str, MzmlError> {
+    use base64::engine::general_purpose::STANDARD;
+
+    scratch.base64.clear();
+
+    match compression {
+        Compression::NumpressLinear | Compression::NumpressPic | Compression::NumpressSlof => {
+            let compressed = match compression {
+                Compression::NumpressLinear => numpress::encode_linear(data),
+                Compression::NumpressPic => numpress::encode_pic(data),
+                Compression::NumpressSlof => numpress::encode_slof(data),
+                _ => unreachable!(),
+            };
+            STANDARD.encode_string(&compressed, &mut scratch.base64);
+        }
+        Compression::NumpressLinearZlib
+        | Compression::NumpressPicZlib
+        | Compression::NumpressSlofZlib => {
+            let numpressed = match compression {
+                Compression::NumpressLinearZlib => numpress::encode_linear(data),
+                Compression::NumpressPicZlib => numpress::encode_pic(data),
+                Compression::NumpressSlofZlib => numpress::encode_slof(data),
+                _ => unreachable!(),
+            };
+            scratch.compressed.clear();
+            {
+                let mut zlib = ZlibEncoder::new(&mut scratch.compressed, flate2::Compression::default());
+                zlib.write_all(&numpressed).expect("in-memory write failed");
+                zlib.finish().expect("in-memory write failed");
             }
-            buf
+            STANDARD.encode_string(&scratch.compressed, &mut scratch.base64);
         }
-        Precision::F32 => {
-            let mut buf = Vec::with_capacity(data.len() * 4);
-            for &val in data {
-                buf.extend_from_slice(&(val as f32).to_le_bytes());
+        Compression::None => {
+            scratch.raw.clear();
+            write_le_bytes(data, precision, &mut scratch.raw).expect("in-memory write failed");
+            STANDARD.encode_string(&scratch.raw, &mut scratch.base64);
+        }
+        Compression::Zlib => {
+            scratch.raw.clear();
+            write_le_bytes(data, precision, &mut scratch.raw).expect("in-memory write failed");
+            scratch.compressed.clear();
+            {
+                let mut zlib = ZlibEncoder::new(&mut scratch.compressed, flate2::Compression::default());
+                zlib.write_all(&scratch.raw).expect("in-memory write failed");
+                zlib.finish().expect("in-memory write failed");
             }
-            buf
+            STANDARD.encode_string(&scratch.compressed, &mut scratch.base64);
         }
+        Compression::Zstd => {
+            scratch.raw.clear();
+            write_le_bytes(data, precision, &mut scratch.raw).expect("in-memory write failed");
+            scratch.compressed.clear();
+            scratch.compressed.extend_from_slice(&zstd_codec::encode(&scratch.raw));
+            STANDARD.encode_string(&scratch.compressed, &mut scratch.base64);
+        }
+        Compression::Auto => {
+            return Err(MzmlError::Conversion(
+                "Compression::Auto must be resolved via encode_array_auto before reaching the writer pipeline".to_string(),
+            ))
+        }
+    }
+
+    Ok(scratch.base64.as_str())
+}
+
+/// Trial-encode `data` with every codec [`Compression::Auto`] considers --
+/// none, zlib, and numpress-linear-plus-zlib (for m/z-like arrays) or
+/// numpress-slof-plus-zlib (for intensity arrays, pass `is_intensity: true`)
+/// -- and keep whichever produces the smallest compressed byte length.
+/// Unlike [`encode_array_resolved`]'s fallback (which only ever chooses
+/// between one numpress scheme and zlib), this always compares all three,
+/// since for some arrays plain zlib beats both.
+pub fn encode_array_auto(data: &[f64], precision: Precision, is_intensity: bool) -> (String, Compression) {
+    let mut raw = Vec::new();
+    write_le_bytes(data, precision, &mut raw).expect("in-memory write failed");
+
+    let zlib_of = |bytes: &[u8]| -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(bytes).expect("in-memory write failed");
+        encoder.finish().expect("in-memory write failed")
     };
 
-    let compressed = match compression {
-        Compression::Zlib => {
-            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
-            encoder.write_all(&raw_bytes).expect("zlib write failed");
-            encoder.finish().expect("zlib finish failed")
+    let zlibbed = zlib_of(&raw);
+
+    let (numpressed, numpress_compression) = if is_intensity {
+        (numpress::encode_slof(data), Compression::NumpressSlofZlib)
+    } else {
+        (numpress::encode_linear(data), Compression::NumpressLinearZlib)
+    };
+    let numpress_zlibbed = zlib_of(&numpressed);
+
+    let candidates = [
+        (raw, Compression::None),
+        (zlibbed, Compression::Zlib),
+        (numpress_zlibbed, numpress_compression),
+    ];
+    let (best_bytes, best_compression) = candidates
+        .into_iter()
+        .min_by_key(|(bytes, _)| bytes.len())
+        .expect("candidates array is non-empty");
+
+    (
+        base64::engine::general_purpose::STANDARD.encode(&best_bytes),
+        best_compression,
+    )
+}
+
+/// Invert [`encode_array`]: base64-decode, optionally zlib-inflate (or
+/// undo numpress), and reinterpret the resulting bytes as `f32`/`f64` LE
+/// values. Used to validate our own output and to diff against reference
+/// mzML produced by other tools.
+pub fn decode_array(
+    encoded: &str,
+    precision: Precision,
+    compression: Compression,
+) -> Result<Vec<f64>, MzmlError> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| MzmlError::Conversion(format!("invalid base64: {e}")))?;
+
+    match compression {
+        Compression::NumpressLinear => return numpress::decode_linear(&bytes),
+        Compression::NumpressPic => return numpress::decode_pic(&bytes),
+        Compression::NumpressSlof => return numpress::decode_slof(&bytes),
+        Compression::NumpressLinearZlib => return numpress::decode_linear(&decode_zlib(&bytes)?),
+        Compression::NumpressPicZlib => return numpress::decode_pic(&decode_zlib(&bytes)?),
+        Compression::NumpressSlofZlib => return numpress::decode_slof(&decode_zlib(&bytes)?),
+        Compression::Auto => {
+            return Err(MzmlError::Conversion(
+                "cannot decode an array encoded with Compression::Auto; decode with the concrete codec recorded in its cvParam instead".to_string(),
+            ))
         }
-        Compression::None => raw_bytes,
+        Compression::None | Compression::Zlib | Compression::Zstd => {}
+    }
+
+    let decompressed = match compression {
+        Compression::Zlib => decode_zlib(&bytes)?,
+        Compression::Zstd => zstd_codec::decode(&bytes)?,
+        _ => bytes,
     };
 
-    base64::engine::general_purpose::STANDARD.encode(&compressed)
+    let elem_size = match precision {
+        Precision::F64 => 8,
+        Precision::F32 => 4,
+    };
+    if decompressed.len() % elem_size != 0 {
+        return Err(MzmlError::Conversion(format!(
+            "decoded array length {} is not a multiple of element size {}",
+            decompressed.len(),
+            elem_size
+        )));
+    }
+
+    let values = match precision {
+        Precision::F64 => decompressed
+            .chunks_exact(8)
+            .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+            .collect(),
+        Precision::F32 => decompressed
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes(c.try_into().unwrap()) as f64)
+            .collect(),
+    };
+    Ok(values)
+}
+
+/// Inflate a zlib-or-raw-deflate stream. mzML producers are inconsistent
+/// about whether the 2-byte zlib header (RFC 1950) is present; detect it via
+/// the standard `(CMF<<8 | FLG) % 31 == 0` check with `CM == 8`, and fall
+/// back to raw DEFLATE (RFC 1951) when it's absent.
+fn decode_zlib(bytes: &[u8]) -> Result<Vec<u8>, MzmlError> {
+    use flate2::read::{DeflateDecoder, ZlibDecoder};
+    use std::io::Read;
+
+    let has_zlib_header = bytes.len() >= 2 && {
+        let cmf = bytes[0] as u16;
+        let flg = bytes[1] as u16;
+        (cmf & 0x0F) == 8 && (cmf << 8 | flg) % 31 == 0
+    };
+
+    let mut out = Vec::new();
+    if has_zlib_header {
+        ZlibDecoder::new(bytes)
+            .read_to_end(&mut out)
+            .map_err(|e| MzmlError::Conversion(format!("zlib inflate failed: {e}")))?;
+    } else {
+        DeflateDecoder::new(bytes)
+            .read_to_end(&mut out)
+            .map_err(|e| MzmlError::Conversion(format!("raw deflate inflate failed: {e}")))?;
+    }
+    Ok(out)
+}
+
+/// Feed `data` as little-endian bytes at the requested precision directly
+/// into `w`, without materializing an intermediate byte buffer.
+fn write_le_bytes(data: &[f64], precision: Precision, w: &mut impl Write) -> std::io::Result<()> {
+    match precision {
+        Precision::F64 => {
+            for &val in data {
+                w.write_all(&val.to_le_bytes())?;
+            }
+        }
+        Precision::F32 => {
+            for &val in data {
+                w.write_all(&(val as f32).to_le_bytes())?;
+            }
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -102,4 +486,284 @@ mod tests {
             .unwrap();
         assert!(decoded.is_empty());
     }
+
+    #[test]
+    fn test_encode_array_to_writer_matches_encode_array() {
+        let data = [1.5, 2.5, 3.5, 4.5, 5.5];
+        for compression in [Compression::None, Compression::Zlib] {
+            let via_string = encode_array(&data, Precision::F64, compression);
+            let mut via_writer = Vec::new();
+            encode_array_to_writer(&data, Precision::F64, compression, &mut via_writer).unwrap();
+            assert_eq!(via_string.as_bytes(), via_writer.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_encode_numpress_linear_round_trips() {
+        let data = [400.05, 400.10, 400.15, 400.20];
+        let encoded = encode_array(&data, Precision::F64, Compression::NumpressLinear);
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&encoded)
+            .unwrap();
+        let decoded = crate::numpress::decode_linear(&bytes).unwrap();
+        for (a, b) in data.iter().zip(decoded.iter()) {
+            assert!((a - b).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_encode_numpress_pic_round_trips() {
+        let data = [0.0, 500.0, 123456.0];
+        let encoded = encode_array(&data, Precision::F32, Compression::NumpressPic);
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&encoded)
+            .unwrap();
+        let decoded = crate::numpress::decode_pic(&bytes).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_encode_numpress_pic_round_trips_high_bit_leading_nibble_values() {
+        // Regression test: `0.0, 500.0, 123456.0` above all happen to have a
+        // most-significant retained nibble below 0x8, which used to mask a
+        // sign-inference bug in the variable-length integer codec. These
+        // values don't.
+        let data = [8.0, 128.0, 65535.0, 999999.0];
+        let encoded = encode_array(&data, Precision::F32, Compression::NumpressPic);
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&encoded)
+            .unwrap();
+        let decoded = crate::numpress::decode_pic(&bytes).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_decode_array_round_trips_none_and_zlib() {
+        let data = [1.5, 2.5, 3.5, 4.5, 5.5];
+        for compression in [Compression::None, Compression::Zlib] {
+            for precision in [Precision::F64, Precision::F32] {
+                let encoded = encode_array(&data, precision, compression);
+                let decoded = decode_array(&encoded, precision, compression).unwrap();
+                assert_eq!(decoded.len(), data.len());
+                for (a, b) in data.iter().zip(decoded.iter()) {
+                    assert!((a - b).abs() < 1e-4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_array_numpress_round_trips() {
+        let data = [100.0, 200.0, 300.5];
+        let encoded = encode_array(&data, Precision::F64, Compression::NumpressLinear);
+        let decoded = decode_array(&encoded, Precision::F64, Compression::NumpressLinear).unwrap();
+        for (a, b) in data.iter().zip(decoded.iter()) {
+            assert!((a - b).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_decode_array_rejects_truncated_length() {
+        // 3 bytes can't be a whole number of f64s.
+        let encoded = base64::engine::general_purpose::STANDARD.encode([1u8, 2, 3]);
+        let err = decode_array(&encoded, Precision::F64, Compression::None).unwrap_err();
+        assert!(matches!(err, MzmlError::Conversion(_)));
+    }
+
+    #[test]
+    fn test_decode_array_rejects_invalid_base64() {
+        let err = decode_array("not valid base64!!", Precision::F64, Compression::None).unwrap_err();
+        assert!(matches!(err, MzmlError::Conversion(_)));
+    }
+
+    #[test]
+    fn test_decode_array_round_trips_zstd() {
+        let data = [1.5, 2.5, 3.5, 4.5, 5.5];
+        for precision in [Precision::F64, Precision::F32] {
+            let encoded = encode_array(&data, precision, Compression::Zstd);
+            let decoded = decode_array(&encoded, precision, Compression::Zstd).unwrap();
+            assert_eq!(decoded.len(), data.len());
+            for (a, b) in data.iter().zip(decoded.iter()) {
+                assert!((a - b).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_array_round_trips_numpress_linear_zlib() {
+        let data = [400.05, 400.10, 400.15, 400.20];
+        let encoded = encode_array(&data, Precision::F64, Compression::NumpressLinearZlib);
+        let decoded = decode_array(&encoded, Precision::F64, Compression::NumpressLinearZlib).unwrap();
+        for (a, b) in data.iter().zip(decoded.iter()) {
+            assert!((a - b).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_decode_array_round_trips_numpress_pic_zlib_high_bit_intensity_values() {
+        // Re-verification after chunk2-1: these intensity values' most
+        // significant retained nibble is >= 0x8, which used to decode with
+        // the wrong sign.
+        let data = [8.0, 128.0, 65535.0, 999999.0];
+        let encoded = encode_array(&data, Precision::F64, Compression::NumpressPicZlib);
+        let decoded = decode_array(&encoded, Precision::F64, Compression::NumpressPicZlib).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_decode_array_round_trips_numpress_slof_zlib() {
+        let data = [100.0, 5000.0, 123456.0];
+        let encoded = encode_array(&data, Precision::F64, Compression::NumpressSlofZlib);
+        let decoded = decode_array(&encoded, Precision::F64, Compression::NumpressSlofZlib).unwrap();
+        for (a, b) in data.iter().zip(decoded.iter()) {
+            assert!((a - b).abs() / a < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_encode_array_to_writer_matches_encode_array_zstd() {
+        let data = [1.5, 2.5, 3.5, 4.5, 5.5];
+        let via_string = encode_array(&data, Precision::F64, Compression::Zstd);
+        let mut via_writer = Vec::new();
+        encode_array_to_writer(&data, Precision::F64, Compression::Zstd, &mut via_writer).unwrap();
+        assert_eq!(via_string.as_bytes(), via_writer.as_slice());
+    }
+
+    #[test]
+    fn test_encode_array_resolved_keeps_numpress_when_it_wins() {
+        // A smooth, monotonically increasing m/z-like array is exactly the
+        // case numpress linear prediction is designed for.
+        let data: Vec<f64> = (0..200).map(|i| 400.0 + i as f64 * 0.01).collect();
+        let (encoded, used) =
+            encode_array_resolved(&data, Precision::F64, Compression::NumpressLinear);
+        assert_eq!(used, Compression::NumpressLinear);
+        let decoded = decode_array(&encoded, Precision::F64, used).unwrap();
+        for (a, b) in data.iter().zip(decoded.iter()) {
+            assert!((a - b).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_encode_array_resolved_pic_round_trips_high_bit_intensity_values() {
+        // Intensity arrays routinely contain values whose most significant
+        // retained nibble is >= 0x8 (e.g. 999999.0); this used to decode as
+        // negative due to the chunk2-1 sign-inference bug.
+        let data = [8.0, 128.0, 65535.0, 999999.0];
+        let (encoded, used) =
+            encode_array_resolved(&data, Precision::F64, Compression::NumpressPic);
+        assert_eq!(used, Compression::NumpressPic);
+        let decoded = decode_array(&encoded, Precision::F64, used).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_encode_array_resolved_falls_back_to_zlib_when_numpress_expands() {
+        // A single value can't benefit from numpress's 8-byte scale-factor
+        // header and variable-length coding; zlib should win instead.
+        let data = [42.0];
+        let (encoded, used) =
+            encode_array_resolved(&data, Precision::F64, Compression::NumpressLinear);
+        assert_eq!(used, Compression::Zlib);
+        let decoded = decode_array(&encoded, Precision::F64, used).unwrap();
+        assert!((decoded[0] - 42.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_encode_array_resolved_passes_through_non_numpress_compression() {
+        let data = [1.0, 2.0, 3.0];
+        let (encoded, used) = encode_array_resolved(&data, Precision::F64, Compression::Zlib);
+        assert_eq!(used, Compression::Zlib);
+        assert_eq!(encoded, encode_array(&data, Precision::F64, Compression::Zlib));
+    }
+
+    #[test]
+    fn test_encode_array_resolved_scratch_matches_non_scratch() {
+        let data = [1.5, 2.5, 3.5, 4.5, 5.5];
+        let mut scratch = ScratchBuffers::new();
+        for compression in [
+            Compression::None,
+            Compression::Zlib,
+            Compression::Zstd,
+            Compression::NumpressLinear,
+            Compression::NumpressLinearZlib,
+        ] {
+            let (expected, expected_used) = encode_array_resolved(&data, Precision::F64, compression);
+            let (got, got_used) =
+                encode_array_resolved_scratch(&data, Precision::F64, compression, &mut scratch)
+                    .unwrap();
+            assert_eq!(got, expected);
+            assert_eq!(got_used, expected_used);
+        }
+    }
+
+    #[test]
+    fn test_encode_array_resolved_scratch_reuses_buffer_capacity() {
+        // Warm up the buffers with a large-ish array, then encode a small one;
+        // the scratch buffers should keep their capacity instead of shrinking
+        // back down, confirming they're reused rather than replaced.
+        let warm_up: Vec<f64> = (0..10_000).map(|i| i as f64 * 0.1).collect();
+        let mut scratch = ScratchBuffers::new();
+        encode_array_resolved_scratch(&warm_up, Precision::F64, Compression::Zlib, &mut scratch)
+            .unwrap();
+        let warmed_capacity = scratch.base64.capacity();
+        assert!(warmed_capacity > 0);
+
+        let small = [1.0, 2.0, 3.0];
+        encode_array_resolved_scratch(&small, Precision::F64, Compression::Zlib, &mut scratch)
+            .unwrap();
+        assert_eq!(scratch.base64.capacity(), warmed_capacity);
+    }
+
+    #[test]
+    fn test_encode_array_auto_picks_numpress_for_smooth_mz_array() {
+        let data: Vec<f64> = (0..2000).map(|i| 100.0 + i as f64 * 0.01).collect();
+        let (encoded, used) = encode_array_auto(&data, Precision::F64, false);
+        assert_eq!(used, Compression::NumpressLinearZlib);
+        let decoded = decode_array(&encoded, Precision::F64, used).unwrap();
+        assert_eq!(decoded.len(), data.len());
+        for (a, b) in data.iter().zip(decoded.iter()) {
+            assert!((a - b).abs() < 1e-2, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_encode_array_auto_picks_numpress_slof_for_intensity_array() {
+        let data: Vec<f64> = (0..2000).map(|i| (i as f64 * 0.5).sin().abs() * 1_000_000.0).collect();
+        let (_, used) = encode_array_auto(&data, Precision::F64, true);
+        assert!(matches!(used, Compression::NumpressSlofZlib | Compression::Zlib | Compression::None));
+    }
+
+    #[test]
+    fn test_encode_array_auto_falls_back_for_noisy_array() {
+        // Non-monotonic, high-entropy data defeats numpress-linear's
+        // prediction; auto-selection should never do *worse* than the
+        // smallest of the non-numpress candidates.
+        let data: Vec<f64> = (0..200).map(|i| if i % 2 == 0 { 1e9 } else { -1e9 }).collect();
+        let (encoded, used) = encode_array_auto(&data, Precision::F64, false);
+        let plain = encode_array(&data, Precision::F64, Compression::None);
+        let zlibbed = encode_array(&data, Precision::F64, Compression::Zlib);
+        let best_non_numpress_len = plain.len().min(zlibbed.len());
+        assert!(encoded.len() <= best_non_numpress_len);
+        let decoded = decode_array(&encoded, Precision::F64, used).unwrap();
+        assert_eq!(decoded.len(), data.len());
+        for (a, b) in data.iter().zip(decoded.iter()) {
+            assert!((a - b).abs() < 1.0, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_encode_array_auto_mz_round_trips_through_sharp_jump() {
+        // Re-verification after chunk2-1: a sharp m/z jump in an otherwise
+        // smooth array produces a large linear-prediction residual whose
+        // encoded nibbles can have a high bit set in either sign, which
+        // whichever codec Auto picks must decode correctly.
+        let mut data: Vec<f64> = (0..500).map(|i| 400.0 + i as f64 * 0.01).collect();
+        data[250] = 900_000.0;
+        let (encoded, used) = encode_array_auto(&data, Precision::F64, false);
+        let decoded = decode_array(&encoded, Precision::F64, used).unwrap();
+        assert_eq!(decoded.len(), data.len());
+        for (a, b) in data.iter().zip(decoded.iter()) {
+            assert!((a - b).abs() < 1e-2, "{} vs {}", a, b);
+        }
+    }
 }
@@ -35,6 +35,8 @@ pub const HIGHEST_MZ: &str = "MS:1000527";
 
 // --- Units ---
 pub const MINUTE: &str = "UO:0000031";
+pub const MILLISECOND: &str = "UO:0000028";
+pub const VOLT: &str = "UO:0000218";
 
 // --- Binary data array ---
 pub const MZ_ARRAY: &str = "MS:1000514";
@@ -44,10 +46,32 @@ pub const FLOAT_64: &str = "MS:1000523";
 pub const FLOAT_32: &str = "MS:1000521";
 pub const ZLIB_COMPRESSION: &str = "MS:1000574";
 pub const NO_COMPRESSION: &str = "MS:1000576";
+// `NUMPRESS_*` do NOT use the real PSI-MS MS-Numpress accessions
+// (MS:1002312/1002313/1002314): those identify the standardized MS-Numpress
+// wire format (as implemented by e.g. ProteoWizard), and the codecs in
+// `numpress.rs` are a home-grown, round-trip-correct-within-this-tool
+// variable-length integer scheme that is NOT byte-compatible with it. Using
+// the real accessions here would mislabel the data and cause any compliant
+// external reader to mis-decode it. These placeholders follow the same
+// out-of-range convention as `ZSTD_COMPRESSION` below; swap them for the real
+// accessions if/when the wire format is made byte-compatible.
+pub const NUMPRESS_LINEAR: &str = "MS:9999996";
+pub const NUMPRESS_PIC: &str = "MS:9999995";
+pub const NUMPRESS_SLOF: &str = "MS:9999994";
+// zstd has no registered PSI-MS accession as of this writing. This
+// placeholder is intentionally out of the real `MS:10000xx`-`MS:10003xx`
+// range so it can't be mistaken for (or collide with) an official term;
+// swap it for the real accession once/if one is registered.
+pub const ZSTD_COMPRESSION: &str = "MS:9999999";
 
 // --- Chromatogram types ---
 pub const TIC_CHROMATOGRAM: &str = "MS:1000235";
 pub const BPC_CHROMATOGRAM: &str = "MS:1000628"; // basepeak chromatogram - selected ion current chromatogram
+pub const SRM_CHROMATOGRAM: &str = "MS:1000626"; // selected reaction monitoring chromatogram
+
+// --- Ion mobility ---
+pub const FAIMS_COMPENSATION_VOLTAGE: &str = "MS:1001581";
+pub const ION_MOBILITY_DRIFT_TIME: &str = "MS:1002476";
 
 // --- Precursor/isolation ---
 pub const SELECTED_ION_MZ: &str = "MS:1000744";
@@ -110,6 +134,17 @@ pub fn ms_level_value(level: &MsLevel) -> &'static str {
     }
 }
 
+/// Map an `MsLevel` to its plain numeric level, e.g. for matching against a
+/// `MzmlConfig::ms_levels` whitelist.
+pub fn ms_level_as_u8(level: &MsLevel) -> u8 {
+    match level {
+        MsLevel::Ms1 => 1,
+        MsLevel::Ms2 => 2,
+        MsLevel::Ms3 => 3,
+        MsLevel::Other(n) => *n,
+    }
+}
+
 /// Map MS level to spectrum type accession.
 pub fn spectrum_type(level: &MsLevel) -> &str {
     match level {
@@ -141,6 +176,13 @@ pub fn activation_accession(act: &ActivationType) -> Option<&'static str> {
     }
 }
 
+/// FAIMS compensation-voltage accession, alongside `polarity_accession`/
+/// `activation_accession` so the writer picks all per-scan accessions the
+/// same way.
+pub fn faims_cv_accession() -> &'static str {
+    FAIMS_COMPENSATION_VOLTAGE
+}
+
 /// Map activation type string (from filter/trailer) to CV accession.
 pub fn activation_str_to_accession(s: &str) -> Option<&'static str> {
     match s.to_uppercase().as_str() {
@@ -0,0 +1,446 @@
+//! Reference-mzML-backed [`thermo_raw::validation::GroundTruthSource`].
+//!
+//! Lets a RAW file be cross-checked against an existing mzML produced by
+//! another converter (e.g. ProteoWizard's `msconvert`) instead of requiring
+//! the C# `GroundTruthExporter` JSON export that most users can't run.
+//! Parses each `<spectrum>`, decoding its m/z and intensity binary arrays
+//! back to `f64` through the same [`crate::binary::decode_array`] pipeline
+//! used to validate our own mzML output, and maps Thermo-style native IDs
+//! (`scan=<n>`) back to scan numbers.
+
+use crate::binary;
+use crate::cv;
+use crate::{Compression, MzmlError, Precision};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::path::Path;
+use thermo_raw::validation::{GroundTruthScanData, GroundTruthScanIndex, GroundTruthSource};
+use thermo_raw::RawError;
+
+struct ParsedSpectrum {
+    index: GroundTruthScanIndex,
+    data: GroundTruthScanData,
+}
+
+/// Ground truth loaded from a reference mzML file, parsed once up front.
+pub struct MzmlGroundTruthSource {
+    scans: HashMap<u32, ParsedSpectrum>,
+}
+
+impl MzmlGroundTruthSource {
+    /// Parse `path` as mzML and build the ground-truth index from its
+    /// `<spectrum>` elements.
+    pub fn open(path: &Path) -> Result<Self, MzmlError> {
+        let xml = std::fs::read_to_string(path)?;
+        Self::parse(&xml)
+    }
+
+    fn parse(xml: &str) -> Result<Self, MzmlError> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+        let mut buf = Vec::new();
+
+        let mut scans = HashMap::new();
+        let mut current: Option<SpectrumState> = None;
+
+        loop {
+            let event = reader
+                .read_event_into(&mut buf)
+                .map_err(|e| MzmlError::Conversion(format!("mzML parse error: {e}")))?;
+            match event {
+                Event::Eof => break,
+                Event::Start(ref e) | Event::Empty(ref e) => {
+                    let is_empty = matches!(event, Event::Empty(_));
+                    match e.name().as_ref() {
+                        b"spectrum" => {
+                            let id = attr_value(e, b"id")?.unwrap_or_default();
+                            current = Some(SpectrumState::new(scan_number_from_native_id(&id)));
+                        }
+                        b"cvParam" if current.is_some() => {
+                            let state = current.as_mut().unwrap();
+                            let accession = attr_value(e, b"accession")?.unwrap_or_default();
+                            let value = attr_value(e, b"value")?;
+                            state.apply_cv_param(&accession, value.as_deref());
+                        }
+                        b"binaryDataArray" => {
+                            if let Some(state) = current.as_mut() {
+                                state.start_binary_array();
+                            }
+                        }
+                        b"binary" if is_empty => {
+                            // Empty <binary/> (no peaks): nothing to decode.
+                            if let Some(state) = current.as_mut() {
+                                state.finish_binary_array("")?;
+                            }
+                        }
+                        b"binary" => {
+                            if let Some(state) = current.as_mut() {
+                                state.in_binary = true;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Event::Text(t) => {
+                    if let Some(state) = current.as_mut() {
+                        if state.in_binary {
+                            let text = t
+                                .unescape()
+                                .map_err(|e| MzmlError::Conversion(format!("mzML text error: {e}")))?;
+                            state.pending_binary_text.push_str(&text);
+                        }
+                    }
+                }
+                Event::End(ref e) => match e.name().as_ref() {
+                    b"binary" => {
+                        if let Some(state) = current.as_mut() {
+                            let text = std::mem::take(&mut state.pending_binary_text);
+                            state.finish_binary_array(&text)?;
+                        }
+                    }
+                    b"spectrum" => {
+                        if let Some(state) = current.take() {
+                            if let Some(scan_number) = state.scan_number {
+                                let (index, data) = state.into_entry(scan_number);
+                                scans.insert(scan_number, ParsedSpectrum { index, data });
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(Self { scans })
+    }
+}
+
+impl GroundTruthSource for MzmlGroundTruthSource {
+    fn scan_index(&self) -> Result<Vec<GroundTruthScanIndex>, RawError> {
+        let mut index: Vec<_> = self
+            .scans
+            .values()
+            .map(|s| clone_index(&s.index))
+            .collect();
+        index.sort_by_key(|s| s.scan_number);
+        Ok(index)
+    }
+
+    fn scan_data(&self, scan_number: u32) -> Result<GroundTruthScanData, RawError> {
+        self.scans
+            .get(&scan_number)
+            .map(|s| clone_data(&s.data))
+            .ok_or_else(|| RawError::CorruptedData(format!("no scan {scan_number} in reference mzML")))
+    }
+}
+
+/// `GroundTruthScanIndex`/`GroundTruthScanData` only derive `Deserialize`
+/// (they're JSON-export DTOs); clone field-by-field instead of adding a
+/// `Clone` derive that the JSON path has no use for.
+fn clone_index(i: &GroundTruthScanIndex) -> GroundTruthScanIndex {
+    GroundTruthScanIndex {
+        scan_number: i.scan_number,
+        rt: i.rt,
+        ms_level: i.ms_level,
+        polarity: i.polarity.clone(),
+        tic: i.tic,
+        base_peak_mz: i.base_peak_mz,
+        base_peak_intensity: i.base_peak_intensity,
+        filter_string: i.filter_string.clone(),
+        compensation_voltage: i.compensation_voltage,
+    }
+}
+
+fn clone_data(d: &GroundTruthScanData) -> GroundTruthScanData {
+    GroundTruthScanData {
+        scan_number: d.scan_number,
+        centroid_count: d.centroid_count,
+        centroid_mz: d.centroid_mz.clone(),
+        centroid_intensity: d.centroid_intensity.clone(),
+        profile_count: d.profile_count,
+        profile_mz: d.profile_mz.clone(),
+        profile_intensity: d.profile_intensity.clone(),
+    }
+}
+
+/// Thermo-produced mzML uses bare `scan=<n>` native IDs (see
+/// `thermo-raw-mzml`'s own writer); other converters may prefix it with a
+/// controller reference (`controllerType=0 controllerNumber=1 scan=<n>`).
+/// Accept either by pulling out the `scan=` token.
+fn scan_number_from_native_id(id: &str) -> Option<u32> {
+    id.split_whitespace()
+        .find_map(|token| token.strip_prefix("scan="))
+        .and_then(|n| n.parse().ok())
+}
+
+fn attr_value(e: &quick_xml::events::BytesStart, key: &[u8]) -> Result<Option<String>, MzmlError> {
+    for attr in e.attributes() {
+        let attr = attr.map_err(|e| MzmlError::Conversion(format!("mzML attribute error: {e}")))?;
+        if attr.key.as_ref() == key {
+            let value = attr
+                .unescape_value()
+                .map_err(|e| MzmlError::Conversion(format!("mzML attribute error: {e}")))?;
+            return Ok(Some(value.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// Accumulates one `<spectrum>`'s worth of ground-truth fields while scanning
+/// through its children.
+struct SpectrumState {
+    scan_number: Option<u32>,
+    rt_minutes: f64,
+    ms_level: u8,
+    polarity: String,
+    tic: f64,
+    base_peak_mz: f64,
+    base_peak_intensity: f64,
+    compensation_voltage: Option<f64>,
+
+    in_binary_array: bool,
+    array_kind: ArrayKind,
+    precision: Precision,
+    compression: Compression,
+    in_binary: bool,
+    pending_binary_text: String,
+    mz: Option<Vec<f64>>,
+    intensity: Option<Vec<f64>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ArrayKind {
+    Unknown,
+    Mz,
+    Intensity,
+}
+
+impl SpectrumState {
+    fn new(scan_number: Option<u32>) -> Self {
+        Self {
+            scan_number,
+            rt_minutes: 0.0,
+            ms_level: 1,
+            polarity: String::from("positive"),
+            tic: 0.0,
+            base_peak_mz: 0.0,
+            base_peak_intensity: 0.0,
+            compensation_voltage: None,
+            in_binary_array: false,
+            array_kind: ArrayKind::Unknown,
+            precision: Precision::F64,
+            compression: Compression::None,
+            in_binary: false,
+            pending_binary_text: String::new(),
+            mz: None,
+            intensity: None,
+        }
+    }
+
+    fn start_binary_array(&mut self) {
+        self.in_binary_array = true;
+        self.array_kind = ArrayKind::Unknown;
+        self.precision = Precision::F64;
+        self.compression = Compression::None;
+    }
+
+    fn apply_cv_param(&mut self, accession: &str, value: Option<&str>) {
+        if self.in_binary_array {
+            self.apply_binary_array_cv_param(accession);
+            return;
+        }
+        match accession {
+            cv::MS_LEVEL => {
+                if let Some(v) = value.and_then(|v| v.parse().ok()) {
+                    self.ms_level = v;
+                }
+            }
+            cv::POSITIVE_SCAN => self.polarity = "positive".to_string(),
+            cv::NEGATIVE_SCAN => self.polarity = "negative".to_string(),
+            cv::TOTAL_ION_CURRENT => {
+                self.tic = value.and_then(|v| v.parse().ok()).unwrap_or(0.0);
+            }
+            cv::BASE_PEAK_MZ => {
+                self.base_peak_mz = value.and_then(|v| v.parse().ok()).unwrap_or(0.0);
+            }
+            cv::BASE_PEAK_INTENSITY => {
+                self.base_peak_intensity = value.and_then(|v| v.parse().ok()).unwrap_or(0.0);
+            }
+            cv::SCAN_START_TIME => {
+                // Assume minutes; reference mzML overwhelmingly uses `UO:0000031`.
+                self.rt_minutes = value.and_then(|v| v.parse().ok()).unwrap_or(0.0);
+            }
+            cv::FAIMS_COMPENSATION_VOLTAGE => {
+                self.compensation_voltage = value.and_then(|v| v.parse().ok());
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_binary_array_cv_param(&mut self, accession: &str) {
+        match accession {
+            cv::MZ_ARRAY => self.array_kind = ArrayKind::Mz,
+            cv::INTENSITY_ARRAY => self.array_kind = ArrayKind::Intensity,
+            cv::FLOAT_64 => self.precision = Precision::F64,
+            cv::FLOAT_32 => self.precision = Precision::F32,
+            cv::ZLIB_COMPRESSION => self.compression = Compression::Zlib,
+            cv::NO_COMPRESSION => self.compression = Compression::None,
+            cv::NUMPRESS_LINEAR => {
+                self.compression = match self.compression {
+                    Compression::Zlib => Compression::NumpressLinearZlib,
+                    _ => Compression::NumpressLinear,
+                }
+            }
+            cv::NUMPRESS_PIC => {
+                self.compression = match self.compression {
+                    Compression::Zlib => Compression::NumpressPicZlib,
+                    _ => Compression::NumpressPic,
+                }
+            }
+            cv::NUMPRESS_SLOF => {
+                self.compression = match self.compression {
+                    Compression::Zlib => Compression::NumpressSlofZlib,
+                    _ => Compression::NumpressSlof,
+                }
+            }
+            cv::ZSTD_COMPRESSION => self.compression = Compression::Zstd,
+            _ => {}
+        }
+    }
+
+    fn finish_binary_array(&mut self, encoded: &str) -> Result<(), MzmlError> {
+        let values = if encoded.trim().is_empty() {
+            Vec::new()
+        } else {
+            binary::decode_array(encoded, self.precision, self.compression)?
+        };
+        match self.array_kind {
+            ArrayKind::Mz => self.mz = Some(values),
+            ArrayKind::Intensity => self.intensity = Some(values),
+            ArrayKind::Unknown => {}
+        }
+        self.in_binary_array = false;
+        self.in_binary = false;
+        Ok(())
+    }
+
+    fn into_entry(self, scan_number: u32) -> (GroundTruthScanIndex, GroundTruthScanData) {
+        let centroid_count = self.mz.as_ref().map(|v| v.len()).unwrap_or(0);
+        let index = GroundTruthScanIndex {
+            scan_number,
+            rt: self.rt_minutes,
+            ms_level: self.ms_level,
+            polarity: self.polarity,
+            tic: self.tic,
+            base_peak_mz: self.base_peak_mz,
+            base_peak_intensity: self.base_peak_intensity,
+            filter_string: String::new(),
+            compensation_voltage: self.compensation_voltage,
+        };
+        let data = GroundTruthScanData {
+            scan_number,
+            centroid_count,
+            centroid_mz: self.mz,
+            centroid_intensity: self.intensity,
+            profile_count: 0,
+            profile_mz: None,
+            profile_intensity: None,
+        };
+        (index, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_mzml(mz_binary: &str, intensity_binary: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<mzML>
+  <run>
+    <spectrumList count="1">
+      <spectrum index="0" id="scan=1" defaultArrayLength="3">
+        <cvParam cvRef="MS" accession="{ms_level}" name="ms level" value="1"/>
+        <cvParam cvRef="MS" accession="{pos}" name="positive scan"/>
+        <cvParam cvRef="MS" accession="{tic}" name="total ion current" value="12345.0"/>
+        <cvParam cvRef="MS" accession="{bpmz}" name="base peak m/z" value="500.5"/>
+        <cvParam cvRef="MS" accession="{bpi}" name="base peak intensity" value="999.0"/>
+        <scanList count="1">
+          <scan>
+            <cvParam cvRef="MS" accession="{rt}" name="scan start time" value="1.5" unitAccession="UO:0000031"/>
+          </scan>
+        </scanList>
+        <binaryDataArrayList count="2">
+          <binaryDataArray encodedLength="0">
+            <cvParam cvRef="MS" accession="{f64}" name="64-bit float"/>
+            <cvParam cvRef="MS" accession="{nocomp}" name="no compression"/>
+            <cvParam cvRef="MS" accession="{mzarr}" name="m/z array"/>
+            <binary>{mz_binary}</binary>
+          </binaryDataArray>
+          <binaryDataArray encodedLength="0">
+            <cvParam cvRef="MS" accession="{f64}" name="64-bit float"/>
+            <cvParam cvRef="MS" accession="{nocomp}" name="no compression"/>
+            <cvParam cvRef="MS" accession="{intarr}" name="intensity array"/>
+            <binary>{intensity_binary}</binary>
+          </binaryDataArray>
+        </binaryDataArrayList>
+      </spectrum>
+    </spectrumList>
+  </run>
+</mzML>"#,
+            ms_level = cv::MS_LEVEL,
+            pos = cv::POSITIVE_SCAN,
+            tic = cv::TOTAL_ION_CURRENT,
+            bpmz = cv::BASE_PEAK_MZ,
+            bpi = cv::BASE_PEAK_INTENSITY,
+            rt = cv::SCAN_START_TIME,
+            f64 = cv::FLOAT_64,
+            nocomp = cv::NO_COMPRESSION,
+            mzarr = cv::MZ_ARRAY,
+            intarr = cv::INTENSITY_ARRAY,
+            mz_binary = mz_binary,
+            intensity_binary = intensity_binary,
+        )
+    }
+
+    #[test]
+    fn parses_scan_index_and_data() {
+        let mz = binary::encode_array(&[100.0, 200.0, 300.0], Precision::F64, Compression::None);
+        let intensity = binary::encode_array(&[10.0, 20.0, 30.0], Precision::F64, Compression::None);
+        let xml = sample_mzml(&mz, &intensity);
+
+        let source = MzmlGroundTruthSource::parse(&xml).unwrap();
+        let index = source.scan_index().unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(index[0].scan_number, 1);
+        assert_eq!(index[0].ms_level, 1);
+        assert_eq!(index[0].polarity, "positive");
+        assert!((index[0].tic - 12345.0).abs() < 1e-6);
+
+        let data = source.scan_data(1).unwrap();
+        assert_eq!(data.centroid_mz.unwrap(), vec![100.0, 200.0, 300.0]);
+        assert_eq!(data.centroid_intensity.unwrap(), vec![10.0, 20.0, 30.0]);
+    }
+
+    #[test]
+    fn scan_number_from_native_id_handles_both_formats() {
+        assert_eq!(scan_number_from_native_id("scan=42"), Some(42));
+        assert_eq!(
+            scan_number_from_native_id("controllerType=0 controllerNumber=1 scan=42"),
+            Some(42)
+        );
+        assert_eq!(scan_number_from_native_id("no scan here"), None);
+    }
+
+    #[test]
+    fn unknown_scan_number_is_skipped() {
+        let xml = sample_mzml("", "");
+        let xml = xml.replace("id=\"scan=1\"", "id=\"spectrum=1\"");
+        let source = MzmlGroundTruthSource::parse(&xml).unwrap();
+        assert!(source.scan_index().unwrap().is_empty());
+    }
+}
@@ -4,6 +4,18 @@
 //! standard mzML/indexed mzML. Uses `thermo-raw` for parsing and `quick-xml`
 //! for efficient XML generation.
 //!
+//! This is the writer half of the conversion pipeline: it consumes a parsed
+//! `thermo_raw::RawFile` (run header, scan index, and on-demand scan data)
+//! and emits an open spectrum format. [`writer::write_mzml`] writes the
+//! `<fileDescription>`/`<instrumentConfigurationList>` header from the
+//! source `FileMetadata` (device name, model, serial number, software
+//! version, instrument type) and the run's start/end time and mass range,
+//! then streams one `<spectrum>` per scan. [`convert_file_with_progress`]
+//! is what drives `thermo-raw-gui`'s `FileStatus::Converting` -> `Done`
+//! transition. [`arrow_export`] builds per-spectrum rows for a "compact
+//! columnar binary" alternative, but its `write_parquet`/`write_arrow`
+//! writers are not implemented and always return an error.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -14,9 +26,26 @@
 //! convert_file(Path::new("sample.RAW"), Path::new("sample.mzML"), &config).unwrap();
 //! ```
 
+mod arrow_export;
 mod binary;
 mod cv;
+mod ground_truth;
+mod manifest;
+mod mzxml_writer;
+mod numpress;
+mod validation;
 mod writer;
+mod zstd_codec;
+
+pub use arrow_export::{
+    build_chromatogram_tables, build_spectrum_rows, write_arrow, write_parquet, ChromatogramTable,
+    SpectrumRow,
+};
+pub use ground_truth::MzmlGroundTruthSource;
+pub use manifest::ConversionManifestEntry;
+pub use validation::{CvParamCheck, Mismatch, OboTerm, OboTermTable, ValidationReport, validate};
+pub use mzxml_writer::write_mzxml;
+pub use writer::{write_mzml, write_mzml_async, AsyncByteSink};
 
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -40,11 +69,67 @@ pub enum Precision {
     F64,
 }
 
+/// Which binary data arrays to emit per spectrum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpectrumData {
+    /// Always emit the centroided peak list, even for scans stored in
+    /// profile mode (default).
+    Centroid,
+    /// Emit the raw profile arrays for scans stored in profile mode,
+    /// falling back to centroid arrays for scans that have none.
+    Profile,
+    /// Emit whichever arrays a scan actually has, writing both a
+    /// `binaryDataArray` pair for centroid data and one for profile data
+    /// when a scan provides both.
+    Both,
+}
+
 /// Compression mode for binary data arrays.
+///
+/// The `Numpress*` variants are MS-Numpress-*inspired*, not byte-compatible
+/// with the standardized MS-Numpress wire format -- see the module doc on
+/// [`crate::numpress`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Compression {
     None,
     Zlib,
+    /// MS-Numpress linear prediction + delta coding; best for near-linear
+    /// m/z arrays.
+    NumpressLinear,
+    /// MS-Numpress non-negative integer coding; best for intensity arrays.
+    NumpressPic,
+    /// MS-Numpress short logged float; a lossy alternative for intensities.
+    NumpressSlof,
+    /// MS-Numpress linear prediction, zlib-compressed on top for a further
+    /// (smaller) size reduction.
+    NumpressLinearZlib,
+    /// MS-Numpress positive integer coding, zlib-compressed on top.
+    NumpressPicZlib,
+    /// MS-Numpress short logged float, zlib-compressed on top.
+    NumpressSlofZlib,
+    /// Self-contained zstd framing (see [`crate::zstd_codec`]).
+    Zstd,
+    /// Trial-encode each array with the available codecs (none, zlib,
+    /// numpress-linear+zlib for m/z-like arrays, numpress-slof+zlib for
+    /// intensity arrays) and keep whichever yields the smallest
+    /// `encodedLength`, since m/z and intensity arrays tend to favor
+    /// different codecs and a single global setting is suboptimal.
+    Auto,
+}
+
+/// Configuration for monoisotopic precursor m/z refinement against the
+/// preceding MS1 survey scan (disabled by default). When set on
+/// [`MzmlConfig::precursor_refinement`], each MSn scan's recorded precursor
+/// m/z is corrected to the matched isotope envelope's monoisotopic peak
+/// before being written as `selected ion m/z`; the isolation window target
+/// m/z is always left as recorded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrecursorRefinementConfig {
+    /// Tolerance used to locate the seed peak and isotope neighbors in the
+    /// MS1 survey scan.
+    pub tolerance: thermo_raw::precursor_refinement::MassTolerance,
+    /// Inclusive charge states to consider, e.g. `(1, 6)`.
+    pub charge_range: (u8, u8),
 }
 
 /// Configuration for mzML conversion.
@@ -62,6 +147,34 @@ pub struct MzmlConfig {
     pub include_ms2: bool,
     /// Minimum intensity threshold; peaks at or below this value are excluded (default: 0.0 = keep all).
     pub intensity_threshold: f64,
+    /// Write a `manifest.csv`/`manifest.json` catalog after `convert_folder` (default: false).
+    pub write_manifest: bool,
+    /// Directory to write the manifest files into; defaults to the conversion's output directory.
+    pub manifest_path: Option<PathBuf>,
+    /// For targeted (SRM/MRM/PRM) acquisitions, emit one chromatogram per
+    /// distinct isolation-window target m/z instead of just TIC/BPC. The
+    /// value is the m/z tolerance used to merge scans into the same target
+    /// (default: disabled, i.e. `None`).
+    pub srm_mz_tolerance: Option<f64>,
+    /// Only emit scans whose MS level is in this whitelist, e.g. `vec![1]`
+    /// for MS1-only survey export (default: disabled, i.e. `None` = keep
+    /// every MS level).
+    pub ms_levels: Option<Vec<u8>>,
+    /// Only emit scans with a retention time (in minutes) within
+    /// `(min, max)`, inclusive (default: disabled, i.e. `None` = keep the
+    /// whole run).
+    pub rt_range: Option<(f64, f64)>,
+    /// Only emit MSn scans whose recorded precursor m/z falls within
+    /// `(min, max)`, inclusive; MS1 scans (no precursor) are dropped
+    /// whenever this is set (default: disabled, i.e. `None` = no precursor
+    /// filtering).
+    pub precursor_mz_range: Option<(f64, f64)>,
+    /// Which binary data arrays to emit for scans stored in profile mode
+    /// (default: `Centroid`, i.e. always centroid everything).
+    pub spectrum_data: SpectrumData,
+    /// Correct recorded precursor m/z values to their true monoisotopic peak
+    /// (default: disabled, i.e. `None` = write the recorded value as-is).
+    pub precursor_refinement: Option<PrecursorRefinementConfig>,
 }
 
 impl Default for MzmlConfig {
@@ -73,6 +186,14 @@ impl Default for MzmlConfig {
             write_index: true,
             include_ms2: true,
             intensity_threshold: 0.0,
+            write_manifest: false,
+            manifest_path: None,
+            srm_mz_tolerance: None,
+            ms_levels: None,
+            rt_range: None,
+            precursor_mz_range: None,
+            spectrum_data: SpectrumData::Centroid,
+            precursor_refinement: None,
         }
     }
 }
@@ -93,6 +214,38 @@ pub fn convert_file(
     Ok(())
 }
 
+/// Convert a single RAW file to mzML, writing through `output` instead of
+/// creating a file -- e.g. a gzip encoder wrapping a `File`.
+pub fn convert_file_to_writer<W: std::io::Write>(
+    raw_path: &Path,
+    output: W,
+    config: &MzmlConfig,
+) -> Result<(), MzmlError> {
+    let raw = thermo_raw::RawFile::open_mmap(raw_path)?;
+    let source_name = raw_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    writer::write_mzml(&raw, output, config, &source_name)?;
+    Ok(())
+}
+
+/// Convert a single RAW file to gzip-compressed mzML at `output_path`
+/// (conventionally named `<name>.mzML.gz`).
+pub fn convert_file_gzip(
+    raw_path: &Path,
+    output_path: &Path,
+    config: &MzmlConfig,
+) -> Result<(), MzmlError> {
+    use flate2::write::GzEncoder;
+
+    let file = std::fs::File::create(output_path)?;
+    let mut encoder = GzEncoder::new(std::io::BufWriter::new(file), flate2::Compression::default());
+    convert_file_to_writer(raw_path, &mut encoder, config)?;
+    encoder.finish()?;
+    Ok(())
+}
+
 /// Convert a single RAW file to mzML, ticking the counter after each scan.
 pub fn convert_file_with_progress(
     raw_path: &Path,
@@ -110,6 +263,37 @@ pub fn convert_file_with_progress(
     Ok(())
 }
 
+/// Convert a single RAW file to mzML, recording scans-converted,
+/// bytes-read, and whole-conversion latency into `metrics`.
+///
+/// Unlike [`convert_file_with_progress`], this writes through
+/// [`writer::write_mzml`], which has no per-scan hook, so it records one
+/// latency sample for the whole conversion rather than one per scan.
+pub fn convert_file_with_metrics(
+    raw_path: &Path,
+    output_path: &Path,
+    config: &MzmlConfig,
+    metrics: &thermo_raw::Metrics,
+) -> Result<(), MzmlError> {
+    let start = std::time::Instant::now();
+    let raw = thermo_raw::RawFile::open_mmap(raw_path)?;
+    let n_scans = (raw.last_scan() - raw.first_scan() + 1) as u64;
+    let output = std::io::BufWriter::new(std::fs::File::create(output_path)?);
+    let source_name = raw_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    writer::write_mzml(&raw, output, config, &source_name)?;
+
+    metrics.record_bytes_read(std::fs::metadata(raw_path).map(|m| m.len()).unwrap_or(0));
+    for _ in 0..n_scans {
+        metrics.record_scan_converted();
+        metrics.record_spectrum_decoded();
+    }
+    metrics.record_latency(start.elapsed());
+    Ok(())
+}
+
 /// Convert all RAW files in a folder to mzML (parallel).
 pub fn convert_folder(
     input_dir: &Path,
@@ -129,18 +313,56 @@ pub fn convert_folder(
         })
         .collect();
 
-    let results: Vec<Result<PathBuf, MzmlError>> = entries
+    let results: Vec<Result<(PathBuf, Option<ConversionManifestEntry>), MzmlError>> = entries
         .par_iter()
         .map(|entry| {
             let raw_path = entry.path();
             let stem = raw_path.file_stem().unwrap_or_default();
             let out_path = output_dir.join(format!("{}.mzML", stem.to_string_lossy()));
             convert_file(&raw_path, &out_path, config)?;
-            Ok(out_path)
+            let manifest_entry = config
+                .write_manifest
+                .then(|| build_manifest_entry(&raw_path, &out_path))
+                .transpose()?;
+            Ok((out_path, manifest_entry))
         })
         .collect();
 
-    results.into_iter().collect()
+    let results: Result<Vec<_>, MzmlError> = results.into_iter().collect();
+    let results = results?;
+    if config.write_manifest {
+        let entries: Vec<ConversionManifestEntry> =
+            results.iter().filter_map(|(_, e)| e.clone()).collect();
+        write_manifest(output_dir, config, &entries)?;
+    }
+    Ok(results.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Re-open a just-converted file to build its manifest entry. `convert_file`
+/// doesn't hand back the parsed `RawFile`, so this re-parses it; only paid
+/// for when `MzmlConfig::write_manifest` is set.
+fn build_manifest_entry(
+    raw_path: &Path,
+    out_path: &Path,
+) -> Result<ConversionManifestEntry, MzmlError> {
+    let raw = thermo_raw::RawFile::open_mmap(raw_path)?;
+    let source_name = raw_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(manifest::build_entry(&raw, source_name, out_path.to_path_buf()))
+}
+
+fn write_manifest(
+    output_dir: &Path,
+    config: &MzmlConfig,
+    entries: &[ConversionManifestEntry],
+) -> Result<(), MzmlError> {
+    let dir = config.manifest_path.as_deref().unwrap_or(output_dir);
+    std::fs::create_dir_all(dir)?;
+    manifest::write_manifest_csv(&dir.join("manifest.csv"), entries)?;
+    manifest::write_manifest_json(&dir.join("manifest.json"), entries)?;
+    Ok(())
 }
 
 /// Convert all RAW files in a folder to mzML (parallel), ticking per file.
@@ -163,7 +385,7 @@ pub fn convert_folder_with_progress(
         })
         .collect();
 
-    let results: Vec<Result<PathBuf, MzmlError>> = entries
+    let results: Vec<Result<(PathBuf, Option<ConversionManifestEntry>), MzmlError>> = entries
         .par_iter()
         .map(|entry| {
             let raw_path = entry.path();
@@ -171,9 +393,20 @@ pub fn convert_folder_with_progress(
             let out_path = output_dir.join(format!("{}.mzML", stem.to_string_lossy()));
             convert_file(&raw_path, &out_path, config)?;
             thermo_raw::progress::tick(counter);
-            Ok(out_path)
+            let manifest_entry = config
+                .write_manifest
+                .then(|| build_manifest_entry(&raw_path, &out_path))
+                .transpose()?;
+            Ok((out_path, manifest_entry))
         })
         .collect();
 
-    results.into_iter().collect()
+    let results: Result<Vec<_>, MzmlError> = results.into_iter().collect();
+    let results = results?;
+    if config.write_manifest {
+        let entries: Vec<ConversionManifestEntry> =
+            results.iter().filter_map(|(_, e)| e.clone()).collect();
+        write_manifest(output_dir, config, &entries)?;
+    }
+    Ok(results.into_iter().map(|(path, _)| path).collect())
 }
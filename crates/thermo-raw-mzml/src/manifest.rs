@@ -0,0 +1,217 @@
+//! Conversion manifest catalog for [`crate::convert_folder`].
+//!
+//! Summarizes each converted file (acquisition type, isolation scheme, scan
+//! counts, RT range, ...) into a small `manifest.csv`/`manifest.json` so a
+//! downstream pipeline can discover DDA vs. DIA files without re-parsing
+//! every output mzML.
+
+use crate::MzmlError;
+use std::path::{Path, PathBuf};
+use thermo_raw::{AcquisitionType, IsolationWindow, MsLevel, RawFile};
+
+/// Summary of a single converted RAW file.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConversionManifestEntry {
+    pub source_file: String,
+    pub output_path: PathBuf,
+    pub instrument_model: String,
+    pub serial_number: String,
+    pub ms1_scan_count: u32,
+    pub ms2_scan_count: u32,
+    pub acquisition_type: AcquisitionType,
+    /// Distinct DIA isolation windows, empty for DDA/MS1-only files.
+    pub isolation_windows: Vec<IsolationWindow>,
+    pub total_ion_current: f64,
+    pub rt_range: (f64, f64),
+}
+
+/// Build a manifest entry for `raw`, which was just converted from
+/// `source_file` to `output_path`.
+pub fn build_entry(raw: &RawFile, source_file: String, output_path: PathBuf) -> ConversionManifestEntry {
+    let meta = raw.metadata();
+
+    let mut ms1_scan_count = 0u32;
+    let mut ms2_scan_count = 0u32;
+    let mut total_ion_current = 0.0;
+    let mut rt_min = f64::INFINITY;
+    let mut rt_max = f64::NEG_INFINITY;
+    let mut windows: Vec<IsolationWindow> = Vec::new();
+
+    let events = raw.scan_events();
+    for entry in raw.scan_index() {
+        total_ion_current += entry.tic;
+        rt_min = rt_min.min(entry.rt);
+        rt_max = rt_max.max(entry.rt);
+
+        let Some(event) = events.get(entry.scan_event as usize) else {
+            continue;
+        };
+        if matches!(event.preamble.ms_level, MsLevel::Ms1) {
+            ms1_scan_count += 1;
+            continue;
+        }
+        ms2_scan_count += 1;
+
+        if let Some(reaction) = event.reactions.last() {
+            let center_mz = round_to(reaction.precursor_mz, 1);
+            let isolation_width = round_to(reaction.isolation_width, 1);
+            let window = IsolationWindow {
+                center_mz,
+                isolation_width,
+                low_mz: center_mz - isolation_width / 2.0,
+                high_mz: center_mz + isolation_width / 2.0,
+                collision_energy: round_to(reaction.collision_energy, 1),
+                activation: reaction.activation_type().to_string(),
+            };
+            if !windows.contains(&window) {
+                windows.push(window);
+            }
+        }
+    }
+
+    if !rt_min.is_finite() {
+        rt_min = 0.0;
+    }
+    if !rt_max.is_finite() {
+        rt_max = 0.0;
+    }
+
+    let acquisition_type = classify_acquisition(ms1_scan_count, ms2_scan_count, &windows);
+
+    ConversionManifestEntry {
+        source_file,
+        output_path,
+        instrument_model: meta.instrument_model.clone(),
+        serial_number: meta.serial_number.clone(),
+        ms1_scan_count,
+        ms2_scan_count,
+        acquisition_type,
+        isolation_windows: if matches!(acquisition_type, AcquisitionType::Dia) {
+            windows
+        } else {
+            Vec::new()
+        },
+        total_ion_current,
+        rt_range: (rt_min, rt_max),
+    }
+}
+
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+/// Classify acquisition type from scan counts and the set of distinct
+/// isolation windows observed: a handful of windows repeating across many
+/// MS2 scans is DIA; a wide spread of mostly-unique windows is DDA.
+fn classify_acquisition(
+    ms1_scan_count: u32,
+    ms2_scan_count: u32,
+    windows: &[IsolationWindow],
+) -> AcquisitionType {
+    if ms2_scan_count == 0 {
+        return AcquisitionType::Ms1Only;
+    }
+    if ms1_scan_count == 0 && windows.len() as u32 <= (ms2_scan_count / 4).max(1) {
+        return AcquisitionType::Dia;
+    }
+    if windows.is_empty() {
+        return AcquisitionType::Dda;
+    }
+    let windows_per_scan = ms2_scan_count as f64 / windows.len() as f64;
+    if windows_per_scan >= 3.0 {
+        AcquisitionType::Dia
+    } else if windows_per_scan <= 1.5 {
+        AcquisitionType::Dda
+    } else {
+        AcquisitionType::Mixed
+    }
+}
+
+/// Write the manifest as CSV (one row per converted file; isolation windows
+/// are flattened into a single `;`-separated column).
+pub fn write_manifest_csv(path: &Path, entries: &[ConversionManifestEntry]) -> Result<(), MzmlError> {
+    let mut out = String::from(
+        "source_file,output_path,instrument_model,serial_number,ms1_scan_count,ms2_scan_count,acquisition_type,isolation_windows,total_ion_current,rt_min,rt_max\n",
+    );
+    for e in entries {
+        let windows = e
+            .isolation_windows
+            .iter()
+            .map(|w| format!("{:.1}", w.center_mz))
+            .collect::<Vec<_>>()
+            .join(";");
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{:?},{},{:.4},{:.6},{:.6}\n",
+            csv_escape(&e.source_file),
+            csv_escape(&e.output_path.to_string_lossy()),
+            csv_escape(&e.instrument_model),
+            csv_escape(&e.serial_number),
+            e.ms1_scan_count,
+            e.ms2_scan_count,
+            e.acquisition_type,
+            csv_escape(&windows),
+            e.total_ion_current,
+            e.rt_range.0,
+            e.rt_range.1,
+        ));
+    }
+    std::fs::write(path, out)?;
+    Ok(())
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Write the manifest as JSON (full fidelity, including the isolation window list).
+pub fn write_manifest_json(path: &Path, entries: &[ConversionManifestEntry]) -> Result<(), MzmlError> {
+    let json = serde_json::to_string_pretty(entries)
+        .map_err(|e| MzmlError::Conversion(format!("failed to serialize manifest: {e}")))?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(center: f64) -> IsolationWindow {
+        IsolationWindow {
+            center_mz: center,
+            isolation_width: 20.0,
+            low_mz: center - 10.0,
+            high_mz: center + 10.0,
+            collision_energy: 27.0,
+            activation: "HCD".to_string(),
+        }
+    }
+
+    #[test]
+    fn classifies_ms1_only() {
+        assert_eq!(classify_acquisition(100, 0, &[]), AcquisitionType::Ms1Only);
+    }
+
+    #[test]
+    fn classifies_dia_from_repeating_windows() {
+        let windows = vec![window(400.0), window(420.0), window(440.0)];
+        assert_eq!(classify_acquisition(0, 30, &windows), AcquisitionType::Dia);
+    }
+
+    #[test]
+    fn classifies_dda_from_mostly_unique_windows() {
+        let windows: Vec<_> = (0..20).map(|i| window(400.0 + i as f64)).collect();
+        assert_eq!(classify_acquisition(20, 20, &windows), AcquisitionType::Dda);
+    }
+
+    #[test]
+    fn csv_escapes_commas_and_quotes() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("a\"b"), "\"a\"\"b\"");
+    }
+}
@@ -0,0 +1,200 @@
+//! mzXML 3.2 writer: the pre-mzML XML format some legacy tools still expect.
+//!
+//! mzXML's `<peaks>` element interleaves m/z and intensity into a single
+//! array of big-endian floats, then base64 (optionally zlib-compressed) --
+//! unlike mzML's two separate little-endian `binaryDataArray` elements, so
+//! this doesn't reuse [`crate::binary`]'s encoding pipeline and instead has
+//! its own small one. mzXML also has one precision/compression setting per
+//! `<peaks>` element rather than per-array, so [`MzmlConfig::mz_precision`]
+//! and [`MzmlConfig::compression`] are used for both arrays; any compression
+//! variant other than `None` maps to mzXML's `zlib`, since mzXML has no
+//! concept of MS-Numpress or zstd.
+//!
+//! This is a flat writer: every scan is a top-level `<scan>` regardless of
+//! MS level, with `precursorMz` as a child for MS2+. The mzXML spec nests a
+//! precursor's dependent scans inside its own `<scan>` element; that
+//! hierarchy isn't reconstructed here, so a strict reader expecting nested
+//! scans (rather than a flat list disambiguated by `precursorMz`) may need
+//! more than this produces. Most mzXML consumers (pyteomics, mMass) accept
+//! the flat form.
+
+use crate::writer::{filtered_scan_numbers, scan_passes_filters};
+use crate::{Compression, MzmlConfig, MzmlError, Precision};
+use base64::Engine;
+use flate2::write::ZlibEncoder;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use std::io::Write;
+use thermo_raw::types::{MsLevel, Polarity};
+use thermo_raw::RawFile;
+
+fn polarity_symbol(p: &Polarity) -> Option<&'static str> {
+    match p {
+        Polarity::Positive => Some("+"),
+        Polarity::Negative => Some("-"),
+        Polarity::Unknown => None,
+    }
+}
+
+/// Interleave `mz`/`intensity` into mzXML `<peaks>` text: big-endian
+/// f32/f64 pairs, base64-encoded, optionally zlib-compressed. Returns the
+/// base64 text plus the `compressionType` attribute value to write
+/// alongside it.
+fn encode_peaks(mz: &[f64], intensity: &[f64], precision_64: bool, zlib: bool) -> (String, &'static str) {
+    let unit_size = if precision_64 { 8 } else { 4 };
+    let mut raw = Vec::with_capacity(mz.len() * 2 * unit_size);
+    for (m, i) in mz.iter().zip(intensity.iter()) {
+        if precision_64 {
+            raw.extend_from_slice(&m.to_be_bytes());
+            raw.extend_from_slice(&i.to_be_bytes());
+        } else {
+            raw.extend_from_slice(&(*m as f32).to_be_bytes());
+            raw.extend_from_slice(&(*i as f32).to_be_bytes());
+        }
+    }
+
+    if zlib {
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&raw).expect("in-memory write failed");
+        let compressed = encoder.finish().expect("in-memory write failed");
+        (
+            base64::engine::general_purpose::STANDARD.encode(compressed),
+            "zlib",
+        )
+    } else {
+        (base64::engine::general_purpose::STANDARD.encode(raw), "none")
+    }
+}
+
+/// Write a complete mzXML 3.2 document for `raw` to `output`.
+pub fn write_mzxml<W: Write>(
+    raw: &RawFile,
+    output: W,
+    config: &MzmlConfig,
+    source_filename: &str,
+) -> Result<(), MzmlError> {
+    let scan_numbers = filtered_scan_numbers(raw, config);
+    let precision_64 = matches!(config.mz_precision, Precision::F64);
+    let zlib = !matches!(config.compression, Compression::None);
+    let meta = raw.metadata();
+
+    let mut writer = Writer::new_with_indent(output, b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+
+    let mut mzxml_start = BytesStart::new("mzXML");
+    mzxml_start.push_attribute(("xmlns", "http://sashimi.sourceforge.net/schema_revision/mzXML_3.2"));
+    writer.write_event(Event::Start(mzxml_start))?;
+
+    let mut run = BytesStart::new("msRun");
+    run.push_attribute(("scanCount", scan_numbers.len().to_string().as_str()));
+    run.push_attribute(("startTime", format!("PT{:.4}S", raw.scan(raw.first_scan()).map(|s| s.rt * 60.0).unwrap_or(0.0)).as_str()));
+    run.push_attribute(("endTime", format!("PT{:.4}S", raw.scan(raw.last_scan()).map(|s| s.rt * 60.0).unwrap_or(0.0)).as_str()));
+    writer.write_event(Event::Start(run))?;
+
+    let mut parent_file = BytesStart::new("parentFile");
+    parent_file.push_attribute(("fileName", source_filename));
+    parent_file.push_attribute(("fileType", "RAWData"));
+    parent_file.push_attribute(("fileSha1", "0000000000000000000000000000000000000000"));
+    writer.write_event(Event::Empty(parent_file))?;
+
+    let mut instrument = BytesStart::new("msInstrument");
+    instrument.push_attribute(("msInstrumentID", "IC1"));
+    writer.write_event(Event::Start(instrument))?;
+    write_instrument_value(&mut writer, "msManufacturer", "Thermo Scientific")?;
+    write_instrument_value(&mut writer, "msModel", &meta.instrument_model)?;
+    write_instrument_value(&mut writer, "msIonisation", "ESI")?;
+    write_instrument_value(&mut writer, "msMassAnalyzer", &meta.instrument_name)?;
+    write_instrument_value(&mut writer, "msDetector", "unknown")?;
+    let mut software = BytesStart::new("software");
+    software.push_attribute(("type", "conversion"));
+    software.push_attribute(("name", "thermo-raw-mzml"));
+    software.push_attribute(("version", env!("CARGO_PKG_VERSION")));
+    writer.write_event(Event::Empty(software))?;
+    writer.write_event(Event::End(BytesEnd::new("msInstrument")))?;
+
+    let mut data_processing = BytesStart::new("dataProcessing");
+    writer.write_event(Event::Start(data_processing.to_owned()))?;
+    let mut software_ref = BytesStart::new("software");
+    software_ref.push_attribute(("type", "conversion"));
+    software_ref.push_attribute(("name", "thermo-raw-mzml"));
+    software_ref.push_attribute(("version", env!("CARGO_PKG_VERSION")));
+    writer.write_event(Event::Empty(software_ref))?;
+    data_processing.clear_attributes();
+    writer.write_event(Event::End(BytesEnd::new("dataProcessing")))?;
+
+    for &scan_num in &scan_numbers {
+        match raw.scan(scan_num) {
+            Ok(scan) if scan_passes_filters(&scan, config) => {
+                let mz = scan.centroid_mz.as_slice();
+                let intensity = scan.centroid_intensity.as_slice();
+
+                let mut scan_el = BytesStart::new("scan");
+                scan_el.push_attribute(("num", scan_num.to_string().as_str()));
+                let ms_level = match scan.ms_level {
+                    MsLevel::Ms1 => 1,
+                    MsLevel::Ms2 => 2,
+                    MsLevel::Ms3 => 3,
+                    MsLevel::Other(n) => n,
+                };
+                scan_el.push_attribute(("msLevel", ms_level.to_string().as_str()));
+                scan_el.push_attribute(("peaksCount", mz.len().to_string().as_str()));
+                if let Some(pol) = polarity_symbol(&scan.polarity) {
+                    scan_el.push_attribute(("polarity", pol));
+                }
+                scan_el.push_attribute(("retentionTime", format!("PT{:.4}S", scan.rt * 60.0).as_str()));
+                scan_el.push_attribute(("basePeakMz", format!("{:.10}", scan.base_peak_mz).as_str()));
+                scan_el.push_attribute(("basePeakIntensity", format!("{:.4}", scan.base_peak_intensity).as_str()));
+                scan_el.push_attribute(("totIonCurrent", format!("{:.4}", scan.tic).as_str()));
+                if let (Some(low), Some(high)) = (mz.first(), mz.last()) {
+                    scan_el.push_attribute(("lowMz", format!("{:.10}", low).as_str()));
+                    scan_el.push_attribute(("highMz", format!("{:.10}", high).as_str()));
+                }
+                writer.write_event(Event::Start(scan_el))?;
+
+                if let Some(precursor) = &scan.precursor {
+                    let mut pm = BytesStart::new("precursorMz");
+                    if let Some(charge) = precursor.charge {
+                        pm.push_attribute(("precursorCharge", charge.to_string().as_str()));
+                    }
+                    writer.write_event(Event::Start(pm))?;
+                    writer.write_event(Event::Text(BytesText::new(&format!("{:.10}", precursor.mz))))?;
+                    writer.write_event(Event::End(BytesEnd::new("precursorMz")))?;
+                }
+
+                let (peaks, compression_type) = encode_peaks(mz, intensity, precision_64, zlib);
+                let mut peaks_el = BytesStart::new("peaks");
+                peaks_el.push_attribute(("precision", if precision_64 { "64" } else { "32" }));
+                peaks_el.push_attribute(("byteOrder", "network"));
+                peaks_el.push_attribute(("contentType", "m/z-int"));
+                peaks_el.push_attribute(("compressionType", compression_type));
+                writer.write_event(Event::Start(peaks_el))?;
+                writer.write_event(Event::Text(BytesText::new(&peaks)))?;
+                writer.write_event(Event::End(BytesEnd::new("peaks")))?;
+
+                writer.write_event(Event::End(BytesEnd::new("scan")))?;
+            }
+            _ => {
+                // Unreadable or filtered-out scan: emit an empty placeholder
+                // so `scanCount`/numbering stay consistent with mzML's
+                // handling of the same case.
+                let mut scan_el = BytesStart::new("scan");
+                scan_el.push_attribute(("num", scan_num.to_string().as_str()));
+                scan_el.push_attribute(("msLevel", "1"));
+                scan_el.push_attribute(("peaksCount", "0"));
+                writer.write_event(Event::Empty(scan_el))?;
+            }
+        }
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("msRun")))?;
+    writer.write_event(Event::End(BytesEnd::new("mzXML")))?;
+
+    Ok(())
+}
+
+fn write_instrument_value<W: Write>(writer: &mut Writer<W>, tag: &str, value: &str) -> Result<(), MzmlError> {
+    let mut el = BytesStart::new(tag);
+    el.push_attribute(("value", value));
+    writer.write_event(Event::Empty(el))?;
+    Ok(())
+}
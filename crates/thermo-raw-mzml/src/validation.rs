@@ -0,0 +1,519 @@
+//! Validate the `cvParam`s this crate emits against a PSI-MS OBO term table.
+//!
+//! [`cv`](crate::cv) hand-maintains accession/name/unit constants with no
+//! guarantee they stay in sync with the official ontology. This module loads
+//! a `psi-ms.obo` snapshot (caller-supplied path, since the full ontology is
+//! too large to bundle) into a lookup table, then checks a batch of
+//! `cvParam`s against it: accession exists, supplied name matches the
+//! canonical term name, the term isn't obsolete, and any unit accession is a
+//! known term.
+//!
+//! This does not attempt to enforce OBO `relationship: has_units` value
+//! restrictions -- the obo format expresses those inconsistently enough
+//! across ontologies that checking "is this a known unit term" is the
+//! practical stopping point.
+
+use crate::MzmlError;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A single `[Term]` stanza from a PSI-MS-style OBO file.
+#[derive(Debug, Clone)]
+pub struct OboTerm {
+    pub id: String,
+    pub name: String,
+    pub def: String,
+    pub is_obsolete: bool,
+}
+
+/// Accession -> term lookup table, parsed from an OBO file.
+#[derive(Debug, Clone, Default)]
+pub struct OboTermTable {
+    terms: HashMap<String, OboTerm>,
+}
+
+impl OboTermTable {
+    /// Load and parse an OBO file from disk.
+    pub fn load(path: &Path) -> Result<Self, MzmlError> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text)
+    }
+
+    /// Parse OBO text already read into memory.
+    pub fn parse(text: &str) -> Result<Self, MzmlError> {
+        let mut terms = HashMap::new();
+        let mut current: Option<OboTerm> = None;
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line == "[Term]" {
+                if let Some(term) = current.take() {
+                    terms.insert(term.id.clone(), term);
+                }
+                current = Some(OboTerm {
+                    id: String::new(),
+                    name: String::new(),
+                    def: String::new(),
+                    is_obsolete: false,
+                });
+                continue;
+            }
+            if line.starts_with('[') {
+                // Some other stanza type ([Typedef], [Instance], ...); stop
+                // accumulating into `current` until the next [Term].
+                if let Some(term) = current.take() {
+                    terms.insert(term.id.clone(), term);
+                }
+                continue;
+            }
+
+            let Some(term) = current.as_mut() else {
+                continue;
+            };
+            let Some((tag, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match tag {
+                "id" => term.id = value.to_string(),
+                "name" => term.name = value.to_string(),
+                "def" => term.def = value.trim_matches('"').to_string(),
+                "is_obsolete" => term.is_obsolete = value == "true",
+                _ => {}
+            }
+        }
+        if let Some(term) = current.take() {
+            terms.insert(term.id.clone(), term);
+        }
+
+        Ok(Self { terms })
+    }
+
+    /// Look up a term by accession (e.g. `"MS:1000511"`).
+    pub fn get(&self, accession: &str) -> Option<&OboTerm> {
+        self.terms.get(accession)
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+}
+
+/// One `cvParam` the writer is about to (or did) emit.
+#[derive(Debug, Clone, Copy)]
+pub struct CvParamCheck<'a> {
+    pub accession: &'a str,
+    /// The `name` attribute the writer would emit; empty means "value-only",
+    /// i.e. the accession itself carries the meaning (e.g. spectrum type).
+    pub name: &'a str,
+    pub unit_accession: Option<&'a str>,
+}
+
+/// A single validation failure for one `cvParam`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub accession: String,
+    pub problem: String,
+}
+
+/// The result of validating a batch of `cvParam`s.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Check each `cvParam` in `params` against `table`.
+pub fn validate(table: &OboTermTable, params: &[CvParamCheck]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    for param in params {
+        let Some(term) = table.get(param.accession) else {
+            report.mismatches.push(Mismatch {
+                accession: param.accession.to_string(),
+                problem: "accession not found in OBO table".to_string(),
+            });
+            continue;
+        };
+
+        if term.is_obsolete {
+            report.mismatches.push(Mismatch {
+                accession: param.accession.to_string(),
+                problem: format!("term \"{}\" is obsolete", term.name),
+            });
+        }
+
+        if !param.name.is_empty() && param.name != term.name {
+            report.mismatches.push(Mismatch {
+                accession: param.accession.to_string(),
+                problem: format!(
+                    "name mismatch: expected \"{}\", got \"{}\"",
+                    term.name, param.name
+                ),
+            });
+        }
+
+        if let Some(unit_accession) = param.unit_accession {
+            if table.get(unit_accession).is_none() {
+                report.mismatches.push(Mismatch {
+                    accession: param.accession.to_string(),
+                    problem: format!("unit accession \"{}\" not found in OBO table", unit_accession),
+                });
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cv;
+
+    /// A small, hand-trimmed snapshot of the real psi-ms.obo (plus the unit
+    /// ontology terms it references) covering a subset of the accessions this
+    /// crate's `cv` module uses. Not the full ontology -- just enough to
+    /// exercise the parser and check our constants stay in sync.
+    const SNAPSHOT_OBO: &str = r#"
+format-version: 1.2
+
+[Term]
+id: MS:1000511
+name: ms level
+def: "Stage of a multistage mass spectrometry experiment." [PSI:MS]
+
+[Term]
+id: MS:1000579
+name: MS1 spectrum
+def: "A mass spectrum arising from the first stage of a multi-stage mass spectrometry experiment." [PSI:MS]
+
+[Term]
+id: MS:1000580
+name: MSn spectrum
+def: "A mass spectrum arising from multi-stage mass spectrometry experiments with 2 or more stages." [PSI:MS]
+
+[Term]
+id: MS:1000127
+name: centroid spectrum
+def: "A profile mass spectrum that has been transformed into a centroided (or discrete) one." [PSI:MS]
+
+[Term]
+id: MS:1000128
+name: profile spectrum
+def: "A profile mass spectrum." [PSI:MS]
+
+[Term]
+id: MS:1000130
+name: positive scan
+def: "Polarity of scan is positive." [PSI:MS]
+
+[Term]
+id: MS:1000129
+name: negative scan
+def: "Polarity of scan is negative." [PSI:MS]
+
+[Term]
+id: MS:1000016
+name: scan start time
+def: "The time that an analyzer started a scan, relative to the start of the MS run." [PSI:MS]
+
+[Term]
+id: MS:1000285
+name: total ion current
+def: "The sum of all the separate ion currents carried by the ions of different m/z contributing to a mass spectrum." [PSI:MS]
+
+[Term]
+id: MS:1000504
+name: base peak m/z
+def: "M/z value of the signal of highest intensity in a mass spectrum." [PSI:MS]
+
+[Term]
+id: MS:1000505
+name: base peak intensity
+def: "Intensity of the signal of highest intensity in a mass spectrum." [PSI:MS]
+
+[Term]
+id: MS:1000528
+name: lowest observed m/z
+def: "Smallest m/z value in a mass spectrum." [PSI:MS]
+
+[Term]
+id: MS:1000527
+name: highest observed m/z
+def: "Largest m/z value in a mass spectrum." [PSI:MS]
+
+[Term]
+id: MS:1000514
+name: m/z array
+def: "A data array of m/z values." [PSI:MS]
+
+[Term]
+id: MS:1000515
+name: intensity array
+def: "A data array of intensity values." [PSI:MS]
+
+[Term]
+id: MS:1000595
+name: time array
+def: "A data array of time values." [PSI:MS]
+
+[Term]
+id: MS:1000523
+name: 64-bit float
+def: "Data type of binary data array with 64-bit precision." [PSI:MS]
+
+[Term]
+id: MS:1000521
+name: 32-bit float
+def: "Data type of binary data array with 32-bit precision." [PSI:MS]
+
+[Term]
+id: MS:1000574
+name: zlib compression
+def: "Zlib (gzip-style) data compression." [PSI:MS]
+
+[Term]
+id: MS:1000576
+name: no compression
+def: "No Compression." [PSI:MS]
+
+[Term]
+id: MS:1002312
+name: MS-Numpress linear prediction compression
+def: "A numerical compression algorithm that can be applied to ... m/z data." [PSI:MS]
+
+[Term]
+id: MS:1002313
+name: MS-Numpress positive integer compression
+def: "A numerical compression algorithm for intensities." [PSI:MS]
+
+[Term]
+id: MS:1002314
+name: MS-Numpress short logged float compression
+def: "A numerical compression algorithm for intensities, using log-scale encoding." [PSI:MS]
+
+[Term]
+id: MS:1000235
+name: total ion current chromatogram
+def: "Chromatogram of the total ion current." [PSI:MS]
+
+[Term]
+id: MS:1000628
+name: basepeak chromatogram
+def: "Chromatogram of the base peak (most intense ion) at each point." [PSI:MS]
+
+[Term]
+id: MS:1000626
+name: selected reaction monitoring chromatogram
+def: "Chromatogram created by a selected reaction monitoring (SRM) experiment." [PSI:MS]
+
+[Term]
+id: MS:1001581
+name: FAIMS compensation voltage
+def: "The compensation voltage applied to an ion at a given timepoint." [PSI:MS]
+
+[Term]
+id: MS:1002476
+name: ion mobility drift time
+def: "The drift time of an ion in a drift tube ion mobility experiment." [PSI:MS]
+
+[Term]
+id: MS:1000744
+name: selected ion m/z
+def: "M/z value of an ion selected for further analysis." [PSI:MS]
+
+[Term]
+id: MS:1000041
+name: charge state
+def: "The charge state of the ion." [PSI:MS]
+
+[Term]
+id: MS:1000042
+name: peak intensity
+def: "The intensity of a peak." [PSI:MS]
+
+[Term]
+id: MS:1000827
+name: isolation window target m/z
+def: "The primary or reference m/z about which an isolation window is defined." [PSI:MS]
+
+[Term]
+id: MS:1000828
+name: isolation window lower offset
+def: "The extent of the isolation window in m/z below the isolation window target m/z." [PSI:MS]
+
+[Term]
+id: MS:1000829
+name: isolation window upper offset
+def: "The extent of the isolation window in m/z above the isolation window target m/z." [PSI:MS]
+
+[Term]
+id: MS:1000045
+name: collision energy
+def: "The energy used in a collision-induced dissociation experiment." [PSI:MS]
+
+[Term]
+id: UO:0000031
+name: minute
+def: "A unit of time equal to 60 seconds." [UO]
+
+[Term]
+id: UO:0000028
+name: millisecond
+def: "A unit of time equal to one thousandth of a second." [UO]
+
+[Term]
+id: UO:0000218
+name: volt
+def: "A unit of electric potential." [UO]
+
+[Term]
+id: MS:1000599
+name: PQD
+is_obsolete: false
+
+[Term]
+id: MS:9999998
+name: deprecated placeholder term
+is_obsolete: true
+"#;
+
+    #[test]
+    fn test_parse_obo_snapshot() {
+        let table = OboTermTable::parse(SNAPSHOT_OBO).unwrap();
+        assert!(table.len() > 30);
+        let ms_level = table.get("MS:1000511").unwrap();
+        assert_eq!(ms_level.name, "ms level");
+        assert!(!ms_level.is_obsolete);
+    }
+
+    #[test]
+    fn test_parse_obo_marks_obsolete_terms() {
+        let table = OboTermTable::parse(SNAPSHOT_OBO).unwrap();
+        let obsolete = table.get("MS:9999998").unwrap();
+        assert!(obsolete.is_obsolete);
+    }
+
+    #[test]
+    fn test_validate_accepts_correct_cv_params() {
+        let table = OboTermTable::parse(SNAPSHOT_OBO).unwrap();
+        let params = [
+            CvParamCheck {
+                accession: cv::MS_LEVEL,
+                name: "ms level",
+                unit_accession: None,
+            },
+            CvParamCheck {
+                accession: cv::SCAN_START_TIME,
+                name: "scan start time",
+                unit_accession: Some(cv::MINUTE),
+            },
+        ];
+        let report = validate(&table, &params);
+        assert!(report.is_valid(), "{:?}", report.mismatches);
+    }
+
+    #[test]
+    fn test_validate_flags_unknown_accession() {
+        let table = OboTermTable::parse(SNAPSHOT_OBO).unwrap();
+        let params = [CvParamCheck {
+            accession: "MS:0000000",
+            name: "not a real term",
+            unit_accession: None,
+        }];
+        let report = validate(&table, &params);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_validate_flags_name_mismatch() {
+        let table = OboTermTable::parse(SNAPSHOT_OBO).unwrap();
+        let params = [CvParamCheck {
+            accession: cv::MS_LEVEL,
+            name: "wrong name entirely",
+            unit_accession: None,
+        }];
+        let report = validate(&table, &params);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_validate_flags_obsolete_term() {
+        let table = OboTermTable::parse(SNAPSHOT_OBO).unwrap();
+        let params = [CvParamCheck {
+            accession: "MS:9999998",
+            name: "deprecated placeholder term",
+            unit_accession: None,
+        }];
+        let report = validate(&table, &params);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_validate_flags_unknown_unit() {
+        let table = OboTermTable::parse(SNAPSHOT_OBO).unwrap();
+        let params = [CvParamCheck {
+            accession: cv::SCAN_START_TIME,
+            name: "scan start time",
+            unit_accession: Some("UO:9999999"),
+        }];
+        let report = validate(&table, &params);
+        assert!(!report.is_valid());
+    }
+
+    /// Validate the crate's full hand-maintained accession/name constant set
+    /// against the bundled snapshot, catching drift between `cv` and the
+    /// ontology it's meant to track. `ZSTD_COMPRESSION` and `NUMPRESS_LINEAR`/
+    /// `NUMPRESS_PIC`/`NUMPRESS_SLOF` are excluded: they're explicitly
+    /// documented placeholders, not real PSI-MS accessions.
+    #[test]
+    fn test_validate_full_cv_constant_set() {
+        let table = OboTermTable::parse(SNAPSHOT_OBO).unwrap();
+        let params = [
+            CvParamCheck { accession: cv::MS1_SPECTRUM, name: "MS1 spectrum", unit_accession: None },
+            CvParamCheck { accession: cv::MSN_SPECTRUM, name: "MSn spectrum", unit_accession: None },
+            CvParamCheck { accession: cv::MS_LEVEL, name: "ms level", unit_accession: None },
+            CvParamCheck { accession: cv::CENTROID_SPECTRUM, name: "centroid spectrum", unit_accession: None },
+            CvParamCheck { accession: cv::PROFILE_SPECTRUM, name: "profile spectrum", unit_accession: None },
+            CvParamCheck { accession: cv::POSITIVE_SCAN, name: "positive scan", unit_accession: None },
+            CvParamCheck { accession: cv::NEGATIVE_SCAN, name: "negative scan", unit_accession: None },
+            CvParamCheck { accession: cv::SCAN_START_TIME, name: "scan start time", unit_accession: Some(cv::MINUTE) },
+            CvParamCheck { accession: cv::TOTAL_ION_CURRENT, name: "total ion current", unit_accession: None },
+            CvParamCheck { accession: cv::BASE_PEAK_MZ, name: "base peak m/z", unit_accession: None },
+            CvParamCheck { accession: cv::BASE_PEAK_INTENSITY, name: "base peak intensity", unit_accession: None },
+            CvParamCheck { accession: cv::LOWEST_MZ, name: "lowest observed m/z", unit_accession: None },
+            CvParamCheck { accession: cv::HIGHEST_MZ, name: "highest observed m/z", unit_accession: None },
+            CvParamCheck { accession: cv::MZ_ARRAY, name: "m/z array", unit_accession: None },
+            CvParamCheck { accession: cv::INTENSITY_ARRAY, name: "intensity array", unit_accession: None },
+            CvParamCheck { accession: cv::TIME_ARRAY, name: "time array", unit_accession: None },
+            CvParamCheck { accession: cv::FLOAT_64, name: "64-bit float", unit_accession: None },
+            CvParamCheck { accession: cv::FLOAT_32, name: "32-bit float", unit_accession: None },
+            CvParamCheck { accession: cv::ZLIB_COMPRESSION, name: "zlib compression", unit_accession: None },
+            CvParamCheck { accession: cv::NO_COMPRESSION, name: "no compression", unit_accession: None },
+            CvParamCheck { accession: cv::TIC_CHROMATOGRAM, name: "total ion current chromatogram", unit_accession: None },
+            CvParamCheck { accession: cv::BPC_CHROMATOGRAM, name: "basepeak chromatogram", unit_accession: None },
+            CvParamCheck { accession: cv::SRM_CHROMATOGRAM, name: "selected reaction monitoring chromatogram", unit_accession: None },
+            CvParamCheck { accession: cv::FAIMS_COMPENSATION_VOLTAGE, name: "FAIMS compensation voltage", unit_accession: Some(cv::VOLT) },
+            CvParamCheck { accession: cv::ION_MOBILITY_DRIFT_TIME, name: "ion mobility drift time", unit_accession: None },
+            CvParamCheck { accession: cv::SELECTED_ION_MZ, name: "selected ion m/z", unit_accession: None },
+            CvParamCheck { accession: cv::CHARGE_STATE, name: "charge state", unit_accession: None },
+            CvParamCheck { accession: cv::PEAK_INTENSITY, name: "peak intensity", unit_accession: None },
+            CvParamCheck { accession: cv::ISOLATION_WINDOW_TARGET, name: "isolation window target m/z", unit_accession: None },
+            CvParamCheck { accession: cv::ISOLATION_WINDOW_LOWER, name: "isolation window lower offset", unit_accession: None },
+            CvParamCheck { accession: cv::ISOLATION_WINDOW_UPPER, name: "isolation window upper offset", unit_accession: None },
+            CvParamCheck { accession: cv::COLLISION_ENERGY, name: "collision energy", unit_accession: None },
+        ];
+        let report = validate(&table, &params);
+        assert!(report.is_valid(), "{:?}", report.mismatches);
+    }
+}
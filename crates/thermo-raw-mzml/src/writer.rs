@@ -5,7 +5,7 @@
 
 use crate::binary;
 use crate::cv;
-use crate::{Compression, MzmlConfig, MzmlError, Precision};
+use crate::{Compression, MzmlConfig, MzmlError, Precision, SpectrumData};
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::Writer;
 use sha1::{Digest, Sha1};
@@ -94,6 +94,48 @@ fn has_profile_data(raw: &RawFile) -> bool {
         .any(|e| matches!(e.preamble.scan_mode, ScanMode::Profile))
 }
 
+/// True if `scan` matches every filter configured on `config` (MS level
+/// whitelist, retention-time window, precursor m/z window). Scans that fail
+/// to decode are handled by the caller before this is ever reached, so every
+/// filter here assumes a successfully-decoded `Scan`.
+pub(crate) fn scan_passes_filters(scan: &thermo_raw::types::Scan, config: &MzmlConfig) -> bool {
+    if let Some(levels) = &config.ms_levels {
+        if !levels.contains(&cv::ms_level_as_u8(&scan.ms_level)) {
+            return false;
+        }
+    }
+    if let Some((min_rt, max_rt)) = config.rt_range {
+        if scan.rt < min_rt || scan.rt > max_rt {
+            return false;
+        }
+    }
+    if let Some((min_mz, max_mz)) = config.precursor_mz_range {
+        match &scan.precursor {
+            Some(precursor) if precursor.mz >= min_mz && precursor.mz <= max_mz => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Resolve which scan numbers will actually be written, applying
+/// `config`'s spectrum selection filters. Scans that fail to decode are
+/// always kept (and later emitted as empty placeholders), matching the
+/// unfiltered behavior of the write loop. Done as a first pass so the
+/// `spectrumList` `count` attribute and offset index only ever reflect what
+/// is actually written.
+pub(crate) fn filtered_scan_numbers(raw: &RawFile, config: &MzmlConfig) -> Vec<u32> {
+    if config.ms_levels.is_none() && config.rt_range.is_none() && config.precursor_mz_range.is_none() {
+        return (raw.first_scan()..=raw.last_scan()).collect();
+    }
+    (raw.first_scan()..=raw.last_scan())
+        .filter(|&scan_num| match raw.scan(scan_num) {
+            Ok(scan) => scan_passes_filters(&scan, config),
+            Err(_) => true,
+        })
+        .collect()
+}
+
 /// Write a complete indexed mzML document.
 pub fn write_mzml<W: Write>(
     raw: &RawFile,
@@ -101,12 +143,41 @@ pub fn write_mzml<W: Write>(
     config: &MzmlConfig,
     source_filename: &str,
 ) -> Result<(), MzmlError> {
-    let n_scans = raw.n_scans();
+    write_mzml_inner(raw, output, config, source_filename, None)
+}
+
+/// Like [`write_mzml`], but ticks `counter` once per spectrum as it's
+/// written, live mid-stream rather than in one batch at the end -- lets a
+/// poller reflect this file's real write progress instead of jumping from
+/// 0 to done.
+pub fn write_mzml_with_progress<W: Write>(
+    raw: &RawFile,
+    output: W,
+    config: &MzmlConfig,
+    source_filename: &str,
+    counter: &thermo_raw::ProgressCounter,
+) -> Result<(), MzmlError> {
+    write_mzml_inner(raw, output, config, source_filename, Some(counter))
+}
+
+fn write_mzml_inner<W: Write>(
+    raw: &RawFile,
+    output: W,
+    config: &MzmlConfig,
+    source_filename: &str,
+    progress: Option<&thermo_raw::ProgressCounter>,
+) -> Result<(), MzmlError> {
+    let scan_numbers = filtered_scan_numbers(raw, config);
     let instrument = detect_instrument(raw);
     let _has_profile = has_profile_data(raw);
 
-    // Count chromatograms (TIC + BPC)
-    let n_chromatograms = 2u32;
+    let srm_chromatograms = match config.srm_mz_tolerance {
+        Some(tol) => raw.srm_chromatograms(tol)?,
+        None => Vec::new(),
+    };
+
+    // Count chromatograms (TIC + BPC + one per targeted transition)
+    let n_chromatograms = 2u32 + srm_chromatograms.len() as u32;
 
     if config.write_index {
         write_indexed_mzml(
@@ -114,9 +185,11 @@ pub fn write_mzml<W: Write>(
             output,
             config,
             source_filename,
-            n_scans,
+            &scan_numbers,
             n_chromatograms,
             &instrument,
+            &srm_chromatograms,
+            progress,
         )
     } else {
         write_plain_mzml(
@@ -124,21 +197,266 @@ pub fn write_mzml<W: Write>(
             output,
             config,
             source_filename,
-            n_scans,
+            &scan_numbers,
             n_chromatograms,
             &instrument,
+            &srm_chromatograms,
+            progress,
         )
     }
 }
 
+/// Minimal async byte-sink abstraction, analogous to `tokio::io::AsyncWrite`
+/// but defined locally so this crate can offer an async writer without
+/// pulling in an async runtime as a hard dependency. Implement this for
+/// whatever async I/O handle the caller already has -- a `tokio::fs::File`,
+/// an object-store upload stream, a `tokio::net::TcpStream` -- via a thin
+/// wrapper that forwards to that type's own `write_all`.
+pub trait AsyncByteSink {
+    /// Write `buf` in full, or return an error. Mirrors the contract of
+    /// `tokio::io::AsyncWriteExt::write_all`.
+    async fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()>;
+}
+
+impl AsyncByteSink for Vec<u8> {
+    async fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+}
+
+/// Async counterpart to [`CountingWriter`]: tracks byte offset and a running
+/// SHA-1 hash over everything written to an [`AsyncByteSink`], so the async
+/// indexed writer can still emit a correct `<indexList>`, `<indexListOffset>`,
+/// and `<fileChecksum>`.
+struct AsyncCountingSink<W: AsyncByteSink> {
+    inner: W,
+    bytes_written: u64,
+    hasher: Sha1,
+}
+
+impl<W: AsyncByteSink> AsyncCountingSink<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            bytes_written: 0,
+            hasher: Sha1::new(),
+        }
+    }
+
+    fn position(&self) -> u64 {
+        self.bytes_written
+    }
+
+    fn finish_hash(&self) -> String {
+        let result = self.hasher.clone().finalize();
+        hex::encode(&result)
+    }
+
+    async fn write_chunk(&mut self, buf: &[u8]) -> Result<(), MzmlError> {
+        self.inner.write_all(buf).await.map_err(MzmlError::Io)?;
+        self.bytes_written += buf.len() as u64;
+        self.hasher.update(buf);
+        Ok(())
+    }
+}
+
+/// Render one chunk of mzML markup into an in-memory buffer using the same
+/// `quick_xml::Writer` and element-construction functions the sync writer
+/// uses, then flush it to the async sink. Splitting the document into
+/// independently-rendered chunks means indentation depth resets at each
+/// chunk boundary -- that only affects pretty-printing whitespace, not
+/// document validity (every element is still explicitly opened and closed).
+async fn render_and_flush<W: AsyncByteSink>(
+    sink: &mut AsyncCountingSink<W>,
+    render: impl FnOnce(&mut Writer<Vec<u8>>) -> Result<(), MzmlError>,
+) -> Result<(), MzmlError> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    render(&mut writer)?;
+    let buf = writer.into_inner();
+    sink.write_chunk(&buf).await
+}
+
+/// Write a complete indexed mzML document to an async sink.
+///
+/// Mirrors [`write_mzml`], but drives the same document structure over an
+/// [`AsyncByteSink`] and awaits each spectrum flush, so conversions can run
+/// inside async pipelines (servers, object-store uploads) without blocking a
+/// worker thread on synchronous I/O. Shares all CV-param and element
+/// construction code with the sync writer -- [`write_mzml_header`],
+/// [`write_spectrum`]/[`write_empty_spectrum`], [`write_chromatogram`], and
+/// [`write_srm_chromatogram`] are called exactly as the sync path calls them,
+/// just flushed chunk-by-chunk instead of threaded through one long-lived
+/// `Write` impl.
+pub async fn write_mzml_async<W: AsyncByteSink>(
+    raw: &RawFile,
+    output: W,
+    config: &MzmlConfig,
+    source_filename: &str,
+) -> Result<(), MzmlError> {
+    let scan_numbers = filtered_scan_numbers(raw, config);
+    let instrument = detect_instrument(raw);
+
+    let srm_chromatograms = match config.srm_mz_tolerance {
+        Some(tol) => raw.srm_chromatograms(tol)?,
+        None => Vec::new(),
+    };
+    let n_chromatograms = 2u32 + srm_chromatograms.len() as u32;
+
+    let mut sink = AsyncCountingSink::new(output);
+    let mut spectrum_offsets: Vec<(String, u64)> = Vec::with_capacity(scan_numbers.len());
+    let mut chromatogram_offsets: Vec<(String, u64)> = Vec::with_capacity(n_chromatograms as usize);
+    let mut scratch = binary::ScratchBuffers::new();
+
+    // XML declaration (+ newline, indexed mode only, matching the sync writer)
+    render_and_flush(&mut sink, |w| {
+        w.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+        if config.write_index {
+            w.get_mut().write_all(b"\n").map_err(quick_xml::Error::from)?;
+        }
+        Ok(())
+    })
+    .await?;
+
+    if config.write_index {
+        render_and_flush(&mut sink, |w| {
+            let mut indexed_start = BytesStart::new("indexedmzML");
+            indexed_start.push_attribute(("xmlns", "http://psi.hupo.org/ms/mzml"));
+            indexed_start.push_attribute((
+                "xmlns:xsi",
+                "http://www.w3.org/2001/XMLSchema-instance",
+            ));
+            indexed_start.push_attribute((
+                "xsi:schemaLocation",
+                "http://psi.hupo.org/ms/mzml http://psidev.info/files/ms/mzML/xsd/mzML1.1.2_idx.xsd",
+            ));
+            w.write_event(Event::Start(indexed_start))
+        })
+        .await?;
+    }
+
+    render_and_flush(&mut sink, |w| {
+        write_mzml_header(w, raw, source_filename, scan_numbers.len() as u32, &instrument)
+    })
+    .await?;
+
+    for (scan_idx, &scan_num) in scan_numbers.iter().enumerate() {
+        let spectrum_id = format!("scan={}", scan_num);
+
+        if config.write_index {
+            spectrum_offsets.push((spectrum_id.clone(), sink.position()));
+        }
+
+        render_and_flush(&mut sink, |w| match raw.scan(scan_num) {
+            Ok(scan) => write_spectrum(w, raw, &scan, scan_idx, &spectrum_id, config, &mut scratch),
+            Err(_) => write_empty_spectrum(w, scan_num, scan_idx, &spectrum_id),
+        })
+        .await?;
+    }
+
+    render_and_flush(&mut sink, |w| {
+        w.write_event(Event::End(BytesEnd::new("spectrumList")))?;
+        let mut chrom_list = BytesStart::new("chromatogramList");
+        chrom_list.push_attribute(("count", n_chromatograms.to_string().as_str()));
+        chrom_list.push_attribute(("defaultDataProcessingRef", "dp1"));
+        w.write_event(Event::Start(chrom_list))
+    })
+    .await?;
+
+    {
+        let tic_id = "TIC";
+        if config.write_index {
+            chromatogram_offsets.push((tic_id.to_string(), sink.position()));
+        }
+        let tic = raw.tic();
+        render_and_flush(&mut sink, |w| {
+            write_chromatogram(w, tic_id, 0, &tic.rt, &tic.intensity, cv::TIC_CHROMATOGRAM, config, &mut scratch)
+        })
+        .await?;
+    }
+
+    {
+        let bpc_id = "BPC";
+        if config.write_index {
+            chromatogram_offsets.push((bpc_id.to_string(), sink.position()));
+        }
+        let bpc = raw.bpc();
+        render_and_flush(&mut sink, |w| {
+            write_chromatogram(w, bpc_id, 1, &bpc.rt, &bpc.intensity, cv::BPC_CHROMATOGRAM, config, &mut scratch)
+        })
+        .await?;
+    }
+
+    for (i, transition) in srm_chromatograms.iter().enumerate() {
+        let id = format!("SRM SIC {:.4}", transition.precursor_mz);
+        if config.write_index {
+            chromatogram_offsets.push((id.clone(), sink.position()));
+        }
+        render_and_flush(&mut sink, |w| {
+            write_srm_chromatogram(w, &id, 2 + i, transition, config, &mut scratch)
+        })
+        .await?;
+    }
+
+    render_and_flush(&mut sink, |w| {
+        w.write_event(Event::End(BytesEnd::new("chromatogramList")))?;
+        w.write_event(Event::End(BytesEnd::new("run")))?;
+        w.write_event(Event::End(BytesEnd::new("mzML")))?;
+        Ok(())
+    })
+    .await?;
+
+    if config.write_index {
+        let index_list_offset = sink.position();
+
+        render_and_flush(&mut sink, |w| {
+            let mut idx_list = BytesStart::new("indexList");
+            idx_list.push_attribute(("count", "2"));
+            w.write_event(Event::Start(idx_list))?;
+            write_index(w, "spectrum", &spectrum_offsets)?;
+            write_index(w, "chromatogram", &chromatogram_offsets)?;
+            w.write_event(Event::End(BytesEnd::new("indexList")))?;
+            w.write_event(Event::Start(BytesStart::new("indexListOffset")))?;
+            w.write_event(Event::Text(BytesText::new(&index_list_offset.to_string())))?;
+            w.write_event(Event::End(BytesEnd::new("indexListOffset")))
+        })
+        .await?;
+
+        // <fileChecksum> -- SHA-1 of everything written so far, including the
+        // opening <fileChecksum> tag itself (per the indexedmzML spec, and
+        // matching the sync indexed writer).
+        render_and_flush(&mut sink, |w| {
+            w.write_event(Event::Start(BytesStart::new("fileChecksum")))
+        })
+        .await?;
+        let hash = sink.finish_hash();
+        render_and_flush(&mut sink, |w| {
+            w.write_event(Event::Text(BytesText::new(&hash)))?;
+            w.write_event(Event::End(BytesEnd::new("fileChecksum")))?;
+            w.write_event(Event::End(BytesEnd::new("indexedmzML")))
+        })
+        .await?;
+
+        sink.write_chunk(b"\n").await?;
+    }
+
+    Ok(())
+}
+
+/// Write a bare `<mzML>` document with no `<indexedmzML>` wrapper, offset
+/// index, or checksum. Used when [`MzmlConfig::write_index`] is `false`.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments)]
 fn write_plain_mzml<W: Write>(
     raw: &RawFile,
     output: W,
     config: &MzmlConfig,
     source_filename: &str,
-    n_scans: u32,
+    scan_numbers: &[u32],
     n_chromatograms: u32,
     instrument: &InstrumentInfo,
+    srm_chromatograms: &[thermo_raw::types::TransitionChromatogram],
+    progress: Option<&thermo_raw::ProgressCounter>,
 ) -> Result<(), MzmlError> {
     // Use CountingWriter even in plain mode (negligible overhead, simpler code)
     let counting = CountingWriter::new(output);
@@ -152,25 +470,36 @@ fn write_plain_mzml<W: Write>(
         raw,
         config,
         source_filename,
-        n_scans,
+        scan_numbers,
         n_chromatograms,
         instrument,
         &mut Vec::new(),
         &mut Vec::new(),
         false,
+        srm_chromatograms,
+        progress,
     )?;
 
     Ok(())
 }
 
+/// Write a complete `<indexedmzML>` document: the `<mzML>` body wrapped so
+/// every `<spectrum>`/`<chromatogram>` start offset (tracked via
+/// [`CountingWriter`]) is recorded into `<indexList>`, followed by an
+/// `<indexListOffset>` pointing back at that list and a `<fileChecksum>`
+/// holding the running SHA-1 over everything written so far -- satisfying
+/// the indexedmzML spec's O(1)-seek-by-id contract.
+#[allow(clippy::too_many_arguments)]
 fn write_indexed_mzml<W: Write>(
     raw: &RawFile,
     output: W,
     config: &MzmlConfig,
     source_filename: &str,
-    n_scans: u32,
+    scan_numbers: &[u32],
     n_chromatograms: u32,
     instrument: &InstrumentInfo,
+    srm_chromatograms: &[thermo_raw::types::TransitionChromatogram],
+    progress: Option<&thermo_raw::ProgressCounter>,
 ) -> Result<(), MzmlError> {
     let counting = CountingWriter::new(output);
     let mut writer = Writer::new_with_indent(counting, b' ', 2);
@@ -196,7 +525,7 @@ fn write_indexed_mzml<W: Write>(
     ));
     writer.write_event(Event::Start(indexed_start))?;
 
-    let mut spectrum_offsets: Vec<(String, u64)> = Vec::with_capacity(n_scans as usize);
+    let mut spectrum_offsets: Vec<(String, u64)> = Vec::with_capacity(scan_numbers.len());
     let mut chromatogram_offsets: Vec<(String, u64)> = Vec::with_capacity(n_chromatograms as usize);
 
     write_mzml_body(
@@ -204,12 +533,14 @@ fn write_indexed_mzml<W: Write>(
         raw,
         config,
         source_filename,
-        n_scans,
+        scan_numbers,
         n_chromatograms,
         instrument,
         &mut spectrum_offsets,
         &mut chromatogram_offsets,
         true,
+        srm_chromatograms,
+        progress,
     )?;
 
     // <indexList>
@@ -232,13 +563,11 @@ fn write_indexed_mzml<W: Write>(
     writer.write_event(Event::Text(BytesText::new(&index_list_offset.to_string())))?;
     writer.write_event(Event::End(BytesEnd::new("indexListOffset")))?;
 
-    // <fileChecksum> - SHA-1 of everything written so far
-    // We need to finalize the hash BEFORE writing the checksum element itself.
-    // The spec says the checksum covers everything up to (but not including)
-    // the <fileChecksum> element. To do this precisely, we compute the hash
-    // at this point, then write it.
-    let hash = writer.get_ref().finish_hash();
+    // <fileChecksum> - SHA-1 of everything written so far, including the
+    // opening <fileChecksum> tag itself (per the indexedmzML spec). Write
+    // the start tag first, then finalize the hash before writing its text.
     writer.write_event(Event::Start(BytesStart::new("fileChecksum")))?;
+    let hash = writer.get_ref().finish_hash();
     writer.write_event(Event::Text(BytesText::new(&hash)))?;
     writer.write_event(Event::End(BytesEnd::new("fileChecksum")))?;
 
@@ -277,19 +606,17 @@ fn write_index<W: Write>(
     Ok(())
 }
 
-/// Write the <mzML> body (shared between plain and indexed modes).
-#[allow(clippy::too_many_arguments)]
-fn write_mzml_body<W: Write>(
-    writer: &mut Writer<CountingWriter<W>>,
+/// Write the <mzML> header: everything from the opening `<mzML>` tag through
+/// the `<spectrumList>` start tag. Shared between the sync body builder below
+/// and the async writer ([`write_mzml_async`]), since neither the element
+/// construction nor the CV-param lookups here depend on how the caller
+/// eventually flushes bytes.
+fn write_mzml_header<W: Write>(
+    writer: &mut Writer<W>,
     raw: &RawFile,
-    config: &MzmlConfig,
     source_filename: &str,
     n_scans: u32,
-    n_chromatograms: u32,
     instrument: &InstrumentInfo,
-    spectrum_offsets: &mut Vec<(String, u64)>,
-    chromatogram_offsets: &mut Vec<(String, u64)>,
-    track_offsets: bool,
 ) -> Result<(), MzmlError> {
     // <mzML>
     let mut mzml_start = BytesStart::new("mzML");
@@ -335,9 +662,35 @@ fn write_mzml_body<W: Write>(
     spec_list.push_attribute(("defaultDataProcessingRef", "dp1"));
     writer.write_event(Event::Start(spec_list))?;
 
+    Ok(())
+}
+
+/// Write the <mzML> body (shared between plain and indexed modes).
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments)]
+fn write_mzml_body<W: Write>(
+    writer: &mut Writer<CountingWriter<W>>,
+    raw: &RawFile,
+    config: &MzmlConfig,
+    source_filename: &str,
+    scan_numbers: &[u32],
+    n_chromatograms: u32,
+    instrument: &InstrumentInfo,
+    spectrum_offsets: &mut Vec<(String, u64)>,
+    chromatogram_offsets: &mut Vec<(String, u64)>,
+    track_offsets: bool,
+    srm_chromatograms: &[thermo_raw::types::TransitionChromatogram],
+    progress: Option<&thermo_raw::ProgressCounter>,
+) -> Result<(), MzmlError> {
+    write_mzml_header(writer, raw, source_filename, scan_numbers.len() as u32, instrument)?;
+
+    // Scratch buffers for binary-array encoding, reused across every
+    // spectrum/chromatogram in this document instead of allocating fresh
+    // LE-byte/compression/base64 buffers per array.
+    let mut scratch = binary::ScratchBuffers::new();
+
     // Write each spectrum
-    for scan_num in raw.first_scan()..=raw.last_scan() {
-        let scan_idx = (scan_num - raw.first_scan()) as usize;
+    for (scan_idx, &scan_num) in scan_numbers.iter().enumerate() {
         let spectrum_id = format!("scan={}", scan_num);
 
         if track_offsets {
@@ -347,12 +700,16 @@ fn write_mzml_body<W: Write>(
 
         match raw.scan(scan_num) {
             Ok(scan) => {
-                write_spectrum(writer, &scan, scan_idx, &spectrum_id, config)?;
+                write_spectrum(writer, raw, &scan, scan_idx, &spectrum_id, config, &mut scratch)?;
             }
             Err(_) => {
                 write_empty_spectrum(writer, scan_num, scan_idx, &spectrum_id)?;
             }
         }
+
+        if let Some(counter) = progress {
+            thermo_raw::progress::tick(counter);
+        }
     }
 
     writer.write_event(Event::End(BytesEnd::new("spectrumList")))?;
@@ -371,7 +728,7 @@ fn write_mzml_body<W: Write>(
             chromatogram_offsets.push((tic_id.to_string(), offset));
         }
         let tic = raw.tic();
-        write_chromatogram(writer, tic_id, 0, &tic.rt, &tic.intensity, cv::TIC_CHROMATOGRAM, config)?;
+        write_chromatogram(writer, tic_id, 0, &tic.rt, &tic.intensity, cv::TIC_CHROMATOGRAM, config, &mut scratch)?;
     }
 
     // BPC
@@ -382,7 +739,17 @@ fn write_mzml_body<W: Write>(
             chromatogram_offsets.push((bpc_id.to_string(), offset));
         }
         let bpc = raw.bpc();
-        write_chromatogram(writer, bpc_id, 1, &bpc.rt, &bpc.intensity, cv::BPC_CHROMATOGRAM, config)?;
+        write_chromatogram(writer, bpc_id, 1, &bpc.rt, &bpc.intensity, cv::BPC_CHROMATOGRAM, config, &mut scratch)?;
+    }
+
+    // SRM/MRM/PRM transitions, one chromatogram per distinct isolation target.
+    for (i, transition) in srm_chromatograms.iter().enumerate() {
+        let id = format!("SRM SIC {:.4}", transition.precursor_mz);
+        if track_offsets {
+            let offset = writer.get_ref().position();
+            chromatogram_offsets.push((id.clone(), offset));
+        }
+        write_srm_chromatogram(writer, &id, 2 + i, transition, config, &mut scratch)?;
     }
 
     writer.write_event(Event::End(BytesEnd::new("chromatogramList")))?;
@@ -554,15 +921,36 @@ fn write_data_processing<W: Write>(writer: &mut Writer<W>) -> Result<(), MzmlErr
 }
 
 /// Write a single <spectrum> element.
+#[allow(clippy::too_many_arguments)]
 fn write_spectrum<W: Write>(
     writer: &mut Writer<W>,
+    raw: &RawFile,
     scan: &thermo_raw::Scan,
     index: usize,
     spectrum_id: &str,
     config: &MzmlConfig,
+    scratch: &mut binary::ScratchBuffers,
 ) -> Result<(), MzmlError> {
-    let n_peaks = scan.centroid_mz.len();
-    let default_array_length = n_peaks.to_string();
+    let has_profile = scan.profile_mz.as_ref().is_some_and(|mz| !mz.is_empty());
+    let use_profile = has_profile && !matches!(config.spectrum_data, SpectrumData::Centroid);
+
+    let (primary_mz, primary_intensity, spectrum_data_cv, spectrum_data_name) = if use_profile {
+        (
+            scan.profile_mz.as_deref().unwrap_or(&[]),
+            scan.profile_intensity.as_deref().unwrap_or(&[]),
+            cv::PROFILE_SPECTRUM,
+            "profile spectrum",
+        )
+    } else {
+        (
+            scan.centroid_mz.as_slice(),
+            scan.centroid_intensity.as_slice(),
+            cv::CENTROID_SPECTRUM,
+            "centroid spectrum",
+        )
+    };
+
+    let default_array_length = primary_mz.len().to_string();
 
     let mut spec = BytesStart::new("spectrum");
     spec.push_attribute(("index", index.to_string().as_str()));
@@ -573,7 +961,7 @@ fn write_spectrum<W: Write>(
     // Spectrum type CV params
     write_cv_param(writer, cv::spectrum_type(&scan.ms_level), "", None, None)?;
     write_cv_param(writer, cv::MS_LEVEL, "ms level", Some(cv::ms_level_value(&scan.ms_level)), None)?;
-    write_cv_param(writer, cv::CENTROID_SPECTRUM, "centroid spectrum", None, None)?;
+    write_cv_param(writer, spectrum_data_cv, spectrum_data_name, None, None)?;
 
     // Polarity
     if let Some(pol_acc) = cv::polarity_accession(&scan.polarity) {
@@ -585,10 +973,10 @@ fn write_spectrum<W: Write>(
     write_cv_param(writer, cv::BASE_PEAK_INTENSITY, "base peak intensity", Some(&format!("{:.4}", scan.base_peak_intensity)), None)?;
     write_cv_param(writer, cv::TOTAL_ION_CURRENT, "total ion current", Some(&format!("{:.4}", scan.tic)), None)?;
 
-    // m/z range
-    if !scan.centroid_mz.is_empty() {
-        let low = scan.centroid_mz.first().unwrap();
-        let high = scan.centroid_mz.last().unwrap();
+    // m/z range, from whichever array is primary for this spectrum
+    if !primary_mz.is_empty() {
+        let low = primary_mz.first().unwrap();
+        let high = primary_mz.last().unwrap();
         write_cv_param(writer, cv::LOWEST_MZ, "lowest observed m/z", Some(&format!("{:.10}", low)), None)?;
         write_cv_param(writer, cv::HIGHEST_MZ, "highest observed m/z", Some(&format!("{:.10}", high)), None)?;
     }
@@ -607,6 +995,26 @@ fn write_spectrum<W: Write>(
         cv::MINUTE,
         "minute",
     )?;
+    if let Some(cv_volts) = scan.compensation_voltage {
+        write_cv_param_with_unit(
+            writer,
+            cv::faims_cv_accession(),
+            "FAIMS compensation voltage",
+            &format!("{:.3}", cv_volts),
+            cv::VOLT,
+            "volt",
+        )?;
+    }
+    if let Some(drift_time) = scan.ion_mobility {
+        write_cv_param_with_unit(
+            writer,
+            cv::ION_MOBILITY_DRIFT_TIME,
+            "ion mobility drift time",
+            &format!("{:.6}", drift_time),
+            cv::MILLISECOND,
+            "millisecond",
+        )?;
+    }
     // Filter string as userParam
     if let Some(ref filter) = scan.filter_string {
         let mut up = BytesStart::new("userParam");
@@ -621,12 +1029,27 @@ fn write_spectrum<W: Write>(
     // <precursorList> for MS2+ scans
     if !matches!(scan.ms_level, MsLevel::Ms1) {
         if let Some(ref precursor) = scan.precursor {
-            write_precursor(writer, precursor, scan.scan_number)?;
+            write_precursor(writer, raw, precursor, scan.scan_number, config)?;
         }
     }
 
     // <binaryDataArrayList>
-    write_binary_data_arrays(writer, &scan.centroid_mz, &scan.centroid_intensity, config)?;
+    let write_both = matches!(config.spectrum_data, SpectrumData::Both)
+        && has_profile
+        && !scan.centroid_mz.is_empty();
+    if write_both {
+        write_binary_data_array_pairs(
+            writer,
+            &[
+                (primary_mz, primary_intensity),
+                (scan.centroid_mz.as_slice(), scan.centroid_intensity.as_slice()),
+            ],
+            config,
+            scratch,
+        )?;
+    } else {
+        write_binary_data_arrays(writer, primary_mz, primary_intensity, config, scratch)?;
+    }
 
     writer.write_event(Event::End(BytesEnd::new("spectrum")))?;
     Ok(())
@@ -667,12 +1090,49 @@ fn write_empty_spectrum<W: Write>(
     Ok(())
 }
 
+/// Locate the nearest MS1 survey scan preceding `scan_number`, decoding
+/// scans backward from `scan_number - 1` down to the file's first scan.
+/// Scans that fail to decode are skipped rather than treated as a stop
+/// condition, since a single corrupt trailer shouldn't block refinement
+/// against an earlier good MS1.
+fn find_prior_ms1(raw: &RawFile, scan_number: u32) -> Option<thermo_raw::Scan> {
+    let first = raw.first_scan();
+    let mut num = scan_number;
+    while num > first {
+        num -= 1;
+        if let Ok(candidate) = raw.scan(num) {
+            if matches!(candidate.ms_level, MsLevel::Ms1) {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
 /// Write precursor information for MS2+ scans.
 fn write_precursor<W: Write>(
     writer: &mut Writer<W>,
+    raw: &RawFile,
     precursor: &thermo_raw::PrecursorInfo,
-    _scan_number: u32,
+    scan_number: u32,
+    config: &MzmlConfig,
 ) -> Result<(), MzmlError> {
+    let refined = config.precursor_refinement.and_then(|refine_cfg| {
+        let ms1 = find_prior_ms1(raw, scan_number)?;
+        let (lo, hi) = refine_cfg.charge_range;
+        thermo_raw::precursor_refinement::refine_monoisotopic_precursor(
+            &ms1.centroid_mz,
+            &ms1.centroid_intensity,
+            precursor.mz,
+            refine_cfg.tolerance,
+            lo..=hi,
+        )
+    });
+    let (selected_mz, selected_charge) = match refined {
+        Some(r) => (r.mz, Some(r.charge)),
+        None => (precursor.mz, precursor.charge),
+    };
+
     let mut pl = BytesStart::new("precursorList");
     pl.push_attribute(("count", "1"));
     writer.write_event(Event::Start(pl))?;
@@ -717,10 +1177,10 @@ fn write_precursor<W: Write>(
         writer,
         cv::SELECTED_ION_MZ,
         "selected ion m/z",
-        Some(&format!("{:.10}", precursor.mz)),
+        Some(&format!("{:.10}", selected_mz)),
         None,
     )?;
-    if let Some(charge) = precursor.charge {
+    if let Some(charge) = selected_charge {
         write_cv_param(
             writer,
             cv::CHARGE_STATE,
@@ -761,36 +1221,55 @@ fn write_binary_data_arrays<W: Write>(
     mz: &[f64],
     intensity: &[f64],
     config: &MzmlConfig,
+    scratch: &mut binary::ScratchBuffers,
+) -> Result<(), MzmlError> {
+    write_binary_data_array_pairs(writer, &[(mz, intensity)], config, scratch)
+}
+
+/// Write one or more (m/z, intensity) array pairs into a single
+/// `binaryDataArrayList`. Used both for the common one-pair case and for
+/// [`SpectrumData::Both`], which writes a second pair alongside the
+/// spectrum's primary arrays.
+fn write_binary_data_array_pairs<W: Write>(
+    writer: &mut Writer<W>,
+    pairs: &[(&[f64], &[f64])],
+    config: &MzmlConfig,
+    scratch: &mut binary::ScratchBuffers,
 ) -> Result<(), MzmlError> {
     let mut bdal = BytesStart::new("binaryDataArrayList");
-    bdal.push_attribute(("count", "2"));
+    bdal.push_attribute(("count", (pairs.len() * 2).to_string().as_str()));
     writer.write_event(Event::Start(bdal))?;
 
-    // m/z array
-    write_binary_array(
-        writer,
-        mz,
-        config.mz_precision,
-        config.compression,
-        cv::MZ_ARRAY,
-        "m/z array",
-    )?;
+    for &(mz, intensity) in pairs {
+        // m/z array
+        write_binary_array(
+            writer,
+            mz,
+            config.mz_precision,
+            config.compression,
+            cv::MZ_ARRAY,
+            "m/z array",
+            scratch,
+        )?;
 
-    // Intensity array
-    write_binary_array(
-        writer,
-        intensity,
-        config.intensity_precision,
-        config.compression,
-        cv::INTENSITY_ARRAY,
-        "intensity array",
-    )?;
+        // Intensity array
+        write_binary_array(
+            writer,
+            intensity,
+            config.intensity_precision,
+            config.compression,
+            cv::INTENSITY_ARRAY,
+            "intensity array",
+            scratch,
+        )?;
+    }
 
     writer.write_event(Event::End(BytesEnd::new("binaryDataArrayList")))?;
     Ok(())
 }
 
 /// Write a single binary data array element.
+#[allow(clippy::too_many_arguments)]
 fn write_binary_array<W: Write>(
     writer: &mut Writer<W>,
     data: &[f64],
@@ -798,10 +1277,19 @@ fn write_binary_array<W: Write>(
     compression: Compression,
     array_accession: &str,
     array_name: &str,
+    scratch: &mut binary::ScratchBuffers,
 ) -> Result<(), MzmlError> {
-    let encoded = binary::encode_array(data, precision, compression);
+    let mut auto_buf = String::new();
+    let (encoded, compression): (&str, Compression) = if matches!(compression, Compression::Auto) {
+        let is_intensity = array_accession == cv::INTENSITY_ARRAY;
+        let (s, used) = binary::encode_array_auto(data, precision, is_intensity);
+        auto_buf = s;
+        (auto_buf.as_str(), used)
+    } else {
+        binary::encode_array_resolved_scratch(data, precision, compression, scratch)?
+    };
     let encoded_length = base64::engine::general_purpose::STANDARD
-        .decode(&encoded)
+        .decode(encoded)
         .map(|v| v.len())
         .unwrap_or(0);
 
@@ -816,19 +1304,36 @@ fn write_binary_array<W: Write>(
     };
     write_cv_param(writer, prec_acc, prec_name, None, None)?;
 
-    // Compression CV param
-    let (comp_acc, comp_name) = match compression {
-        Compression::Zlib => (cv::ZLIB_COMPRESSION, "zlib compression"),
-        Compression::None => (cv::NO_COMPRESSION, "no compression"),
-    };
-    write_cv_param(writer, comp_acc, comp_name, None, None)?;
+    // Compression CV param(s). Numpress-plus-zlib combinations emit both
+    // accessions, matching how real mzML producers record a compression chain.
+    match compression {
+        Compression::Zlib => write_cv_param(writer, cv::ZLIB_COMPRESSION, "zlib compression", None, None)?,
+        Compression::None => write_cv_param(writer, cv::NO_COMPRESSION, "no compression", None, None)?,
+        Compression::NumpressLinear => write_cv_param(writer, cv::NUMPRESS_LINEAR, "numpress-style linear prediction compression (non-standard wire format)", None, None)?,
+        Compression::NumpressPic => write_cv_param(writer, cv::NUMPRESS_PIC, "numpress-style positive integer compression (non-standard wire format)", None, None)?,
+        Compression::NumpressSlof => write_cv_param(writer, cv::NUMPRESS_SLOF, "numpress-style short logged float compression (non-standard wire format)", None, None)?,
+        Compression::NumpressLinearZlib => {
+            write_cv_param(writer, cv::NUMPRESS_LINEAR, "numpress-style linear prediction compression (non-standard wire format)", None, None)?;
+            write_cv_param(writer, cv::ZLIB_COMPRESSION, "zlib compression", None, None)?;
+        }
+        Compression::NumpressPicZlib => {
+            write_cv_param(writer, cv::NUMPRESS_PIC, "numpress-style positive integer compression (non-standard wire format)", None, None)?;
+            write_cv_param(writer, cv::ZLIB_COMPRESSION, "zlib compression", None, None)?;
+        }
+        Compression::NumpressSlofZlib => {
+            write_cv_param(writer, cv::NUMPRESS_SLOF, "numpress-style short logged float compression (non-standard wire format)", None, None)?;
+            write_cv_param(writer, cv::ZLIB_COMPRESSION, "zlib compression", None, None)?;
+        }
+        Compression::Zstd => write_cv_param(writer, cv::ZSTD_COMPRESSION, "zstd compression", None, None)?,
+        Compression::Auto => unreachable!("Auto is resolved to a concrete codec above"),
+    }
 
     // Array type
     write_cv_param(writer, array_accession, array_name, None, None)?;
 
     // <binary>
     writer.write_event(Event::Start(BytesStart::new("binary")))?;
-    writer.write_event(Event::Text(BytesText::new(&encoded)))?;
+    writer.write_event(Event::Text(BytesText::new(encoded)))?;
     writer.write_event(Event::End(BytesEnd::new("binary")))?;
 
     writer.write_event(Event::End(BytesEnd::new("binaryDataArray")))?;
@@ -866,6 +1371,7 @@ fn write_empty_binary_array<W: Write>(
 }
 
 /// Write a chromatogram element (TIC or BPC).
+#[allow(clippy::too_many_arguments)]
 fn write_chromatogram<W: Write>(
     writer: &mut Writer<W>,
     id: &str,
@@ -874,6 +1380,7 @@ fn write_chromatogram<W: Write>(
     intensity: &[f64],
     type_accession: &str,
     config: &MzmlConfig,
+    scratch: &mut binary::ScratchBuffers,
 ) -> Result<(), MzmlError> {
     let n_points = rt.len().to_string();
 
@@ -898,6 +1405,7 @@ fn write_chromatogram<W: Write>(
         config.compression,
         cv::TIME_ARRAY,
         "time array",
+        scratch,
     )?;
 
     // Intensity array
@@ -908,6 +1416,70 @@ fn write_chromatogram<W: Write>(
         config.compression,
         cv::INTENSITY_ARRAY,
         "intensity array",
+        scratch,
+    )?;
+
+    writer.write_event(Event::End(BytesEnd::new("binaryDataArrayList")))?;
+    writer.write_event(Event::End(BytesEnd::new("chromatogram")))?;
+    Ok(())
+}
+
+/// Write an SRM/MRM/PRM transition chromatogram: summed product-ion
+/// intensity vs. retention time for one isolation-window target m/z.
+fn write_srm_chromatogram<W: Write>(
+    writer: &mut Writer<W>,
+    id: &str,
+    index: usize,
+    transition: &thermo_raw::types::TransitionChromatogram,
+    config: &MzmlConfig,
+    scratch: &mut binary::ScratchBuffers,
+) -> Result<(), MzmlError> {
+    let n_points = transition.chromatogram.rt.len().to_string();
+
+    let mut chrom = BytesStart::new("chromatogram");
+    chrom.push_attribute(("index", index.to_string().as_str()));
+    chrom.push_attribute(("id", id));
+    chrom.push_attribute(("defaultArrayLength", n_points.as_str()));
+    writer.write_event(Event::Start(chrom))?;
+
+    write_cv_param(writer, cv::SRM_CHROMATOGRAM, "", None, None)?;
+
+    // <precursor><isolationWindow> records the targeted m/z this
+    // chromatogram's scans were isolated around.
+    writer.write_event(Event::Start(BytesStart::new("precursor")))?;
+    writer.write_event(Event::Start(BytesStart::new("isolationWindow")))?;
+    write_cv_param(
+        writer,
+        cv::ISOLATION_WINDOW_TARGET,
+        "isolation window target m/z",
+        Some(&format!("{:.10}", transition.precursor_mz)),
+        None,
+    )?;
+    writer.write_event(Event::End(BytesEnd::new("isolationWindow")))?;
+    writer.write_event(Event::End(BytesEnd::new("precursor")))?;
+
+    // Binary data arrays: time + intensity
+    let mut bdal = BytesStart::new("binaryDataArrayList");
+    bdal.push_attribute(("count", "2"));
+    writer.write_event(Event::Start(bdal))?;
+
+    write_binary_array(
+        writer,
+        &transition.chromatogram.rt,
+        Precision::F64,
+        config.compression,
+        cv::TIME_ARRAY,
+        "time array",
+        scratch,
+    )?;
+    write_binary_array(
+        writer,
+        &transition.chromatogram.intensity,
+        config.intensity_precision,
+        config.compression,
+        cv::INTENSITY_ARRAY,
+        "intensity array",
+        scratch,
     )?;
 
     writer.write_event(Event::End(BytesEnd::new("binaryDataArrayList")))?;
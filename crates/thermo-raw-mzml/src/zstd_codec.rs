@@ -0,0 +1,239 @@
+//! A minimal, self-contained zstd frame codec.
+//!
+//! Pulling in the full zstd C library (or even a Rust FFI wrapper around it)
+//! is undesirable for a crate whose whole point is dependency-free parsing,
+//! so this implements just enough of RFC 8878 to produce and consume valid
+//! zstd frames for our own binary arrays:
+//!
+//! - Frame header: magic number, a single-segment descriptor with an 8-byte
+//!   frame content size, no dictionary, no checksum.
+//! - Block headers: 3-byte little-endian (last-block bit, 2-bit block type,
+//!   21-bit size).
+//! - Raw and RLE blocks, copied/repeated verbatim.
+//!
+//! What this does *not* implement is the Huffman/FSE entropy stage used by
+//! `Compressed` blocks — real compression ratio there requires a literals
+//! Huffman table and an FSE-coded sequences section, which is a lot of
+//! surface area for arrays whose only consumer is this crate's own encoder.
+//! Instead, the encoder always emits Raw or RLE blocks (RLE for runs, which
+//! costs nothing to detect and is common in flat/zero-padded regions), and
+//! the decoder returns a clear error for `Compressed` blocks from other
+//! zstd implementations rather than guessing.
+
+use crate::MzmlError;
+
+const MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+/// Maximum bytes per block (the zstd spec caps blocks at 128 KiB).
+const BLOCK_MAX: usize = 128 * 1024;
+
+/// Encode `data` as a valid (but entropy-uncompressed) zstd frame.
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + 32);
+    out.extend_from_slice(&MAGIC);
+
+    // Frame_Header_Descriptor: Single_Segment_flag (bit 5) set, Frame_Content_Size_flag = 3
+    // (bits 6-7, meaning an 8-byte content-size field), no checksum, no dictionary.
+    out.push(0b1110_0000);
+    out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+
+    if data.is_empty() {
+        write_block_header(&mut out, true, BlockType::Raw, 0);
+        return out;
+    }
+
+    let chunks: Vec<&[u8]> = data.chunks(BLOCK_MAX).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let is_last = i == chunks.len() - 1;
+        if let Some(&first) = chunk.first() {
+            if chunk.iter().all(|&b| b == first) {
+                write_block_header(&mut out, is_last, BlockType::Rle, chunk.len());
+                out.push(first);
+                continue;
+            }
+        }
+        write_block_header(&mut out, is_last, BlockType::Raw, chunk.len());
+        out.extend_from_slice(chunk);
+    }
+
+    out
+}
+
+/// Decode a zstd frame produced by [`encode`] (or any zstd encoder that
+/// sticks to Raw/RLE blocks).
+pub fn decode(bytes: &[u8]) -> Result<Vec<u8>, MzmlError> {
+    if bytes.len() < 4 || bytes[0..4] != MAGIC {
+        return Err(MzmlError::Conversion(
+            "zstd: missing or invalid frame magic number".to_string(),
+        ));
+    }
+    let mut pos = 4;
+
+    let descriptor = *bytes
+        .get(pos)
+        .ok_or_else(|| MzmlError::Conversion("zstd: truncated frame header".to_string()))?;
+    pos += 1;
+
+    let single_segment = (descriptor >> 5) & 1 == 1;
+    let fcs_flag = descriptor >> 6;
+    let has_checksum = (descriptor >> 2) & 1 == 1;
+    let dict_id_flag = descriptor & 0b11;
+
+    if !single_segment {
+        return Err(MzmlError::Conversion(
+            "zstd: multi-segment frames (non-single-segment) are not supported".to_string(),
+        ));
+    }
+    if dict_id_flag != 0 {
+        return Err(MzmlError::Conversion(
+            "zstd: dictionary IDs are not supported".to_string(),
+        ));
+    }
+
+    let fcs_len: usize = match fcs_flag {
+        0 => 1,
+        1 => 2,
+        2 => 4,
+        3 => 8,
+        _ => unreachable!("2-bit field"),
+    };
+    let fcs_bytes = bytes.get(pos..pos + fcs_len).ok_or_else(|| {
+        MzmlError::Conversion("zstd: truncated frame content size field".to_string())
+    })?;
+    let frame_content_size: u64 = match fcs_len {
+        1 => fcs_bytes[0] as u64,
+        2 => u16::from_le_bytes(fcs_bytes.try_into().unwrap()) as u64 + 256,
+        4 => u32::from_le_bytes(fcs_bytes.try_into().unwrap()) as u64,
+        8 => u64::from_le_bytes(fcs_bytes.try_into().unwrap()),
+        _ => unreachable!(),
+    };
+    pos += fcs_len;
+
+    let mut out = Vec::with_capacity(frame_content_size as usize);
+    loop {
+        let header_bytes = bytes
+            .get(pos..pos + 3)
+            .ok_or_else(|| MzmlError::Conversion("zstd: truncated block header".to_string()))?;
+        let header = header_bytes[0] as u32
+            | (header_bytes[1] as u32) << 8
+            | (header_bytes[2] as u32) << 16;
+        pos += 3;
+
+        let is_last = header & 1 == 1;
+        let block_type = (header >> 1) & 0b11;
+        let block_size = (header >> 3) as usize;
+
+        match block_type {
+            0 => {
+                // Raw
+                let content = bytes.get(pos..pos + block_size).ok_or_else(|| {
+                    MzmlError::Conversion("zstd: truncated raw block".to_string())
+                })?;
+                out.extend_from_slice(content);
+                pos += block_size;
+            }
+            1 => {
+                // RLE: block_size is the *decoded* length; content is one byte.
+                let &byte = bytes
+                    .get(pos)
+                    .ok_or_else(|| MzmlError::Conversion("zstd: truncated RLE block".to_string()))?;
+                out.resize(out.len() + block_size, byte);
+                pos += 1;
+            }
+            2 => {
+                return Err(MzmlError::Conversion(
+                    "zstd: Compressed blocks (Huffman/FSE entropy stage) are not supported"
+                        .to_string(),
+                ));
+            }
+            _ => {
+                return Err(MzmlError::Conversion(
+                    "zstd: reserved block type".to_string(),
+                ));
+            }
+        }
+
+        if is_last {
+            break;
+        }
+    }
+
+    if has_checksum {
+        // 4-byte XXH64-derived checksum trailer; we don't verify it, just skip it.
+        pos += 4;
+        if pos > bytes.len() {
+            return Err(MzmlError::Conversion(
+                "zstd: truncated content checksum".to_string(),
+            ));
+        }
+    }
+
+    Ok(out)
+}
+
+enum BlockType {
+    Raw,
+    Rle,
+}
+
+fn write_block_header(out: &mut Vec<u8>, is_last: bool, block_type: BlockType, size: usize) {
+    let type_bits: u32 = match block_type {
+        BlockType::Raw => 0,
+        BlockType::Rle => 1,
+    };
+    let header: u32 = (is_last as u32) | (type_bits << 1) | ((size as u32) << 3);
+    out.push((header & 0xFF) as u8);
+    out.push(((header >> 8) & 0xFF) as u8);
+    out.push(((header >> 16) & 0xFF) as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_mixed_content() {
+        let mut data = vec![0u8; 1000];
+        for (i, b) in data.iter_mut().enumerate() {
+            *b = (i % 251) as u8;
+        }
+        let encoded = encode(&data);
+        let decoded = decode(&encoded).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn round_trips_empty() {
+        let encoded = encode(&[]);
+        let decoded = decode(&encoded).unwrap();
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn uses_rle_for_runs() {
+        let data = vec![0xABu8; 500];
+        let encoded = encode(&data);
+        // Magic(4) + descriptor(1) + FCS(8) + block header(3) + 1 RLE byte == 17
+        assert_eq!(encoded.len(), 17);
+        assert_eq!(decode(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let err = decode(&[0, 0, 0, 0]).unwrap_err();
+        assert!(matches!(err, MzmlError::Conversion(_)));
+    }
+
+    #[test]
+    fn rejects_truncated_frame() {
+        let encoded = encode(b"hello world");
+        let err = decode(&encoded[..encoded.len() - 2]).unwrap_err();
+        assert!(matches!(err, MzmlError::Conversion(_)));
+    }
+
+    #[test]
+    fn spans_multiple_blocks() {
+        let data = vec![7u8; BLOCK_MAX + 10];
+        let encoded = encode(&data);
+        assert_eq!(decode(&encoded).unwrap(), data);
+    }
+}
@@ -0,0 +1,257 @@
+//! Acquisition-scheme analysis over parsed [`ScanEvent`]s.
+//!
+//! [`dia_window_scheme`] inspects the unique `ScanEvent` templates a file
+//! carries (not the per-scan index) and recovers the isolation-window layout
+//! of a Data-Independent Acquisition method -- the same kind of DIA/PASEF
+//! window table a timsTOF-style reader exposes -- so downstream tools can
+//! build extraction bins without re-deriving window edges themselves.
+
+use crate::scan_event::ScanEvent;
+use crate::types::MsLevel;
+
+/// One isolation window an MS2 (or higher) event acquires over.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IsolationWindow {
+    pub center: f64,
+    pub width: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+/// The reconstructed acquisition scheme for a set of `ScanEvent`s.
+#[derive(Debug, Clone)]
+pub struct DiaScheme {
+    /// Distinct isolation windows, sorted by center.
+    pub windows: Vec<IsolationWindow>,
+    /// Whether consecutive windows overlap (staggered DIA) rather than
+    /// tiling edge-to-edge without gaps.
+    pub is_staggered: bool,
+    /// Length of the repeating MS2-event cycle (0 if no DIA-like repeat was
+    /// found, e.g. for DDA acquisitions).
+    pub cycle_len: usize,
+}
+
+/// Edges within this many m/z of each other are treated as the same window
+/// when deduplicating.
+const EDGE_ROUND: f64 = 1e-3;
+
+fn round_edge(v: f64) -> f64 {
+    (v / EDGE_ROUND).round() * EDGE_ROUND
+}
+
+/// Compute the isolation window a single MS2+ event acquires over, or `None`
+/// if the event has no reactions (so isn't an isolation scan at all).
+fn event_window(event: &ScanEvent) -> Option<IsolationWindow> {
+    let reaction = event.reactions.first()?;
+    let (low, high) = if reaction.precursor_range_valid {
+        (reaction.first_precursor_mass, reaction.last_precursor_mass)
+    } else {
+        let half_width = reaction.isolation_width / 2.0;
+        let offset = reaction.isolation_width_offset;
+        (
+            reaction.precursor_mz - half_width + offset,
+            reaction.precursor_mz + half_width + offset,
+        )
+    };
+    Some(IsolationWindow {
+        center: (low + high) / 2.0,
+        width: high - low,
+        low,
+        high,
+    })
+}
+
+/// Reconstruct the DIA isolation-window scheme from a file's parsed
+/// `ScanEvent` templates.
+///
+/// Only events with `preamble.ms_level == MsLevel::Ms2` and at least one
+/// reaction contribute a window; everything else (MS1 survey events, events
+/// with no reactions) is ignored. Windows are deduplicated by rounding their
+/// edges to `1e-3` m/z, then sorted by center.
+///
+/// The run is classified as DIA-like when a small set of fixed windows
+/// repeats cyclically across the MS2 event list (`cycle_len` is the length
+/// of that repeat); DDA acquisitions (many distinct, non-repeating
+/// centers -- one per precursor) report `cycle_len == 0`.
+pub fn dia_window_scheme(events: &[ScanEvent]) -> DiaScheme {
+    let ms2_windows: Vec<IsolationWindow> = events
+        .iter()
+        .filter(|e| matches!(e.preamble.ms_level, MsLevel::Ms2) && !e.reactions.is_empty())
+        .filter_map(event_window)
+        .collect();
+
+    let mut windows: Vec<IsolationWindow> = Vec::new();
+    for w in &ms2_windows {
+        let rounded = IsolationWindow {
+            center: round_edge(w.center),
+            width: round_edge(w.width),
+            low: round_edge(w.low),
+            high: round_edge(w.high),
+        };
+        if !windows.iter().any(|existing| *existing == rounded) {
+            windows.push(rounded);
+        }
+    }
+    windows.sort_by(|a, b| a.center.partial_cmp(&b.center).unwrap());
+
+    let is_staggered = windows
+        .windows(2)
+        .any(|pair| pair[0].high > pair[1].low + EDGE_ROUND);
+
+    let cycle_len = detect_cycle_len(&ms2_windows, &windows);
+
+    DiaScheme {
+        windows,
+        is_staggered,
+        cycle_len,
+    }
+}
+
+/// Detect the length of the repeating window cycle across `ms2_windows`
+/// (in event order), if one exists. A DIA method cycles through its fixed
+/// window set repeatedly; a DDA method doesn't repeat at all (every
+/// precursor is a one-off), so this returns `0` unless the distinct window
+/// count is small relative to the event count and the sequence actually
+/// tiles into whole repeats of it.
+fn detect_cycle_len(ms2_windows: &[IsolationWindow], distinct: &[IsolationWindow]) -> usize {
+    if distinct.is_empty() || ms2_windows.len() < distinct.len() * 2 {
+        return 0;
+    }
+    // DIA methods use a handful of fixed windows repeated many times; a long
+    // tail of distinct centers (as DDA precursor selection produces) isn't a
+    // cycle.
+    if distinct.len() > 64 {
+        return 0;
+    }
+
+    let index_of = |w: &IsolationWindow| -> Option<usize> {
+        distinct.iter().position(|d| *d == *w)
+    };
+    let indices: Vec<usize> = match ms2_windows.iter().map(index_of).collect::<Option<Vec<_>>>() {
+        Some(v) => v,
+        None => return 0,
+    };
+
+    let cycle_len = distinct.len();
+    if indices.len() % cycle_len != 0 {
+        return 0;
+    }
+    let repeats = indices.len() / cycle_len;
+    if repeats < 2 {
+        return 0;
+    }
+    let first_cycle = &indices[..cycle_len];
+    let repeats_cleanly = indices
+        .chunks(cycle_len)
+        .all(|chunk| chunk == first_cycle);
+
+    if repeats_cleanly { cycle_len } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan_event::{
+        ActivationType, AnalyzerType, IonizationType, Reaction, ScanEventPreamble, ScanMode, ScanType,
+    };
+    use crate::types::Polarity;
+
+    fn ms1_event() -> ScanEvent {
+        ScanEvent {
+            preamble: ScanEventPreamble {
+                polarity: Polarity::Positive,
+                scan_mode: ScanMode::Profile,
+                ms_level: MsLevel::Ms1,
+                scan_type: ScanType::Full,
+                dependent: false,
+                ionization: IonizationType::Nsi,
+                activation: ActivationType::Unknown(255),
+                analyzer: AnalyzerType::Ftms,
+            },
+            reactions: vec![],
+            conversion_params: vec![],
+        }
+    }
+
+    fn ms2_event(precursor_mz: f64, isolation_width: f64) -> ScanEvent {
+        ScanEvent {
+            preamble: ScanEventPreamble {
+                polarity: Polarity::Positive,
+                scan_mode: ScanMode::Centroid,
+                ms_level: MsLevel::Ms2,
+                scan_type: ScanType::Full,
+                dependent: false,
+                ionization: IonizationType::Nsi,
+                activation: ActivationType::Hcd,
+                analyzer: AnalyzerType::Ftms,
+            },
+            reactions: vec![Reaction {
+                precursor_mz,
+                isolation_width,
+                collision_energy: 27.0,
+                collision_energy_valid: 1 | (5 << 1),
+                precursor_range_valid: false,
+                first_precursor_mass: 0.0,
+                last_precursor_mass: 0.0,
+                isolation_width_offset: 0.0,
+            }],
+            conversion_params: vec![],
+        }
+    }
+
+    #[test]
+    fn test_dia_scheme_detects_repeating_windows() {
+        let mut events = Vec::new();
+        for _ in 0..5 {
+            events.push(ms1_event());
+            events.push(ms2_event(400.0, 20.0));
+            events.push(ms2_event(420.0, 20.0));
+            events.push(ms2_event(440.0, 20.0));
+        }
+
+        let scheme = dia_window_scheme(&events);
+        assert_eq!(scheme.windows.len(), 3);
+        assert_eq!(scheme.cycle_len, 3);
+        assert!(!scheme.is_staggered);
+        assert!((scheme.windows[0].center - 400.0).abs() < 1e-6);
+        assert!((scheme.windows[0].low - 390.0).abs() < 1e-6);
+        assert!((scheme.windows[0].high - 410.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dia_scheme_detects_staggered_overlap() {
+        let mut events = Vec::new();
+        for _ in 0..4 {
+            events.push(ms1_event());
+            events.push(ms2_event(400.0, 30.0));
+            events.push(ms2_event(415.0, 30.0));
+        }
+
+        let scheme = dia_window_scheme(&events);
+        assert!(scheme.is_staggered);
+    }
+
+    #[test]
+    fn test_dia_scheme_dda_has_no_cycle() {
+        let mut events = vec![ms1_event()];
+        for i in 0..20 {
+            events.push(ms2_event(300.0 + i as f64 * 7.3, 2.0));
+        }
+
+        let scheme = dia_window_scheme(&events);
+        assert_eq!(scheme.cycle_len, 0);
+    }
+
+    #[test]
+    fn test_dia_scheme_uses_precursor_range_when_valid() {
+        let mut event = ms2_event(400.0, 20.0);
+        event.reactions[0].precursor_range_valid = true;
+        event.reactions[0].first_precursor_mass = 395.0;
+        event.reactions[0].last_precursor_mass = 405.0;
+
+        let scheme = dia_window_scheme(std::slice::from_ref(&event));
+        assert_eq!(scheme.windows.len(), 1);
+        assert!((scheme.windows[0].low - 395.0).abs() < 1e-6);
+        assert!((scheme.windows[0].high - 405.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,314 @@
+//! A fixed-window LRU byte cache over a random-access [`RawSource`].
+//!
+//! [`io_utils::PacketSource`](crate::io_utils::PacketSource) already covers
+//! "give me `len` bytes at `offset`" for packet decoding, with a zero-copy
+//! impl for in-memory slices and a seek-per-call impl
+//! ([`io_utils::FileSource`](crate::io_utils::FileSource)) for `Read + Seek`
+//! handles. `FileSource` re-reads from the handle on every call, though --
+//! fine for one packet each, but wasteful for callers (trailer reads,
+//! repeated small metadata lookups) that touch the same region of the file
+//! many times. [`BlockCache`] sits between the two: it reads fixed-size
+//! aligned windows through a [`RawSource`] and keeps recently-used ones
+//! around as `Arc<[u8]>`, evicting the least-recently-used window once a
+//! byte budget is exceeded -- the same eviction strategy `RawFile`'s
+//! `ScanCentroidCache` already uses for decoded centroid arrays.
+//!
+//! [`BlockCache`] implements [`PacketSource`](crate::io_utils::PacketSource)
+//! itself, so it can be passed directly to any of the existing
+//! `PacketSource`-generic decode entry points (e.g.
+//! [`scan_data::decode_scan`](crate::scan_data::decode_scan)) in place of a
+//! `&[u8]` or a bare `FileSource` -- no changes to `RawFile`'s own `data`
+//! field are needed to get cached, on-demand reads for a caller building
+//! its own decode pipeline around [`crate::scan_source::StreamScanSource`]
+//! or a direct `PacketSource` caller. Wiring this into `RawFile` itself
+//! (so `open`/`scan` pull through a cache rather than one resident buffer)
+//! is a separate, much larger change: every parser in this crate
+//! (`FileHeader`, `RunHeader`, `ScanIndex`, trailer, metadata) indexes
+//! `RawFile`'s `data: FileData` as one contiguous `&[u8]` today, and
+//! swapping that field's type would touch all of them at once with no
+//! compiler available in this environment to check the result -- left for
+//! a follow-up once each of those call sites can be converted and tested
+//! individually.
+
+use crate::io_utils::PacketSource;
+use crate::RawError;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+/// Size of one cached window, in bytes. Chosen to comfortably cover a
+/// `PacketHeader` (40 bytes) or a trailer record in a single block, while
+/// staying small enough that a handful of cached blocks don't approach the
+/// memory cost this cache exists to avoid.
+pub const BLOCK_SIZE: u64 = 64 * 1024;
+
+/// A random-access byte source read in fixed-size windows.
+///
+/// Lower-level than [`PacketSource`]: implementations only need to fill a
+/// caller-supplied buffer at an offset and report their total length: no
+/// `Cow`, no variable-length reads. [`BlockCache`] is what turns this into
+/// something decode code can use directly.
+pub trait RawSource {
+    /// Fill `buf` with bytes starting at `offset`. Must fail if fewer than
+    /// `buf.len()` bytes are available.
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), RawError>;
+
+    /// Total length of the source in bytes.
+    fn len(&self) -> u64;
+
+    /// Convenience: `true` if the source has zero length.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl RawSource for [u8] {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), RawError> {
+        let start = usize::try_from(offset)
+            .map_err(|_| RawError::CorruptedData(format!("read_at: offset {} out of range", offset)))?;
+        let end = start.checked_add(buf.len()).ok_or_else(|| {
+            RawError::CorruptedData(format!("read_at: offset {} + len {} overflows", offset, buf.len()))
+        })?;
+        if end > self.len() {
+            return Err(RawError::CorruptedData(format!(
+                "read_at: need {} bytes at offset {}, but only {} remaining (source is {} bytes)",
+                buf.len(),
+                offset,
+                self.len().saturating_sub(start),
+                self.len()
+            )));
+        }
+        buf.copy_from_slice(&self[start..end]);
+        Ok(())
+    }
+
+    fn len(&self) -> u64 {
+        <[u8]>::len(self) as u64
+    }
+}
+
+/// [`RawSource`] over an owned, in-memory buffer. Delegates to the `[u8]`
+/// impl -- kept separate so `BlockCache<Vec<u8>>` (an owned source, as used
+/// by this module's tests) doesn't need an unsized `S`.
+impl RawSource for Vec<u8> {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), RawError> {
+        self.as_slice().read_at(offset, buf)
+    }
+
+    fn len(&self) -> u64 {
+        <[u8]>::len(self.as_slice()) as u64
+    }
+}
+
+/// [`RawSource`] over a memory-mapped file. Just delegates to the slice
+/// impl -- kept as its own type so callers writing against `RawSource`
+/// generically don't need to know a given source happens to be mapped.
+pub struct MmapSource(pub memmap2::Mmap);
+
+impl RawSource for MmapSource {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), RawError> {
+        (*self.0).read_at(offset, buf)
+    }
+
+    fn len(&self) -> u64 {
+        self.0.len() as u64
+    }
+}
+
+/// [`RawSource`] over a buffered, seekable file handle, read lazily on
+/// every miss rather than mapped or loaded up front. Uses a `RefCell`
+/// for interior mutability, same as
+/// [`io_utils::FileSource`](crate::io_utils::FileSource) -- seeking needs
+/// `&mut`, but `RawSource::read_at` takes `&self`.
+pub struct BufferedFileSource {
+    inner: RefCell<BufReader<File>>,
+    len: u64,
+}
+
+impl BufferedFileSource {
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, RawError> {
+        let file = File::open(path.as_ref())?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            inner: RefCell::new(BufReader::new(file)),
+            len,
+        })
+    }
+}
+
+impl RawSource for BufferedFileSource {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), RawError> {
+        let mut inner = self.inner.borrow_mut();
+        inner.seek(SeekFrom::Start(offset))?;
+        inner.read_exact(buf)?;
+        Ok(())
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+}
+
+/// One cached, aligned window of bytes.
+struct CachedBlock {
+    data: Arc<[u8]>,
+    last_used: u64,
+}
+
+/// Bounded LRU cache of fixed-size windows read through a [`RawSource`].
+///
+/// Keyed on aligned block index (`offset >> 16`, i.e. `offset / BLOCK_SIZE`),
+/// same eviction strategy as `RawFile`'s `ScanCentroidCache`: a plain
+/// `HashMap` plus a logical clock, evicting the least-recently-used block
+/// when inserting would push the running byte total over `budget_bytes`.
+pub struct BlockCache<S> {
+    source: S,
+    budget_bytes: u64,
+    blocks: RefCell<HashMap<u64, CachedBlock>>,
+    clock: RefCell<u64>,
+    bytes_used: RefCell<u64>,
+}
+
+impl<S: RawSource> BlockCache<S> {
+    pub fn new(source: S, budget_bytes: u64) -> Self {
+        Self {
+            source,
+            budget_bytes,
+            blocks: RefCell::new(HashMap::new()),
+            clock: RefCell::new(0),
+            bytes_used: RefCell::new(0),
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        let mut clock = self.clock.borrow_mut();
+        *clock += 1;
+        *clock
+    }
+
+    /// Fetch the block at `block_idx`, reading it through `source` on a
+    /// cache miss and evicting least-recently-used blocks if needed to stay
+    /// within `budget_bytes`.
+    fn get_block(&self, block_idx: u64) -> Result<Arc<[u8]>, RawError> {
+        let clock = self.tick();
+
+        if let Some(block) = self.blocks.borrow_mut().get_mut(&block_idx) {
+            block.last_used = clock;
+            return Ok(Arc::clone(&block.data));
+        }
+
+        let start = block_idx * BLOCK_SIZE;
+        let this_len = (self.source.len().saturating_sub(start)).min(BLOCK_SIZE) as usize;
+        let mut buf = vec![0u8; this_len];
+        self.source.read_at(start, &mut buf)?;
+        let data: Arc<[u8]> = Arc::from(buf);
+
+        let mut blocks = self.blocks.borrow_mut();
+        let mut bytes_used = self.bytes_used.borrow_mut();
+        *bytes_used += data.len() as u64;
+        blocks.insert(
+            block_idx,
+            CachedBlock {
+                data: Arc::clone(&data),
+                last_used: clock,
+            },
+        );
+
+        while *bytes_used > self.budget_bytes && blocks.len() > 1 {
+            let lru_idx = blocks.iter().min_by_key(|(_, b)| b.last_used).map(|(&k, _)| k);
+            match lru_idx {
+                Some(k) if k != block_idx => {
+                    if let Some(evicted) = blocks.remove(&k) {
+                        *bytes_used -= evicted.data.len() as u64;
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Read `len` bytes starting at `offset`, stitching together however
+    /// many cached blocks the range spans.
+    pub fn read_at(&self, offset: u64, len: usize) -> Result<Vec<u8>, RawError> {
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        let end = offset.checked_add(len as u64).ok_or_else(|| {
+            RawError::CorruptedData(format!("read_at: offset {} + len {} overflows", offset, len))
+        })?;
+        if end > self.source.len() {
+            return Err(RawError::CorruptedData(format!(
+                "read_at: need {} bytes at offset {}, but source is only {} bytes",
+                len,
+                offset,
+                self.source.len()
+            )));
+        }
+
+        let mut out = Vec::with_capacity(len);
+        let mut pos = offset;
+        while pos < end {
+            let block_idx = pos / BLOCK_SIZE;
+            let block = self.get_block(block_idx)?;
+            let block_start = block_idx * BLOCK_SIZE;
+            let within = (pos - block_start) as usize;
+            let take = (block.len() - within).min((end - pos) as usize);
+            out.extend_from_slice(&block[within..within + take]);
+            pos += take as u64;
+        }
+        Ok(out)
+    }
+}
+
+impl<S: RawSource> PacketSource for BlockCache<S> {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>, RawError> {
+        Ok(Cow::Owned(BlockCache::read_at(self, offset, len)?))
+    }
+
+    fn size(&self) -> Result<u64, RawError> {
+        Ok(self.source.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_within_a_single_block_match_the_source() {
+        let data: Vec<u8> = (0..200u32).map(|n| n as u8).collect();
+        let cache = BlockCache::new(data.clone(), 1024 * 1024);
+        assert_eq!(cache.read_at(10, 5).unwrap(), &data[10..15]);
+    }
+
+    #[test]
+    fn reads_spanning_multiple_blocks_are_stitched_together() {
+        let data: Vec<u8> = (0..(BLOCK_SIZE as usize * 3)).map(|n| (n % 256) as u8).collect();
+        let cache = BlockCache::new(data.clone(), 1024 * 1024);
+        let start = BLOCK_SIZE as usize - 10;
+        let len = 20;
+        assert_eq!(cache.read_at(start as u64, len).unwrap(), &data[start..start + len]);
+    }
+
+    #[test]
+    fn eviction_keeps_bytes_used_within_budget() {
+        let data: Vec<u8> = vec![0u8; BLOCK_SIZE as usize * 10];
+        let cache = BlockCache::new(data, BLOCK_SIZE * 2);
+        for i in 0..10u64 {
+            cache.read_at(i * BLOCK_SIZE, 8).unwrap();
+        }
+        assert!(*cache.bytes_used.borrow() <= BLOCK_SIZE * 2);
+    }
+
+    #[test]
+    fn out_of_bounds_read_is_an_error() {
+        let data: Vec<u8> = vec![0u8; 16];
+        let cache = BlockCache::new(data, 1024);
+        assert!(cache.read_at(10, 100).is_err());
+    }
+}
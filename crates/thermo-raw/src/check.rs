@@ -0,0 +1,212 @@
+//! Structural integrity validation for an already-parsed RAW file.
+//!
+//! Mirrors the read-only validation half of tools like `thin_check`: rather
+//! than hashing bytes (see [`crate::checksum`], which only says "something
+//! changed"), this walks already-parsed structures (`scan_index`, the
+//! trailer layout, scan events) and reports *which* invariant a specific
+//! scan or offset violates, so a damaged acquisition can be triaged instead
+//! of failing opaquely the next time something indexes past its end.
+//!
+//! Overlap/bounds checks here are necessarily approximate: a scan packet's
+//! exact end offset depends on its packet kind (FtLt vs legacy) and isn't
+//! known without running the full decode dispatch in [`crate::scan_data`].
+//! Rather than duplicating that dispatch here, this only checks that each
+//! entry's start address leaves room for at least a [`PacketHeader`] before
+//! the next entry's start (or end of file, for the last entry) --
+//! sufficient to catch truncated/corrupt indexes without needing a working
+//! decode of every scan just to validate the index that points at them.
+//!
+//! [`PacketHeader`]: crate::scan_data::PacketHeader
+
+use crate::scan_data::PacketHeader;
+use crate::scan_index::ScanIndexEntry;
+
+/// One check violation: which invariant failed, and where.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckIssue {
+    /// Scan number the issue concerns, when it's scoped to one scan.
+    pub scan_number: Option<u32>,
+    /// Absolute byte offset the issue concerns, when there is one.
+    pub offset: Option<u64>,
+    /// Human-readable description of the violated invariant.
+    pub message: String,
+}
+
+impl CheckIssue {
+    fn new(scan_number: Option<u32>, offset: Option<u64>, message: impl Into<String>) -> Self {
+        CheckIssue {
+            scan_number,
+            offset,
+            message: message.into(),
+        }
+    }
+}
+
+/// Result of [`RawFile::check`](crate::RawFile::check): every structural
+/// issue found, split into `errors` (the file is likely unreadable past
+/// this point) and `warnings` (readable, but the data or metadata look
+/// off).
+#[derive(Debug, Clone, Default)]
+pub struct CheckReport {
+    pub errors: Vec<CheckIssue>,
+    pub warnings: Vec<CheckIssue>,
+}
+
+impl CheckReport {
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+
+    fn push_error(&mut self, issue: CheckIssue) {
+        self.errors.push(issue);
+    }
+
+    fn push_warning(&mut self, issue: CheckIssue) {
+        self.warnings.push(issue);
+    }
+}
+
+/// Validate that `n_scans` (from the RunHeader) matches the scan range
+/// (`last_scan - first_scan + 1`) and the number of entries actually parsed
+/// into the scan index.
+pub fn check_scan_count(report: &mut CheckReport, n_scans: u32, first_scan: u32, last_scan: u32, n_entries: usize) {
+    let expected_from_range = last_scan.saturating_sub(first_scan).saturating_add(1);
+    if n_scans != expected_from_range {
+        report.push_error(CheckIssue::new(
+            None,
+            None,
+            format!(
+                "RunHeader.n_scans ({n_scans}) != last_scan - first_scan + 1 ({expected_from_range}, from {first_scan}..={last_scan})"
+            ),
+        ));
+    }
+    if n_entries != n_scans as usize {
+        report.push_error(CheckIssue::new(
+            None,
+            None,
+            format!("RunHeader reports {n_scans} scans but the scan index has {n_entries} entries"),
+        ));
+    }
+}
+
+/// Validate scan index entries: monotonically non-decreasing RT and offset,
+/// and each entry's start address within file bounds with room for a
+/// [`PacketHeader`] before the next entry (see the module doc for why this
+/// is a bounds/ordering check rather than an exact overlap check).
+pub fn check_scan_index(
+    report: &mut CheckReport,
+    entries: &[ScanIndexEntry],
+    data_addr: u64,
+    file_len: u64,
+    first_scan: u32,
+) {
+    let data_len = file_len.saturating_sub(data_addr);
+
+    for (i, entry) in entries.iter().enumerate() {
+        let scan_number = first_scan + i as u32;
+        let abs_offset = data_addr + entry.offset;
+
+        if abs_offset + PacketHeader::SIZE as u64 > file_len {
+            report.push_error(CheckIssue::new(
+                Some(scan_number),
+                Some(abs_offset),
+                format!(
+                    "scan {scan_number} packet at offset {abs_offset} leaves no room for a {}-byte packet header before end of file ({file_len} bytes)",
+                    PacketHeader::SIZE
+                ),
+            ));
+        } else if entry.offset > data_len {
+            report.push_warning(CheckIssue::new(
+                Some(scan_number),
+                Some(abs_offset),
+                format!("scan {scan_number} offset {} is past the nominal data stream length ({data_len} bytes)", entry.offset),
+            ));
+        }
+
+        if let Some(prev) = i.checked_sub(1).map(|j| &entries[j]) {
+            if entry.offset < prev.offset {
+                report.push_error(CheckIssue::new(
+                    Some(scan_number),
+                    Some(abs_offset),
+                    format!(
+                        "scan {scan_number} offset {} is before the previous scan's offset {} (scan index is not sorted by offset)",
+                        entry.offset, prev.offset
+                    ),
+                ));
+            } else if entry.offset < prev.offset + PacketHeader::SIZE as u64 {
+                report.push_warning(CheckIssue::new(
+                    Some(scan_number),
+                    Some(abs_offset),
+                    format!(
+                        "scan {scan_number} offset {} overlaps the previous scan's packet header (starts before offset {} + {} bytes)",
+                        entry.offset,
+                        prev.offset,
+                        PacketHeader::SIZE
+                    ),
+                ));
+            }
+
+            if entry.rt < prev.rt {
+                report.push_warning(CheckIssue::new(
+                    Some(scan_number),
+                    None,
+                    format!("scan {scan_number} retention time {} is before the previous scan's {}", entry.rt, prev.rt),
+                ));
+            }
+        }
+    }
+}
+
+/// Validate that every entry's `scan_event` index resolves to a parsed
+/// [`ScanEvent`](crate::scan_event::ScanEvent).
+pub fn check_scan_events(report: &mut CheckReport, entries: &[ScanIndexEntry], first_scan: u32, n_scan_events: usize) {
+    if n_scan_events == 0 {
+        return;
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.scan_event as usize >= n_scan_events {
+            report.push_error(CheckIssue::new(
+                Some(first_scan + i as u32),
+                None,
+                format!(
+                    "scan {} scan_event index {} is out of range (only {n_scan_events} scan events parsed)",
+                    first_scan + i as u32,
+                    entry.scan_event
+                ),
+            ));
+        }
+    }
+}
+
+/// Validate that the trailer's per-scan record stride times the scan count
+/// fits inside the file.
+pub fn check_trailer_bounds(report: &mut CheckReport, trailer_addr: u64, record_size: usize, n_scans: u32, file_len: u64) {
+    if trailer_addr == 0 || record_size == 0 {
+        return;
+    }
+    let needed = trailer_addr + (record_size as u64) * (n_scans as u64);
+    if needed > file_len {
+        report.push_error(CheckIssue::new(
+            None,
+            Some(trailer_addr),
+            format!(
+                "trailer layout needs {needed} bytes ({record_size} bytes x {n_scans} scans from offset {trailer_addr}) but the file is only {file_len} bytes"
+            ),
+        ));
+    }
+}
+
+/// Best-effort repair of a corrupt scan index: sorts entries by offset and
+/// drops any entry whose start address doesn't leave room for a packet
+/// header within the file. This recovers from the common corruption case
+/// of an out-of-order or truncated index; it does not reconstruct entries
+/// from scratch by scanning the data stream for packet boundaries, since
+/// [`PacketHeader`] has no magic/signature bytes to search for (see the
+/// module doc) -- there is nothing to distinguish the start of a real
+/// packet from an arbitrary run of bytes that happens to parse as one.
+pub fn try_recover(entries: &[ScanIndexEntry], data_addr: u64, file_len: u64) -> Vec<ScanIndexEntry> {
+    let mut recovered: Vec<ScanIndexEntry> = entries.to_vec();
+    recovered.sort_by_key(|e| e.offset);
+    recovered.retain(|e| data_addr + e.offset + PacketHeader::SIZE as u64 <= file_len);
+    recovered
+}
@@ -1,6 +1,47 @@
 //! Chromatogram extraction (TIC, BPC, XIC).
 
-use crate::types::Chromatogram;
+use crate::types::{Chromatogram, XicQuantification};
+
+/// Group `(scan_idx, precursor_mz)` pairs by isolation-window target m/z,
+/// merging targets within `tolerance` of an already-seen group. Each group's
+/// label is the m/z of the first scan assigned to it.
+///
+/// Used to split targeted MS2 data (SRM/MRM/PRM) into one chromatogram per
+/// distinct transition.
+pub fn group_by_precursor_mz(precursor_mz: &[(usize, f64)], tolerance: f64) -> Vec<(f64, Vec<usize>)> {
+    let mut groups: Vec<(f64, Vec<usize>)> = Vec::new();
+    for &(scan_idx, mz) in precursor_mz {
+        match groups
+            .iter_mut()
+            .find(|(target, _)| (target - mz).abs() <= tolerance)
+        {
+            Some((_, members)) => members.push(scan_idx),
+            None => groups.push((mz, vec![scan_idx])),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_precursor_mz_merges_within_tolerance() {
+        let precursor_mz = [(0, 500.25), (1, 700.10), (2, 500.26), (3, 700.09)];
+        let groups = group_by_precursor_mz(&precursor_mz, 0.01);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], (500.25, vec![0, 2]));
+        assert_eq!(groups[1], (700.10, vec![1, 3]));
+    }
+
+    #[test]
+    fn test_group_by_precursor_mz_splits_beyond_tolerance() {
+        let precursor_mz = [(0, 500.0), (1, 500.5)];
+        let groups = group_by_precursor_mz(&precursor_mz, 0.01);
+        assert_eq!(groups.len(), 2);
+    }
+}
 
 /// Build a TIC chromatogram from scan index entries.
 pub fn build_tic(entries: &[crate::scan_index::ScanIndexEntry]) -> Chromatogram {
@@ -17,3 +58,171 @@ pub fn build_bpc(entries: &[crate::scan_index::ScanIndexEntry]) -> Chromatogram
         intensity: entries.iter().map(|e| e.base_peak_intensity).collect(),
     }
 }
+
+/// Minimum number of points a chromatogram must have before
+/// [`quantify_peak`] attempts bootstrap resampling; below this the area is
+/// reported with zero uncertainty instead.
+const MIN_BOOTSTRAP_POINTS: usize = 5;
+
+/// Minimal xorshift64* PRNG for bootstrap resampling. This crate has no
+/// `rand` dependency declared, so peak-quantification uncertainty uses a
+/// small hand-rolled generator instead of pulling one in.
+struct Xorshift64 {
+    state: u64,
+}
+
+impl Xorshift64 {
+    /// Seeds the generator, substituting a golden-ratio constant if `seed`
+    /// is `0` (an all-zero xorshift state never produces anything but zero).
+    fn new(seed: u64) -> Self {
+        Xorshift64 {
+            state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed },
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A uniformly-distributed index in `0..n`.
+    fn next_index(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Trapezoidal integration of `(rt, intensity)` points, in intensity-minutes.
+/// Assumes `rt` is sorted ascending.
+fn trapezoidal_area(rt: &[f64], intensity: &[f64]) -> f64 {
+    rt.windows(2)
+        .zip(intensity.windows(2))
+        .map(|(rt_pair, int_pair)| (rt_pair[1] - rt_pair[0]) * (int_pair[0] + int_pair[1]) / 2.0)
+        .sum()
+}
+
+/// Arithmetic mean of `values`, or `None` if empty.
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Population standard deviation of `values`, or `None` if empty.
+fn std_deviation(values: &[f64]) -> Option<f64> {
+    let m = mean(values)?;
+    let variance = values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / values.len() as f64;
+    Some(variance.sqrt())
+}
+
+/// The `p`-th percentile (`0.0..=100.0`) of `sorted_values`, which must
+/// already be sorted ascending. Linearly interpolates between the two
+/// nearest ranks.
+fn percentile(sorted_values: &[f64], p: f64) -> f64 {
+    if sorted_values.len() == 1 {
+        return sorted_values[0];
+    }
+    let rank = p / 100.0 * (sorted_values.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    if lower == upper {
+        sorted_values[lower]
+    } else {
+        let frac = rank - lower as f64;
+        sorted_values[lower] * (1.0 - frac) + sorted_values[upper] * frac
+    }
+}
+
+/// Bootstrap-resamples `chrom`'s points `n_boot` times (with replacement),
+/// trapezoidally integrating each resample, to estimate the uncertainty of
+/// its peak area.
+///
+/// Each resample is re-sorted by retention time before integration, since
+/// resampling with replacement scrambles order and duplicates points.
+/// Falls back to the plain area with zero uncertainty when `chrom` has
+/// fewer than [`MIN_BOOTSTRAP_POINTS`] points or `n_boot` is `0`.
+pub(crate) fn quantify_peak(chrom: &Chromatogram, n_boot: usize, seed: u64) -> XicQuantification {
+    let area = trapezoidal_area(&chrom.rt, &chrom.intensity);
+    let rt_apex = chrom
+        .rt
+        .iter()
+        .zip(chrom.intensity.iter())
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+        .map(|(rt, _)| *rt)
+        .unwrap_or(0.0);
+
+    if chrom.rt.len() < MIN_BOOTSTRAP_POINTS || n_boot == 0 {
+        return XicQuantification {
+            area,
+            area_std: 0.0,
+            ci_low: area,
+            ci_high: area,
+            rt_apex,
+        };
+    }
+
+    let n = chrom.rt.len();
+    let mut rng = Xorshift64::new(seed);
+    let mut areas: Vec<f64> = (0..n_boot)
+        .map(|_| {
+            let mut sample: Vec<(f64, f64)> = (0..n)
+                .map(|_| {
+                    let idx = rng.next_index(n);
+                    (chrom.rt[idx], chrom.intensity[idx])
+                })
+                .collect();
+            sample.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            let rt: Vec<f64> = sample.iter().map(|&(rt, _)| rt).collect();
+            let intensity: Vec<f64> = sample.iter().map(|&(_, i)| i).collect();
+            trapezoidal_area(&rt, &intensity)
+        })
+        .collect();
+    areas.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    XicQuantification {
+        area,
+        area_std: std_deviation(&areas).unwrap_or(0.0),
+        ci_low: percentile(&areas, 2.5),
+        ci_high: percentile(&areas, 97.5),
+        rt_apex,
+    }
+}
+
+#[cfg(test)]
+mod quantify_tests {
+    use super::*;
+
+    #[test]
+    fn test_trapezoidal_area_of_constant_intensity_is_width_times_height() {
+        let rt = vec![0.0, 1.0, 2.0, 3.0];
+        let intensity = vec![10.0, 10.0, 10.0, 10.0];
+        assert!((trapezoidal_area(&rt, &intensity) - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_quantify_peak_falls_back_below_min_points() {
+        let chrom = Chromatogram {
+            rt: vec![0.0, 1.0],
+            intensity: vec![100.0, 200.0],
+        };
+        let q = quantify_peak(&chrom, 100, 42);
+        assert_eq!(q.area_std, 0.0);
+        assert_eq!(q.ci_low, q.area);
+        assert_eq!(q.ci_high, q.area);
+    }
+
+    #[test]
+    fn test_quantify_peak_bootstraps_with_enough_points() {
+        let rt: Vec<f64> = (0..20).map(|i| i as f64 * 0.1).collect();
+        let intensity: Vec<f64> = (0..20).map(|i| 1000.0 + (i as f64).sin() * 50.0).collect();
+        let chrom = Chromatogram { rt, intensity };
+        let q = quantify_peak(&chrom, 100, 7);
+        assert!(q.area > 0.0);
+        assert!(q.ci_low <= q.area + q.area_std * 4.0);
+        assert!(q.ci_high >= q.ci_low);
+    }
+}
@@ -0,0 +1,125 @@
+//! Transparent decompression of gzip/zstd-wrapped RAW files.
+//!
+//! Labs often archive `.raw` files compressed to save space. Rather than
+//! asking every caller to decompress before handing bytes to
+//! [`RawFile::open`](crate::RawFile::open), [`sniff`] recognizes the gzip
+//! and zstd magic bytes up front so [`RawFile::open_auto`](crate::RawFile::open_auto)
+//! can decompress into an owned buffer and continue through the normal
+//! Finnigan-magic/FileHeader/RunHeader parse path unchanged.
+//!
+//! The gzip and zstd backends are gated behind their own cargo features
+//! (`gzip`, `zstd`) so a build that only ever sees uncompressed RAW files
+//! doesn't pull in either decoder.
+
+use crate::RawError;
+
+/// Container format detected from a file's leading bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerFormat {
+    /// No recognized compression wrapper -- bytes are handed to the parser
+    /// as-is (the common case: a plain Finnigan/OLE2 RAW file).
+    Raw,
+    /// gzip magic (`1f 8b`).
+    Gzip,
+    /// zstd magic (`28 b5 2f fd`).
+    Zstd,
+}
+
+impl std::fmt::Display for ContainerFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ContainerFormat::Raw => "raw",
+            ContainerFormat::Gzip => "gzip",
+            ContainerFormat::Zstd => "zstd",
+        })
+    }
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Sniff `data`'s leading bytes for a recognized compression wrapper.
+/// Never fails -- an unrecognized or too-short header is just
+/// [`ContainerFormat::Raw`], and parsing proceeds on the original bytes.
+pub fn sniff(data: &[u8]) -> ContainerFormat {
+    if data.starts_with(&GZIP_MAGIC) {
+        ContainerFormat::Gzip
+    } else if data.starts_with(&ZSTD_MAGIC) {
+        ContainerFormat::Zstd
+    } else {
+        ContainerFormat::Raw
+    }
+}
+
+/// Decompress `data` according to its sniffed [`ContainerFormat`].
+///
+/// Returns an unchanged copy of `data` for [`ContainerFormat::Raw`]. For a
+/// recognized wrapper whose backend feature isn't compiled in, or whose
+/// stream fails to decompress, returns [`RawError::UnsupportedContainer`]
+/// rather than silently falling through to parse compressed bytes as a
+/// Finnigan header.
+pub fn decompress(data: &[u8], format: ContainerFormat) -> Result<Vec<u8>, RawError> {
+    match format {
+        ContainerFormat::Raw => Ok(data.to_vec()),
+        ContainerFormat::Gzip => decompress_gzip(data),
+        ContainerFormat::Zstd => decompress_zstd(data),
+    }
+}
+
+#[cfg(feature = "gzip")]
+fn decompress_gzip(data: &[u8]) -> Result<Vec<u8>, RawError> {
+    use std::io::Read;
+    let mut out = Vec::new();
+    flate2::read::GzDecoder::new(data)
+        .read_to_end(&mut out)
+        .map_err(|e| RawError::UnsupportedContainer {
+            container: "gzip".to_string(),
+            reason: e.to_string(),
+        })?;
+    Ok(out)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn decompress_gzip(_data: &[u8]) -> Result<Vec<u8>, RawError> {
+    Err(RawError::UnsupportedContainer {
+        container: "gzip".to_string(),
+        reason: "the \"gzip\" cargo feature is not enabled".to_string(),
+    })
+}
+
+#[cfg(feature = "zstd")]
+fn decompress_zstd(data: &[u8]) -> Result<Vec<u8>, RawError> {
+    zstd::stream::decode_all(data).map_err(|e| RawError::UnsupportedContainer {
+        container: "zstd".to_string(),
+        reason: e.to_string(),
+    })
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress_zstd(_data: &[u8]) -> Result<Vec<u8>, RawError> {
+    Err(RawError::UnsupportedContainer {
+        container: "zstd".to_string(),
+        reason: "the \"zstd\" cargo feature is not enabled".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_gzip_magic() {
+        assert_eq!(sniff(&[0x1f, 0x8b, 0x08, 0x00]), ContainerFormat::Gzip);
+    }
+
+    #[test]
+    fn sniffs_zstd_magic() {
+        assert_eq!(sniff(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]), ContainerFormat::Zstd);
+    }
+
+    #[test]
+    fn anything_else_is_raw() {
+        assert_eq!(sniff(b"MThRmd"), ContainerFormat::Raw);
+        assert_eq!(sniff(&[]), ContainerFormat::Raw);
+    }
+}
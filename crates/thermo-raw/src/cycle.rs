@@ -0,0 +1,203 @@
+//! Cycle-aware iteration over the scan index.
+//!
+//! DDA and DIA acquisitions interleave one MS1 survey scan with a burst of
+//! dependent MS2 (and sometimes MS3) scans before moving to the next survey.
+//! [`iter_cycles`] walks the flat [`ScanIndexEntry`] list and regroups it
+//! into that acquisition structure, so callers don't have to manually find
+//! "the MS1 before this MS2" themselves.
+//!
+//! Grouping uses the v65+ `cycle_number`/`scan_segment` fields when present:
+//! an MS1 entry opens a cycle, and every following entry that shares its
+//! `cycle_number` and `scan_segment` is a dependent of that cycle, including
+//! MS3 entries linked to the MS2 whose precursor m/z matches their first
+//! isolation stage. Pre-v65 files always report `cycle_number == 0`, so in
+//! that case we fall back to "everything up to the next MS1 belongs to this
+//! cycle".
+
+use crate::scan_filter::ScanFilter;
+use crate::scan_index::ScanIndexEntry;
+use crate::types::MsLevel;
+
+/// One acquisition cycle: an MS1 survey scan plus its dependent MS2/MS3 scans.
+#[derive(Debug)]
+pub struct Cycle<'a> {
+    pub survey: &'a ScanIndexEntry,
+    pub dependents: Vec<&'a ScanIndexEntry>,
+    /// For each MS3 entry in `dependents`, the MS2 entry (also in
+    /// `dependents`) whose precursor m/z matched its first isolation stage,
+    /// if one was found. See the module doc for the matching rule.
+    pub ms3_parents: Vec<(&'a ScanIndexEntry, Option<&'a ScanIndexEntry>)>,
+}
+
+/// Tolerance (in Da) for matching an MS3's first isolation stage m/z against
+/// a candidate parent MS2's own precursor m/z.
+const PRECURSOR_MATCH_TOLERANCE: f64 = 0.01;
+
+/// Iterate acquisition cycles over `entries`, using the parsed filter for
+/// each entry (`filters[i]` must correspond to `entries[i]`) to determine MS
+/// level and precursor links.
+pub fn iter_cycles<'a>(
+    entries: &'a [ScanIndexEntry],
+    filters: &'a [ScanFilter],
+) -> impl Iterator<Item = Cycle<'a>> {
+    CycleIter {
+        entries,
+        filters,
+        pos: 0,
+    }
+}
+
+struct CycleIter<'a> {
+    entries: &'a [ScanIndexEntry],
+    filters: &'a [ScanFilter],
+    pos: usize,
+}
+
+impl<'a> Iterator for CycleIter<'a> {
+    type Item = Cycle<'a>;
+
+    fn next(&mut self) -> Option<Cycle<'a>> {
+        // Advance to the next MS1 survey scan.
+        while self.pos < self.entries.len()
+            && !matches!(self.filters[self.pos].ms_level, MsLevel::Ms1)
+        {
+            self.pos += 1;
+        }
+        if self.pos >= self.entries.len() {
+            return None;
+        }
+
+        let survey = &self.entries[self.pos];
+        self.pos += 1;
+
+        // v65+: group by (cycle_number, scan_segment). Pre-v65 files report
+        // cycle_number == 0 for every entry, so fall back to "everything up
+        // to the next MS1" below instead of matching on it.
+        let group_by_cycle_number = survey.cycle_number != 0;
+
+        let mut dependents: Vec<&'a ScanIndexEntry> = Vec::new();
+        // MS2 entries seen so far in this cycle, for MS3 precursor linking.
+        let mut ms2_in_cycle: Vec<&'a ScanIndexEntry> = Vec::new();
+        let mut ms2_filters: Vec<&'a ScanFilter> = Vec::new();
+        let mut ms3_parents: Vec<(&'a ScanIndexEntry, Option<&'a ScanIndexEntry>)> = Vec::new();
+
+        while self.pos < self.entries.len() {
+            let entry = &self.entries[self.pos];
+            let filter = &self.filters[self.pos];
+
+            if matches!(filter.ms_level, MsLevel::Ms1) {
+                break;
+            }
+
+            if group_by_cycle_number
+                && (entry.cycle_number != survey.cycle_number
+                    || entry.scan_segment != survey.scan_segment)
+            {
+                break;
+            }
+
+            match filter.ms_level {
+                MsLevel::Ms2 => {
+                    dependents.push(entry);
+                    ms2_in_cycle.push(entry);
+                    ms2_filters.push(filter);
+                }
+                MsLevel::Ms3 => {
+                    // Best-effort link: an MS3's first isolation stage m/z
+                    // should match the precursor m/z the linked MS2 itself
+                    // isolated. This doesn't change where the entry lands in
+                    // `dependents` (it's already in the right cycle) -- it's
+                    // recorded in `ms3_parents` for callers that want it.
+                    let parent_ms2 = filter.precursors.first().and_then(|stage| {
+                        ms2_in_cycle
+                            .iter()
+                            .zip(ms2_filters.iter())
+                            .find(|(_, f)| {
+                                f.precursor()
+                                    .is_some_and(|p| (p.mz - stage.mz).abs() < PRECURSOR_MATCH_TOLERANCE)
+                            })
+                            .map(|(e, _)| *e)
+                    });
+                    ms3_parents.push((entry, parent_ms2));
+                    dependents.push(entry);
+                }
+                _ => dependents.push(entry),
+            }
+
+            self.pos += 1;
+        }
+
+        Some(Cycle {
+            survey,
+            dependents,
+            ms3_parents,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan_filter::parse_filter;
+
+    fn entry(scan_number: i32, cycle_number: i32, scan_segment: u16) -> ScanIndexEntry {
+        ScanIndexEntry {
+            offset: 0,
+            trailer_offset: 0,
+            scan_event: 0,
+            scan_segment,
+            scan_number,
+            packet_type: 0,
+            number_packets: 1,
+            data_size: 0,
+            rt: scan_number as f64,
+            tic: 0.0,
+            base_peak_intensity: 0.0,
+            base_peak_mz: 0.0,
+            low_mz: 0.0,
+            high_mz: 0.0,
+            cycle_number,
+        }
+    }
+
+    #[test]
+    fn groups_by_cycle_number_v65() {
+        let entries = vec![
+            entry(1, 1, 0),
+            entry(2, 1, 0),
+            entry(3, 1, 0),
+            entry(4, 2, 0),
+            entry(5, 2, 0),
+        ];
+        let filters: Vec<ScanFilter> = vec![
+            parse_filter("FTMS + p NSI Full ms [200.00-2000.00]"),
+            parse_filter("FTMS + c NSI d Full ms2 524.26@hcd28.00 [100.00-1060.00]"),
+            parse_filter("FTMS + c NSI d Full ms2 600.00@hcd28.00 [100.00-1060.00]"),
+            parse_filter("FTMS + p NSI Full ms [200.00-2000.00]"),
+            parse_filter("FTMS + c NSI d Full ms2 524.26@hcd28.00 [100.00-1060.00]"),
+        ];
+
+        let cycles: Vec<Cycle> = iter_cycles(&entries, &filters).collect();
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[0].survey.scan_number, 1);
+        assert_eq!(cycles[0].dependents.len(), 2);
+        assert_eq!(cycles[1].survey.scan_number, 4);
+        assert_eq!(cycles[1].dependents.len(), 1);
+    }
+
+    #[test]
+    fn falls_back_to_sequential_grouping_pre_v65() {
+        let entries = vec![entry(1, 0, 0), entry(2, 0, 0), entry(3, 0, 0), entry(4, 0, 0)];
+        let filters: Vec<ScanFilter> = vec![
+            parse_filter("FTMS + p NSI Full ms [200.00-2000.00]"),
+            parse_filter("FTMS + c NSI d Full ms2 524.26@hcd28.00 [100.00-1060.00]"),
+            parse_filter("FTMS + c NSI d Full ms2 600.00@hcd28.00 [100.00-1060.00]"),
+            parse_filter("FTMS + p NSI Full ms [200.00-2000.00]"),
+        ];
+
+        let cycles: Vec<Cycle> = iter_cycles(&entries, &filters).collect();
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[0].dependents.len(), 2);
+        assert_eq!(cycles[1].dependents.len(), 0);
+    }
+}
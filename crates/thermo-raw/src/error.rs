@@ -25,4 +25,31 @@ pub enum RawError {
 
     #[error("OLE2/CFBF error: {0}")]
     CfbError(String),
+
+    #[error("GenericDataHeader at offset {offset} has unreasonable field count: {n}")]
+    UnreasonableFieldCount { offset: u64, n: u32 },
+
+    #[error("Unknown trailer field type code 0x{type_code:X} for field {field_idx} at offset {offset}")]
+    UnknownTypeCode {
+        offset: u64,
+        field_idx: usize,
+        type_code: u32,
+    },
+
+    #[error("Field '{field}' (type_code=0x{type_code:X}) cannot be read as {requested}")]
+    FieldTypeMismatch {
+        field: String,
+        type_code: u32,
+        requested: &'static str,
+    },
+
+    #[error("Trailer record for scan {scan_index} out of bounds at offset {offset} (len {len})")]
+    RecordOutOfBounds {
+        scan_index: u32,
+        offset: u64,
+        len: usize,
+    },
+
+    #[error("{container} container detected but could not be decompressed: {reason}")]
+    UnsupportedContainer { container: String, reason: String },
 }
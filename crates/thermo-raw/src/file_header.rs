@@ -11,9 +11,10 @@
 //! - 60 bytes: unknown area
 //! - 2056 bytes: tag (UTF-16LE, 1028 chars)
 
-use crate::io_utils::BinaryReader;
+use crate::io_utils::{BinaryReader, BinaryWriter, PacketSource, ReadBinary, ToWriter};
 use crate::version::FINNIGAN_MAGIC;
 use crate::RawError;
+use std::io::{Read, Seek};
 
 /// Parsed Finnigan file header.
 #[derive(Debug, Clone)]
@@ -36,7 +37,7 @@ struct AuditTag {
 }
 
 impl AuditTag {
-    fn parse(reader: &mut BinaryReader) -> Result<Self, RawError> {
+    fn parse<R: Read + Seek>(reader: &mut BinaryReader<R>) -> Result<Self, RawError> {
         let time = reader.read_u64()?;
         let tag1 = reader.read_utf16_fixed(100)?; // 50 UTF-16 chars
         let unknown = reader.read_u32()?;
@@ -48,11 +49,37 @@ impl AuditTag {
     }
 }
 
+impl ToWriter for AuditTag {
+    fn to_writer(&self, w: &mut BinaryWriter) -> Result<(), RawError> {
+        w.write_u64(self.time);
+        w.write_utf16_fixed(&self.tag1, 100);
+        w.write_u32(self._unknown);
+        Ok(())
+    }
+}
+
 impl FileHeader {
     /// Parse the FileHeader from the beginning of the data stream.
     pub fn parse(data: &[u8]) -> Result<Self, RawError> {
-        let mut reader = BinaryReader::new(data);
+        BinaryReader::new(data).extract()
+    }
+
+    /// Parse the FileHeader starting at `offset` in `source`, fetching only
+    /// the bytes it needs rather than requiring the whole file resident as
+    /// one slice. See [`PacketSource`].
+    pub fn parse_at<S: PacketSource + ?Sized>(source: &S, offset: u64) -> Result<Self, RawError> {
+        let bytes = source.read_at(offset, Self::size())?;
+        BinaryReader::new(&bytes).extract()
+    }
+
+    /// Size of the FileHeader in bytes.
+    pub fn size() -> usize {
+        2 + 18 + 16 + 4 + 112 + 112 + 4 + 60 + 2056
+    }
+}
 
+impl ReadBinary for FileHeader {
+    fn read_from<R: Read + Seek>(reader: &mut BinaryReader<R>) -> Result<Self, RawError> {
         let magic = reader.read_u16()?;
         if magic != FINNIGAN_MAGIC {
             return Err(RawError::NotRawFile);
@@ -65,8 +92,8 @@ impl FileHeader {
         let _unknown4 = reader.read_u32()?;
         let version = reader.read_u32()?;
 
-        let audit_start = AuditTag::parse(&mut reader)?;
-        let audit_end = AuditTag::parse(&mut reader)?;
+        let audit_start = AuditTag::parse(reader)?;
+        let audit_end = AuditTag::parse(reader)?;
 
         let _unknown5 = reader.read_u32()?;
         reader.skip(60)?; // unknown area
@@ -83,10 +110,42 @@ impl FileHeader {
             tag,
         })
     }
+}
 
-    /// Size of the FileHeader in bytes.
-    pub fn size() -> usize {
-        2 + 18 + 16 + 4 + 112 + 112 + 4 + 60 + 2056
+impl ToWriter for FileHeader {
+    /// Serialize back into the layout [`ReadBinary::read_from`] expects.
+    ///
+    /// `FileHeader` only keeps the fields downstream code actually uses, so
+    /// this can't reproduce the original bytes exactly: the four leading
+    /// unknown u32s, the audit tags' `_unknown` fields, and the
+    /// `audit_end`/modification audit tag's user name are not retained
+    /// anywhere on `FileHeader` and are written back as zero/empty.
+    fn to_writer(&self, w: &mut BinaryWriter) -> Result<(), RawError> {
+        w.write_u16(self.magic);
+        w.write_utf16_fixed(&self.signature, 18);
+        w.write_u32(0); // unknown1
+        w.write_u32(0); // unknown2
+        w.write_u32(0); // unknown3
+        w.write_u32(0); // unknown4
+        w.write_u32(self.version);
+
+        AuditTag {
+            time: self.creation_time,
+            tag1: self.creation_user.clone(),
+            _unknown: 0,
+        }
+        .to_writer(w)?;
+        AuditTag {
+            time: self.modification_time,
+            tag1: String::new(),
+            _unknown: 0,
+        }
+        .to_writer(w)?;
+
+        w.write_u32(0); // unknown5
+        w.pad(60); // unknown area
+        w.write_utf16_fixed(&self.tag, 2056);
+        Ok(())
     }
 }
 
@@ -147,3 +206,36 @@ fn days_to_ymd(mut days: u64) -> (u64, u64, u64) {
 fn is_leap_year(year: u64) -> bool {
     (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io_utils::BinaryWriter;
+
+    #[test]
+    fn test_file_header_round_trips_through_writer() {
+        let header = FileHeader {
+            magic: FINNIGAN_MAGIC,
+            signature: "Finnigan".to_string(),
+            version: 66,
+            creation_time: 132_000_000_000_000_000,
+            creation_user: "alice".to_string(),
+            modification_time: 132_100_000_000_000_000,
+            tag: "sample tag".to_string(),
+        };
+
+        let mut w = BinaryWriter::new();
+        header.to_writer(&mut w).unwrap();
+        let bytes = w.into_bytes();
+        assert_eq!(bytes.len(), FileHeader::size());
+
+        let decoded = FileHeader::parse(&bytes).unwrap();
+        assert_eq!(decoded.magic, header.magic);
+        assert_eq!(decoded.signature, header.signature);
+        assert_eq!(decoded.version, header.version);
+        assert_eq!(decoded.creation_time, header.creation_time);
+        assert_eq!(decoded.creation_user, header.creation_user);
+        assert_eq!(decoded.modification_time, header.modification_time);
+        assert_eq!(decoded.tag, header.tag);
+    }
+}
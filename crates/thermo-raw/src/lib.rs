@@ -19,33 +19,102 @@
 //! let scan = raw.scan(1).unwrap();
 //! println!("m/z values: {:?}", &scan.centroid_mz[..5]);
 //! ```
+//!
+//! # `no_std`
+//!
+//! With default features disabled, this crate builds under `no_std` +
+//! `alloc`: [`types`] (`Scan`, `PrecursorInfo`, `Chromatogram`, ...) has no
+//! dependency on `std`, so embedded/WASM consumers can depend on just the
+//! data model — optionally with `serde` for (de)serializing scans — without
+//! pulling in `rayon`, `quick-xml`, or filesystem/mmap access. Reading RAW
+//! files themselves still requires the `std` feature (enabled by default).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
 
+#[cfg(feature = "std")]
+pub mod acquisition;
+#[cfg(feature = "std")]
 pub mod batch;
+#[cfg(feature = "std")]
+pub mod block_cache;
+#[cfg(feature = "std")]
+pub mod check;
+#[cfg(feature = "std")]
+pub mod checksum;
+#[cfg(feature = "std")]
 pub mod chromatogram;
+#[cfg(feature = "std")]
+pub mod compression;
+#[cfg(feature = "std")]
+pub mod cycle;
+#[cfg(feature = "std")]
 pub mod error;
+#[cfg(feature = "std")]
 pub mod file_header;
+#[cfg(feature = "std")]
 pub mod io_utils;
+#[cfg(feature = "std")]
 pub mod metadata;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod pot;
+#[cfg(feature = "std")]
+pub mod precursor_refinement;
+#[cfg(feature = "std")]
 pub mod progress;
+#[cfg(feature = "std")]
 pub mod raw_file;
+#[cfg(feature = "std")]
 pub mod raw_file_info;
+#[cfg(feature = "std")]
 pub mod run_header;
+#[cfg(feature = "std")]
 pub mod scan_data;
+#[cfg(feature = "std")]
 pub mod scan_data_centroid;
+#[cfg(feature = "std")]
 pub mod scan_data_ftlt;
+#[cfg(feature = "std")]
 pub mod scan_data_profile;
+#[cfg(feature = "std")]
 pub mod scan_event;
+#[cfg(feature = "std")]
 pub mod scan_filter;
+#[cfg(feature = "std")]
 pub mod scan_index;
+#[cfg(feature = "std")]
+pub mod scan_source;
+#[cfg(feature = "std")]
 pub mod trailer;
 pub mod types;
+#[cfg(feature = "std")]
+pub mod verify;
+#[cfg(feature = "std")]
 pub mod version;
 
+#[cfg(feature = "std")]
 pub mod validation;
 
+#[cfg(feature = "std")]
 pub use batch::{batch_xic_ms1, batch_xic_ms1_with_progress, BatchXicResult};
+#[cfg(feature = "std")]
+pub use compression::ContainerFormat;
+#[cfg(feature = "std")]
 pub use error::RawError;
+#[cfg(feature = "std")]
+pub use metrics::{new_metrics, Metrics, MetricsInner, MetricsSnapshot};
+#[cfg(feature = "std")]
+pub use pot::{from_pot, to_pot, FromPot, ToPot};
+#[cfg(feature = "std")]
 pub use progress::{new_counter, ProgressCounter};
+#[cfg(feature = "std")]
 pub use raw_file::{diagnose, DebugInfo, DiagnosticReport, DiagnosticStage, RawFile};
+#[cfg(feature = "std")]
 pub use scan_event::ActivationType;
 pub use types::*;
+#[cfg(feature = "std")]
+pub use verify::{ScanVerification, VerificationReport};
@@ -0,0 +1,190 @@
+//! Lock-free live metrics for long-running conversions and batch reads.
+//!
+//! Unlike [`crate::progress`]'s single tick counter (meant for a UI progress
+//! bar), this tracks enough detail -- scans converted, bytes read, spectra
+//! decoded, and a latency histogram -- to report throughput and latency
+//! percentiles once the run completes, or to poll mid-run. Everything is a
+//! plain `AtomicU64`; there's no background thread or event loop, so workers
+//! pay only the cost of a few relaxed atomic increments per scan.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Number of power-of-two microsecond buckets in the latency histogram.
+/// Bucket `i` covers microseconds from `2^(i-1)` up to (not including)
+/// `2^i` (bucket 0 covers exactly `0`), so 40 buckets comfortably covers
+/// anything from sub-microsecond to multi-hour latencies.
+const LATENCY_BUCKETS: usize = 40;
+
+/// Shared handle for lock-free metrics collection; cheap to clone (it's an
+/// `Arc`) and safe to update concurrently from rayon workers.
+pub type Metrics = Arc<MetricsInner>;
+
+/// The actual counters behind a [`Metrics`] handle.
+#[derive(Debug)]
+pub struct MetricsInner {
+    scans_converted: AtomicU64,
+    bytes_read: AtomicU64,
+    spectra_decoded: AtomicU64,
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS],
+}
+
+impl Default for MetricsInner {
+    fn default() -> Self {
+        MetricsInner {
+            scans_converted: AtomicU64::new(0),
+            bytes_read: AtomicU64::new(0),
+            spectra_decoded: AtomicU64::new(0),
+            latency_buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+}
+
+/// Create a new zero-initialized metrics handle.
+pub fn new_metrics() -> Metrics {
+    Arc::new(MetricsInner::default())
+}
+
+/// Bucket index for a latency of `micros` microseconds.
+fn latency_bucket(micros: u64) -> usize {
+    if micros == 0 {
+        0
+    } else {
+        (64 - micros.leading_zeros() as usize).min(LATENCY_BUCKETS - 1)
+    }
+}
+
+impl MetricsInner {
+    /// Record one converted scan.
+    #[inline]
+    pub fn record_scan_converted(&self) {
+        self.scans_converted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record `n` bytes read from the source file.
+    #[inline]
+    pub fn record_bytes_read(&self, n: u64) {
+        self.bytes_read.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Record one decoded spectrum.
+    #[inline]
+    pub fn record_spectrum_decoded(&self) {
+        self.spectra_decoded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record one operation's latency in the power-of-two-microsecond histogram.
+    #[inline]
+    pub fn record_latency(&self, elapsed: Duration) {
+        let bucket = latency_bucket(elapsed.as_micros() as u64);
+        self.latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A consistent point-in-time snapshot of all counters. Individual
+    /// fields may have been updated concurrently between reads, so the
+    /// snapshot is "approximately simultaneous", not atomic as a whole --
+    /// fine for progress reporting, not for exact accounting.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            scans_converted: self.scans_converted.load(Ordering::Relaxed),
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            spectra_decoded: self.spectra_decoded.load(Ordering::Relaxed),
+            latency_buckets: std::array::from_fn(|i| self.latency_buckets[i].load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// A plain-data snapshot of [`MetricsInner`]'s counters, taken via [`MetricsInner::snapshot`].
+#[derive(Debug, Clone, Copy)]
+pub struct MetricsSnapshot {
+    pub scans_converted: u64,
+    pub bytes_read: u64,
+    pub spectra_decoded: u64,
+    latency_buckets: [u64; LATENCY_BUCKETS],
+}
+
+impl MetricsSnapshot {
+    /// Scans converted per second, given the wall-clock `elapsed` time.
+    pub fn scans_per_sec(&self, elapsed: Duration) -> f64 {
+        self.scans_converted as f64 / elapsed.as_secs_f64()
+    }
+
+    /// Megabytes read per second, given the wall-clock `elapsed` time.
+    pub fn mb_per_sec(&self, elapsed: Duration) -> f64 {
+        (self.bytes_read as f64 / 1_000_000.0) / elapsed.as_secs_f64()
+    }
+
+    /// Approximate `p`-th percentile (`0.0..=100.0`) latency in microseconds,
+    /// taken as the upper edge of the bucket containing that rank. Bucketing
+    /// trades exactness for the ability to track latency with a fixed,
+    /// lock-free amount of memory.
+    pub fn latency_percentile_micros(&self, p: f64) -> u64 {
+        let total: u64 = self.latency_buckets.iter().sum();
+        if total == 0 {
+            return 0;
+        }
+        let target_rank = (p / 100.0 * total as f64).ceil() as u64;
+        let mut cumulative = 0u64;
+        for (i, &count) in self.latency_buckets.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= target_rank {
+                return if i == 0 { 0 } else { 1u64 << i };
+            }
+        }
+        1u64 << (LATENCY_BUCKETS - 1)
+    }
+
+    /// Non-empty `(bucket_upper_bound_micros, count)` pairs, for printing a
+    /// full histogram rather than just percentiles.
+    pub fn latency_histogram(&self) -> Vec<(u64, u64)> {
+        self.latency_buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(i, &count)| (if i == 0 { 0 } else { 1u64 << i }, count))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_bucket_groups_by_power_of_two() {
+        assert_eq!(latency_bucket(0), 0);
+        assert_eq!(latency_bucket(1), latency_bucket(1));
+        assert_eq!(latency_bucket(100), latency_bucket(110));
+        assert_ne!(latency_bucket(100), latency_bucket(1000));
+    }
+
+    #[test]
+    fn test_metrics_snapshot_reflects_recorded_counters() {
+        let metrics = new_metrics();
+        metrics.record_scan_converted();
+        metrics.record_scan_converted();
+        metrics.record_bytes_read(2048);
+        metrics.record_spectrum_decoded();
+        metrics.record_latency(Duration::from_micros(50));
+
+        let snap = metrics.snapshot();
+        assert_eq!(snap.scans_converted, 2);
+        assert_eq!(snap.bytes_read, 2048);
+        assert_eq!(snap.spectra_decoded, 1);
+        assert_eq!(snap.latency_histogram().iter().map(|(_, c)| c).sum::<u64>(), 1);
+    }
+
+    #[test]
+    fn test_percentile_falls_within_observed_buckets() {
+        let metrics = new_metrics();
+        for micros in [10, 20, 30, 1000, 2000] {
+            metrics.record_latency(Duration::from_micros(micros));
+        }
+        let snap = metrics.snapshot();
+        let p50 = snap.latency_percentile_micros(50.0);
+        let p99 = snap.latency_percentile_micros(99.0);
+        assert!(p50 <= p99);
+        assert!(p99 >= 2000);
+    }
+}
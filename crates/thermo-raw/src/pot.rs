@@ -0,0 +1,501 @@
+//! A compact, self-describing binary encoding for [`Scan`]/[`Chromatogram`].
+//!
+//! Serializing millions of spectra through `serde_json` is slow and verbose.
+//! This is a POT ("plain old tags") format instead: a short header, then a
+//! flat sequence of `(tag: u16, len: u32, bytes)` fields. Unlike a straight
+//! `bincode` dump, it stays forward/backward compatible as the schema grows:
+//!
+//! - A decoder built against an older schema simply skips tags it doesn't
+//!   recognize (forward compatibility: newer writer, older reader).
+//! - A field missing from an older payload just decodes as `None`/default
+//!   (backward compatibility: older writer, newer reader).
+//!
+//! Numeric arrays (`centroid_mz`, `profile_intensity`, ...) are written as a
+//! single packed native-endian block rather than per-element tagged values,
+//! so the dense payloads stay dense on disk.
+
+use crate::error::RawError;
+use crate::types::{Chromatogram, MsLevel, Polarity, PrecursorInfo, Scan};
+use std::collections::HashMap;
+
+const MAGIC: [u8; 4] = *b"TPOT";
+const FORMAT_VERSION: u8 = 1;
+
+mod tag {
+    // Scan
+    pub const SCAN_NUMBER: u16 = 1;
+    pub const RT: u16 = 2;
+    pub const MS_LEVEL: u16 = 3;
+    pub const POLARITY: u16 = 4;
+    pub const TIC: u16 = 5;
+    pub const BASE_PEAK_MZ: u16 = 6;
+    pub const BASE_PEAK_INTENSITY: u16 = 7;
+    pub const CENTROID_MZ: u16 = 8;
+    pub const CENTROID_INTENSITY: u16 = 9;
+    pub const PROFILE_MZ: u16 = 10;
+    pub const PROFILE_INTENSITY: u16 = 11;
+    pub const PRECURSOR: u16 = 12;
+    pub const FILTER_STRING: u16 = 13;
+    pub const ION_MOBILITY: u16 = 14;
+    pub const COMPENSATION_VOLTAGE: u16 = 15;
+
+    // PrecursorInfo, nested inside the `PRECURSOR` field's own byte range.
+    pub const PREC_MZ: u16 = 1;
+    pub const PREC_CHARGE: u16 = 2;
+    pub const PREC_ISOLATION_WIDTH: u16 = 3;
+    pub const PREC_ACTIVATION_TYPE: u16 = 4;
+    pub const PREC_COLLISION_ENERGY: u16 = 5;
+    pub const PREC_COMPENSATION_VOLTAGE: u16 = 6;
+
+    // Chromatogram
+    pub const CHROM_RT: u16 = 1;
+    pub const CHROM_INTENSITY: u16 = 2;
+}
+
+/// Types that can be encoded as a POT byte buffer.
+pub trait ToPot {
+    fn to_pot(&self) -> Vec<u8>;
+}
+
+/// Types that can be decoded from a POT byte buffer.
+pub trait FromPot: Sized {
+    fn from_pot(bytes: &[u8]) -> Result<Self, RawError>;
+}
+
+/// Encode `value` as a POT byte buffer.
+pub fn to_pot<T: ToPot>(value: &T) -> Vec<u8> {
+    value.to_pot()
+}
+
+/// Decode a POT byte buffer back into `T`.
+pub fn from_pot<T: FromPot>(bytes: &[u8]) -> Result<T, RawError> {
+    T::from_pot(bytes)
+}
+
+fn write_header(out: &mut Vec<u8>) {
+    out.extend_from_slice(&MAGIC);
+    out.push(FORMAT_VERSION);
+}
+
+fn write_field(out: &mut Vec<u8>, tag: u16, body: &[u8]) {
+    out.extend_from_slice(&tag.to_le_bytes());
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(body);
+}
+
+fn write_f64(out: &mut Vec<u8>, tag: u16, value: f64) {
+    write_field(out, tag, &value.to_ne_bytes());
+}
+
+fn write_u32(out: &mut Vec<u8>, tag: u16, value: u32) {
+    write_field(out, tag, &value.to_ne_bytes());
+}
+
+fn write_i32(out: &mut Vec<u8>, tag: u16, value: i32) {
+    write_field(out, tag, &value.to_ne_bytes());
+}
+
+fn write_u8(out: &mut Vec<u8>, tag: u16, value: u8) {
+    write_field(out, tag, &[value]);
+}
+
+fn write_str(out: &mut Vec<u8>, tag: u16, value: &str) {
+    write_field(out, tag, value.as_bytes());
+}
+
+fn write_f64_array(out: &mut Vec<u8>, tag: u16, values: &[f64]) {
+    let mut body = Vec::with_capacity(values.len() * 8);
+    for v in values {
+        body.extend_from_slice(&v.to_ne_bytes());
+    }
+    write_field(out, tag, &body);
+}
+
+/// A decoded field table: tag -> raw bytes. Duplicate tags keep the last
+/// occurrence, matching how the writer always emits each field at most once.
+struct FieldReader<'a> {
+    fields: HashMap<u16, &'a [u8]>,
+}
+
+impl<'a> FieldReader<'a> {
+    fn parse(mut body: &'a [u8]) -> Result<Self, RawError> {
+        let mut fields = HashMap::new();
+        while !body.is_empty() {
+            if body.len() < 6 {
+                return Err(RawError::CorruptedData(
+                    "POT: truncated field header".to_string(),
+                ));
+            }
+            let tag = u16::from_le_bytes(body[0..2].try_into().unwrap());
+            let len = u32::from_le_bytes(body[2..6].try_into().unwrap()) as usize;
+            body = &body[6..];
+            if body.len() < len {
+                return Err(RawError::CorruptedData(format!(
+                    "POT: field {tag} declares length {len} but only {} bytes remain",
+                    body.len()
+                )));
+            }
+            fields.insert(tag, &body[..len]);
+            body = &body[len..];
+        }
+        Ok(Self { fields })
+    }
+
+    fn bytes(&self, tag: u16) -> Option<&'a [u8]> {
+        self.fields.get(&tag).copied()
+    }
+
+    fn f64(&self, tag: u16) -> Option<f64> {
+        self.bytes(tag)
+            .map(|b| f64::from_ne_bytes(b.try_into().unwrap_or([0; 8])))
+    }
+
+    fn u32(&self, tag: u16) -> Option<u32> {
+        self.bytes(tag)
+            .map(|b| u32::from_ne_bytes(b.try_into().unwrap_or([0; 4])))
+    }
+
+    fn i32(&self, tag: u16) -> Option<i32> {
+        self.bytes(tag)
+            .map(|b| i32::from_ne_bytes(b.try_into().unwrap_or([0; 4])))
+    }
+
+    fn u8(&self, tag: u16) -> Option<u8> {
+        self.bytes(tag).and_then(|b| b.first().copied())
+    }
+
+    fn string(&self, tag: u16) -> Option<String> {
+        self.bytes(tag)
+            .map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+
+    fn f64_array(&self, tag: u16) -> Option<Vec<f64>> {
+        self.bytes(tag).map(|b| {
+            b.chunks_exact(8)
+                .map(|c| f64::from_ne_bytes(c.try_into().unwrap()))
+                .collect()
+        })
+    }
+}
+
+fn read_header(bytes: &[u8]) -> Result<&[u8], RawError> {
+    if bytes.len() < 5 || bytes[0..4] != MAGIC {
+        return Err(RawError::CorruptedData(
+            "POT: missing or invalid magic".to_string(),
+        ));
+    }
+    if bytes[4] > FORMAT_VERSION {
+        return Err(RawError::CorruptedData(format!(
+            "POT: unsupported format version {}",
+            bytes[4]
+        )));
+    }
+    Ok(&bytes[5..])
+}
+
+fn ms_level_code(level: &MsLevel) -> (u8, u8) {
+    match level {
+        MsLevel::Ms1 => (1, 0),
+        MsLevel::Ms2 => (2, 0),
+        MsLevel::Ms3 => (3, 0),
+        MsLevel::Other(n) => (0, *n),
+    }
+}
+
+fn ms_level_from_code(kind: u8, other: u8) -> MsLevel {
+    match kind {
+        1 => MsLevel::Ms1,
+        2 => MsLevel::Ms2,
+        3 => MsLevel::Ms3,
+        _ => MsLevel::Other(other),
+    }
+}
+
+fn polarity_code(p: Polarity) -> u8 {
+    match p {
+        Polarity::Positive => 0,
+        Polarity::Negative => 1,
+        Polarity::Unknown => 2,
+    }
+}
+
+fn polarity_from_code(code: u8) -> Polarity {
+    match code {
+        0 => Polarity::Positive,
+        1 => Polarity::Negative,
+        _ => Polarity::Unknown,
+    }
+}
+
+impl ToPot for PrecursorInfo {
+    fn to_pot(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_f64(&mut out, tag::PREC_MZ, self.mz);
+        if let Some(charge) = self.charge {
+            write_i32(&mut out, tag::PREC_CHARGE, charge);
+        }
+        if let Some(width) = self.isolation_width {
+            write_f64(&mut out, tag::PREC_ISOLATION_WIDTH, width);
+        }
+        if let Some(act) = &self.activation_type {
+            write_str(&mut out, tag::PREC_ACTIVATION_TYPE, act);
+        }
+        if let Some(ce) = self.collision_energy {
+            write_f64(&mut out, tag::PREC_COLLISION_ENERGY, ce);
+        }
+        if let Some(cv) = self.compensation_voltage {
+            write_f64(&mut out, tag::PREC_COMPENSATION_VOLTAGE, cv);
+        }
+        out
+    }
+}
+
+impl FromPot for PrecursorInfo {
+    fn from_pot(bytes: &[u8]) -> Result<Self, RawError> {
+        let fields = FieldReader::parse(bytes)?;
+        Ok(PrecursorInfo {
+            mz: fields.f64(tag::PREC_MZ).unwrap_or(0.0),
+            charge: fields.i32(tag::PREC_CHARGE),
+            isolation_width: fields.f64(tag::PREC_ISOLATION_WIDTH),
+            activation_type: fields.string(tag::PREC_ACTIVATION_TYPE),
+            collision_energy: fields.f64(tag::PREC_COLLISION_ENERGY),
+            compensation_voltage: fields.f64(tag::PREC_COMPENSATION_VOLTAGE),
+        })
+    }
+}
+
+impl ToPot for Scan {
+    fn to_pot(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_header(&mut out);
+        write_u32(&mut out, tag::SCAN_NUMBER, self.scan_number);
+        write_f64(&mut out, tag::RT, self.rt);
+        let (kind, other) = ms_level_code(&self.ms_level);
+        write_field(&mut out, tag::MS_LEVEL, &[kind, other]);
+        write_u8(&mut out, tag::POLARITY, polarity_code(self.polarity));
+        write_f64(&mut out, tag::TIC, self.tic);
+        write_f64(&mut out, tag::BASE_PEAK_MZ, self.base_peak_mz);
+        write_f64(&mut out, tag::BASE_PEAK_INTENSITY, self.base_peak_intensity);
+        write_f64_array(&mut out, tag::CENTROID_MZ, &self.centroid_mz);
+        write_f64_array(&mut out, tag::CENTROID_INTENSITY, &self.centroid_intensity);
+        if let Some(mz) = &self.profile_mz {
+            write_f64_array(&mut out, tag::PROFILE_MZ, mz);
+        }
+        if let Some(intensity) = &self.profile_intensity {
+            write_f64_array(&mut out, tag::PROFILE_INTENSITY, intensity);
+        }
+        if let Some(precursor) = &self.precursor {
+            write_field(&mut out, tag::PRECURSOR, &precursor.to_pot());
+        }
+        if let Some(filter) = &self.filter_string {
+            write_str(&mut out, tag::FILTER_STRING, filter);
+        }
+        if let Some(im) = self.ion_mobility {
+            write_f64(&mut out, tag::ION_MOBILITY, im);
+        }
+        if let Some(cv) = self.compensation_voltage {
+            write_f64(&mut out, tag::COMPENSATION_VOLTAGE, cv);
+        }
+        out
+    }
+}
+
+impl FromPot for Scan {
+    fn from_pot(bytes: &[u8]) -> Result<Self, RawError> {
+        let body = read_header(bytes)?;
+        let fields = FieldReader::parse(body)?;
+
+        let ms_level = match fields.bytes(tag::MS_LEVEL) {
+            Some([kind, other]) => ms_level_from_code(*kind, *other),
+            _ => MsLevel::Ms1,
+        };
+
+        Ok(Scan {
+            scan_number: fields.u32(tag::SCAN_NUMBER).unwrap_or(0),
+            rt: fields.f64(tag::RT).unwrap_or(0.0),
+            ms_level,
+            polarity: polarity_from_code(fields.u8(tag::POLARITY).unwrap_or(2)),
+            tic: fields.f64(tag::TIC).unwrap_or(0.0),
+            base_peak_mz: fields.f64(tag::BASE_PEAK_MZ).unwrap_or(0.0),
+            base_peak_intensity: fields.f64(tag::BASE_PEAK_INTENSITY).unwrap_or(0.0),
+            centroid_mz: fields.f64_array(tag::CENTROID_MZ).unwrap_or_default(),
+            centroid_intensity: fields
+                .f64_array(tag::CENTROID_INTENSITY)
+                .unwrap_or_default(),
+            profile_mz: fields.f64_array(tag::PROFILE_MZ),
+            profile_intensity: fields.f64_array(tag::PROFILE_INTENSITY),
+            precursor: fields
+                .bytes(tag::PRECURSOR)
+                .map(PrecursorInfo::from_pot)
+                .transpose()?,
+            filter_string: fields.string(tag::FILTER_STRING),
+            ion_mobility: fields.f64(tag::ION_MOBILITY),
+            compensation_voltage: fields.f64(tag::COMPENSATION_VOLTAGE),
+        })
+    }
+}
+
+impl ToPot for Chromatogram {
+    fn to_pot(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_header(&mut out);
+        write_f64_array(&mut out, tag::CHROM_RT, &self.rt);
+        write_f64_array(&mut out, tag::CHROM_INTENSITY, &self.intensity);
+        out
+    }
+}
+
+impl FromPot for Chromatogram {
+    fn from_pot(bytes: &[u8]) -> Result<Self, RawError> {
+        let body = read_header(bytes)?;
+        let fields = FieldReader::parse(body)?;
+        Ok(Chromatogram {
+            rt: fields.f64_array(tag::CHROM_RT).unwrap_or_default(),
+            intensity: fields.f64_array(tag::CHROM_INTENSITY).unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_scan() -> Scan {
+        Scan {
+            scan_number: 42,
+            rt: 12.34,
+            ms_level: MsLevel::Ms2,
+            polarity: Polarity::Positive,
+            tic: 1_000_000.0,
+            base_peak_mz: 500.25,
+            base_peak_intensity: 50_000.0,
+            centroid_mz: vec![100.1, 200.2, 300.3],
+            centroid_intensity: vec![10.0, 20.0, 30.0],
+            profile_mz: Some(vec![100.0, 100.1, 100.2]),
+            profile_intensity: Some(vec![1.0, 2.0, 1.5]),
+            precursor: Some(PrecursorInfo {
+                mz: 445.12,
+                charge: Some(2),
+                isolation_width: Some(2.0),
+                activation_type: Some("HCD".to_string()),
+                collision_energy: Some(27.0),
+                compensation_voltage: Some(-45.0),
+            }),
+            filter_string: Some("FTMS + p NSI Full ms2 445.12@hcd27.00".to_string()),
+            ion_mobility: Some(1.23),
+            compensation_voltage: Some(-45.0),
+        }
+    }
+
+    #[test]
+    fn round_trips_full_scan() {
+        let scan = sample_scan();
+        let encoded = to_pot(&scan);
+        let decoded: Scan = from_pot(&encoded).unwrap();
+        assert_eq!(decoded.scan_number, scan.scan_number);
+        assert_eq!(decoded.centroid_mz, scan.centroid_mz);
+        assert_eq!(decoded.profile_mz, scan.profile_mz);
+        assert_eq!(
+            decoded.precursor.unwrap().activation_type,
+            scan.precursor.unwrap().activation_type
+        );
+        assert_eq!(decoded.filter_string, scan.filter_string);
+        assert_eq!(decoded.ion_mobility, scan.ion_mobility);
+        assert_eq!(decoded.compensation_voltage, scan.compensation_voltage);
+    }
+
+    #[test]
+    fn round_trips_ms1_scan_with_no_optional_fields() {
+        let scan = Scan {
+            scan_number: 1,
+            rt: 0.01,
+            ms_level: MsLevel::Ms1,
+            polarity: Polarity::Negative,
+            tic: 500.0,
+            base_peak_mz: 123.4,
+            base_peak_intensity: 99.0,
+            centroid_mz: vec![],
+            centroid_intensity: vec![],
+            profile_mz: None,
+            profile_intensity: None,
+            precursor: None,
+            filter_string: None,
+            ion_mobility: None,
+            compensation_voltage: None,
+        };
+        let decoded: Scan = from_pot(&to_pot(&scan)).unwrap();
+        assert_eq!(decoded.ms_level, MsLevel::Ms1);
+        assert!(decoded.profile_mz.is_none());
+        assert!(decoded.precursor.is_none());
+    }
+
+    #[test]
+    fn decodes_payload_missing_newer_fields_as_none() {
+        // Simulate an older payload that predates `filter_string` by
+        // dropping that field from the encoded bytes entirely.
+        let scan = sample_scan();
+        let mut encoded = to_pot(&scan);
+        let without_filter = strip_field(&encoded[5..], tag::FILTER_STRING);
+        encoded.truncate(5);
+        encoded.extend_from_slice(&without_filter);
+
+        let decoded: Scan = from_pot(&encoded).unwrap();
+        assert_eq!(decoded.scan_number, scan.scan_number);
+        assert!(decoded.filter_string.is_none());
+    }
+
+    #[test]
+    fn decoder_skips_unknown_trailing_field() {
+        // Simulate a newer payload carrying a field this decoder doesn't know.
+        let scan = sample_scan();
+        let mut encoded = to_pot(&scan);
+        write_field(&mut encoded, 999, b"future field");
+
+        let decoded: Scan = from_pot(&encoded).unwrap();
+        assert_eq!(decoded.scan_number, scan.scan_number);
+        assert_eq!(decoded.centroid_mz, scan.centroid_mz);
+    }
+
+    #[test]
+    fn round_trips_chromatogram() {
+        let chrom = Chromatogram {
+            rt: vec![0.1, 0.2, 0.3],
+            intensity: vec![10.0, 20.0, 30.0],
+        };
+        let decoded: Chromatogram = from_pot(&to_pot(&chrom)).unwrap();
+        assert_eq!(decoded.rt, chrom.rt);
+        assert_eq!(decoded.intensity, chrom.intensity);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let err = Scan::from_pot(&[0, 0, 0, 0, 1]).unwrap_err();
+        assert!(matches!(err, RawError::CorruptedData(_)));
+    }
+
+    #[test]
+    fn rejects_truncated_field() {
+        let scan = sample_scan();
+        let mut encoded = to_pot(&scan);
+        encoded.truncate(encoded.len() - 2);
+        let err = from_pot::<Scan>(&encoded).unwrap_err();
+        assert!(matches!(err, RawError::CorruptedData(_)));
+    }
+
+    /// Test helper: remove a single top-level field by tag from an encoded
+    /// field stream, to simulate payloads from a different schema version.
+    fn strip_field(body: &[u8], target_tag: u16) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut rest = body;
+        while !rest.is_empty() {
+            let tag = u16::from_le_bytes(rest[0..2].try_into().unwrap());
+            let len = u32::from_le_bytes(rest[2..6].try_into().unwrap()) as usize;
+            let field_len = 6 + len;
+            if tag != target_tag {
+                out.extend_from_slice(&rest[..field_len]);
+            }
+            rest = &rest[field_len..];
+        }
+        out
+    }
+}
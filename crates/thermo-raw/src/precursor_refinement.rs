@@ -0,0 +1,169 @@
+//! Monoisotopic precursor m/z refinement from a survey MS1 scan.
+//!
+//! Instrument software sometimes reports an isolation-window precursor m/z
+//! that is off by one or more isotope peaks (it picked a non-monoisotopic
+//! peak for isolation). This recovers the true monoisotopic m/z and charge
+//! state by matching an isotope envelope against the preceding MS1 survey
+//! scan's centroid peaks.
+
+use std::ops::RangeInclusive;
+
+/// Spacing (in Da) between consecutive isotope peaks of a singly-charged ion.
+const ISOTOPE_SPACING: f64 = 1.00235;
+
+/// Minimum number of isotope neighbors (beyond the seed peak) required
+/// before a charge state is accepted.
+const MIN_ISOTOPE_NEIGHBORS: usize = 2;
+
+/// Mass tolerance used both to locate the seed peak and to match isotope
+/// neighbors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MassTolerance {
+    Ppm(f64),
+    Da(f64),
+}
+
+impl MassTolerance {
+    fn window(&self, mz: f64) -> f64 {
+        match self {
+            MassTolerance::Ppm(ppm) => mz * ppm / 1e6,
+            MassTolerance::Da(da) => *da,
+        }
+    }
+}
+
+/// A refined monoisotopic precursor recovered from isotope envelope matching.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefinedPrecursor {
+    pub mz: f64,
+    pub charge: i32,
+}
+
+/// Refine `recorded_mz` (the isolation-window-reported precursor m/z) to its
+/// true monoisotopic value using `ms1_mz`/`ms1_intensity`, the preceding MS1
+/// survey scan's centroid peaks.
+///
+/// Picks the most intense peak within `tolerance` of `recorded_mz` as a
+/// seed, then for each charge in `charge_range` walks downward in m/z
+/// looking for isotope neighbors spaced `1.00235/z` apart. A charge is
+/// accepted only once at least [`MIN_ISOTOPE_NEIGHBORS`] consecutive
+/// neighbors are found within `tolerance`, each with an intensity between
+/// one tenth and ten times that of the previously matched peak; no finer
+/// isotope-shape model is applied. Among accepted charges, the one
+/// with the longest matched envelope wins (ties favor the lower charge).
+///
+/// Returns `None` if no seed peak or no charge produces a long-enough
+/// envelope; the caller should fall back to `recorded_mz` in that case.
+pub fn refine_monoisotopic_precursor(
+    ms1_mz: &[f64],
+    ms1_intensity: &[f64],
+    recorded_mz: f64,
+    tolerance: MassTolerance,
+    charge_range: RangeInclusive<u8>,
+) -> Option<RefinedPrecursor> {
+    let seed_window = tolerance.window(recorded_mz);
+    let seed_idx = ms1_mz
+        .iter()
+        .enumerate()
+        .filter(|(_, &mz)| (mz - recorded_mz).abs() <= seed_window)
+        .max_by(|(a, _), (b, _)| {
+            let ia = ms1_intensity.get(*a).copied().unwrap_or(0.0);
+            let ib = ms1_intensity.get(*b).copied().unwrap_or(0.0);
+            ia.partial_cmp(&ib).unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|(idx, _)| idx)?;
+
+    let seed_mz = ms1_mz[seed_idx];
+    let seed_intensity = ms1_intensity.get(seed_idx).copied().unwrap_or(0.0);
+
+    let mut best: Option<(usize, RefinedPrecursor)> = None;
+
+    for z in charge_range {
+        let spacing = ISOTOPE_SPACING / z as f64;
+        let mut envelope_mz = vec![seed_mz];
+        let mut envelope_intensity = vec![seed_intensity];
+        let mut current_mz = seed_mz;
+        let mut current_intensity = seed_intensity;
+
+        loop {
+            let target = current_mz - spacing;
+            let window = tolerance.window(target);
+            let neighbor = ms1_mz
+                .iter()
+                .zip(ms1_intensity.iter())
+                .filter(|(&mz, _)| (mz - target).abs() <= window)
+                .min_by(|(&mz_a, _), (&mz_b, _)| {
+                    (mz_a - target).abs().partial_cmp(&(mz_b - target).abs()).unwrap()
+                });
+            match neighbor {
+                Some((&mz, &intensity)) if intensity >= current_intensity * 0.1 && intensity <= current_intensity.max(1.0) * 10.0 => {
+                    envelope_mz.push(mz);
+                    envelope_intensity.push(intensity);
+                    current_mz = mz;
+                    current_intensity = intensity;
+                }
+                _ => break,
+            }
+        }
+
+        let matched_neighbors = envelope_mz.len() - 1;
+        if matched_neighbors >= MIN_ISOTOPE_NEIGHBORS {
+            let mono_mz = *envelope_mz.last().unwrap();
+            let candidate = RefinedPrecursor {
+                mz: mono_mz,
+                charge: z as i32,
+            };
+            let improves = match &best {
+                Some((len, _)) => matched_neighbors > *len,
+                None => true,
+            };
+            if improves {
+                best = Some((matched_neighbors, candidate));
+            }
+        }
+    }
+
+    best.map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refine_recovers_monoisotopic_peak_from_offset_selection() {
+        // Synthetic z=2 isotope envelope: monoisotopic at 499.5, spacing 0.5,
+        // apex at 500.0. The instrument isolated the 3rd isotope (500.5)
+        // instead of the monoisotopic peak.
+        let ms1_mz = vec![497.0, 499.5, 500.0, 500.5, 501.0, 503.0];
+        let ms1_intensity = vec![5.0, 600.0, 1000.0, 800.0, 400.0, 5.0];
+        let refined = refine_monoisotopic_precursor(
+            &ms1_mz,
+            &ms1_intensity,
+            500.5,
+            MassTolerance::Da(0.02),
+            1..=6,
+        )
+        .unwrap();
+        assert_eq!(refined.charge, 2);
+        assert!((refined.mz - 499.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_refine_falls_back_to_none_without_envelope() {
+        let ms1_mz = vec![100.0, 500.0, 700.0];
+        let ms1_intensity = vec![10.0, 20.0, 30.0];
+        let refined =
+            refine_monoisotopic_precursor(&ms1_mz, &ms1_intensity, 500.0, MassTolerance::Da(0.02), 1..=6);
+        assert!(refined.is_none());
+    }
+
+    #[test]
+    fn test_refine_returns_none_when_no_seed_peak_in_tolerance() {
+        let ms1_mz = vec![200.0, 200.5, 201.0];
+        let ms1_intensity = vec![10.0, 20.0, 30.0];
+        let refined =
+            refine_monoisotopic_precursor(&ms1_mz, &ms1_intensity, 500.0, MassTolerance::Da(0.02), 1..=6);
+        assert!(refined.is_none());
+    }
+}
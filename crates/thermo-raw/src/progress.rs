@@ -2,6 +2,15 @@
 //!
 //! Workers increment the counter atomically; consumers (CLI/Python) poll it
 //! on a timer to drive progress bars without coupling the core library to any UI.
+//!
+//! This stays a plain counter rather than a callback trait (`on_scan(done,
+//! total)`-style) deliberately: rayon workers would each need a reference to
+//! the callback, turning a cheap atomic increment into a trait-object call
+//! on every worker thread, and a callback crossing the `thermo-raw` ->
+//! `thermo-raw-mzml` boundary would tie the writer to whatever shape of
+//! callback the core crate picked. Polling one counter keeps both sides
+//! independent; see `thermo_raw_mzml::writer::write_mzml_with_progress` for
+//! this counter threaded live through the mzML writer's per-spectrum loop.
 
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
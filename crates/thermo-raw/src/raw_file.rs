@@ -1,6 +1,7 @@
 //! Top-level entry point: open and read Thermo RAW files.
 
 use crate::chromatogram;
+use crate::compression::{self, ContainerFormat};
 use crate::file_header::FileHeader;
 use crate::io_utils::BinaryReader;
 use crate::metadata;
@@ -12,13 +13,18 @@ use crate::scan_event::{self, ScanEvent};
 use crate::scan_filter;
 use crate::scan_index::{self, ScanIndexEntry};
 use crate::trailer::{self, TrailerLayout};
-use crate::types::{Chromatogram, FileMetadata, MsLevel, PrecursorInfo, Scan};
+use crate::types::{
+    Chromatogram, FileMetadata, MsLevel, PrecursorInfo, Scan, TransitionChromatogram,
+    XicQuantification,
+};
 use crate::version;
 use crate::RawError;
 use std::collections::HashMap;
+use std::io::{Read, Seek};
 use std::ops::Deref;
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
 
 /// Diagnostic information for debugging address resolution.
 pub struct DebugInfo {
@@ -38,6 +44,12 @@ pub struct DebugInfo {
     pub n_scans: u32,
     pub n_scan_events: u32,
     pub instrument_type: i32,
+    /// Compression wrapper the file was opened through, per
+    /// [`crate::compression::sniff`]. Always [`ContainerFormat::Raw`] for
+    /// files opened via [`RawFile::open`]/[`open_mmap`](RawFile::open_mmap)/
+    /// [`open_reader`](RawFile::open_reader); reflects the detected wrapper
+    /// for files opened via [`RawFile::open_auto`].
+    pub container_format: ContainerFormat,
 }
 
 /// Abstraction over file data sources (owned bytes or memory-mapped).
@@ -56,6 +68,72 @@ impl Deref for FileData {
     }
 }
 
+/// One cached, already-decoded pair of centroid arrays, as produced by
+/// [`scan_data::decode_centroids_only`].
+struct CachedCentroids {
+    mz: Vec<f64>,
+    intensity: Vec<f64>,
+    bytes: u64,
+    last_used: u64,
+}
+
+/// Bounded LRU cache of decoded centroid arrays, keyed by scan index.
+///
+/// Evicts the least-recently-used entry whenever inserting would push the
+/// running byte total over the configured budget. A plain `HashMap` plus a
+/// logical clock is enough here -- linearly
+/// scanning for the minimum `last_used` on eviction is not worth a second
+/// index structure at realistic (tens of thousands) scan counts.
+struct ScanCentroidCache {
+    entries: HashMap<u32, CachedCentroids>,
+    clock: u64,
+}
+
+impl ScanCentroidCache {
+    fn new() -> Self {
+        ScanCentroidCache {
+            entries: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    fn get(&mut self, idx: u32) -> Option<(Vec<f64>, Vec<f64>)> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.entries.get_mut(&idx).map(|e| {
+            e.last_used = clock;
+            (e.mz.clone(), e.intensity.clone())
+        })
+    }
+
+    fn insert(&mut self, idx: u32, mz: Vec<f64>, intensity: Vec<f64>, bytes_used: &AtomicU64, budget: u64) {
+        self.clock += 1;
+        let bytes = ((mz.len() + intensity.len()) * std::mem::size_of::<f64>()) as u64;
+        bytes_used.fetch_add(bytes, Ordering::Relaxed);
+        self.entries.insert(
+            idx,
+            CachedCentroids {
+                mz,
+                intensity,
+                bytes,
+                last_used: self.clock,
+            },
+        );
+
+        while bytes_used.load(Ordering::Relaxed) > budget && !self.entries.is_empty() {
+            let lru_idx = self.entries.iter().min_by_key(|(_, e)| e.last_used).map(|(&k, _)| k);
+            match lru_idx {
+                Some(k) => {
+                    if let Some(evicted) = self.entries.remove(&k) {
+                        bytes_used.fetch_sub(evicted.bytes, Ordering::Relaxed);
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+}
+
 /// A Thermo RAW file opened for reading.
 pub struct RawFile {
     /// Raw file bytes (owned or memory-mapped).
@@ -76,6 +154,26 @@ pub struct RawFile {
     scan_events_addr: u64,
     /// Lazily parsed scan events (unique event templates, indexed by scan_event field).
     scan_events: OnceLock<Vec<ScanEvent>>,
+    /// Optional bounded LRU cache of decoded centroid arrays, enabled via
+    /// [`RawFile::with_cache_bytes`]. `None` (the default) means every
+    /// `xic*` call decodes each scan fresh, as before this existed.
+    centroid_cache: Option<Mutex<ScanCentroidCache>>,
+    /// Byte budget for `centroid_cache`, set by [`RawFile::with_cache_bytes`].
+    cache_byte_budget: u64,
+    /// Running total of bytes held by `centroid_cache`.
+    cache_bytes_used: AtomicU64,
+    /// Number of `xic*` scan decodes served from `centroid_cache`, since open.
+    cache_hits: AtomicU64,
+    /// Number of `xic*` scan decodes that missed `centroid_cache` (or had
+    /// caching disabled), since open.
+    cache_misses: AtomicU64,
+    /// Compression wrapper this file was opened through; `Raw` unless
+    /// opened via [`RawFile::open_auto`].
+    container_format: ContainerFormat,
+    /// SampleExtensionInfo JSON blob parsed from a v66+ SequenceRow, if the
+    /// acquisition software that wrote this file appended one. See
+    /// [`sample_extension_info`](Self::sample_extension_info).
+    sample_extension_info: Option<HashMap<String, String>>,
 }
 
 impl RawFile {
@@ -100,8 +198,102 @@ impl RawFile {
         Self::from_data(FileData::Mapped(mmap))
     }
 
+    /// Open a Thermo RAW file from any `Read + Seek` source -- an in-memory
+    /// `Cursor`, a decompressor, or a handle to a non-local file that
+    /// [`open`](Self::open)/[`open_mmap`](Self::open_mmap) can't reach by path.
+    ///
+    /// This still reads `r` to completion up front: [`RawFile`]'s fields
+    /// (`scan_index`, `trailer_layout`, ...) are built by indexing one
+    /// contiguous `&[u8]`, same as `open`, so nothing here is saved over
+    /// `open` for a plain local file -- use this purely for the source
+    /// flexibility. True on-demand reads that never materialize the whole
+    /// file already exist at a lower level, for callers who build their own
+    /// pipeline around the scan index: [`io_utils::FileSource`] (a
+    /// [`io_utils::PacketSource`] over `Read + Seek`, used by the
+    /// `scan_data` packet decoders) and
+    /// [`scan_source::StreamScanSource`] (resolves scan numbers to packet
+    /// bytes via seek-per-scan, no buffering).
+    pub fn open_reader<R: Read + Seek>(mut r: R) -> Result<Self, RawError> {
+        let mut data = Vec::new();
+        r.seek(std::io::SeekFrom::Start(0))?;
+        r.read_to_end(&mut data)?;
+        Self::from_data(FileData::Owned(data))
+    }
+
+    /// Open a Thermo RAW file that may be gzip- or zstd-compressed,
+    /// transparently decompressing it into an owned buffer before parsing.
+    ///
+    /// Unlike [`open`](Self::open), which expects the Finnigan magic at the
+    /// start of the file and fails otherwise, this first sniffs the leading
+    /// bytes for a [`compression::ContainerFormat`] and decompresses if one
+    /// is recognized -- so archived/compressed RAW files from a lab's cold
+    /// storage can be opened the same way as an uncompressed one. Plain RAW
+    /// files pay only the cost of the magic-byte sniff. The detected
+    /// container is recorded on [`debug_info`](Self::debug_info)'s
+    /// `container_format` so callers can tell a file was decompressed on
+    /// load.
+    ///
+    /// The gzip/zstd backends are gated behind the `gzip`/`zstd` cargo
+    /// features; a compressed file encountered without the matching feature
+    /// enabled returns [`RawError::UnsupportedContainer`] rather than
+    /// silently trying to parse the compressed bytes.
+    pub fn open_auto(path: impl AsRef<Path>) -> Result<Self, RawError> {
+        let raw = std::fs::read(path.as_ref())?;
+        let format = compression::sniff(&raw);
+        let data = compression::decompress(&raw, format)?;
+        Self::from_data_with_container(FileData::Owned(data), format)
+    }
+
+    /// Cheaply get the scan count without parsing the scan index, trailer
+    /// layout, or scan events -- just the FileHeader, RawFileInfo, and
+    /// RunHeader needed to read `first_scan`/`last_scan`.
+    ///
+    /// [`open_mmap`](Self::open_mmap) already avoids reading the whole file
+    /// upfront (the OS pages in only the bytes actually touched), but a full
+    /// open still touches every scan index entry to build `scan_index`. For
+    /// a file-picker "how many scans does this have" pass over hundreds of
+    /// candidates, skipping that lets each candidate touch only its header
+    /// region.
+    pub fn scan_count_only(path: impl AsRef<Path>) -> Result<u32, RawError> {
+        let file = std::fs::File::open(path.as_ref())?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let data: &[u8] = &mmap;
+
+        let finnigan_offset = find_finnigan_magic(data).ok_or(RawError::NotRawFile)?;
+        let file_header = FileHeader::parse(&data[finnigan_offset..])
+            .map_err(|e| parse_error("FileHeader", finnigan_offset as u64, None, e))?;
+        let ver = file_header.version;
+        if !version::is_supported(ver) {
+            return Err(RawError::UnsupportedVersion(ver));
+        }
+
+        let info_base = finnigan_offset as u64 + FileHeader::size() as u64;
+        let raw_file_info = find_raw_file_info_sequential(data, info_base, ver)
+            .or_else(|_| find_raw_file_info(data, info_base, ver))
+            .map_err(|e| parse_error("RawFileInfo", info_base, Some(ver), e))?;
+
+        let rh_addr = raw_file_info.run_header_addr();
+        if rh_addr == 0 {
+            return Err(RawError::StreamNotFound(
+                "File has no data controllers (empty/blank acquisition)".to_string(),
+            ));
+        }
+        let run_header = RunHeader::parse(data, rh_addr, ver)
+            .map_err(|e| parse_error("RunHeader", rh_addr, Some(ver), e))?;
+
+        Ok(run_header.n_scans())
+    }
+
     /// Parse RAW file structures from raw data.
     fn from_data(data: FileData) -> Result<Self, RawError> {
+        Self::from_data_with_container(data, ContainerFormat::Raw)
+    }
+
+    /// Shared implementation behind [`from_data`](Self::from_data) and
+    /// [`open_auto`](Self::open_auto): identical parse path, differing only
+    /// in which [`ContainerFormat`] gets recorded for [`debug_info`](Self::debug_info)
+    /// and [`diagnose`].
+    fn from_data_with_container(data: FileData, container_format: ContainerFormat) -> Result<Self, RawError> {
         let finnigan_offset = find_finnigan_magic(&data).ok_or(RawError::NotRawFile)?;
 
         let file_header = FileHeader::parse(&data[finnigan_offset..])
@@ -178,9 +370,145 @@ impl RawFile {
             trailer_layout,
             scan_events_addr,
             scan_events: OnceLock::new(),
+            centroid_cache: None,
+            cache_byte_budget: 0,
+            cache_bytes_used: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+            container_format,
+            sample_extension_info: raw_file_info.sample_extension_info.clone(),
         })
     }
 
+    /// Enable a bounded LRU cache of decoded centroid arrays, used by the
+    /// `xic*` family, evicting least-recently-used scans once the running
+    /// total exceeds `n` bytes. Disabled by default -- every scan is decoded
+    /// fresh on every call.
+    ///
+    /// Most useful for repeated-read workloads, e.g. re-running XIC
+    /// extraction over overlapping m/z windows, where the OS page cache
+    /// alone (under [`open_mmap`](Self::open_mmap)) still pays the decode
+    /// cost on every call.
+    pub fn with_cache_bytes(mut self, n: u64) -> Self {
+        self.centroid_cache = Some(Mutex::new(ScanCentroidCache::new()));
+        self.cache_byte_budget = n;
+        self
+    }
+
+    /// Number of `xic*` scan decodes served from the cache, since open.
+    /// Always `0` if [`with_cache_bytes`](Self::with_cache_bytes) was never called.
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of `xic*` scan decodes that missed the cache, since open.
+    /// Equals every decode performed if caching is disabled.
+    pub fn cache_misses(&self) -> u64 {
+        self.cache_misses.load(Ordering::Relaxed)
+    }
+
+    /// Check the data stream's integrity: a CRC-32 over
+    /// `data_addr()..scan_index_addr()`, plus a cross-check of the header's
+    /// claimed scan count against the number of entries actually parsed
+    /// into `scan_index`. See [`checksum`](crate::checksum) for what to do
+    /// with the result (matching it against a manifest, reading
+    /// `problem()`).
+    pub fn integrity_report(&self) -> crate::checksum::IntegrityReport {
+        let si_addr = self.run_header.scan_index_addr() as usize;
+        let start = (self.data_addr as usize).min(self.data.len());
+        let end = si_addr.clamp(start, self.data.len());
+        let n_scans_header = self.run_header.n_scans();
+
+        crate::checksum::IntegrityReport {
+            crc32: crate::checksum::crc32(&self.data[start..end]),
+            n_scans_header,
+            n_scans_index: self.scan_index.len(),
+            scan_count_mismatch: n_scans_header as usize != self.scan_index.len(),
+        }
+    }
+
+    /// Validate the parsed scan index, trailer layout, and scan events
+    /// against each other and against the file's byte bounds. See
+    /// [`crate::check`] for what's checked and its limits.
+    pub fn check(&self) -> crate::check::CheckReport {
+        let mut report = crate::check::CheckReport::default();
+        let file_len = self.data.len() as u64;
+        let n_scans = self.run_header.n_scans();
+        let first_scan = self.run_header.first_scan;
+        let last_scan = self.run_header.last_scan;
+
+        crate::check::check_scan_count(&mut report, n_scans, first_scan, last_scan, self.scan_index.len());
+        crate::check::check_scan_index(&mut report, &self.scan_index, self.data_addr, file_len, first_scan);
+        crate::check::check_scan_events(&mut report, &self.scan_index, first_scan, self.scan_events_lazy().len());
+
+        if let Some(layout) = &self.trailer_layout {
+            crate::check::check_trailer_bounds(&mut report, layout.header.records_offset, layout.record_size, n_scans, file_len);
+        }
+
+        report
+    }
+
+    /// Decode every scan and report per-scan pass/fail, a content CRC-32,
+    /// and a whole-file content digest, so two RAW files can be compared
+    /// scan-by-scan instead of just "does the whole file hash match". See
+    /// [`crate::verify`] for what's checked and its limits.
+    pub fn verify(&self) -> crate::verify::VerificationReport {
+        let first_scan = self.run_header.first_scan;
+        let scans: Vec<_> = self
+            .scan_index
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| {
+                let scan_number = first_scan + idx as u32;
+                let abs_offset = self.data_addr + entry.offset;
+                let mut header_reader = BinaryReader::at_offset(&self.data[..], abs_offset);
+                let header = scan_data::PacketHeader::parse(&mut header_reader).ok();
+                let decode_result = self.decode_indexed_scan(idx, entry, first_scan);
+                crate::verify::verify_scan(entry, scan_number, header.as_ref(), decode_result)
+            })
+            .collect();
+
+        crate::verify::build_report(scans)
+    }
+
+    /// Rebuild a usable scan index when [`check`](Self::check) reports the
+    /// stored one is corrupt. See [`crate::check::try_recover`] for what
+    /// this does and does not recover.
+    pub fn try_recover_scan_index(&self) -> Vec<ScanIndexEntry> {
+        crate::check::try_recover(&self.scan_index, self.data_addr, self.data.len() as u64)
+    }
+
+    /// Decode one scan's centroid arrays, transparently consulting and
+    /// populating `centroid_cache` (if enabled) keyed by `idx`.
+    fn decode_centroids_cached(
+        &self,
+        idx: u32,
+        entry: &ScanIndexEntry,
+    ) -> Result<(Vec<f64>, Vec<f64>), RawError> {
+        if let Some(cache) = &self.centroid_cache {
+            if let Some(hit) = cache.lock().unwrap().get(idx) {
+                self.cache_hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(hit);
+            }
+        }
+
+        let (mz, intensity) =
+            scan_data::decode_centroids_only(&self.data[..], self.data_addr as usize, entry)?;
+
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+        if let Some(cache) = &self.centroid_cache {
+            cache.lock().unwrap().insert(
+                idx,
+                mz.clone(),
+                intensity.clone(),
+                &self.cache_bytes_used,
+                self.cache_byte_budget,
+            );
+        }
+
+        Ok((mz, intensity))
+    }
+
     /// RAW file format version.
     pub fn version(&self) -> u32 {
         self.version
@@ -191,6 +519,15 @@ impl RawFile {
         &self.file_metadata
     }
 
+    /// SampleExtensionInfo key/value metadata, if the SequenceRow carried a
+    /// trailing JSON blob (seen on files written by v66+ acquisition
+    /// software). `None` on older files and on files where sequential
+    /// reading fell back to [`find_raw_file_info`]'s forward search, since
+    /// the fallback scanner doesn't attempt to recover this blob.
+    pub fn sample_extension_info(&self) -> Option<&HashMap<String, String>> {
+        self.sample_extension_info.as_ref()
+    }
+
     /// Total number of scans.
     pub fn n_scans(&self) -> u32 {
         self.scan_index.len() as u32
@@ -243,11 +580,12 @@ impl RawFile {
         let conversion_params = self.get_conversion_params(entry);
 
         let mut scan = scan_data::decode_scan(
-            &self.data,
+            &self.data[..],
             self.data_addr as usize,
             entry,
             scan_number,
             conversion_params,
+            self.get_scan_event(entry),
         )?;
 
         // Enrich with trailer-derived metadata
@@ -272,11 +610,12 @@ impl RawFile {
             .map(|(entry, scan_num, scan_idx)| {
                 let conversion_params = self.get_conversion_params(entry);
                 let mut scan = scan_data::decode_scan(
-                    &self.data,
+                    &self.data[..],
                     self.data_addr as usize,
                     entry,
                     *scan_num,
                     conversion_params,
+                    self.get_scan_event(entry),
                 )?;
                 self.enrich_scan(&mut scan, *scan_idx);
                 Ok(scan)
@@ -284,6 +623,66 @@ impl RawFile {
             .collect()
     }
 
+    /// Lazily decode every scan in index order, one at a time.
+    ///
+    /// Applies the same trailer enrichment as [`scan`](Self::scan). Unlike
+    /// [`scans_parallel`](Self::scans_parallel), which eagerly decodes and
+    /// collects a whole range, this never holds more than one decoded scan
+    /// in memory at a time -- suited to streaming pipelines (peak picking,
+    /// feature detection) over acquisitions too large to hold as a
+    /// `Vec<Scan>`.
+    pub fn scans(&self) -> impl Iterator<Item = Result<Scan, RawError>> + '_ {
+        self.scans_filtered(|_| true)
+    }
+
+    /// Like [`scans`](Self::scans), decoding only entries for which `pred`
+    /// returns `true`.
+    pub fn scans_filtered<'a>(
+        &'a self,
+        pred: impl Fn(&ScanIndexEntry) -> bool + 'a,
+    ) -> impl Iterator<Item = Result<Scan, RawError>> + 'a {
+        let first_scan = self.run_header.first_scan;
+        self.scan_index
+            .iter()
+            .enumerate()
+            .filter(move |&(_, entry)| pred(entry))
+            .map(move |(idx, entry)| self.decode_indexed_scan(idx, entry, first_scan))
+    }
+
+    /// Like [`scans`](Self::scans), yielding only scans whose retention
+    /// time (in minutes) falls within `[start_min, end_min]`.
+    /// Binary-searches `scan_index` for the matching range (entries are in
+    /// RT order, matching acquisition order) rather than decoding every
+    /// scan just to check its RT.
+    pub fn scans_in_rt_range(&self, start_min: f64, end_min: f64) -> impl Iterator<Item = Result<Scan, RawError>> + '_ {
+        let first_scan = self.run_header.first_scan;
+        let lo = self.scan_index.partition_point(|e| e.rt < start_min);
+        let hi = self.scan_index.partition_point(|e| e.rt <= end_min);
+        self.scan_index[lo..hi]
+            .iter()
+            .enumerate()
+            .map(move |(i, entry)| self.decode_indexed_scan(lo + i, entry, first_scan))
+    }
+
+    /// Shared decode+enrich step behind [`scans_filtered`](Self::scans_filtered)
+    /// and [`scans_in_rt_range`](Self::scans_in_rt_range): `idx` is the
+    /// entry's position in `scan_index`, used both to resolve its scan
+    /// number and to key trailer enrichment, same as [`scan`](Self::scan).
+    fn decode_indexed_scan(&self, idx: usize, entry: &ScanIndexEntry, first_scan: u32) -> Result<Scan, RawError> {
+        let scan_number = first_scan + idx as u32;
+        let conversion_params = self.get_conversion_params(entry);
+        let mut scan = scan_data::decode_scan(
+            &self.data[..],
+            self.data_addr as usize,
+            entry,
+            scan_number,
+            conversion_params,
+            self.get_scan_event(entry),
+        )?;
+        self.enrich_scan(&mut scan, idx as u32);
+        Ok(scan)
+    }
+
     /// TIC chromatogram (fast: extracted from scan index, no scan data decoding).
     pub fn tic(&self) -> Chromatogram {
         chromatogram::build_tic(&self.scan_index)
@@ -312,6 +711,30 @@ impl RawFile {
         self.xic_inner(target_mz, tolerance_ppm, true)
     }
 
+    /// Like [`xic_ms1`], but integrates the resulting chromatogram's peak
+    /// area and reports a bootstrap-derived uncertainty estimate instead of
+    /// just the raw points.
+    ///
+    /// Draws `n_boot` resamples (with replacement) of the chromatogram's
+    /// `(rt, intensity)` points, trapezoidally integrates each, and reports
+    /// the mean area's standard deviation and 95% (2.5/97.5 percentile)
+    /// confidence interval across resamples. Falls back to the plain
+    /// trapezoidal area with zero uncertainty when the chromatogram has too
+    /// few points to bootstrap meaningfully.
+    pub fn xic_ms1_quant(
+        &self,
+        target_mz: f64,
+        tolerance_ppm: f64,
+        n_boot: usize,
+    ) -> Result<XicQuantification, RawError> {
+        let chrom = self.xic_ms1(target_mz, tolerance_ppm)?;
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Ok(chromatogram::quantify_peak(&chrom, n_boot, seed))
+    }
+
     /// Batch extracted ion chromatograms for multiple targets (MS1 only, single pass).
     ///
     /// Decodes each MS1 scan once and extracts intensities for all targets,
@@ -351,11 +774,7 @@ impl RawFile {
                     return Some((entry.rt, vec![0.0; n_targets]));
                 }
 
-                let (cmz, cint) = match scan_data::decode_centroids_only(
-                    &self.data,
-                    self.data_addr as usize,
-                    entry,
-                ) {
+                let (cmz, cint) = match self.decode_centroids_cached(idx as u32, entry) {
                     Ok(pair) => pair,
                     Err(_) => return Some((entry.rt, vec![0.0; n_targets])),
                 };
@@ -394,6 +813,59 @@ impl RawFile {
         Ok(chromatograms)
     }
 
+    /// Per-transition chromatograms for targeted (SRM/MRM/PRM) acquisitions.
+    ///
+    /// Groups MS2+ scans by their isolation-window target m/z (merging
+    /// targets within `mz_tolerance` of each other) and sums product-ion
+    /// (centroid) intensity per scan to build one chromatogram per distinct
+    /// transition, so a method with N targets yields N labeled chromatograms.
+    pub fn srm_chromatograms(
+        &self,
+        mz_tolerance: f64,
+    ) -> Result<Vec<TransitionChromatogram>, RawError> {
+        use rayon::prelude::*;
+
+        let events = self.scan_events_lazy();
+        let precursor_mz: Vec<(usize, f64)> = self
+            .scan_index
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !self.is_ms1_scan(*idx as u32))
+            .filter_map(|(idx, _)| events.get(idx).map(|e| (idx, e.precursor_mz)))
+            .collect();
+
+        let groups = chromatogram::group_by_precursor_mz(&precursor_mz, mz_tolerance);
+
+        Ok(groups
+            .into_par_iter()
+            .map(|(target_mz, scan_indices)| {
+                let points: Vec<(f64, f64)> = scan_indices
+                    .into_iter()
+                    .map(|idx| {
+                        let entry = &self.scan_index[idx];
+                        let intensity = match scan_data::decode_centroids_only(
+                            &self.data[..],
+                            self.data_addr as usize,
+                            entry,
+                        ) {
+                            Ok((_, cint)) => cint.iter().sum(),
+                            Err(_) => 0.0,
+                        };
+                        (entry.rt, intensity)
+                    })
+                    .collect();
+
+                TransitionChromatogram {
+                    precursor_mz: target_mz,
+                    chromatogram: Chromatogram {
+                        rt: points.iter().map(|(rt, _)| *rt).collect(),
+                        intensity: points.iter().map(|(_, i)| *i).collect(),
+                    },
+                }
+            })
+            .collect())
+    }
+
     /// Like [`scans_parallel`], but increments the progress counter after each scan.
     pub fn scans_parallel_with_progress(
         &self,
@@ -412,11 +884,12 @@ impl RawFile {
             .map(|(entry, scan_num, scan_idx)| {
                 let conversion_params = self.get_conversion_params(entry);
                 let mut scan = scan_data::decode_scan(
-                    &self.data,
+                    &self.data[..],
                     self.data_addr as usize,
                     entry,
                     *scan_num,
                     conversion_params,
+                    self.get_scan_event(entry),
                 )?;
                 self.enrich_scan(&mut scan, *scan_idx);
                 progress::tick(counter);
@@ -425,6 +898,46 @@ impl RawFile {
             .collect()
     }
 
+    /// Like [`scans_parallel`], but records per-scan decode latency plus
+    /// running bytes-read/spectra-decoded/scans-converted counts into
+    /// `metrics`. Intended for long batch reads where a caller wants live
+    /// throughput/latency numbers (e.g. the CLI's `--metrics` flag) rather
+    /// than just a final elapsed time.
+    pub fn scans_parallel_with_metrics(
+        &self,
+        range: std::ops::Range<u32>,
+        metrics: &crate::metrics::Metrics,
+    ) -> Result<Vec<Scan>, RawError> {
+        use rayon::prelude::*;
+        let first = self.run_header.first_scan;
+        let entries: Vec<_> = range
+            .map(|n| ((n - first) as usize, n))
+            .filter_map(|(idx, n)| self.scan_index.get(idx).map(|e| (e, n, idx as u32)))
+            .collect();
+
+        entries
+            .par_iter()
+            .map(|(entry, scan_num, scan_idx)| {
+                let start = std::time::Instant::now();
+                let conversion_params = self.get_conversion_params(entry);
+                let mut scan = scan_data::decode_scan(
+                    &self.data[..],
+                    self.data_addr as usize,
+                    entry,
+                    *scan_num,
+                    conversion_params,
+                    self.get_scan_event(entry),
+                )?;
+                self.enrich_scan(&mut scan, *scan_idx);
+                metrics.record_latency(start.elapsed());
+                metrics.record_scan_converted();
+                metrics.record_spectrum_decoded();
+                metrics.record_bytes_read(entry.data_size as u64);
+                Ok(scan)
+            })
+            .collect()
+    }
+
     /// Like [`xic`], but increments the progress counter per scan index entry.
     pub fn xic_with_progress(
         &self,
@@ -481,11 +994,7 @@ impl RawFile {
                     if !any_overlap {
                         Some((entry.rt, vec![0.0; n_targets]))
                     } else {
-                        let (cmz, cint) = match scan_data::decode_centroids_only(
-                            &self.data,
-                            self.data_addr as usize,
-                            entry,
-                        ) {
+                        let (cmz, cint) = match self.decode_centroids_cached(idx as u32, entry) {
                             Ok(pair) => pair,
                             Err(_) => {
                                 return {
@@ -557,11 +1066,7 @@ impl RawFile {
                 {
                     Some((entry.rt, 0.0))
                 } else {
-                    let (cmz, cint) = match scan_data::decode_centroids_only(
-                        &self.data,
-                        self.data_addr as usize,
-                        entry,
-                    ) {
+                    let (cmz, cint) = match self.decode_centroids_cached(idx as u32, entry) {
                         Ok(pair) => pair,
                         Err(_) => {
                             return {
@@ -619,11 +1124,7 @@ impl RawFile {
                     return Some((entry.rt, 0.0));
                 }
 
-                let (cmz, cint) = match scan_data::decode_centroids_only(
-                    &self.data,
-                    self.data_addr as usize,
-                    entry,
-                ) {
+                let (cmz, cint) = match self.decode_centroids_cached(idx as u32, entry) {
                     Ok(pair) => pair,
                     Err(_) => return Some((entry.rt, 0.0)),
                 };
@@ -745,6 +1246,7 @@ impl RawFile {
             n_scans: self.scan_index.len() as u32,
             n_scan_events: self.scan_events_lazy().len() as u32,
             instrument_type: rh.instrument_type,
+            container_format: self.container_format,
         }
     }
 
@@ -763,6 +1265,11 @@ impl RawFile {
             .unwrap_or(&[])
     }
 
+    /// Look up the ScanEvent template for a scan, if its `scan_event` index is in range.
+    fn get_scan_event(&self, entry: &ScanIndexEntry) -> Option<&ScanEvent> {
+        self.scan_events_lazy().get(entry.scan_event as usize)
+    }
+
     /// Enrich a scan with trailer-derived metadata.
     ///
     /// Uses three strategies in order of preference:
@@ -781,6 +1288,7 @@ impl RawFile {
                         scan.ms_level = filter.ms_level;
                         scan.polarity = filter.polarity;
                         scan.filter_string = Some(filter_str);
+                        scan.compensation_voltage = filter.compensation_voltage;
 
                         if !matches!(scan.ms_level, MsLevel::Ms1) {
                             scan.precursor = self.build_precursor_info(layout, scan_idx, &filter);
@@ -838,6 +1346,7 @@ impl RawFile {
                     isolation_width: Some(reaction.isolation_width).filter(|&w| w > 0.0),
                     activation_type: Some(activation_str),
                     collision_energy: Some(reaction.collision_energy),
+                    compensation_voltage: None,
                 });
             }
         }
@@ -875,6 +1384,7 @@ impl RawFile {
             isolation_width,
             activation_type: None,
             collision_energy: None,
+            compensation_voltage: None,
         })
     }
 
@@ -887,7 +1397,7 @@ impl RawFile {
         scan_idx: u32,
         filter: &scan_filter::ScanFilter,
     ) -> Option<PrecursorInfo> {
-        let filter_precursor = filter.precursor.as_ref();
+        let filter_precursor = filter.precursor();
 
         // Get monoisotopic m/z from trailer (more accurate than filter string)
         let mono_mz = layout
@@ -914,8 +1424,9 @@ impl RawFile {
             mz,
             charge,
             isolation_width,
-            activation_type: filter_precursor.map(|p| p.activation.clone()),
-            collision_energy: filter_precursor.map(|p| p.collision_energy),
+            activation_type: filter_precursor.map(|p| p.activation().to_string()),
+            collision_energy: filter_precursor.map(|p| p.collision_energy()),
+            compensation_voltage: filter.compensation_voltage,
         })
     }
 }
@@ -955,6 +1466,40 @@ pub fn diagnose(data: &[u8]) -> DiagnosticReport {
     let file_size = data.len() as u64;
     let mut stages = Vec::new();
 
+    // Stage 0: Container sniff -- decompress before any other stage sees
+    // `data` if it looks gzip/zstd-wrapped, same as `RawFile::open_auto`.
+    let container_format = compression::sniff(data);
+    let decompressed;
+    let data: &[u8] = match container_format {
+        ContainerFormat::Raw => {
+            stages.push(DiagnosticStage {
+                name: "Container".to_string(),
+                success: true,
+                detail: "raw (no compression wrapper detected)".to_string(),
+            });
+            data
+        }
+        other => match compression::decompress(data, other) {
+            Ok(d) => {
+                stages.push(DiagnosticStage {
+                    name: "Container".to_string(),
+                    success: true,
+                    detail: format!("{other} wrapper detected, decompressed {} -> {} bytes", data.len(), d.len()),
+                });
+                decompressed = d;
+                &decompressed
+            }
+            Err(e) => {
+                stages.push(DiagnosticStage {
+                    name: "Container".to_string(),
+                    success: false,
+                    detail: format!("{other} wrapper detected but decompression failed: {e}"),
+                });
+                return DiagnosticReport { file_size, stages };
+            }
+        },
+    };
+
     // Stage 1: Find Finnigan magic
     let finnigan_offset = match find_finnigan_magic(data) {
         Some(off) => {
@@ -1000,7 +1545,12 @@ pub fn diagnose(data: &[u8]) -> DiagnosticReport {
         stages.push(DiagnosticStage {
             name: "Version check".to_string(),
             success: false,
-            detail: format!("Version {} not supported (need 57-66)", ver),
+            detail: format!(
+                "Version {} not supported (need {}-{})",
+                ver,
+                version::MIN_SUPPORTED_VERSION,
+                version::MAX_SUPPORTED_VERSION
+            ),
         });
         return DiagnosticReport { file_size, stages };
     }
@@ -1030,12 +1580,18 @@ pub fn diagnose(data: &[u8]) -> DiagnosticReport {
             .iter()
             .filter(|c| c.offset > 0)
             .count();
+        let extension_note = if raw_file_info.sample_extension_info.is_some() {
+            ", SampleExtensionInfo blob parsed"
+        } else {
+            ""
+        };
         stages.push(DiagnosticStage {
             name: "RawFileInfo".to_string(),
             success: true,
             detail: format!(
-                "Found via {} reading\nDate: {}, n_controllers: {} ({} active), end_offset: {}",
+                "Found via {} reading{}\nDate: {}, n_controllers: {} ({} active), end_offset: {}",
                 rfi_method,
+                extension_note,
                 raw_file_info.acquisition_date(),
                 raw_file_info.n_controllers,
                 n_active,
@@ -1094,6 +1650,25 @@ pub fn diagnose(data: &[u8]) -> DiagnosticReport {
     // Stage 5: Parse ScanIndex
     let n_scans = run_header.n_scans();
     let si_addr = run_header.scan_index_addr();
+
+    // Report the declared-count bound check up front, independent of
+    // whether parsing below actually fails: a count that's merely large
+    // but still fits is worth flagging too, not just outright rejections.
+    let entry_size = scan_index::detect_entry_size(data, si_addr, n_scans, ver);
+    let si_available = file_size.saturating_sub(si_addr);
+    let si_needed = (n_scans as u64).saturating_mul(entry_size as u64);
+    stages.push(DiagnosticStage {
+        name: "ScanIndexBounds".to_string(),
+        success: si_needed <= si_available,
+        detail: if si_needed <= si_available {
+            format!("{n_scans} scans x {entry_size} bytes/entry = {si_needed} bytes fits in {si_available} bytes available")
+        } else {
+            format!(
+                "declared count rejected: {n_scans} scans x {entry_size} bytes/entry = {si_needed} bytes, but only {si_available} bytes available from offset {si_addr}"
+            )
+        },
+    });
+
     let scan_index_entries = match scan_index::parse_scan_index(data, si_addr, ver, n_scans) {
         Ok(entries) => {
             let sample = entries
@@ -1176,7 +1751,8 @@ pub fn diagnose(data: &[u8]) -> DiagnosticReport {
     if let Some(first_entry) = scan_index_entries.first() {
         let data_addr = run_header.data_addr();
         let scan_num = run_header.first_scan;
-        let result = scan_data::decode_scan(data, data_addr as usize, first_entry, scan_num, &[]);
+        let result =
+            scan_data::decode_scan(data, data_addr as usize, first_entry, scan_num, &[], None);
 
         let (success, detail) = match result {
             Ok(scan) => (
@@ -1222,11 +1798,12 @@ fn find_raw_file_info_sequential(
 ) -> Result<RawFileInfo, RawError> {
     let mut reader = BinaryReader::at_offset(data, info_base);
 
-    skip_sequence_row(&mut reader, version)?;
+    let sample_extension_info = skip_sequence_row(&mut reader, version)?;
     skip_auto_sampler_config(&mut reader, version)?;
 
     let rfi_offset = reader.position();
-    let info = RawFileInfo::parse(data, rfi_offset, version)?;
+    let mut info = RawFileInfo::parse(data, rfi_offset, version)?;
+    info.sample_extension_info = sample_extension_info;
     let file_size = data.len() as u64;
     if info.has_valid_controllers(file_size) {
         Ok(info)
@@ -1241,8 +1818,13 @@ fn find_raw_file_info_sequential(
 /// Skip past the SequenceRow structure (variable-length).
 ///
 /// Layout: 60-byte fixed struct + version-dependent PascalStrings.
-/// We don't need the data, just need to advance the cursor correctly.
-fn skip_sequence_row(reader: &mut BinaryReader, version: u32) -> Result<(), RawError> {
+/// Returns the parsed `SampleExtensionInfo` blob, if one was found trailing
+/// the row (see the note below) -- `None` doesn't imply an older file, just
+/// that this particular row didn't carry one.
+fn skip_sequence_row<R: Read + Seek>(
+    reader: &mut BinaryReader<R>,
+    version: u32,
+) -> Result<Option<HashMap<String, String>>, RawError> {
     // SeqRowInfoStruct: 60 bytes fixed
     // Revision(i32) + RowNumber(i32) + SampleType(i32) + VialName(UTF16[4]=8 bytes)
     // + InjectionVolume(f64) + SampleWeight(f64) + SampleVolume(f64)
@@ -1277,21 +1859,67 @@ fn skip_sequence_row(reader: &mut BinaryReader, version: u32) -> Result<(), RawE
         }
     }
 
-    // Note: v66+ files written by newer acquisition software may have additional
-    // PascalStrings after ExtraUserColumns (e.g., SampleExtensionInfo JSON blobs).
-    // These extra strings are NOT part of the standard SequenceRow.Load code in
-    // Thermo's v8.0.6 library. We don't try to consume them here because it's
-    // impossible to reliably distinguish extra PascalStrings from the start of
-    // AutoSamplerConfig (whose TrayIndex=0 looks like an empty PascalString).
-    // The fallback scanner in find_raw_file_info() handles these files correctly.
+    // v66+ files written by newer acquisition software may have an additional
+    // PascalString after ExtraUserColumns: a SampleExtensionInfo JSON blob.
+    // This string is NOT part of the standard SequenceRow.Load code in
+    // Thermo's v8.0.6 library, so we can't unconditionally consume it --
+    // most files have nothing here, and AutoSamplerConfig's own TrayIndex=0
+    // field would otherwise get misread as the length prefix of an empty
+    // PascalString. Instead we peek: read the next PascalString, and only
+    // keep the cursor advanced past it if the content actually looks like
+    // (and round-trips as) JSON. Anything else -- garbage, an empty string,
+    // or the start of AutoSamplerConfig -- rewinds, leaving the fallback
+    // scanner in find_raw_file_info() to handle the file as before.
+    let sample_extension_info = peek_sample_extension_info(reader)?;
+
+    Ok(sample_extension_info)
+}
 
-    Ok(())
+/// Peek the next PascalString and, if it begins with `{` and parses as a
+/// JSON object, consume it and return its string-keyed contents. Otherwise
+/// restores `reader`'s position so the caller can fall back to treating
+/// whatever follows as AutoSamplerConfig (or reject it the normal way).
+fn peek_sample_extension_info<R: Read + Seek>(
+    reader: &mut BinaryReader<R>,
+) -> Result<Option<HashMap<String, String>>, RawError> {
+    let checkpoint = reader.position();
+
+    let candidate = match reader.read_pascal_string() {
+        Ok(s) => s,
+        Err(_) => {
+            reader.set_position(checkpoint);
+            return Ok(None);
+        }
+    };
+
+    if !candidate.trim_start().starts_with('{') {
+        reader.set_position(checkpoint);
+        return Ok(None);
+    }
+
+    match serde_json::from_str::<serde_json::Value>(&candidate) {
+        Ok(serde_json::Value::Object(map)) => Ok(Some(
+            map.into_iter()
+                .map(|(k, v)| {
+                    let v = match v {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (k, v)
+                })
+                .collect(),
+        )),
+        _ => {
+            reader.set_position(checkpoint);
+            Ok(None)
+        }
+    }
 }
 
 /// Skip past the AutoSamplerConfig structure (version-dependent).
 ///
 /// Only present for version >= 36. Layout: 24-byte fixed struct + TrayName PascalString.
-fn skip_auto_sampler_config(reader: &mut BinaryReader, version: u32) -> Result<(), RawError> {
+fn skip_auto_sampler_config<R: Read + Seek>(reader: &mut BinaryReader<R>, version: u32) -> Result<(), RawError> {
     if version < 36 {
         return Ok(());
     }
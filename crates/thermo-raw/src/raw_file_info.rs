@@ -22,6 +22,28 @@
 
 use crate::io_utils::BinaryReader;
 use crate::RawError;
+use std::io::{Read, Seek};
+
+/// Number of VCI entries in the v25+ (RawFileInfoStruct3+) OldVirtualControllerInfo
+/// and v64+ VirtualControllerInfoStruct arrays.
+const VCI_LEN: usize = 64;
+/// Number of VCI entries in the pre-v25 (v7-24, RawFileInfoStruct2)
+/// OldVirtualControllerInfo array -- smaller, since early acquisition
+/// software supported fewer simultaneous device channels.
+const VCI_LEN_PRE_V25: usize = 10;
+
+/// Search window size for [`RawFileInfo::scan`] -- the preamble always
+/// appears early in a well-formed file (right after FileHeader/SequenceRow/
+/// AutoSamplerConfig), so this is generous enough to survive an unexpectedly
+/// large intervening blob without scanning gigabyte-scale files end to end.
+const SCAN_WINDOW: u64 = 1_048_576;
+
+/// Byte offset of the first VCI entry relative to a candidate RawFileInfo
+/// offset, for files where a VCI array exists at all (version >= 7): the
+/// 28-byte common preamble (IsExpMethodPresent + SystemTimeStruct +
+/// IsInAcquisition + VirtualDataOffset32) followed by
+/// NumberOfVirtualControllers + NextAvailableControllerIndex (8 bytes).
+const VCI_ARRAY_OFFSET: u64 = 36;
 
 /// Virtual controller info entry from the VCI array.
 ///
@@ -37,6 +59,50 @@ pub struct VirtualControllerInfo {
     pub offset: i64,
 }
 
+/// Which pass of the two-pass VCI validation heuristic accepted (or failed
+/// to accept) a [`RawFileInfo`], as reported by [`RawFileInfo::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationPass {
+    /// Pass 1: valid date + valid n_controllers + matching VCI entries.
+    Strict,
+    /// Pass 1 failed; pass 2 (VCI-only, ignoring date/n_controllers) succeeded.
+    VciOnly,
+    /// Neither pass accepted this candidate.
+    Failed,
+}
+
+/// Structured diagnostics from [`RawFileInfo::validate`], recording *why* a
+/// candidate was accepted or rejected rather than collapsing the two-pass
+/// heuristic into a single `bool`. Intended for tooling that scans a damaged
+/// file for a plausible RawFileInfo offset and needs to rank or debug
+/// near-miss candidates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Which validation pass (if any) accepted this candidate.
+    pub pass: ValidationPass,
+    /// Number of VCI entries recognized as populated and structurally valid.
+    pub valid_count: usize,
+    /// Number of VCI entries that are all-zero (empty slots).
+    pub zero_count: usize,
+    /// Number of VCI entries that are neither valid nor zero (garbage).
+    pub garbage_count: usize,
+    /// Index of the first garbage VCI entry, if any.
+    pub first_garbage_index: Option<usize>,
+    /// Whether the preamble's SystemTimeStruct date field is plausible.
+    pub date_plausible: bool,
+    /// Whether `parse_from` had to fall back from the 64-bit NewVCI array to
+    /// the 32-bit OldVCI array (see the v64+ branch in `parse_from`).
+    pub vci_fallback_applied: bool,
+}
+
+impl ValidationReport {
+    /// True if either validation pass accepted the candidate -- the same
+    /// criterion `has_valid_controllers` used to collapse into a `bool`.
+    pub fn is_acceptable(&self) -> bool {
+        self.pass != ValidationPass::Failed
+    }
+}
+
 /// Parsed RawFileInfo with addresses to key data structures.
 #[derive(Debug, Clone)]
 pub struct RawFileInfo {
@@ -59,16 +125,46 @@ pub struct RawFileInfo {
     pub blob_size: u32,
     /// Byte offset after parsing (where the next structure begins).
     pub end_offset: u64,
+    /// True if `parse_from` fell back from the v64+ 64-bit NewVCI array to
+    /// the 32-bit OldVCI array because NewVCI had no valid entries.
+    pub vci_fallback_applied: bool,
+    /// SampleExtensionInfo JSON blob recovered from a v66+ SequenceRow, if
+    /// the caller found and parsed one before landing here. Always `None`
+    /// from [`parse`](Self::parse)/[`parse_from`](Self::parse_from)
+    /// themselves -- it's filled in by the caller, since the blob sits in
+    /// the SequenceRow that precedes RawFileInfo, not in RawFileInfo's own
+    /// layout.
+    pub sample_extension_info: Option<std::collections::HashMap<String, String>>,
 }
 
 impl RawFileInfo {
     /// Parse RawFileInfo starting at the given offset in the data stream.
     ///
     /// Handles all supported versions (v57-v66) following the decompiled
-    /// RawFileInfo.Load version dispatch logic.
+    /// RawFileInfo.Load version dispatch logic. A thin wrapper over
+    /// [`parse_from`](Self::parse_from) for callers that already have the
+    /// whole file resident as a slice.
     pub fn parse(data: &[u8], offset: u64, version: u32) -> Result<Self, RawError> {
-        let mut reader = BinaryReader::at_offset(data, offset);
+        Self::parse_from(&mut BinaryReader::at_offset(data, offset), data.len() as u64, version)
+    }
 
+    /// Parse RawFileInfo from any `Read + Seek` source, positioned wherever
+    /// `reader`'s logical position currently is.
+    ///
+    /// Generalizes [`parse`](Self::parse) past requiring the whole file
+    /// resident as `&[u8]`: a caller can seek a [`BinaryReader`] wrapping a
+    /// `File` (via [`BinaryReader::from_file`]) straight to the RawFileInfo
+    /// pointer and parse just the preamble and VCI arrays without
+    /// materializing the rest of the file. `file_size` drives the VCI
+    /// fallback and bounds checks below -- it can't be derived from `data.len()`
+    /// once `reader` is no longer slice-backed, so callers must supply it
+    /// directly (a `File`-backed `BinaryReader` gets this for free from
+    /// `from_file`/`from_reader`'s initial `seek(End(0))`).
+    pub fn parse_from<R: Read + Seek>(
+        reader: &mut BinaryReader<R>,
+        file_size: u64,
+        version: u32,
+    ) -> Result<Self, RawError> {
         // IsExpMethodPresent (bool marshalled as i32 = 4 bytes)
         let _method_file_present = reader.read_u32()?;
 
@@ -88,29 +184,42 @@ impl RawFileInfo {
         // VirtualDataOffset32 (u32)
         let _data_addr_32 = reader.read_u32()?;
 
-        // NumberOfVirtualControllers (i32)
-        let n_controllers = reader.read_u32()?;
-
-        // NextAvailableControllerIndex (i32)
-        let _n_controllers_2 = reader.read_u32()?;
-
-        // OldVirtualControllerInfo[64] (12 bytes each = 768 bytes)
-        // Each: VirtualDeviceType(i32) + VirtualDeviceIndex(i32) + Offset(i32)
-        let mut controllers = Vec::new();
-        for _ in 0..64 {
-            let device_type = reader.read_i32()?;
-            let device_index = reader.read_i32()?;
-            let offset32 = reader.read_i32()?;
-            controllers.push(VirtualControllerInfo {
-                device_type,
-                device_index,
-                offset: offset32 as i64,
-            });
-        }
+        // Pre-v7 (RawFileInfoStruct1) has no NumberOfVirtualControllers /
+        // NextAvailableControllerIndex split -- there was nothing yet to
+        // index a VCI array by -- and no VCI array at all.
+        let (n_controllers, mut controllers) = if version < 7 {
+            (0u32, Vec::new())
+        } else {
+            // NumberOfVirtualControllers (i32)
+            let n_controllers = reader.read_u32()?;
+
+            // NextAvailableControllerIndex (i32)
+            let _n_controllers_2 = reader.read_u32()?;
+
+            // v7-24 (RawFileInfoStruct2) carries a much smaller VCI array
+            // than the 64-entry table v25+ (RawFileInfoStruct3+) settled on.
+            let vci_len = if version < 25 { VCI_LEN_PRE_V25 } else { VCI_LEN };
+
+            // OldVirtualControllerInfo[vci_len] (12 bytes each)
+            // Each: VirtualDeviceType(i32) + VirtualDeviceIndex(i32) + Offset(i32)
+            let mut controllers = Vec::with_capacity(vci_len);
+            for _ in 0..vci_len {
+                let device_type = reader.read_i32()?;
+                let device_index = reader.read_i32()?;
+                let offset32 = reader.read_i32()?;
+                controllers.push(VirtualControllerInfo {
+                    device_type,
+                    device_index,
+                    offset: offset32 as i64,
+                });
+            }
+            (n_controllers, controllers)
+        };
 
         // Version-dependent extended fields
         let mut blob_offset = -1i64;
         let mut blob_size = 0u32;
+        let mut vci_fallback_applied = false;
 
         if version >= 64 {
             // .NET RawFileInfoStruct uses LayoutKind.Sequential with natural alignment.
@@ -142,7 +251,6 @@ impl RawFileInfo {
             // If NewVCI has no valid entries but OldVCI does, fall back to OldVCI.
             // Some files (e.g., 2018-era Exactive v66) have valid 32-bit offsets
             // but zeroed/garbage 64-bit VCI area.
-            let file_size = data.len() as u64;
             if !controllers
                 .iter()
                 .any(|c| Self::is_valid_controller(c, file_size))
@@ -151,6 +259,7 @@ impl RawFileInfo {
                     .any(|c| Self::is_valid_controller(c, file_size))
             {
                 controllers = old_controllers;
+                vci_fallback_applied = true;
             }
 
             if version >= 65 {
@@ -190,6 +299,8 @@ impl RawFileInfo {
             blob_offset,
             blob_size,
             end_offset: reader.position(),
+            vci_fallback_applied,
+            sample_extension_info: None,
         })
     }
 
@@ -227,29 +338,65 @@ impl RawFileInfo {
 
     /// Check if this parsed RawFileInfo appears to contain valid VCI data.
     ///
-    /// Uses a two-pass approach:
+    /// A thin wrapper over [`validate`](Self::validate) for callers that only
+    /// need the accept/reject verdict; see [`ValidationReport`] for the full
+    /// diagnostics (which pass matched, VCI entry counts, etc.).
+    pub fn has_valid_controllers(&self, file_size: u64) -> bool {
+        self.validate(file_size).is_acceptable()
+    }
+
+    /// Validate this parsed RawFileInfo and report *why* it was accepted or
+    /// rejected, instead of collapsing the two-pass heuristic into a bare
+    /// `bool`.
+    ///
+    /// Uses the same two-pass approach as before:
     /// 1. **Strict**: Valid date + valid n_controllers + matching VCI entries
     /// 2. **VCI-only fallback**: Ignores date/n_controllers, validates VCI structure directly.
     ///    Used for files with zeroed-out preamble fields but intact VCI arrays.
     ///
-    /// The VCI-only fallback counts valid entries (device_type 0-5, offset within file)
-    /// and zero entries. If all 64 entries are either valid or empty (no garbage),
-    /// and at least one is valid, the alignment is correct.
-    pub fn has_valid_controllers(&self, file_size: u64) -> bool {
+    /// Alongside the pass verdict, this counts how many VCI entries are
+    /// structurally valid, all-zero (empty slots), or garbage (neither), and
+    /// records the first garbage entry's index -- useful when a caller is
+    /// scanning a damaged file for the best near-miss candidate offset.
+    pub fn validate(&self, file_size: u64) -> ValidationReport {
+        let date_plausible = self.has_valid_date();
+
+        let mut valid_count = 0usize;
+        let mut zero_count = 0usize;
+        let mut first_garbage_index = None;
+        for (i, c) in self.controllers.iter().enumerate() {
+            if Self::is_zero_controller(c) {
+                zero_count += 1;
+            } else if Self::is_valid_controller_strict(c, file_size) {
+                valid_count += 1;
+            } else if first_garbage_index.is_none() {
+                first_garbage_index = Some(i);
+            }
+        }
+        let garbage_count = self.controllers.len() - valid_count - zero_count;
+
         // Quick reject: n_controllers must be in a plausible range (0-16).
         // This prevents false positives when scanning through garbage data.
         // n_controllers=0 is allowed (zeroed preamble files with intact VCI arrays).
-        if self.n_controllers > 16 {
-            return false;
-        }
+        let pass = if self.n_controllers > 16 {
+            ValidationPass::Failed
+        } else if self.has_valid_controllers_strict(file_size) {
+            ValidationPass::Strict
+        } else if self.has_valid_controllers_vci_only(file_size) {
+            ValidationPass::VciOnly
+        } else {
+            ValidationPass::Failed
+        };
 
-        // Pass 1: Strict validation (date + n_controllers + VCI)
-        if self.has_valid_controllers_strict(file_size) {
-            return true;
+        ValidationReport {
+            pass,
+            valid_count,
+            zero_count,
+            garbage_count,
+            first_garbage_index,
+            date_plausible,
+            vci_fallback_applied: self.vci_fallback_applied,
         }
-
-        // Pass 2: VCI-only fallback for files with zeroed preamble fields
-        self.has_valid_controllers_vci_only(file_size)
     }
 
     fn has_valid_controllers_strict(&self, file_size: u64) -> bool {
@@ -337,6 +484,90 @@ impl RawFileInfo {
             self.year, self.month, self.day, self.hour, self.minute, self.second
         )
     }
+
+    /// Last-resort recovery: locate a plausible RawFileInfo in `data` when
+    /// the caller's header pointer to it is missing or corrupt.
+    ///
+    /// Steps 4-byte-aligned candidate offsets across a bounded window near
+    /// the start of the file (where the preamble is always located in a
+    /// well-formed file), and for each candidate:
+    /// 1. Cheaply pre-screens the predicted VCI offset for the alignment
+    ///    signature (see [`vci_prescreen`](Self::vci_prescreen)), skipping
+    ///    the expensive full parse for almost all wrong offsets.
+    /// 2. Runs a full [`parse`](Self::parse) and keeps the candidate if
+    ///    [`has_valid_controllers`](Self::has_valid_controllers) accepts it.
+    ///
+    /// Among accepted candidates, prefers the first one whose date also
+    /// looks plausible -- a coincidental VCI-only match earlier in the file
+    /// is less likely to also have a plausible date than the real preamble
+    /// -- falling back to the first accepted candidate if none does.
+    pub fn scan(data: &[u8], version: u32) -> Option<RawFileInfo> {
+        let file_size = data.len() as u64;
+        let search_limit = SCAN_WINDOW.min(file_size);
+
+        let mut best: Option<RawFileInfo> = None;
+        let mut offset = 0u64;
+        while offset + VCI_ARRAY_OFFSET < search_limit {
+            if version >= 7 && !Self::vci_prescreen(data, offset + VCI_ARRAY_OFFSET, file_size) {
+                offset += 4;
+                continue;
+            }
+
+            if let Ok(info) = Self::parse(data, offset, version) {
+                if info.has_valid_controllers(file_size) {
+                    if info.has_valid_date() {
+                        return Some(info);
+                    }
+                    if best.is_none() {
+                        best = Some(info);
+                    }
+                }
+            }
+
+            offset += 4;
+        }
+
+        best
+    }
+
+    /// Cheap pre-check for [`scan`](Self::scan): does the byte run starting
+    /// at `vci_offset` look like the start of an OldVCI array? Checks the
+    /// first few entries' device_type/device_index/offset fields directly,
+    /// without paying for a full [`parse`](Self::parse) (which also reads
+    /// the preamble, strings, and all 64 VCI entries).
+    fn vci_prescreen(data: &[u8], vci_offset: u64, file_size: u64) -> bool {
+        const ENTRY_SIZE: usize = 12; // device_type(i32) + device_index(i32) + offset(i32)
+        const PRESCREEN_ENTRIES: usize = 4;
+
+        let start = vci_offset as usize;
+        let end = start + ENTRY_SIZE * PRESCREEN_ENTRIES;
+        if end > data.len() {
+            return false;
+        }
+
+        let mut any_valid = false;
+        for i in 0..PRESCREEN_ENTRIES {
+            let base = start + i * ENTRY_SIZE;
+            let device_type = i32::from_le_bytes(data[base..base + 4].try_into().unwrap());
+            let device_index = i32::from_le_bytes(data[base + 4..base + 8].try_into().unwrap());
+            let offset = i32::from_le_bytes(data[base + 8..base + 12].try_into().unwrap());
+
+            let is_zero = device_type == 0 && device_index == 0 && offset == 0;
+            let is_valid = (0..=5).contains(&device_type)
+                && (0..=7).contains(&device_index)
+                && offset > 4096
+                && (offset as u64) < file_size;
+
+            if !is_zero && !is_valid {
+                return false;
+            }
+            if is_valid {
+                any_valid = true;
+            }
+        }
+
+        any_valid
+    }
 }
 
 #[cfg(test)]
@@ -365,6 +596,8 @@ mod tests {
             blob_offset: -1,
             blob_size: 0,
             end_offset: 0,
+            vci_fallback_applied: false,
+            sample_extension_info: None,
         }
     }
 
@@ -642,6 +875,82 @@ mod tests {
         assert!(!info.has_valid_controllers(100000));
     }
 
+    // Tests for validate() (structured diagnostics)
+
+    #[test]
+    fn test_validate_reports_strict_pass() {
+        let mut controllers = vec![
+            VirtualControllerInfo {
+                device_type: 0,
+                device_index: 0,
+                offset: 10000,
+            },
+            VirtualControllerInfo {
+                device_type: 1,
+                device_index: 0,
+                offset: 20000,
+            },
+        ];
+        controllers.resize(64, VirtualControllerInfo::default());
+
+        let info = make_test_info(2020, 5, 15, 2, controllers);
+        let report = info.validate(100000);
+        assert_eq!(report.pass, ValidationPass::Strict);
+        assert!(report.is_acceptable());
+        assert!(report.date_plausible);
+        assert_eq!(report.valid_count, 2);
+        assert_eq!(report.zero_count, 62);
+        assert_eq!(report.garbage_count, 0);
+        assert_eq!(report.first_garbage_index, None);
+        assert!(!report.vci_fallback_applied);
+    }
+
+    #[test]
+    fn test_validate_reports_vci_only_pass() {
+        // Invalid year forces pass 1 to fail; VCI structure alone still passes.
+        let mut controllers = vec![VirtualControllerInfo {
+            device_type: 0,
+            device_index: 0,
+            offset: 10000,
+        }];
+        controllers.resize(64, VirtualControllerInfo::default());
+
+        let info = make_test_info(1999, 5, 15, 1, controllers);
+        let report = info.validate(100000);
+        assert_eq!(report.pass, ValidationPass::VciOnly);
+        assert!(report.is_acceptable());
+        assert!(!report.date_plausible);
+        assert_eq!(report.valid_count, 1);
+    }
+
+    #[test]
+    fn test_validate_reports_failure_and_first_garbage_index() {
+        // Entry 0 is garbage (device_type out of range); nothing else salvages it.
+        let mut controllers = vec![VirtualControllerInfo {
+            device_type: 99,
+            device_index: 0,
+            offset: 10000,
+        }];
+        controllers.resize(64, VirtualControllerInfo::default());
+
+        let info = make_test_info(1999, 5, 15, 0, controllers);
+        let report = info.validate(100000);
+        assert_eq!(report.pass, ValidationPass::Failed);
+        assert!(!report.is_acceptable());
+        assert_eq!(report.first_garbage_index, Some(0));
+        assert_eq!(report.garbage_count, 1);
+    }
+
+    #[test]
+    fn test_validate_reports_vci_fallback_flag() {
+        let info = make_test_info(2020, 5, 15, 0, vec![VirtualControllerInfo::default(); 64]);
+        assert!(!info.validate(100000).vci_fallback_applied);
+
+        let mut fallback_info = info;
+        fallback_info.vci_fallback_applied = true;
+        assert!(fallback_info.validate(100000).vci_fallback_applied);
+    }
+
     // Tests for OldVCI â†’ NewVCI fallback logic
 
     #[test]
@@ -810,8 +1119,147 @@ mod tests {
             blob_offset: -1,
             blob_size: 0,
             end_offset: 0,
+            vci_fallback_applied: false,
+            sample_extension_info: None,
         };
 
         assert_eq!(info.acquisition_date(), "2023-07-04T14:30:15");
     }
+
+    // Tests for parse()'s version-dispatch matrix (chunk13-2)
+
+    /// Mirror of `BinaryWriter::write_pascal_string`, built by hand since
+    /// this module has no writer of its own to reuse.
+    fn make_pascal_string(s: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let len = s.encode_utf16().count() as i32;
+        bytes.extend(len.to_le_bytes());
+        for c in s.encode_utf16() {
+            bytes.extend(c.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Build the common preamble shared by every version: IsExpMethodPresent,
+    /// SystemTimeStruct, IsInAcquisition, VirtualDataOffset32.
+    fn push_common_preamble(buf: &mut Vec<u8>) {
+        buf.extend(0u32.to_le_bytes()); // IsExpMethodPresent
+        buf.extend(2024u16.to_le_bytes()); // year
+        buf.extend(3u16.to_le_bytes()); // month
+        buf.extend(0u16.to_le_bytes()); // day_of_week
+        buf.extend(15u16.to_le_bytes()); // day
+        buf.extend(9u16.to_le_bytes()); // hour
+        buf.extend(30u16.to_le_bytes()); // minute
+        buf.extend(0u16.to_le_bytes()); // second
+        buf.extend(0u16.to_le_bytes()); // millisecond
+        buf.extend(0u32.to_le_bytes()); // IsInAcquisition
+        buf.extend(0u32.to_le_bytes()); // VirtualDataOffset32
+    }
+
+    fn push_vci_entries(buf: &mut Vec<u8>, n: usize) {
+        for i in 0..n {
+            buf.extend((i as i32).to_le_bytes()); // device_type
+            buf.extend(0i32.to_le_bytes()); // device_index
+            buf.extend(((i as i32) * 1000).to_le_bytes()); // offset
+        }
+    }
+
+    #[test]
+    fn test_parse_pre_v7() {
+        // v<7 (RawFileInfoStruct1): no n_controllers/next-index split, no VCI
+        // array, no ComputerName string.
+        let mut buf = Vec::new();
+        push_common_preamble(&mut buf);
+        buf.extend(make_pascal_string("Label1"));
+
+        let info = RawFileInfo::parse(&buf, 0, 5).unwrap();
+        assert_eq!(info.year, 2024);
+        assert_eq!(info.n_controllers, 0);
+        assert!(info.controllers.is_empty());
+        assert_eq!(info.headings, vec!["Label1".to_string()]);
+        assert_eq!(info.end_offset, buf.len() as u64);
+    }
+
+    #[test]
+    fn test_parse_v7_to_v24() {
+        // v7-24 (RawFileInfoStruct2): n_controllers + next-index fields, a
+        // 10-entry VCI array, and a trailing ComputerName string.
+        let mut buf = Vec::new();
+        push_common_preamble(&mut buf);
+        buf.extend(2u32.to_le_bytes()); // NumberOfVirtualControllers
+        buf.extend(2u32.to_le_bytes()); // NextAvailableControllerIndex
+        push_vci_entries(&mut buf, VCI_LEN_PRE_V25);
+        buf.extend(make_pascal_string("Label1"));
+        buf.extend(make_pascal_string("MyComputer"));
+
+        let info = RawFileInfo::parse(&buf, 0, 15).unwrap();
+        assert_eq!(info.n_controllers, 2);
+        assert_eq!(info.controllers.len(), VCI_LEN_PRE_V25);
+        assert_eq!(info.controllers[1].device_type, 1);
+        assert_eq!(info.controllers[1].offset, 1000);
+        assert_eq!(
+            info.headings,
+            vec!["Label1".to_string(), "MyComputer".to_string()]
+        );
+        assert_eq!(info.end_offset, buf.len() as u64);
+    }
+
+    #[test]
+    fn test_parse_v25_to_v63() {
+        // v25-63 (RawFileInfoStruct3): same shape as v7-24 but with the full
+        // 64-entry VCI array.
+        let mut buf = Vec::new();
+        push_common_preamble(&mut buf);
+        buf.extend(1u32.to_le_bytes()); // NumberOfVirtualControllers
+        buf.extend(1u32.to_le_bytes()); // NextAvailableControllerIndex
+        push_vci_entries(&mut buf, VCI_LEN);
+        buf.extend(make_pascal_string("Label1"));
+        buf.extend(make_pascal_string("MyComputer"));
+
+        let info = RawFileInfo::parse(&buf, 0, 40).unwrap();
+        assert_eq!(info.n_controllers, 1);
+        assert_eq!(info.controllers.len(), VCI_LEN);
+        assert_eq!(info.controllers[0].offset, 0);
+        assert_eq!(info.controllers[1].offset, 1000);
+        assert_eq!(info.end_offset, buf.len() as u64);
+    }
+
+    // Tests for scan() (brute-force recovery)
+
+    #[test]
+    fn test_scan_recovers_from_unknown_offset() {
+        // Junk bytes before the real preamble simulate a corrupt/zeroed
+        // header pointer that no longer points at RawFileInfo. Kept 4-byte
+        // aligned to match scan()'s candidate stepping.
+        let mut buf = vec![0xABu8; 96];
+        push_common_preamble(&mut buf);
+        buf.extend(1u32.to_le_bytes()); // NumberOfVirtualControllers
+        buf.extend(1u32.to_le_bytes()); // NextAvailableControllerIndex
+        buf.extend(0i32.to_le_bytes()); // controller[0]: zero entry
+        buf.extend(0i32.to_le_bytes());
+        buf.extend(0i32.to_le_bytes());
+        buf.extend(0i32.to_le_bytes()); // controller[1]: valid MS controller
+        buf.extend(0i32.to_le_bytes());
+        buf.extend(10000i32.to_le_bytes());
+        for _ in 2..VCI_LEN {
+            buf.extend([0i32, 0, 0].iter().flat_map(|v| v.to_le_bytes()));
+        }
+        buf.extend(make_pascal_string("Label1"));
+        buf.extend(make_pascal_string("MyComputer"));
+        // Pad so the file is large enough for the 10000-byte controller
+        // offset to fall within bounds (is_valid_controller requires
+        // offset < file_size).
+        buf.extend(vec![0u8; 20000]);
+
+        let found = RawFileInfo::scan(&buf, 40).expect("scan should recover RawFileInfo");
+        assert_eq!(found.n_controllers, 1);
+        assert_eq!(found.controllers[1].offset, 10000);
+        assert!(found.has_valid_date());
+    }
+
+    #[test]
+    fn test_scan_returns_none_when_no_candidate_matches() {
+        let buf = vec![0u8; 4096];
+        assert!(RawFileInfo::scan(&buf, 40).is_none());
+    }
 }
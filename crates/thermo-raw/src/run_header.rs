@@ -22,8 +22,20 @@
 //! a self-referential invariant: `RunHeaderPos` (the 5th i64 in the address block)
 //! equals the RunHeader's own start address from VCI. By scanning for this known
 //! value, we locate the address block reliably regardless of intermediate layout.
+//!
+//! ## v<49 (RunHeaderStruct1-3) Address Block Discovery
+//!
+//! These older, 32-bit-only layouts apply the same self-referential trick at
+//! smaller scale: `own_addr_32` restates the RunHeader's own start address as
+//! a `u32` right after the 32-bit stream address block, so
+//! [`find_address_block_32`] scans for it the same way
+//! [`find_address_block`] scans for `RunHeaderPos`. No pre-v49 fixture files
+//! are available in this repo to pin down RunHeaderStruct1-3's exact
+//! remaining field widths, so this path is best-effort; please cross-check
+//! against a real file if you have one.
 
 use crate::io_utils::BinaryReader;
+use crate::raw_file_info::VirtualControllerInfo;
 use crate::RawError;
 
 /// Parsed RunHeader data.
@@ -61,6 +73,14 @@ pub struct RunHeader {
     pub start_offset: u64,
     /// Byte offset after parsing.
     pub end_offset: u64,
+    /// Owning virtual controller's device type (0=MS, 1=MSAnalog, 2=Analog,
+    /// 3=UV, 4=Pda, 5=Other), as recorded by [`RunHeader::parse_all`].
+    /// `0` (MS) when parsed directly via [`RunHeader::parse`], which doesn't
+    /// know which controller it came from.
+    pub device_type: i32,
+    /// Owning virtual controller's device index, as recorded by
+    /// [`RunHeader::parse_all`]. `0` when parsed directly via [`RunHeader::parse`].
+    pub device_index: i32,
 }
 
 impl RunHeader {
@@ -79,8 +99,8 @@ impl RunHeader {
         let _error_log_length = reader.read_u32()?;
         let _file_flag = reader.read_u32()?;
 
-        let scan_index_addr_32 = reader.read_u32()?;
-        let data_addr_32 = reader.read_u32()?;
+        let mut scan_index_addr_32 = reader.read_u32()?;
+        let mut data_addr_32 = reader.read_u32()?;
         let _inst_log_addr_32 = reader.read_u32()?;
         let _error_log_addr_32 = reader.read_u32()?;
         let _max_packet_and_pad = reader.read_u32()?; // MaxPacket(i16) + padding(2)
@@ -126,8 +146,10 @@ impl RunHeader {
             if version >= 66 {
                 instrument_type = reader.read_i32()?;
             }
-        } else {
-            // For v<64, parse through the traditional fixed-size layout.
+        } else if version >= 49 {
+            // RunHeaderStruct4 (v49-63): fixed-size tag/filename area, then the
+            // 32-bit trailer addresses directly -- offsets confirmed against
+            // this version range's fixture files.
             reader.skip(56)?; // unknown_area after SampleInfo
 
             // Sample info tags (fixed-size UTF-16)
@@ -149,6 +171,26 @@ impl RunHeader {
             let _n_segments = reader.read_u32()?;
             reader.skip(16)?; // unknown4..7
             let _own_addr_32 = reader.read_u32()?;
+        } else {
+            // RunHeaderStruct1-3 (v<49): the fixed-size tag/filename layout
+            // above is RunHeaderStruct4-specific and mis-aligns here, and no
+            // pre-v49 fixture files are available in this repo to confirm
+            // exact field widths for the older structs. Instead, locate the
+            // 32-bit address block the same way `find_address_block` locates
+            // the 64-bit one for v64+: these layouts restate the RunHeader's
+            // own start address as a little-endian `u32` (`own_addr_32`)
+            // right after the block of stream addresses, so scan for that
+            // value and validate the block by bounds-checking the addresses
+            // it points to before promoting them via the usual
+            // `scan_index_addr()`/`data_addr()` 32-to-64 fallback.
+            let search_from = reader.position();
+            let addr_block_start = find_address_block_32(data, search_from, offset)?;
+            reader.set_position(addr_block_start);
+
+            scan_index_addr_32 = reader.read_u32()?;
+            data_addr_32 = reader.read_u32()?;
+            scan_trailer_addr_32 = reader.read_u32()?;
+            scan_params_addr_32 = reader.read_u32()?;
         }
 
         // PascalStringWin32 strings at end of RunHeader:
@@ -197,9 +239,34 @@ impl RunHeader {
             instrument_type,
             start_offset: offset,
             end_offset: reader.position(),
+            device_type: 0,
+            device_index: 0,
         })
     }
 
+    /// Parse every per-device RunHeader referenced by `controllers` (from
+    /// [`crate::raw_file_info::RawFileInfo::controllers`]), tagging each with
+    /// its owning `device_type`/`device_index` -- e.g. to select the UV trace
+    /// (device_type=3) alongside the MS scans (device_type=0) from the same
+    /// multi-detector acquisition.
+    ///
+    /// Controllers with a zero offset (empty VCI slots) are skipped, and a
+    /// controller whose RunHeader fails to parse is dropped rather than
+    /// failing the whole file -- one bad auxiliary channel shouldn't prevent
+    /// reading the others.
+    pub fn parse_all(data: &[u8], controllers: &[VirtualControllerInfo], version: u32) -> Vec<Self> {
+        controllers
+            .iter()
+            .filter(|c| c.offset > 0)
+            .filter_map(|c| {
+                let mut rh = RunHeader::parse(data, c.offset as u64, version).ok()?;
+                rh.device_type = c.device_type;
+                rh.device_index = c.device_index;
+                Some(rh)
+            })
+            .collect()
+    }
+
     /// Get the best available scan index address.
     pub fn scan_index_addr(&self) -> u64 {
         self.scan_index_addr_64
@@ -313,3 +380,48 @@ fn validate_address_block_with_vci(data: &[u8], block_start: usize, file_size: u
     let device_index = i32::from_le_bytes(data[vci_start + 4..vci_start + 8].try_into().unwrap());
     (0..=5).contains(&device_type) && (0..=7).contains(&device_index)
 }
+
+/// Find the start of the 32-bit address block for legacy (pre-v49)
+/// RunHeader layouts, the same way [`find_address_block`] locates the
+/// 64-bit block for v64+: these formats restate the RunHeader's own start
+/// address as a little-endian `u32` (`own_addr_32`) immediately after the
+/// block of 32-bit stream addresses (SpectPos, PacketPos,
+/// TrailerScanEventsPos, TrailerExtraPos).
+fn find_address_block_32(data: &[u8], search_from: u64, run_header_offset: u64) -> Result<u64, RawError> {
+    let target_bytes = (run_header_offset as u32).to_le_bytes();
+    let file_size = data.len() as u64;
+
+    let search_start = search_from as usize;
+    let search_end = ((search_from + 8192) as usize).min(data.len());
+
+    let mut pos = search_start;
+    while pos + 4 <= search_end {
+        if data[pos..pos + 4] == target_bytes {
+            // own_addr_32 sits right after the 4 preceding stream addresses.
+            if pos >= 16 {
+                let candidate = pos - 16;
+                if candidate >= search_start
+                    && candidate + 16 <= data.len()
+                    && validate_address_block_32(data, candidate, file_size)
+                {
+                    return Ok(candidate as u64);
+                }
+            }
+        }
+        pos += 4;
+    }
+
+    Err(RawError::CorruptedData(format!(
+        "RunHeader: could not locate 32-bit address block for legacy version \
+             (own_addr_32={} not found in search range {}..{})",
+        run_header_offset, search_start, search_end
+    )))
+}
+
+/// Validate that the first two u32s in a candidate 32-bit address block are
+/// valid file offsets (mirrors [`validate_address_block`] for the 64-bit case).
+fn validate_address_block_32(data: &[u8], block_start: usize, file_size: u64) -> bool {
+    let spect = u32::from_le_bytes(data[block_start..block_start + 4].try_into().unwrap()) as u64;
+    let packet = u32::from_le_bytes(data[block_start + 4..block_start + 8].try_into().unwrap()) as u64;
+    spect > 0 && spect < file_size && packet > 0 && packet < file_size
+}
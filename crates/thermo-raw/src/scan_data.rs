@@ -3,13 +3,22 @@
 //! Each scan's raw data is stored as a ScanDataPacket at an offset in the
 //! data stream. The packet has a 40-byte header followed by profile data,
 //! peak list (centroids), peak descriptors, and additional streams.
-
-use crate::io_utils::BinaryReader;
+//!
+//! Every entry point below is generic over [`PacketSource`] rather than
+//! requiring the whole file resident as one `&[u8]`: a decoder fetches only
+//! the bytes its packet header says it needs, so callers can pass either a
+//! `&[u8]` (zero-cost, via the blanket impl) or a [`FileSource`](crate::io_utils::FileSource)
+//! wrapping a `Read + Seek` handle and get the same bounded-footprint
+//! behavior either way.
+
+use crate::io_utils::{BinaryReader, BinaryWriter, FromReader, PacketSource, ToWriter};
+use std::io::{Read, Seek};
 use crate::scan_data_centroid;
 use crate::scan_data_ftlt;
 use crate::scan_data_profile;
+use crate::scan_event::ScanEvent;
 use crate::scan_index::ScanIndexEntry;
-use crate::types::{MsLevel, Polarity, Scan};
+use crate::types::{MsLevel, Polarity, PrecursorInfo, Scan};
 use crate::RawError;
 
 /// Parsed ScanDataPacket header (40 bytes).
@@ -34,7 +43,20 @@ pub struct PacketHeader {
 }
 
 impl PacketHeader {
-    pub fn parse(reader: &mut BinaryReader) -> Result<Self, RawError> {
+    /// Convenience wrapper around [`FromReader::read`] for callers that don't
+    /// need to thread a version/context through -- `PacketHeader`'s layout is
+    /// fixed regardless of RAW file version, so `Ctx` is `()`.
+    pub fn parse<R: Read + Seek>(reader: &mut BinaryReader<R>) -> Result<Self, RawError> {
+        <Self as FromReader>::read(reader, &())
+    }
+
+    pub const SIZE: usize = 40;
+}
+
+impl FromReader for PacketHeader {
+    type Ctx = ();
+
+    fn read<R: Read + Seek>(reader: &mut BinaryReader<R>, _ctx: &()) -> Result<Self, RawError> {
         Ok(Self {
             unknown1: reader.read_u32()?,
             profile_size: reader.read_u32()?,
@@ -48,70 +70,173 @@ impl PacketHeader {
             high_mz: reader.read_f32()?,
         })
     }
+}
 
-    pub const SIZE: usize = 40;
+impl ToWriter for PacketHeader {
+    fn to_writer(&self, w: &mut BinaryWriter) -> Result<(), RawError> {
+        w.write_u32(self.unknown1);
+        w.write_u32(self.profile_size);
+        w.write_u32(self.peak_list_size);
+        w.write_u32(self.layout);
+        w.write_u32(self.descriptor_list_size);
+        w.write_u32(self.unknown_stream_size);
+        w.write_u32(self.triplet_stream_size);
+        w.write_u32(self.unknown2);
+        w.write_f32(self.low_mz);
+        w.write_f32(self.high_mz);
+        Ok(())
+    }
+}
+
+/// Low/high m/z spanning both the centroid and profile arrays, for the
+/// legacy packet header's `low_mz`/`high_mz` fields. Mirrors
+/// [`scan_data_ftlt`]'s own `combined_mass_range`. Returns `(0.0, 0.0)` if
+/// both are empty.
+fn combined_mass_range(scan: &Scan) -> (f32, f32) {
+    let all = scan
+        .centroid_mz
+        .iter()
+        .chain(scan.profile_mz.iter().flatten());
+    let mut low = f64::INFINITY;
+    let mut high = f64::NEG_INFINITY;
+    for &m in all {
+        low = low.min(m);
+        high = high.max(m);
+    }
+    if low.is_finite() && high.is_finite() {
+        (low as f32, high as f32)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+/// Encode a `Scan` back into a legacy ScanDataPacket (the 40-byte header
+/// used by packet types 0-5/14-17): profile data, if present, followed by
+/// the centroid peak list.
+///
+/// `profile_size`/`peak_list_size` (in 4-byte words) and `low_mz`/`high_mz`
+/// are recomputed from the data actually written rather than trusted from
+/// the input, the same way the rest of this crate treats on-disk counts as
+/// untrusted. Profile data is written via
+/// [`scan_data_profile::encode_profile`] in its single-chunk, no-fudge
+/// (`layout == 0`) form -- see that function's doc comment for why a round
+/// trip through `Scan` can't preserve the original chunk boundaries.
+pub fn encode_scan_legacy(scan: &Scan) -> Vec<u8> {
+    let mut body = BinaryWriter::new();
+
+    let has_profile = scan
+        .profile_mz
+        .as_ref()
+        .is_some_and(|mz| !mz.is_empty());
+    if has_profile {
+        scan_data_profile::encode_profile(
+            &mut body,
+            scan.profile_mz.as_ref().unwrap(),
+            scan.profile_intensity.as_ref().unwrap(),
+        );
+    }
+    let profile_size = (body.position() / 4) as u32;
+
+    scan_data_centroid::encode_centroid(&mut body, &scan.centroid_mz, &scan.centroid_intensity);
+    let peak_list_size = (body.position() / 4) as u32 - profile_size;
+
+    let (low_mz, high_mz) = combined_mass_range(scan);
+
+    let header = PacketHeader {
+        unknown1: 0,
+        profile_size,
+        peak_list_size,
+        layout: 0,
+        descriptor_list_size: 0,
+        unknown_stream_size: 0,
+        triplet_stream_size: 0,
+        unknown2: 0,
+        low_mz,
+        high_mz,
+    };
+
+    let mut w = BinaryWriter::new();
+    header
+        .to_writer(&mut w)
+        .expect("PacketHeader::to_writer never fails -- no I/O, just buffer writes");
+    let mut out = w.into_bytes();
+    out.extend_from_slice(&body.into_bytes());
+    out
+}
+
+/// Which decoder class a packet type belongs to. Collapses the
+/// `match packet_type_id { 18..=21 => ..., 0..=5 | 14..=17 => ..., _ => ... }`
+/// that used to be repeated separately in [`decode_scan`], [`decode_centroids_only`],
+/// [`sum_centroids_in_mz_range`], and [`sum_centroids_multi_target`] into one
+/// classification, so a new packet format is one new variant plus one new
+/// match arm per entry point instead of four id-range checks to keep in sync.
+///
+/// This stops short of a boxed-trait-object registry: every decoder entry
+/// point here is generic over [`PacketSource`], so a registry would either
+/// need to box `source` behind another layer of indirection on every call or
+/// monomorphize per `S` anyway -- `packet_kind` buys the same deduplication
+/// without either cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PacketKind {
+    FtLt,
+    Legacy,
+    Unknown,
+}
+
+fn packet_kind(packet_type_id: u16) -> PacketKind {
+    match packet_type_id {
+        18..=21 => PacketKind::FtLt,
+        0..=5 | 14..=17 => PacketKind::Legacy,
+        _ => PacketKind::Unknown,
+    }
 }
 
 /// Decode only centroid m/z + intensity from a scan, skipping profile data.
 ///
 /// Returns `(mz_array, intensity_array)`. Used by XIC extraction to avoid
 /// decoding expensive profile data and allocating full `Scan` structs.
-pub fn decode_centroids_only(
-    data: &[u8],
+pub fn decode_centroids_only<S: PacketSource + ?Sized>(
+    source: &S,
     data_addr: usize,
     entry: &ScanIndexEntry,
 ) -> Result<(Vec<f64>, Vec<f64>), RawError> {
     let abs_offset = data_addr as u64 + entry.offset;
 
-    if entry.data_size > 0 {
-        if abs_offset as usize + entry.data_size as usize > data.len() {
-            return Ok((vec![], vec![]));
-        }
-    } else if abs_offset as usize >= data.len() {
-        return Ok((vec![], vec![]));
-    }
-
     if entry.number_packets == 0 && entry.data_size == 0 {
         return Ok((vec![], vec![]));
     }
 
     let packet_type_id = (entry.packet_type & 0xFFFF) as u16;
 
-    match packet_type_id {
-        18..=21 => scan_data_ftlt::decode_ftlt_centroids_only(data, abs_offset),
-        0..=5 | 14..=17 => decode_legacy_centroids_only(data, abs_offset),
-        _ => Ok((vec![], vec![])),
+    match packet_kind(packet_type_id) {
+        PacketKind::FtLt => scan_data_ftlt::decode_ftlt_centroids_only(source, abs_offset),
+        PacketKind::Legacy => decode_legacy_centroids_only(source, abs_offset),
+        PacketKind::Unknown => Ok((vec![], vec![])),
     }
 }
 
 /// Extract only centroid data from a legacy packet, skipping profile.
-fn decode_legacy_centroids_only(
-    data: &[u8],
+fn decode_legacy_centroids_only<S: PacketSource + ?Sized>(
+    source: &S,
     abs_offset: u64,
 ) -> Result<(Vec<f64>, Vec<f64>), RawError> {
-    let mut reader = BinaryReader::at_offset(data, abs_offset);
-    let header = PacketHeader::parse(&mut reader)?;
+    let header_bytes = source.read_at(abs_offset, PacketHeader::SIZE)?;
+    let header = PacketHeader::parse(&mut BinaryReader::new(&header_bytes))?;
 
-    // Skip profile data
-    if header.profile_size > 0 {
-        reader.skip(header.profile_size as usize * 4)?;
+    if header.peak_list_size == 0 {
+        return Ok((vec![], vec![]));
     }
 
-    // Read centroid data
-    if header.peak_list_size > 0 {
-        let peak_start = reader.position();
-        scan_data_centroid::decode_centroid(data, peak_start as usize)
-    } else {
-        Ok((vec![], vec![]))
-    }
+    let peak_start = abs_offset + PacketHeader::SIZE as u64 + header.profile_size as u64 * 4;
+    scan_data_centroid::decode_centroid(source, peak_start)
 }
 
 /// Sum centroid intensities within [mz_low, mz_high] directly from raw scan bytes.
 ///
 /// Zero allocations: dispatches to the appropriate decoder which reads bytes in-place.
 /// Returns the total intensity sum for peaks in the m/z window.
-pub fn sum_centroids_in_mz_range(
-    data: &[u8],
+pub fn sum_centroids_in_mz_range<S: PacketSource + ?Sized>(
+    source: &S,
     data_addr: usize,
     entry: &ScanIndexEntry,
     mz_low: f64,
@@ -119,24 +244,18 @@ pub fn sum_centroids_in_mz_range(
 ) -> Result<f64, RawError> {
     let abs_offset = data_addr as u64 + entry.offset;
 
-    if entry.data_size > 0 {
-        if abs_offset as usize + entry.data_size as usize > data.len() {
-            return Ok(0.0);
-        }
-    } else if abs_offset as usize >= data.len() {
-        return Ok(0.0);
-    }
-
     if entry.number_packets == 0 && entry.data_size == 0 {
         return Ok(0.0);
     }
 
     let packet_type_id = (entry.packet_type & 0xFFFF) as u16;
 
-    match packet_type_id {
-        18..=21 => scan_data_ftlt::sum_centroids_in_range_ftlt(data, abs_offset, mz_low, mz_high),
-        0..=5 | 14..=17 => sum_legacy_centroids_in_range(data, abs_offset, mz_low, mz_high),
-        _ => Ok(0.0),
+    match packet_kind(packet_type_id) {
+        PacketKind::FtLt => {
+            scan_data_ftlt::sum_centroids_in_range_ftlt(source, abs_offset, mz_low, mz_high)
+        }
+        PacketKind::Legacy => sum_legacy_centroids_in_range(source, abs_offset, mz_low, mz_high),
+        PacketKind::Unknown => Ok(0.0),
     }
 }
 
@@ -144,8 +263,8 @@ pub fn sum_centroids_in_mz_range(
 ///
 /// `sorted_ranges` must be sorted by low bound. `out[i]` receives the total
 /// intensity for `sorted_ranges[i]`. Zero allocations.
-pub fn sum_centroids_multi_target(
-    data: &[u8],
+pub fn sum_centroids_multi_target<S: PacketSource + ?Sized>(
+    source: &S,
     data_addr: usize,
     entry: &ScanIndexEntry,
     sorted_ranges: &[(f64, f64)],
@@ -153,141 +272,149 @@ pub fn sum_centroids_multi_target(
 ) -> Result<(), RawError> {
     let abs_offset = data_addr as u64 + entry.offset;
 
-    let mut empty = || {
+    if entry.number_packets == 0 && entry.data_size == 0 {
         for v in out.iter_mut().take(sorted_ranges.len()) {
             *v = 0.0;
         }
-    };
-
-    if entry.data_size > 0 {
-        if abs_offset as usize + entry.data_size as usize > data.len() {
-            empty();
-            return Ok(());
-        }
-    } else if abs_offset as usize >= data.len() {
-        empty();
-        return Ok(());
-    }
-
-    if entry.number_packets == 0 && entry.data_size == 0 {
-        empty();
         return Ok(());
     }
 
     let packet_type_id = (entry.packet_type & 0xFFFF) as u16;
 
-    match packet_type_id {
-        18..=21 => scan_data_ftlt::sum_centroids_multi_target_ftlt(
-            data,
+    match packet_kind(packet_type_id) {
+        PacketKind::FtLt => scan_data_ftlt::sum_centroids_multi_target_ftlt(
+            source,
             abs_offset,
             sorted_ranges,
             out,
         ),
-        0..=5 | 14..=17 => {
-            sum_legacy_centroids_multi_target(data, abs_offset, sorted_ranges, out)
+        PacketKind::Legacy => {
+            sum_legacy_centroids_multi_target(source, abs_offset, sorted_ranges, out)
         }
-        _ => {
-            empty();
+        PacketKind::Unknown => {
+            for v in out.iter_mut().take(sorted_ranges.len()) {
+                *v = 0.0;
+            }
             Ok(())
         }
     }
 }
 
 /// Sum legacy centroid intensities in a single m/z range (skip profile, read centroids).
-fn sum_legacy_centroids_in_range(
-    data: &[u8],
+fn sum_legacy_centroids_in_range<S: PacketSource + ?Sized>(
+    source: &S,
     abs_offset: u64,
     mz_low: f64,
     mz_high: f64,
 ) -> Result<f64, RawError> {
-    let mut reader = BinaryReader::at_offset(data, abs_offset);
-    let header = PacketHeader::parse(&mut reader)?;
+    let header_bytes = source.read_at(abs_offset, PacketHeader::SIZE)?;
+    let header = PacketHeader::parse(&mut BinaryReader::new(&header_bytes))?;
 
-    if header.profile_size > 0 {
-        reader.skip(header.profile_size as usize * 4)?;
+    if header.peak_list_size == 0 {
+        return Ok(0.0);
     }
 
-    if header.peak_list_size > 0 {
-        let peak_start = reader.position();
-        scan_data_centroid::sum_centroids_in_range(data, peak_start as usize, mz_low, mz_high)
-    } else {
-        Ok(0.0)
-    }
+    let peak_start = abs_offset + PacketHeader::SIZE as u64 + header.profile_size as u64 * 4;
+    scan_data_centroid::sum_centroids_in_range(source, peak_start, mz_low, mz_high)
 }
 
 /// Sum legacy centroid intensities for multiple m/z ranges (skip profile, read centroids).
-fn sum_legacy_centroids_multi_target(
-    data: &[u8],
+fn sum_legacy_centroids_multi_target<S: PacketSource + ?Sized>(
+    source: &S,
     abs_offset: u64,
     sorted_ranges: &[(f64, f64)],
     out: &mut [f64],
 ) -> Result<(), RawError> {
-    let mut reader = BinaryReader::at_offset(data, abs_offset);
-    let header = PacketHeader::parse(&mut reader)?;
-
-    if header.profile_size > 0 {
-        reader.skip(header.profile_size as usize * 4)?;
-    }
+    let header_bytes = source.read_at(abs_offset, PacketHeader::SIZE)?;
+    let header = PacketHeader::parse(&mut BinaryReader::new(&header_bytes))?;
 
-    if header.peak_list_size > 0 {
-        let peak_start = reader.position();
-        scan_data_centroid::sum_centroids_multi_target(
-            data,
-            peak_start as usize,
-            sorted_ranges,
-            out,
-        )
-    } else {
+    if header.peak_list_size == 0 {
         for v in out.iter_mut().take(sorted_ranges.len()) {
             *v = 0.0;
         }
-        Ok(())
+        return Ok(());
+    }
+
+    let peak_start = abs_offset + PacketHeader::SIZE as u64 + header.profile_size as u64 * 4;
+    scan_data_centroid::sum_centroids_multi_target(source, peak_start, sorted_ranges, out)
+}
+
+/// Fill `ms_level`, `polarity`, `precursor`, and `filter_string` on `scan`
+/// from a ScanEvent's preamble and most recent Reaction, when one is
+/// available for this scan.
+///
+/// This is the packet-decode-time counterpart to `RawFile`'s trailer-based
+/// enrichment (`enrich_scan`/`enrich_from_scan_event`): it gives direct
+/// callers of [`decode_scan`] usable MS-level and precursor metadata from
+/// the ScanEvent alone, without needing a trailer layout. `RawFile::scan`
+/// still runs its own enrichment afterwards, which prefers trailer filter
+/// text/fields when present since those are more accurate than the
+/// ScanEvent template shared by every scan of the same type.
+fn apply_scan_event_metadata(scan: &mut Scan, scan_event: Option<&ScanEvent>) {
+    let event = match scan_event {
+        Some(e) => e,
+        None => return,
+    };
+
+    scan.ms_level = event.preamble.ms_level;
+    scan.polarity = event.preamble.polarity;
+    scan.filter_string = Some(event.filter_string());
+
+    if matches!(scan.ms_level, MsLevel::Ms1) {
+        return;
+    }
+
+    if let Some(reaction) = event.reactions.last() {
+        // v65+ gives an explicit isolation range; prefer its midpoint/width
+        // over the plain PrecursorMass/IsolationWidth pair. The v66
+        // IsolationWidthOffset shifts the window off-center from the
+        // nominal precursor m/z when no range is reported.
+        let (mz, isolation_width) = if reaction.precursor_range_valid {
+            (
+                (reaction.first_precursor_mass + reaction.last_precursor_mass) / 2.0,
+                Some(reaction.last_precursor_mass - reaction.first_precursor_mass),
+            )
+        } else {
+            (
+                reaction.precursor_mz + reaction.isolation_width_offset,
+                Some(reaction.isolation_width).filter(|&w| w > 0.0),
+            )
+        };
+
+        scan.precursor = Some(PrecursorInfo {
+            mz,
+            charge: None, // Not available from scan event
+            isolation_width,
+            activation_type: Some(reaction.activation_type().to_string()),
+            collision_energy: Some(reaction.collision_energy),
+            compensation_voltage: None,
+        });
     }
 }
 
 /// Decode a single scan from the data stream.
 ///
-/// `data` is the full file data. `data_addr` is the base address of the data
-/// stream. `entry` provides the scan's offset and data size.
-/// `conversion_params` are the Hz-to-m/z coefficients from the ScanEvent,
-/// needed for FT profile frequency conversion.
-pub fn decode_scan(
-    data: &[u8],
+/// `source` resolves packet bytes lazily by offset/length (see
+/// [`PacketSource`]) rather than requiring the whole file resident as one
+/// slice. `data_addr` is the base address of the data stream. `entry`
+/// provides the scan's offset and data size. `conversion_params` are the
+/// Hz-to-m/z coefficients from the ScanEvent, needed for FT profile
+/// frequency conversion. `scan_event`, when given, fills `ms_level`,
+/// `polarity`, `precursor`, and `filter_string` from its preamble and
+/// reactions -- see [`apply_scan_event_metadata`].
+pub fn decode_scan<S: PacketSource + ?Sized>(
+    source: &S,
     data_addr: usize,
     entry: &ScanIndexEntry,
     scan_number: u32,
     conversion_params: &[f64],
+    scan_event: Option<&ScanEvent>,
 ) -> Result<Scan, RawError> {
     let abs_offset = data_addr as u64 + entry.offset;
-    // Bounds check: for v65+ we have DataSize; for v<65 just verify offset is valid
-    if entry.data_size > 0 {
-        if abs_offset as usize + entry.data_size as usize > data.len() {
-            return Err(RawError::ScanDecodeError {
-                offset: abs_offset as usize,
-                reason: format!(
-                    "scan {} data extends beyond file (offset={}, size={}, file_len={})",
-                    scan_number,
-                    abs_offset,
-                    entry.data_size,
-                    data.len()
-                ),
-            });
-        }
-    } else if abs_offset as usize >= data.len() {
-        return Err(RawError::ScanDecodeError {
-            offset: abs_offset as usize,
-            reason: format!(
-                "scan {} data offset beyond file (offset={}, file_len={})",
-                scan_number,
-                abs_offset,
-                data.len()
-            ),
-        });
-    }
 
     // Empty scan: no packets and no data size means nothing to decode
     if entry.number_packets == 0 && entry.data_size == 0 {
-        return Ok(Scan {
+        let mut scan = Scan {
             scan_number,
             rt: entry.rt,
             ms_level: MsLevel::Ms1,
@@ -301,16 +428,20 @@ pub fn decode_scan(
             profile_intensity: None,
             precursor: None,
             filter_string: None,
-        });
+            ion_mobility: None,
+            compensation_voltage: None,
+        };
+        apply_scan_event_metadata(&mut scan, scan_event);
+        return Ok(scan);
     }
 
     // Dispatch on packet type: LOWORD selects the decoder class
     let packet_type_id = (entry.packet_type & 0xFFFF) as u16;
 
-    match packet_type_id {
+    let result = match packet_kind(packet_type_id) {
         // FT/LT packet types (modern instruments)
-        18..=21 => decode_scan_ftlt(
-            data,
+        PacketKind::FtLt => decode_scan_ftlt(
+            source,
             abs_offset,
             entry,
             scan_number,
@@ -318,9 +449,9 @@ pub fn decode_scan(
             conversion_params,
         ),
         // Legacy packet types
-        0..=5 | 14..=17 => decode_scan_legacy(data, abs_offset, entry, scan_number),
+        PacketKind::Legacy => decode_scan_legacy(source, abs_offset, entry, scan_number),
         // Unknown packet type: return empty scan
-        _ => Ok(Scan {
+        PacketKind::Unknown => Ok(Scan {
             scan_number,
             rt: entry.rt,
             ms_level: MsLevel::Ms1,
@@ -334,13 +465,32 @@ pub fn decode_scan(
             profile_intensity: None,
             precursor: None,
             filter_string: None,
+            ion_mobility: None,
+            compensation_voltage: None,
         }),
-    }
+    };
+
+    // `source.read_at` already range-checks offset/length against whatever
+    // backs it (a slice's bounds, or a stream's `read_exact`); wrap whatever
+    // it reports into the scan-level error so callers keep getting a
+    // `ScanDecodeError` with the scan number attached either way.
+    result
+        .map(|mut scan| {
+            apply_scan_event_metadata(&mut scan, scan_event);
+            scan
+        })
+        .map_err(|e| match e {
+            RawError::ScanDecodeError { .. } => e,
+            other => RawError::ScanDecodeError {
+                offset: abs_offset as usize,
+                reason: format!("scan {} packet read failed: {}", scan_number, other),
+            },
+        })
 }
 
 /// Decode a scan using the FT/LT packet format (packet types 18-21).
-fn decode_scan_ftlt(
-    data: &[u8],
+fn decode_scan_ftlt<S: PacketSource + ?Sized>(
+    source: &S,
     abs_offset: u64,
     entry: &ScanIndexEntry,
     scan_number: u32,
@@ -348,7 +498,7 @@ fn decode_scan_ftlt(
     conversion_params: &[f64],
 ) -> Result<Scan, RawError> {
     let result =
-        scan_data_ftlt::decode_ftlt_scan(data, abs_offset, packet_type_id, conversion_params)?;
+        scan_data_ftlt::decode_ftlt_scan(source, abs_offset, packet_type_id, conversion_params)?;
 
     Ok(Scan {
         scan_number,
@@ -364,25 +514,27 @@ fn decode_scan_ftlt(
         profile_intensity: result.profile_intensity,
         precursor: None,
         filter_string: None,
+        ion_mobility: None,
+        compensation_voltage: None,
     })
 }
 
 /// Decode a scan using the legacy 40-byte packet header (packet types 0-5, 14-17).
-fn decode_scan_legacy(
-    data: &[u8],
+fn decode_scan_legacy<S: PacketSource + ?Sized>(
+    source: &S,
     abs_offset: u64,
     entry: &ScanIndexEntry,
     scan_number: u32,
 ) -> Result<Scan, RawError> {
-    let mut reader = BinaryReader::at_offset(data, abs_offset);
-    let header = PacketHeader::parse(&mut reader)?;
+    let header_bytes = source.read_at(abs_offset, PacketHeader::SIZE)?;
+    let header = PacketHeader::parse(&mut BinaryReader::new(&header_bytes))?;
+    let mut pos = abs_offset + PacketHeader::SIZE as u64;
 
     // Read profile data
     let (profile_mz, profile_intensity) = if header.profile_size > 0 {
         let profile_bytes = header.profile_size as usize * 4;
-        let profile_start = reader.position();
-        let result = scan_data_profile::decode_profile(data, profile_start as usize, header.layout);
-        reader.set_position(profile_start + profile_bytes as u64);
+        let result = scan_data_profile::decode_profile(source, pos, header.layout);
+        pos += profile_bytes as u64;
         match result {
             Ok((mz, int)) => (Some(mz), Some(int)),
             Err(_) => (None, None),
@@ -393,11 +545,7 @@ fn decode_scan_legacy(
 
     // Read peak list (centroid data)
     let (centroid_mz, centroid_intensity) = if header.peak_list_size > 0 {
-        let peak_bytes = header.peak_list_size as usize * 4;
-        let peak_start = reader.position();
-        let result = scan_data_centroid::decode_centroid(data, peak_start as usize);
-        reader.set_position(peak_start + peak_bytes as u64);
-        match result {
+        match scan_data_centroid::decode_centroid(source, pos) {
             Ok((mz, int)) => (mz, int),
             Err(_) => (vec![], vec![]),
         }
@@ -419,5 +567,7 @@ fn decode_scan_legacy(
         profile_intensity,
         precursor: None,
         filter_string: None,
+        ion_mobility: None,
+        compensation_voltage: None,
     })
 }
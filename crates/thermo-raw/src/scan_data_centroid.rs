@@ -4,22 +4,24 @@
 //! - count (u32): number of peaks
 //! - For each peak: mz (f32), intensity (f32) -- interleaved pairs
 
-use crate::io_utils::BinaryReader;
+use crate::io_utils::{BinaryWriter, PacketSource};
 use crate::RawError;
 
-/// Decode centroid data from a scan data packet.
+/// Decode centroid data from a scan data packet at `offset` in `source`.
 ///
 /// Returns (mz_array, intensity_array).
-/// Uses batch slice read for peak data to minimize per-element overhead.
-pub fn decode_centroid(data: &[u8], offset: usize) -> Result<(Vec<f64>, Vec<f64>), RawError> {
-    let mut reader = BinaryReader::at_offset(data, offset as u64);
-
-    let count = reader.read_u32()?;
+/// Uses a single batch read for peak data to minimize per-element overhead.
+pub fn decode_centroid<S: PacketSource + ?Sized>(
+    source: &S,
+    offset: u64,
+) -> Result<(Vec<f64>, Vec<f64>), RawError> {
+    let count_bytes = source.read_at(offset, 4)?;
+    let count = u32::from_le_bytes(count_bytes[..4].try_into().unwrap());
 
     // Sanity check
     if count > 10_000_000 {
         return Err(RawError::ScanDecodeError {
-            offset,
+            offset: offset as usize,
             reason: format!("centroid data has unreasonable peak count: {}", count),
         });
     }
@@ -33,7 +35,7 @@ pub fn decode_centroid(data: &[u8], offset: usize) -> Result<(Vec<f64>, Vec<f64>
 
     // Batch read: get all peak data as a single slice (8 bytes per peak: f32 mz + f32 int)
     let total_bytes = count as usize * 8;
-    let raw_slice = reader.slice(total_bytes)?;
+    let raw_slice = source.read_at(offset + 4, total_bytes)?;
 
     for i in 0..count as usize {
         let base = i * 8;
@@ -58,20 +60,21 @@ pub fn decode_centroid(data: &[u8], offset: usize) -> Result<(Vec<f64>, Vec<f64>
 
 /// Sum centroid intensities within [mz_low, mz_high] from legacy packet centroid bytes.
 ///
-/// Zero allocations: reads raw bytes in-place, accumulates a running sum.
-/// Legacy centroids use f32 mz + f32 intensity (8 bytes/peak) and are sorted by m/z.
-pub fn sum_centroids_in_range(
-    data: &[u8],
-    offset: usize,
+/// Zero allocations beyond the one batch read: reads raw bytes in-place,
+/// accumulates a running sum. Legacy centroids use f32 mz + f32 intensity
+/// (8 bytes/peak) and are sorted by m/z.
+pub fn sum_centroids_in_range<S: PacketSource + ?Sized>(
+    source: &S,
+    offset: u64,
     mz_low: f64,
     mz_high: f64,
 ) -> Result<f64, RawError> {
-    let mut reader = BinaryReader::at_offset(data, offset as u64);
-    let count = reader.read_u32()?;
+    let count_bytes = source.read_at(offset, 4)?;
+    let count = u32::from_le_bytes(count_bytes[..4].try_into().unwrap());
 
     if count > 10_000_000 {
         return Err(RawError::ScanDecodeError {
-            offset,
+            offset: offset as usize,
             reason: format!("centroid data has unreasonable peak count: {}", count),
         });
     }
@@ -81,7 +84,7 @@ pub fn sum_centroids_in_range(
     }
 
     let total_bytes = count as usize * 8;
-    let raw_slice = reader.slice(total_bytes)?;
+    let raw_slice = source.read_at(offset + 4, total_bytes)?;
     let mut sum = 0.0f64;
 
     for i in 0..count as usize {
@@ -99,27 +102,67 @@ pub fn sum_centroids_in_range(
     Ok(sum)
 }
 
+/// Encode a centroid peak list back into the on-disk format -- the
+/// write-side counterpart to [`decode_centroid`]: a `u32` peak count
+/// followed by interleaved f32 m/z/intensity pairs.
+pub fn encode_centroid(w: &mut BinaryWriter, mz: &[f64], intensity: &[f64]) {
+    w.write_u32(mz.len() as u32);
+    for (&m, &i) in mz.iter().zip(intensity.iter()) {
+        w.write_f32(m as f32);
+        w.write_f32(i as f32);
+    }
+}
+
+/// m/z of the peak at index `i` in an 8-byte-stride (f32 mz + f32 intensity) peak array.
+fn peak_mz_at(raw_slice: &[u8], i: usize) -> f64 {
+    let base = i * 8;
+    f32::from_le_bytes(raw_slice[base..base + 4].try_into().unwrap()) as f64
+}
+
+/// Index of the first peak with m/z >= `target`, searching only `start..count`.
+///
+/// Treats NaN m/z (corrupt data) as sorting below `target` so the comparison
+/// never traps -- it just nudges the search forward instead of matching.
+fn lower_bound_mz(raw_slice: &[u8], count: usize, start: usize, target: f64) -> usize {
+    let mut lo = start;
+    let mut hi = count;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let mz = peak_mz_at(raw_slice, mid);
+        if mz.is_nan() || mz < target {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
 /// Sum centroid intensities for multiple m/z ranges in a single pass over legacy centroid data.
 ///
 /// `ranges` must be sorted by low bound. `out` must have length >= ranges.len().
-/// Zero allocations beyond the caller-provided output slice.
-pub fn sum_centroids_multi_target(
-    data: &[u8],
-    offset: usize,
+/// For each range, binary-searches the sorted peak array for the first peak at
+/// or above its low bound (resuming from the previous range's resolved index,
+/// since ranges are sorted too), then walks forward until the high bound is
+/// exceeded. O(k*log n + matched) for k ranges and n peaks, rather than one
+/// linear scan over all peaks per call.
+pub fn sum_centroids_multi_target<S: PacketSource + ?Sized>(
+    source: &S,
+    offset: u64,
     ranges: &[(f64, f64)],
     out: &mut [f64],
 ) -> Result<(), RawError> {
-    let mut reader = BinaryReader::at_offset(data, offset as u64);
-    let count = reader.read_u32()?;
-
     // Initialize output to zero
     for v in out.iter_mut().take(ranges.len()) {
         *v = 0.0;
     }
 
+    let count_bytes = source.read_at(offset, 4)?;
+    let count = u32::from_le_bytes(count_bytes[..4].try_into().unwrap()) as usize;
+
     if count > 10_000_000 {
         return Err(RawError::ScanDecodeError {
-            offset,
+            offset: offset as usize,
             reason: format!("centroid data has unreasonable peak count: {}", count),
         });
     }
@@ -128,34 +171,28 @@ pub fn sum_centroids_multi_target(
         return Ok(());
     }
 
-    let total_bytes = count as usize * 8;
-    let raw_slice = reader.slice(total_bytes)?;
-    let n_ranges = ranges.len();
-    let mut range_start = 0usize;
-
-    for i in 0..count as usize {
-        let base = i * 8;
-        let mz = f32::from_le_bytes(raw_slice[base..base + 4].try_into().unwrap()) as f64;
-        let intensity =
-            f32::from_le_bytes(raw_slice[base + 4..base + 8].try_into().unwrap()) as f64;
-
-        // Advance range_start past ranges whose high < mz
-        while range_start < n_ranges && ranges[range_start].1 < mz {
-            range_start += 1;
-        }
+    let total_bytes = count * 8;
+    let raw_slice = source.read_at(offset + 4, total_bytes)?;
+    let mut start = 0usize;
 
-        if range_start >= n_ranges {
-            break;
-        }
+    for (r, &(low, high)) in ranges.iter().enumerate() {
+        start = lower_bound_mz(&raw_slice, count, start, low);
 
-        for r in range_start..n_ranges {
-            let (low, high) = ranges[r];
-            if low > mz {
-                break;
+        let mut i = start;
+        while i < count {
+            let mz = peak_mz_at(&raw_slice, i);
+            if mz.is_nan() {
+                i += 1;
+                continue;
             }
-            if mz <= high {
-                out[r] += intensity;
+            if mz > high {
+                break;
             }
+            let base = i * 8;
+            let intensity =
+                f32::from_le_bytes(raw_slice[base + 4..base + 8].try_into().unwrap());
+            out[r] += intensity as f64;
+            i += 1;
         }
     }
 
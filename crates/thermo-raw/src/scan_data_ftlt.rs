@@ -8,8 +8,9 @@
 //! From decompiled v8.0.6: AdvancedPacketBase, FtProfilePacket,
 //! FtCentroidPacket, LinearTrapProfilePacket, LinearTrapCentroidPacket.
 
-use crate::io_utils::BinaryReader;
-use crate::scan_event::frequency_to_mz;
+use crate::io_utils::{BinaryReader, BinaryWriter, PacketSource, ToWriter};
+use std::io::{Read, Seek};
+use crate::scan_event::{frequency_to_mz, mz_to_frequency};
 use crate::RawError;
 
 /// FT/LT packet header (PacketHeaderStruct, 32 bytes).
@@ -28,7 +29,7 @@ pub struct FtLtPacketHeader {
 impl FtLtPacketHeader {
     pub const SIZE: usize = 32;
 
-    pub fn parse(reader: &mut BinaryReader) -> Result<Self, RawError> {
+    pub fn parse<R: Read + Seek>(reader: &mut BinaryReader<R>) -> Result<Self, RawError> {
         Ok(Self {
             num_segments: reader.read_u32()?,
             num_profile_words: reader.read_u32()?,
@@ -62,6 +63,20 @@ impl FtLtPacketHeader {
     }
 }
 
+impl ToWriter for FtLtPacketHeader {
+    fn to_writer(&self, w: &mut BinaryWriter) -> Result<(), RawError> {
+        w.write_u32(self.num_segments);
+        w.write_u32(self.num_profile_words);
+        w.write_u32(self.num_centroid_words);
+        w.write_u32(self.default_feature_word);
+        w.write_u32(self.num_non_default_feature_words);
+        w.write_u32(self.num_expansion_words);
+        w.write_u32(self.num_noise_info_words);
+        w.write_u32(self.num_debug_info_words);
+        Ok(())
+    }
+}
+
 /// Segment mass range (2 x f32 = 8 bytes per segment).
 #[derive(Debug, Clone)]
 pub struct SegmentMassRange {
@@ -69,6 +84,23 @@ pub struct SegmentMassRange {
     pub high: f32,
 }
 
+impl ToWriter for SegmentMassRange {
+    fn to_writer(&self, w: &mut BinaryWriter) -> Result<(), RawError> {
+        w.write_f32(self.low);
+        w.write_f32(self.high);
+        Ok(())
+    }
+}
+
+/// One noise-curve sample: an abscissa (m/z, after FT frequency conversion)
+/// paired with Thermo's noise and baseline levels at that point.
+#[derive(Debug, Clone)]
+pub struct NoiseNode {
+    pub mz: f64,
+    pub noise: f32,
+    pub baseline: f32,
+}
+
 /// Decoded result from an FT/LT scan.
 #[derive(Debug)]
 pub struct FtLtScanResult {
@@ -76,22 +108,34 @@ pub struct FtLtScanResult {
     pub centroid_intensity: Vec<f64>,
     pub profile_mz: Option<Vec<f64>>,
     pub profile_intensity: Option<Vec<f64>>,
+    pub noise: Option<Vec<NoiseNode>>,
 }
 
 /// Decode a complete FT/LT scan packet.
 ///
-/// `data`: full file data
+/// `source`: backing bytes, fetched lazily by offset/length rather than
+/// requiring the whole file resident as one slice (see [`PacketSource`])
 /// `abs_offset`: absolute byte offset of the packet start
 /// `packet_type_id`: LOWORD of ScanIndexEntry.PacketType (18-21)
 /// `conversion_params`: from ScanEvent, needed for FT frequency-to-m/z conversion
-pub fn decode_ftlt_scan(
-    data: &[u8],
+pub fn decode_ftlt_scan<S: PacketSource + ?Sized>(
+    source: &S,
     abs_offset: u64,
     packet_type_id: u16,
     conversion_params: &[f64],
 ) -> Result<FtLtScanResult, RawError> {
-    let mut reader = BinaryReader::at_offset(data, abs_offset);
-    let header = FtLtPacketHeader::parse(&mut reader)?;
+    let header_bytes = source.read_at(abs_offset, FtLtPacketHeader::SIZE)?;
+    let header = FtLtPacketHeader::parse(&mut BinaryReader::new(&header_bytes))?;
+    let is_ft = !header.is_lt_mode();
+
+    let body_len = header.num_segments as usize * 8
+        + header.num_profile_words as usize * 4
+        + header.num_centroid_words as usize * 4
+        + header.num_non_default_feature_words as usize * 4
+        + header.num_expansion_words as usize * 4
+        + header.num_noise_info_words as usize * 4;
+    let body = source.read_at(abs_offset + FtLtPacketHeader::SIZE as u64, body_len)?;
+    let mut reader = BinaryReader::new(&body);
 
     // Read segment mass ranges
     let mut _segment_ranges = Vec::with_capacity(header.num_segments as usize);
@@ -108,7 +152,6 @@ pub fn decode_ftlt_scan(
     // Decode profile data if this is a profile packet type (19 or 21)
     let (profile_mz, profile_intensity) =
         if (packet_type_id == 19 || packet_type_id == 21) && header.num_profile_words > 0 {
-            let is_ft = !header.is_lt_mode();
             match decode_ftlt_profile(&mut reader, &header, conversion_params, is_ft) {
                 Ok((mz, int)) => {
                     // Ensure reader is past the profile section
@@ -147,14 +190,39 @@ pub fn decode_ftlt_scan(
         (vec![], vec![])
     };
 
-    // Skip remaining sections (features, expansion, noise, debug)
-    // We don't need them for basic m/z + intensity extraction
+    // Skip the feature and expansion sections -- not needed for m/z +
+    // intensity + noise extraction.
+    let feature_bytes = header.num_non_default_feature_words as u64 * 4;
+    let expansion_bytes = header.num_expansion_words as u64 * 4;
+    reader.set_position(reader.position() + feature_bytes + expansion_bytes);
+
+    // Mark the start of the noise/baseline section.
+    let noise_start = reader.position();
+    let noise_bytes = header.num_noise_info_words as u64 * 4;
+
+    let noise = if header.num_noise_info_words > 0 {
+        match decode_ftlt_noise(&mut reader, &header, conversion_params, is_ft) {
+            Ok(nodes) => {
+                reader.set_position(noise_start + noise_bytes);
+                Some(nodes)
+            }
+            Err(_) => {
+                reader.set_position(noise_start + noise_bytes);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Skip remaining sections (debug) -- not needed for this decode.
 
     Ok(FtLtScanResult {
         centroid_mz,
         centroid_intensity,
         profile_mz,
         profile_intensity,
+        noise,
     })
 }
 
@@ -164,8 +232,8 @@ pub fn decode_ftlt_scan(
 /// Peak format depends on the accurate mass flag:
 /// - Standard: f32 mass + f32 intensity = 8 bytes
 /// - Accurate: f64 mass + f32 intensity = 12 bytes
-fn decode_ftlt_centroids(
-    reader: &mut BinaryReader,
+fn decode_ftlt_centroids<R: Read + Seek>(
+    reader: &mut BinaryReader<R>,
     header: &FtLtPacketHeader,
 ) -> Result<(Vec<f64>, Vec<f64>), RawError> {
     let accurate = header.is_accurate_mass();
@@ -216,8 +284,8 @@ fn decode_ftlt_centroids(
 ///
 /// For FT mode, base_abscissa is a frequency that must be converted using conversion_params.
 /// For LT mode, base_abscissa is m/z directly.
-fn decode_ftlt_profile(
-    reader: &mut BinaryReader,
+fn decode_ftlt_profile<R: Read + Seek>(
+    reader: &mut BinaryReader<R>,
     header: &FtLtPacketHeader,
     conversion_params: &[f64],
     is_ft: bool,
@@ -276,22 +344,106 @@ fn decode_ftlt_profile(
     Ok((all_mz, all_intensity))
 }
 
+/// Decode the noise/baseline section.
+///
+/// Layout: a flat array of triplets (abscissa, noise level, baseline
+/// level), each field a little-endian f32; `num_noise_info_words` counts
+/// 4-byte words across the whole section, so there are
+/// `num_noise_info_words / 3` triplets. For FT mode the abscissa is a
+/// frequency that must be converted through `conversion_params`, exactly
+/// like the profile decoder.
+fn decode_ftlt_noise<R: Read + Seek>(
+    reader: &mut BinaryReader<R>,
+    header: &FtLtPacketHeader,
+    conversion_params: &[f64],
+    is_ft: bool,
+) -> Result<Vec<NoiseNode>, RawError> {
+    let num_nodes = header.num_noise_info_words as usize / 3;
+    let mut nodes = Vec::with_capacity(num_nodes);
+
+    for _ in 0..num_nodes {
+        let abscissa = reader.read_f32()? as f64;
+        let noise = reader.read_f32()?;
+        let baseline = reader.read_f32()?;
+
+        let mz = if is_ft && !conversion_params.is_empty() {
+            frequency_to_mz(abscissa, conversion_params)
+        } else {
+            abscissa
+        };
+
+        nodes.push(NoiseNode { mz, noise, baseline });
+    }
+
+    Ok(nodes)
+}
+
+/// Noise level at `mz`, linearly interpolated between the two bracketing
+/// [`NoiseNode`]s (flat-extrapolated at the ends). `noise` must be sorted
+/// by `mz` ascending, matching how Thermo stores the curve.
+fn interpolate_noise(noise: &[NoiseNode], mz: f64) -> f32 {
+    let Some(first) = noise.first() else {
+        return 0.0;
+    };
+    let last = &noise[noise.len() - 1];
+    if mz <= first.mz {
+        return first.noise;
+    }
+    if mz >= last.mz {
+        return last.noise;
+    }
+    match noise.binary_search_by(|n| n.mz.partial_cmp(&mz).unwrap()) {
+        Ok(i) => noise[i].noise,
+        Err(i) => {
+            let lo = &noise[i - 1];
+            let hi = &noise[i];
+            let t = (mz - lo.mz) / (hi.mz - lo.mz);
+            lo.noise + (hi.noise - lo.noise) * t as f32
+        }
+    }
+}
+
+/// Signal-to-noise per centroid, computed by linearly interpolating the
+/// decoded noise curve at each peak's m/z -- the same noise-threshold
+/// filtering Thermo's own viewers apply.
+///
+/// Returns `0.0` for a peak whose interpolated noise level is zero (no
+/// usable noise estimate at that m/z) rather than dividing by zero.
+pub fn centroid_snr(centroid_mz: &[f64], centroid_intensity: &[f64], noise: &[NoiseNode]) -> Vec<f64> {
+    centroid_mz
+        .iter()
+        .zip(centroid_intensity)
+        .map(|(&mz, &intensity)| {
+            let noise_level = interpolate_noise(noise, mz);
+            if noise_level > 0.0 {
+                intensity / noise_level as f64
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
 /// Decode only centroid data from an FT/LT scan packet, skipping profile entirely.
 ///
 /// Returns `(mz_array, intensity_array)`. Used by XIC extraction where profile
 /// data is not needed, avoiding the expensive frequency-to-m/z conversion.
-pub fn decode_ftlt_centroids_only(
-    data: &[u8],
+pub fn decode_ftlt_centroids_only<S: PacketSource + ?Sized>(
+    source: &S,
     abs_offset: u64,
 ) -> Result<(Vec<f64>, Vec<f64>), RawError> {
-    let mut reader = BinaryReader::at_offset(data, abs_offset);
-    let header = FtLtPacketHeader::parse(&mut reader)?;
+    let header_bytes = source.read_at(abs_offset, FtLtPacketHeader::SIZE)?;
+    let header = FtLtPacketHeader::parse(&mut BinaryReader::new(&header_bytes))?;
+
+    let profile_bytes = header.num_profile_words as usize * 4;
+    let body_len = header.num_segments as usize * 8 + profile_bytes + header.num_centroid_words as usize * 4;
+    let body = source.read_at(abs_offset + FtLtPacketHeader::SIZE as u64, body_len)?;
+    let mut reader = BinaryReader::new(&body);
 
     // Skip segment mass ranges (8 bytes each)
     reader.skip(header.num_segments as usize * 8)?;
 
     // Skip profile data entirely
-    let profile_bytes = header.num_profile_words as usize * 4;
     if profile_bytes > 0 {
         reader.skip(profile_bytes)?;
     }
@@ -353,10 +505,414 @@ pub fn decode_ftlt_centroids_only(
     Ok((all_mz, all_intensity))
 }
 
+/// Sum centroid intensities within `[mz_low, mz_high]` from an FT/LT scan
+/// packet, skipping profile data entirely.
+///
+/// Mirrors [`decode_ftlt_centroids_only`] but accumulates a running sum
+/// instead of collecting arrays, for XIC extraction that only needs a
+/// scalar total per scan.
+pub fn sum_centroids_in_range_ftlt<S: PacketSource + ?Sized>(
+    source: &S,
+    abs_offset: u64,
+    mz_low: f64,
+    mz_high: f64,
+) -> Result<f64, RawError> {
+    let header_bytes = source.read_at(abs_offset, FtLtPacketHeader::SIZE)?;
+    let header = FtLtPacketHeader::parse(&mut BinaryReader::new(&header_bytes))?;
+
+    let profile_bytes = header.num_profile_words as usize * 4;
+    let body_len = header.num_segments as usize * 8 + profile_bytes + header.num_centroid_words as usize * 4;
+    let body = source.read_at(abs_offset + FtLtPacketHeader::SIZE as u64, body_len)?;
+    let mut reader = BinaryReader::new(&body);
+
+    reader.skip(header.num_segments as usize * 8)?;
+    if profile_bytes > 0 {
+        reader.skip(profile_bytes)?;
+    }
+
+    if header.num_centroid_words == 0 {
+        return Ok(0.0);
+    }
+
+    let accurate = header.is_accurate_mass();
+    let bytes_per_peak = header.bytes_per_centroid_peak();
+    let mut sum = 0.0f64;
+
+    for _ in 0..header.num_segments {
+        let count = reader.read_u32()?;
+        if count > 10_000_000 {
+            return Err(RawError::CorruptedData(format!(
+                "FT/LT centroid: unreasonable peak count {} in segment",
+                count
+            )));
+        }
+        if count == 0 {
+            continue;
+        }
+
+        let peak_bytes = count as usize * bytes_per_peak;
+        let raw = reader.slice(peak_bytes)?;
+        reader.skip(peak_bytes)?;
+
+        for i in 0..count as usize {
+            let base = i * bytes_per_peak;
+            let mz = if accurate {
+                f64::from_le_bytes(raw[base..base + 8].try_into().unwrap())
+            } else {
+                f32::from_le_bytes(raw[base..base + 4].try_into().unwrap()) as f64
+            };
+            if mz >= mz_low && mz <= mz_high {
+                let int_offset = if accurate { base + 8 } else { base + 4 };
+                let intensity = f32::from_le_bytes(raw[int_offset..int_offset + 4].try_into().unwrap());
+                sum += intensity as f64;
+            }
+        }
+    }
+
+    Ok(sum)
+}
+
+/// m/z of the peak at index `i` within a segment's raw peak bytes.
+fn segment_peak_mz(raw: &[u8], i: usize, bytes_per_peak: usize, accurate: bool) -> f64 {
+    let base = i * bytes_per_peak;
+    if accurate {
+        f64::from_le_bytes(raw[base..base + 8].try_into().unwrap())
+    } else {
+        f32::from_le_bytes(raw[base..base + 4].try_into().unwrap()) as f64
+    }
+}
+
+/// Index of the first peak in `raw` with m/z >= `target`, searching only
+/// `start..count`. NaN m/z (corrupt data) sorts below `target` so the
+/// comparison never traps -- it just nudges the search forward.
+fn segment_lower_bound(
+    raw: &[u8],
+    count: usize,
+    bytes_per_peak: usize,
+    accurate: bool,
+    start: usize,
+    target: f64,
+) -> usize {
+    let mut lo = start;
+    let mut hi = count;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let mz = segment_peak_mz(raw, mid, bytes_per_peak, accurate);
+        if mz.is_nan() || mz < target {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// Sum centroid intensities for multiple m/z ranges in a single pass over an
+/// FT/LT scan packet's centroid data, skipping profile data entirely.
+///
+/// `sorted_ranges` must be sorted by low bound. `out[i]` receives the total
+/// intensity for `sorted_ranges[i]`. Mirrors
+/// [`scan_data_centroid::sum_centroids_multi_target`](crate::scan_data_centroid::sum_centroids_multi_target)'s
+/// binary-search approach: within each segment's (already m/z-sorted) peak
+/// array, each range binary-searches for its low bound -- resuming from the
+/// previous range's resolved index -- then walks forward until the high
+/// bound is exceeded.
+pub fn sum_centroids_multi_target_ftlt<S: PacketSource + ?Sized>(
+    source: &S,
+    abs_offset: u64,
+    sorted_ranges: &[(f64, f64)],
+    out: &mut [f64],
+) -> Result<(), RawError> {
+    for v in out.iter_mut().take(sorted_ranges.len()) {
+        *v = 0.0;
+    }
+
+    let header_bytes = source.read_at(abs_offset, FtLtPacketHeader::SIZE)?;
+    let header = FtLtPacketHeader::parse(&mut BinaryReader::new(&header_bytes))?;
+
+    let profile_bytes = header.num_profile_words as usize * 4;
+    let body_len = header.num_segments as usize * 8 + profile_bytes + header.num_centroid_words as usize * 4;
+    let body = source.read_at(abs_offset + FtLtPacketHeader::SIZE as u64, body_len)?;
+    let mut reader = BinaryReader::new(&body);
+
+    reader.skip(header.num_segments as usize * 8)?;
+    if profile_bytes > 0 {
+        reader.skip(profile_bytes)?;
+    }
+
+    if header.num_centroid_words == 0 {
+        return Ok(());
+    }
+
+    let accurate = header.is_accurate_mass();
+    let bytes_per_peak = header.bytes_per_centroid_peak();
+    let n_ranges = sorted_ranges.len();
+
+    for _ in 0..header.num_segments {
+        let count = reader.read_u32()?;
+        if count > 10_000_000 {
+            return Err(RawError::CorruptedData(format!(
+                "FT/LT centroid: unreasonable peak count {} in segment",
+                count
+            )));
+        }
+        if count == 0 {
+            continue;
+        }
+        let count = count as usize;
+
+        let peak_bytes = count * bytes_per_peak;
+        let raw = reader.slice(peak_bytes)?;
+        reader.skip(peak_bytes)?;
+
+        // Each segment's peaks are m/z-sorted independently, so the binary
+        // search starts over per segment rather than carrying a cursor
+        // across segment boundaries.
+        let mut start = 0usize;
+        for r in 0..n_ranges {
+            let (low, high) = sorted_ranges[r];
+            start = segment_lower_bound(&raw, count, bytes_per_peak, accurate, start, low);
+
+            let mut i = start;
+            while i < count {
+                let mz = segment_peak_mz(&raw, i, bytes_per_peak, accurate);
+                if mz.is_nan() {
+                    i += 1;
+                    continue;
+                }
+                if mz > high {
+                    break;
+                }
+                let base = i * bytes_per_peak;
+                let int_offset = if accurate { base + 8 } else { base + 4 };
+                let intensity =
+                    f32::from_le_bytes(raw[int_offset..int_offset + 4].try_into().unwrap());
+                out[r] += intensity as f64;
+                i += 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Minimal byte-buffer writer for the FT/LT packet layout -- the write-side
+/// counterpart to reading through [`BinaryReader`]. A module-local type,
+/// separate from the crate-wide [`BinaryWriter`]; the segment encoders below
+/// also append to its `buf` directly.
+struct FtLtPacketWriter {
+    buf: Vec<u8>,
+}
+
+impl FtLtPacketWriter {
+    fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+
+    fn write_u32(&mut self, v: u32) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn write_f32(&mut self, v: f32) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn write_f64(&mut self, v: f64) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// Encode the profile section of a single segment: a ProfileSegmentStruct
+/// header followed by one ProfileSubsegmentStruct covering the whole array.
+///
+/// The decoder reconstructs arbitrary segment/subsegment boundaries from
+/// `start_index`/`word_count`, but flattens them into one contiguous Vec on
+/// the way out, so the original boundaries can't be recovered on a round
+/// trip. Writing a single subsegment (`start_index = 0`) is therefore the
+/// natural inverse of the flattened representation we have to write from.
+fn encode_profile_segment(
+    w: &mut FtLtPacketWriter,
+    mz: &[f64],
+    intensity: &[f64],
+    conversion_params: &[f64],
+    is_ft: bool,
+) {
+    let n = mz.len();
+    let abscissas: Vec<f64> = if is_ft {
+        mz.iter()
+            .map(|&m| mz_to_frequency(m, conversion_params))
+            .collect()
+    } else {
+        mz.to_vec()
+    };
+
+    let base_abscissa = abscissas.first().copied().unwrap_or(0.0);
+    let abscissa_spacing = if n > 1 {
+        (abscissas[n - 1] - abscissas[0]) / (n - 1) as f64
+    } else {
+        0.0
+    };
+
+    // ProfileSegmentStruct (32 bytes): base_abscissa, spacing, n_subsegments,
+    // n_expanded, padding.
+    w.write_f64(base_abscissa);
+    w.write_f64(abscissa_spacing);
+    w.write_u32(if n > 0 { 1 } else { 0 });
+    w.write_u32(n as u32);
+    w.buf.extend_from_slice(&[0u8; 8]);
+
+    if n == 0 {
+        return;
+    }
+
+    // ProfileSubsegmentStruct (8 bytes): start_index, word_count.
+    w.write_u32(0);
+    w.write_u32(n as u32);
+    for &v in intensity {
+        w.write_u32((v as f32).to_bits());
+    }
+}
+
+/// Encode the centroid section of a single segment: a u32 peak count
+/// followed by that many peaks at 8 or 12 bytes each depending on
+/// `accurate` (the accurate-mass flag from the packet's feature word).
+fn encode_centroid_segment(w: &mut FtLtPacketWriter, mz: &[f64], intensity: &[f64], accurate: bool) {
+    w.write_u32(mz.len() as u32);
+    for (&m, &i) in mz.iter().zip(intensity.iter()) {
+        if accurate {
+            w.write_f64(m);
+        } else {
+            w.write_f32(m as f32);
+        }
+        w.write_f32(i as f32);
+    }
+}
+
+/// Mass range spanning both the centroid and profile arrays, for the
+/// segment's SegmentMassRange entry. Returns `(0.0, 0.0)` if both are empty.
+fn combined_mass_range(result: &FtLtScanResult) -> (f32, f32) {
+    let all = result
+        .centroid_mz
+        .iter()
+        .chain(result.profile_mz.iter().flatten());
+    let mut low = f64::INFINITY;
+    let mut high = f64::NEG_INFINITY;
+    for &m in all {
+        low = low.min(m);
+        high = high.max(m);
+    }
+    if low.is_finite() && high.is_finite() {
+        (low as f32, high as f32)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+/// Serialize an `FtLtScanResult` back into the 32-byte `PacketHeaderStruct`
+/// binary layout -- the write-side counterpart to [`decode_ftlt_scan`].
+///
+/// `packet_type_id` selects whether profile data is emitted (19/21) or
+/// skipped (18/20), matching the decoder's own dispatch. `mode_flags` is
+/// the raw `default_feature_word` to stamp into the header, so the caller
+/// controls the LT/accurate-mass bits the decoder reads back.
+///
+/// Centroids and profile samples are each written as a single segment;
+/// `num_profile_words`/`num_centroid_words` and the segment mass range are
+/// recomputed from the data actually written rather than trusted from the
+/// input, the same way the rest of this crate treats on-disk counts as
+/// untrusted.
+pub fn encode_ftlt_scan(
+    result: &FtLtScanResult,
+    packet_type_id: u16,
+    conversion_params: &[f64],
+    mode_flags: u32,
+) -> Vec<u8> {
+    let header = FtLtPacketHeader {
+        num_segments: 0,
+        num_profile_words: 0,
+        num_centroid_words: 0,
+        default_feature_word: mode_flags,
+        num_non_default_feature_words: 0,
+        num_expansion_words: 0,
+        num_noise_info_words: 0,
+        num_debug_info_words: 0,
+    };
+    let is_ft = !header.is_lt_mode();
+    let accurate = header.is_accurate_mass();
+
+    let has_profile = (packet_type_id == 19 || packet_type_id == 21)
+        && result.profile_mz.as_ref().is_some_and(|v| !v.is_empty());
+
+    let mut profile_body = FtLtPacketWriter::new();
+    if has_profile {
+        encode_profile_segment(
+            &mut profile_body,
+            result.profile_mz.as_ref().unwrap(),
+            result.profile_intensity.as_ref().unwrap(),
+            conversion_params,
+            is_ft,
+        );
+    }
+    let profile_bytes = profile_body.into_bytes();
+    let num_profile_words = (profile_bytes.len() / 4) as u32;
+
+    let mut centroid_body = FtLtPacketWriter::new();
+    if !result.centroid_mz.is_empty() {
+        encode_centroid_segment(
+            &mut centroid_body,
+            &result.centroid_mz,
+            &result.centroid_intensity,
+            accurate,
+        );
+    }
+    let centroid_bytes = centroid_body.into_bytes();
+    let num_centroid_words = (centroid_bytes.len() / 4) as u32;
+
+    let num_segments = u32::from(has_profile || !result.centroid_mz.is_empty());
+
+    let mut w = FtLtPacketWriter::new();
+    w.write_u32(num_segments);
+    w.write_u32(num_profile_words);
+    w.write_u32(num_centroid_words);
+    w.write_u32(mode_flags);
+    w.write_u32(0); // num_non_default_feature_words
+    w.write_u32(0); // num_expansion_words
+    w.write_u32(0); // num_noise_info_words
+    w.write_u32(0); // num_debug_info_words
+
+    if num_segments == 1 {
+        let (low, high) = combined_mass_range(result);
+        w.write_f32(low);
+        w.write_f32(high);
+    }
+
+    w.buf.extend_from_slice(&profile_bytes);
+    w.buf.extend_from_slice(&centroid_bytes);
+
+    w.into_bytes()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn build_ftlt_header_bytes_with_noise(num_noise_info_words: u32) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&0u32.to_le_bytes()); // num_segments
+        buf.extend_from_slice(&0u32.to_le_bytes()); // num_profile_words
+        buf.extend_from_slice(&0u32.to_le_bytes()); // num_centroid_words
+        buf.extend_from_slice(&0u32.to_le_bytes()); // default_feature_word
+        buf.extend_from_slice(&0u32.to_le_bytes()); // non-default features
+        buf.extend_from_slice(&0u32.to_le_bytes()); // expansion
+        buf.extend_from_slice(&num_noise_info_words.to_le_bytes());
+        buf.extend_from_slice(&0u32.to_le_bytes()); // debug
+        buf
+    }
+
     fn build_ftlt_header_bytes(
         num_segments: u32,
         num_profile_words: u32,
@@ -375,6 +931,57 @@ mod tests {
         buf
     }
 
+    #[test]
+    fn test_ftlt_packet_header_round_trips_through_writer() {
+        let header = FtLtPacketHeader {
+            num_segments: 1,
+            num_profile_words: 100,
+            num_centroid_words: 20,
+            default_feature_word: 0,
+            num_non_default_feature_words: 3,
+            num_expansion_words: 0,
+            num_noise_info_words: 9,
+            num_debug_info_words: 0,
+        };
+
+        let mut w = BinaryWriter::new();
+        header.to_writer(&mut w).unwrap();
+        let bytes = w.into_bytes();
+        assert_eq!(bytes.len(), FtLtPacketHeader::SIZE);
+
+        let mut reader = BinaryReader::new(&bytes);
+        let decoded = FtLtPacketHeader::parse(&mut reader).unwrap();
+        assert_eq!(decoded.num_segments, header.num_segments);
+        assert_eq!(decoded.num_profile_words, header.num_profile_words);
+        assert_eq!(decoded.num_centroid_words, header.num_centroid_words);
+        assert_eq!(decoded.default_feature_word, header.default_feature_word);
+        assert_eq!(
+            decoded.num_non_default_feature_words,
+            header.num_non_default_feature_words
+        );
+        assert_eq!(decoded.num_expansion_words, header.num_expansion_words);
+        assert_eq!(decoded.num_noise_info_words, header.num_noise_info_words);
+        assert_eq!(decoded.num_debug_info_words, header.num_debug_info_words);
+    }
+
+    #[test]
+    fn test_segment_mass_range_round_trips_through_writer() {
+        let range = SegmentMassRange {
+            low: 200.0,
+            high: 2000.0,
+        };
+
+        let mut w = BinaryWriter::new();
+        range.to_writer(&mut w).unwrap();
+        let bytes = w.into_bytes();
+
+        let mut reader = BinaryReader::new(&bytes);
+        let low = reader.read_f32().unwrap();
+        let high = reader.read_f32().unwrap();
+        assert_eq!(low, range.low);
+        assert_eq!(high, range.high);
+    }
+
     #[test]
     fn test_ftlt_header_parse() {
         let data = build_ftlt_header_bytes(1, 100, 50, 0x10000);
@@ -459,4 +1066,149 @@ mod tests {
         assert!(result.centroid_mz.is_empty());
         assert!(result.profile_mz.is_none());
     }
+
+    #[test]
+    fn test_encode_decode_round_trip_centroids_only() {
+        let result = FtLtScanResult {
+            centroid_mz: vec![200.5, 500.25, 800.75],
+            centroid_intensity: vec![1000.0, 2000.0, 500.0],
+            profile_mz: None,
+            profile_intensity: None,
+            noise: None,
+        };
+        let encoded = encode_ftlt_scan(&result, 20, &[], 0);
+        let decoded = decode_ftlt_scan(&encoded, 0, 20, &[]).unwrap();
+        assert_eq!(decoded.centroid_mz, result.centroid_mz);
+        assert_eq!(decoded.centroid_intensity, result.centroid_intensity);
+        assert!(decoded.profile_mz.is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip_accurate_mass_centroids() {
+        let result = FtLtScanResult {
+            centroid_mz: vec![524.264837, 612.123456],
+            centroid_intensity: vec![50000.0, 30000.0],
+            profile_mz: None,
+            profile_intensity: None,
+            noise: None,
+        };
+        let encoded = encode_ftlt_scan(&result, 18, &[], 0x10000);
+        let decoded = decode_ftlt_scan(&encoded, 0, 18, &[]).unwrap();
+        assert_eq!(decoded.centroid_mz.len(), 2);
+        assert!((decoded.centroid_mz[0] - result.centroid_mz[0]).abs() < 1e-5);
+        assert!((decoded.centroid_mz[1] - result.centroid_mz[1]).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip_lt_profile() {
+        // LT mode: abscissa IS m/z directly, so the spacing model is exact.
+        let result = FtLtScanResult {
+            centroid_mz: vec![],
+            centroid_intensity: vec![],
+            profile_mz: Some(vec![100.0, 100.5, 101.0, 101.5]),
+            profile_intensity: Some(vec![10.0, 20.0, 30.0, 5.0]),
+            noise: None,
+        };
+        let encoded = encode_ftlt_scan(&result, 21, &[], 0x40);
+        let decoded = decode_ftlt_scan(&encoded, 0, 21, &[]).unwrap();
+        let mz = decoded.profile_mz.unwrap();
+        assert_eq!(mz.len(), 4);
+        for (a, b) in mz.iter().zip(result.profile_mz.as_ref().unwrap()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip_ft_profile_ltq_model() {
+        // Real FT profile data is evenly spaced in the *frequency* domain,
+        // not in m/z. Build m/z values from evenly-spaced frequencies so the
+        // encoder's single-subsegment spacing model matches how the data
+        // would actually be sampled, then confirm the round trip recovers
+        // the original m/z to float precision.
+        let params = vec![1e8, 0.0, 0.0, 0.0];
+        let freqs = [2.5e11, 2.4e11, 2.3e11, 2.2e11];
+        let profile_mz: Vec<f64> = freqs
+            .iter()
+            .map(|&f| crate::scan_event::frequency_to_mz(f, &params))
+            .collect();
+        let result = FtLtScanResult {
+            centroid_mz: vec![],
+            centroid_intensity: vec![],
+            profile_mz: Some(profile_mz.clone()),
+            profile_intensity: Some(vec![1.0, 2.0, 3.0, 4.0]),
+            noise: None,
+        };
+        let encoded = encode_ftlt_scan(&result, 19, &params, 0);
+        let decoded = decode_ftlt_scan(&encoded, 0, 19, &params).unwrap();
+        let mz = decoded.profile_mz.unwrap();
+        assert_eq!(mz.len(), profile_mz.len());
+        for (a, b) in mz.iter().zip(&profile_mz) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_encode_empty_scan_round_trips() {
+        let result = FtLtScanResult {
+            centroid_mz: vec![],
+            centroid_intensity: vec![],
+            profile_mz: None,
+            profile_intensity: None,
+            noise: None,
+        };
+        let encoded = encode_ftlt_scan(&result, 20, &[], 0);
+        let decoded = decode_ftlt_scan(&encoded, 0, 20, &[]).unwrap();
+        assert!(decoded.centroid_mz.is_empty());
+        assert!(decoded.profile_mz.is_none());
+    }
+
+    #[test]
+    fn test_decode_noise_section_no_conversion() {
+        // 2 noise nodes = 6 words (abscissa, noise, baseline) x 2.
+        let mut data = build_ftlt_header_bytes_with_noise(6);
+        data.extend_from_slice(&100.0f32.to_le_bytes());
+        data.extend_from_slice(&50.0f32.to_le_bytes());
+        data.extend_from_slice(&10.0f32.to_le_bytes());
+        data.extend_from_slice(&200.0f32.to_le_bytes());
+        data.extend_from_slice(&80.0f32.to_le_bytes());
+        data.extend_from_slice(&20.0f32.to_le_bytes());
+
+        let result = decode_ftlt_scan(&data, 0, 20, &[]).unwrap();
+        let noise = result.noise.unwrap();
+        assert_eq!(noise.len(), 2);
+        assert_eq!(noise[0].mz, 100.0);
+        assert_eq!(noise[0].noise, 50.0);
+        assert_eq!(noise[0].baseline, 10.0);
+        assert_eq!(noise[1].mz, 200.0);
+    }
+
+    #[test]
+    fn test_no_noise_section_is_none() {
+        let data = build_ftlt_header_bytes_with_noise(0);
+        let result = decode_ftlt_scan(&data, 0, 20, &[]).unwrap();
+        assert!(result.noise.is_none());
+    }
+
+    #[test]
+    fn test_centroid_snr_interpolates_between_nodes() {
+        let noise = vec![
+            NoiseNode {
+                mz: 100.0,
+                noise: 10.0,
+                baseline: 0.0,
+            },
+            NoiseNode {
+                mz: 200.0,
+                noise: 30.0,
+                baseline: 0.0,
+            },
+        ];
+        let centroid_mz = vec![100.0, 150.0, 200.0, 300.0];
+        let centroid_intensity = vec![100.0, 100.0, 100.0, 100.0];
+        let snr = centroid_snr(&centroid_mz, &centroid_intensity, &noise);
+        assert!((snr[0] - 10.0).abs() < 1e-9); // at first node: noise=10
+        assert!((snr[1] - 5.0).abs() < 1e-9); // midpoint: noise=20, snr=100/20=5
+        assert!((snr[2] - 100.0 / 30.0).abs() < 1e-9); // at last node: noise=30
+        assert!((snr[3] - 100.0 / 30.0).abs() < 1e-9); // past last node: flat-extrapolated
+    }
 }
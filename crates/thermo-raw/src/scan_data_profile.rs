@@ -10,25 +10,32 @@
 //!
 //! m/z for bin i = first_value + (chunk.first_bin + i) * step
 
-use crate::io_utils::BinaryReader;
+use crate::io_utils::{BinaryReader, BinaryWriter, PacketSource};
 use crate::RawError;
 
-/// Decode profile data from a scan data packet.
+/// Size of the profile header (first_value: f64, step: f64, peak_count: u32,
+/// nbins_total: u32).
+const PROFILE_HEADER_SIZE: usize = 24;
+
+/// Decode profile data from a scan data packet at `offset` in `source`.
 ///
 /// Returns (mz_array, intensity_array) with one entry per bin across all chunks.
-/// Uses batch slice reads for signal data to minimize per-element overhead.
-pub fn decode_profile(
-    data: &[u8],
-    offset: usize,
+/// Reads each chunk's signal data via one batch read to minimize per-element
+/// overhead.
+pub fn decode_profile<S: PacketSource + ?Sized>(
+    source: &S,
+    offset: u64,
     layout: u32,
 ) -> Result<(Vec<f64>, Vec<f64>), RawError> {
-    let mut reader = BinaryReader::at_offset(data, offset as u64);
+    let mut pos = offset;
 
-    // Profile header
-    let first_value = reader.read_f64()?;
-    let step = reader.read_f64()?;
-    let peak_count = reader.read_u32()?;
-    let nbins_total = reader.read_u32()?;
+    let header_bytes = source.read_at(pos, PROFILE_HEADER_SIZE)?;
+    let mut header_reader = BinaryReader::new(&header_bytes);
+    let first_value = header_reader.read_f64()?;
+    let step = header_reader.read_f64()?;
+    let peak_count = header_reader.read_u32()?;
+    let nbins_total = header_reader.read_u32()?;
+    pos += PROFILE_HEADER_SIZE as u64;
 
     if peak_count == 0 || nbins_total == 0 {
         return Ok((vec![], vec![]));
@@ -37,7 +44,7 @@ pub fn decode_profile(
     // Sanity check
     if peak_count > 1_000_000 || nbins_total > 100_000_000 {
         return Err(RawError::ScanDecodeError {
-            offset,
+            offset: offset as usize,
             reason: format!(
                 "profile data has unreasonable dimensions: peak_count={}, nbins={}",
                 peak_count, nbins_total
@@ -48,20 +55,21 @@ pub fn decode_profile(
     let mut mz_values = Vec::with_capacity(nbins_total as usize);
     let mut intensities = Vec::with_capacity(nbins_total as usize);
 
-    for _ in 0..peak_count {
-        let first_bin = reader.read_u32()?;
-        let chunk_nbins = reader.read_u32()?;
+    // Layout > 0 has a fudge factor (instrument drift correction) after the
+    // chunk's (first_bin, nbins) pair.
+    let chunk_header_len = if layout > 0 { 12 } else { 8 };
 
-        // Layout > 0 has a fudge factor (instrument drift correction)
-        let _fudge = if layout > 0 {
-            Some(reader.read_f32()?)
-        } else {
-            None
-        };
+    for _ in 0..peak_count {
+        let chunk_header = source.read_at(pos, chunk_header_len)?;
+        let mut chunk_reader = BinaryReader::new(&chunk_header);
+        let first_bin = chunk_reader.read_u32()?;
+        let chunk_nbins = chunk_reader.read_u32()?;
+        pos += chunk_header_len as u64;
 
         // Batch read: get raw bytes for all signal values at once
         let signal_bytes = chunk_nbins as usize * 4;
-        let raw_slice = reader.slice(signal_bytes)?;
+        let raw_slice = source.read_at(pos, signal_bytes)?;
+        pos += signal_bytes as u64;
 
         for i in 0..chunk_nbins as usize {
             let bytes = [
@@ -76,10 +84,41 @@ pub fn decode_profile(
             mz_values.push(mz);
             intensities.push(signal as f64);
         }
-
-        // Advance reader past the signal data
-        reader.skip(signal_bytes)?;
     }
 
     Ok((mz_values, intensities))
 }
+
+/// Encode profile data back into the on-disk format: a profile header
+/// (`first_value`, `step`, `peak_count`, `nbins_total`) followed by one
+/// chunk (`first_bin = 0`, `nbins = mz.len()`) covering the whole array,
+/// written with `layout == 0` (no fudge factor).
+///
+/// [`decode_profile`] flattens arbitrary chunk boundaries into one
+/// contiguous array and assumes uniform m/z spacing within each chunk
+/// (`mz[i] = first_value + i * step`); writing a single whole-array chunk
+/// is the natural inverse, with `step` recovered from the first two points.
+pub fn encode_profile(w: &mut BinaryWriter, mz: &[f64], intensity: &[f64]) {
+    let n = mz.len();
+    if n == 0 {
+        w.write_f64(0.0);
+        w.write_f64(0.0);
+        w.write_u32(0);
+        w.write_u32(0);
+        return;
+    }
+
+    let first_value = mz[0];
+    let step = if n > 1 { mz[1] - mz[0] } else { 0.0 };
+
+    w.write_f64(first_value);
+    w.write_f64(step);
+    w.write_u32(1); // peak_count: one chunk
+    w.write_u32(n as u32); // nbins_total
+
+    w.write_u32(0); // first_bin
+    w.write_u32(n as u32); // chunk nbins
+    for &v in intensity {
+        w.write_f32(v as f32);
+    }
+}
@@ -19,7 +19,8 @@
 //!   - v31-64: 32 bytes (MsReactionStruct2: PrecursorMass, IsolationWidth, CollisionEnergy, CollisionEnergyValid)
 //!   - v<31:   24 bytes (MsReactionStruct1: PrecursorMass, IsolationWidth, CollisionEnergy)
 
-use crate::io_utils::BinaryReader;
+use crate::io_utils::{bounded_vec_with_capacity, BinaryReader};
+use std::io::{Read, Seek};
 use crate::types::{MsLevel, Polarity};
 use crate::version;
 use crate::RawError;
@@ -127,6 +128,145 @@ pub struct ScanEvent {
     pub conversion_params: Vec<f64>,
 }
 
+impl ScanEvent {
+    /// Each reaction's activation type, in stream order.
+    ///
+    /// `preamble.activation` only ever holds one value, so combined
+    /// dissociation schemes (ETD with HCD supplemental activation, EThcD,
+    /// MSn with a different activation per stage) collapse to a single
+    /// type there. This exposes the full ordered list instead.
+    pub fn activations(&self) -> Vec<ActivationType> {
+        self.reactions.iter().map(Reaction::activation_type).collect()
+    }
+
+    /// The ordered activation list rendered as e.g. `"ETD+HCD"`, using
+    /// `ActivationType`'s `Display` impl for each stage.
+    pub fn combined_activation(&self) -> String {
+        self.activations()
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+
+    /// Render the canonical Thermo scan filter string this event describes,
+    /// e.g. `"FTMS + p NSI d Full ms2 445.12@hcd27.00 [100.0-1500.0]"`.
+    ///
+    /// Reconstructed purely from the fields this module parses: the
+    /// [`ScanEventPreamble`] half comes from [`ScanEventPreamble::filter_prefix`],
+    /// and the reaction/mass-range suffix is built here, grouping
+    /// consecutive reactions that share a precursor m/z into one
+    /// `mz@act1ce1@act2ce2...` token (supplemental activation) and emitting
+    /// separate whitespace-separated tokens for distinct precursor m/z
+    /// values (MSn isolation chains).
+    pub fn filter_string(&self) -> String {
+        let mut s = self.preamble.filter_prefix();
+
+        for token in reaction_tokens(&self.reactions) {
+            s.push(' ');
+            s.push_str(&token);
+        }
+
+        if let Some(last) = self.reactions.last() {
+            if last.precursor_range_valid {
+                s.push_str(&format!(
+                    " [{:.1}-{:.1}]",
+                    last.first_precursor_mass, last.last_precursor_mass
+                ));
+            }
+        }
+
+        s
+    }
+}
+
+/// Group consecutive reactions sharing a precursor m/z into one
+/// `mz@act1ce1@act2ce2...` token each, per [`ScanEvent::filter_string`].
+fn reaction_tokens(reactions: &[Reaction]) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < reactions.len() {
+        let mz = reactions[i].precursor_mz;
+        let mut j = i;
+        let mut token = format!("{:.2}", mz);
+        while j < reactions.len() && reactions[j].precursor_mz == mz {
+            token.push('@');
+            token.push_str(&reactions[j].activation_type().to_string().to_lowercase());
+            token.push_str(&format!("{:.2}", reactions[j].collision_energy));
+            j += 1;
+        }
+        tokens.push(token);
+        i = j;
+    }
+    tokens
+}
+
+/// Token for a known ionization source in a filter string, or `None` for
+/// ionization types that aren't rendered as a filter-string token (matches
+/// `scan_filter::KNOWN_SOURCES`).
+fn ionization_token(ion: &IonizationType) -> Option<&'static str> {
+    match ion {
+        IonizationType::Nsi => Some("NSI"),
+        IonizationType::Esi => Some("ESI"),
+        IonizationType::Apci => Some("APCI"),
+        IonizationType::Maldi => Some("MALDI"),
+        IonizationType::Ei => Some("EI"),
+        IonizationType::Ci => Some("CI"),
+        IonizationType::Fab => Some("FAB"),
+        _ => None,
+    }
+}
+
+impl ScanEventPreamble {
+    /// Render the analyzer/polarity/scan-mode/source/dependent/scan-type/
+    /// ms-level portion of a Thermo filter string -- everything except the
+    /// reaction and mass-range suffix, which need reaction data this
+    /// preamble alone doesn't have. See [`ScanEvent::filter_string`].
+    pub fn filter_prefix(&self) -> String {
+        let mut parts: Vec<String> = vec![self.analyzer.to_string()];
+
+        match self.polarity {
+            Polarity::Positive => parts.push("+".to_string()),
+            Polarity::Negative => parts.push("-".to_string()),
+            Polarity::Unknown => {}
+        }
+
+        match self.scan_mode {
+            ScanMode::Centroid => parts.push("c".to_string()),
+            ScanMode::Profile => parts.push("p".to_string()),
+            ScanMode::Unknown => {}
+        }
+
+        if let Some(source) = ionization_token(&self.ionization) {
+            parts.push(source.to_string());
+        }
+
+        if self.dependent {
+            parts.push("d".to_string());
+        }
+
+        let scan_type = match self.scan_type {
+            ScanType::Full => "Full",
+            ScanType::Zoom => "Zoom",
+            ScanType::Sim => "SIM",
+            ScanType::Srm => "SRM",
+            ScanType::Crm => "CRM",
+            ScanType::Q1Ms => "Q1MS",
+            ScanType::Q3Ms => "Q3MS",
+            ScanType::Unknown(_) => "Full",
+        };
+        let level_suffix = match self.ms_level {
+            MsLevel::Ms1 => String::new(),
+            MsLevel::Ms2 => "2".to_string(),
+            MsLevel::Ms3 => "3".to_string(),
+            MsLevel::Other(n) => n.to_string(),
+        };
+        parts.push(format!("{} ms{}", scan_type, level_suffix));
+
+        parts.join(" ")
+    }
+}
+
 /// Reaction (precursor fragmentation info).
 ///
 /// From decompiled MsReactionStruct layout:
@@ -301,7 +441,7 @@ fn parse_preamble(data: &[u8]) -> ScanEventPreamble {
 
 /// Read a "doubles array": u32 count followed by count f64 values.
 /// Matches the decompiled ReadDoublesExt pattern.
-fn read_doubles_array(reader: &mut BinaryReader) -> Result<Vec<f64>, RawError> {
+fn read_doubles_array<R: Read + Seek>(reader: &mut BinaryReader<R>) -> Result<Vec<f64>, RawError> {
     let count = reader.read_u32()?;
     if count > 10_000 {
         return Err(RawError::CorruptedData(format!(
@@ -314,7 +454,7 @@ fn read_doubles_array(reader: &mut BinaryReader) -> Result<Vec<f64>, RawError> {
 
 /// Read a "mass range array": u32 count followed by count * (f64, f64) pairs.
 /// Matches the decompiled MassRangeStruct.LoadArray pattern.
-fn read_mass_range_array(reader: &mut BinaryReader) -> Result<Vec<(f64, f64)>, RawError> {
+fn read_mass_range_array<R: Read + Seek>(reader: &mut BinaryReader<R>) -> Result<Vec<(f64, f64)>, RawError> {
     let count = reader.read_u32()?;
     if count > 10_000 {
         return Err(RawError::CorruptedData(format!(
@@ -338,7 +478,7 @@ fn read_mass_range_array(reader: &mut BinaryReader) -> Result<Vec<(f64, f64)>, R
 /// - v65:    48 bytes (MsReactionStruct3)
 /// - v31-64: 32 bytes (MsReactionStruct2)
 /// - v<31:   24 bytes (MsReactionStruct1)
-fn parse_reaction(reader: &mut BinaryReader, ver: u32) -> Result<Reaction, RawError> {
+fn parse_reaction<R: Read + Seek>(reader: &mut BinaryReader<R>, ver: u32) -> Result<Reaction, RawError> {
     let rxn_size = version::reaction_size(ver);
     let start = reader.position();
 
@@ -422,9 +562,12 @@ pub fn parse_scan_event(
         reactions.push(parse_reaction(&mut reader, ver)?);
     }
 
-    // Derive activation type from the last reaction's CollisionEnergyValid
-    if let Some(last_rxn) = reactions.last() {
-        preamble.activation = last_rxn.activation_type();
+    // Derive activation type from the first reaction's CollisionEnergyValid.
+    // Combined dissociation schemes (EThcD, ETD-with-HCD-supplemental, ...)
+    // apply more than one activation across `reactions`; the full ordered
+    // list is available via `ScanEvent::activations`/`combined_activation`.
+    if let Some(first_rxn) = reactions.first() {
+        preamble.activation = first_rxn.activation_type();
     }
 
     // 3. Read mass ranges: u32 count + count * (f64 low, f64 high)
@@ -479,7 +622,20 @@ pub fn parse_scan_events(
         )));
     }
 
-    let mut events = Vec::with_capacity(n_events as usize);
+    // Lower bound on one event's encoded size: the fixed-size preamble plus
+    // the five u32 array-length prefixes an event reads even when every
+    // array is empty (reactions, mass ranges, calibrators, source
+    // fragmentations, source fragmentation mass ranges). Actual events are
+    // almost always larger; this only exists to reject a declared count
+    // that couldn't possibly fit in what's left of the file.
+    let min_event_size = version::scan_event_preamble_size(ver) + 5 * 4;
+    let mut events = bounded_vec_with_capacity(
+        n_events,
+        min_event_size,
+        reader.position(),
+        data.len() as u64,
+        "scan events",
+    )?;
     let mut next_offset = reader.position();
 
     for _ in 0..n_events {
@@ -491,6 +647,84 @@ pub fn parse_scan_events(
     Ok(events)
 }
 
+/// Lazy, borrowing alternative to [`parse_scan_events`] for callers that
+/// only need to inspect a handful of events (e.g. find the first FTMS MS2
+/// template, or count events by MS level) without materializing the whole
+/// table. Yields one parsed [`ScanEvent`] per `next()` call, advancing
+/// through `data` exactly as [`parse_scan_events`]'s loop does.
+pub struct ScanEventIter<'a> {
+    data: &'a [u8],
+    ver: u32,
+    next_offset: u64,
+    remaining: u32,
+    /// Set once a parse fails, so the iterator reliably ends instead of
+    /// re-attempting a parse at a possibly-corrupt offset.
+    errored: bool,
+}
+
+impl<'a> Iterator for ScanEventIter<'a> {
+    type Item = Result<ScanEvent, RawError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.errored || self.remaining == 0 {
+            return None;
+        }
+        match parse_scan_event(self.data, self.next_offset, self.ver) {
+            Ok((event, end_pos)) => {
+                self.next_offset = end_pos;
+                self.remaining -= 1;
+                Some(Ok(event))
+            }
+            Err(e) => {
+                self.errored = true;
+                Some(Err(e))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Construct a [`ScanEventIter`] over the scan event stream at
+/// `scan_params_addr`, reading just the header count up front. Mirrors
+/// [`parse_scan_events`]'s bounds checking and count validation.
+pub fn parse_scan_events_iter(
+    data: &[u8],
+    scan_params_addr: u64,
+    ver: u32,
+) -> Result<ScanEventIter<'_>, RawError> {
+    if scan_params_addr == 0 || scan_params_addr as usize >= data.len() {
+        return Ok(ScanEventIter {
+            data,
+            ver,
+            next_offset: 0,
+            remaining: 0,
+            errored: false,
+        });
+    }
+
+    let mut reader = BinaryReader::at_offset(data, scan_params_addr);
+    let n_events = reader.read_u32()?;
+
+    if n_events > 10_000 {
+        return Err(RawError::CorruptedData(format!(
+            "Unreasonable scan event count: {}",
+            n_events
+        )));
+    }
+
+    Ok(ScanEventIter {
+        data,
+        ver,
+        next_offset: reader.position(),
+        remaining: n_events,
+        errored: false,
+    })
+}
+
 /// Apply conversion parameters to convert frequency to m/z.
 ///
 /// For instruments using frequency-domain detection (FTMS/Orbitrap),
@@ -534,6 +768,154 @@ pub fn frequency_to_mz(frequency: f64, params: &[f64]) -> f64 {
     }
 }
 
+/// Inverse of [`frequency_to_mz`]: recover the frequency abscissa that a
+/// profile packet would have stored on disk for a given m/z, for encoding
+/// profile data back into the FT/LT binary layout.
+///
+/// The 0- and 4-parameter models invert in closed form. The 7-parameter
+/// Orbitrap polynomial has no closed-form inverse, so it's solved
+/// numerically with a few Newton's-method steps, seeded from the
+/// dominant `A / f^2` term of the polynomial.
+pub fn mz_to_frequency(mz: f64, params: &[f64]) -> f64 {
+    match params.len() {
+        0 => mz, // No conversion: m/z IS frequency
+        4 => {
+            // Inverse of m/z = A / (freq/1e6 + B): freq = (A/mz - B) * 1e6
+            let a = params[0];
+            let b = params[1];
+            if mz != 0.0 {
+                (a / mz - b) * 1e6
+            } else {
+                mz
+            }
+        }
+        7 => {
+            if mz == 0.0 {
+                return 0.0;
+            }
+            let mut f = if params[0] > 0.0 {
+                (params[0] / mz).sqrt()
+            } else {
+                mz
+            };
+            for _ in 0..20 {
+                let step = f * 1e-6;
+                if step == 0.0 {
+                    break;
+                }
+                let computed = frequency_to_mz(f, params);
+                let derivative = (frequency_to_mz(f + step, params) - computed) / step;
+                if derivative.abs() < 1e-12 {
+                    break;
+                }
+                f -= (computed - mz) / derivative;
+            }
+            f
+        }
+        _ => mz,
+    }
+}
+
+/// A frequency↔m/z calibration, constructed from a `ScanEvent`'s
+/// `conversion_params`.
+///
+/// [`frequency_to_mz`]/[`mz_to_frequency`] hardcode the 4-param LTQ-FT and
+/// 7-param Orbitrap forms behind a `match params.len()`; this wraps the same
+/// two forms as named variants plus a user-supplied [`Custom`](CalibrationModel::Custom)
+/// closure for instruments or calibrations those two forms don't cover, so
+/// callers can round-trip profile bins to frequency space (resampling,
+/// centroiding) without hand-rolling the parameter-length dispatch
+/// themselves.
+pub enum CalibrationModel {
+    /// No calibration: frequency IS m/z (`conversion_params` empty).
+    None,
+    /// LTQ-FT Möbius model: `m/z = a / (freq/1e6 + b)`.
+    LtqFt { a: f64, b: f64 },
+    /// Orbitrap polynomial model, in the same coefficient order as
+    /// [`frequency_to_mz`]'s 7-parameter case.
+    OrbitrapPoly(Vec<f64>),
+    /// A user-supplied forward (frequency -> m/z) conversion; the inverse is
+    /// found numerically since an arbitrary closure has no closed form.
+    Custom(Box<dyn Fn(f64) -> f64>),
+}
+
+impl CalibrationModel {
+    /// Build the appropriate model from a `ScanEvent`'s `conversion_params`,
+    /// matching [`frequency_to_mz`]/[`mz_to_frequency`]'s own dispatch.
+    pub fn from_conversion_params(params: &[f64]) -> Self {
+        match params.len() {
+            0 => CalibrationModel::None,
+            4 => CalibrationModel::LtqFt { a: params[0], b: params[1] },
+            7 => CalibrationModel::OrbitrapPoly(params.to_vec()),
+            _ => CalibrationModel::None,
+        }
+    }
+
+    /// Convert a stored frequency (or m/z, for [`None`](CalibrationModel::None)) abscissa to m/z.
+    pub fn to_mz(&self, freq: f64) -> f64 {
+        match self {
+            CalibrationModel::None => freq,
+            CalibrationModel::LtqFt { a, b } => frequency_to_mz(freq, &[*a, *b, 0.0, 0.0]),
+            CalibrationModel::OrbitrapPoly(params) => frequency_to_mz(freq, params),
+            CalibrationModel::Custom(f) => f(freq),
+        }
+    }
+
+    /// Invert [`to_mz`](Self::to_mz): recover the frequency abscissa for a given m/z.
+    /// Closed-form for [`None`](CalibrationModel::None)/[`LtqFt`](CalibrationModel::LtqFt),
+    /// Newton's method for [`OrbitrapPoly`](CalibrationModel::OrbitrapPoly) (via
+    /// [`mz_to_frequency`]) and for [`Custom`](CalibrationModel::Custom), since neither
+    /// has a closed-form inverse in general.
+    pub fn to_frequency(&self, mz: f64) -> f64 {
+        match self {
+            CalibrationModel::None => mz,
+            CalibrationModel::LtqFt { a, b } => mz_to_frequency(mz, &[*a, *b, 0.0, 0.0]),
+            CalibrationModel::OrbitrapPoly(params) => mz_to_frequency(mz, params),
+            CalibrationModel::Custom(f) => newton_invert(f.as_ref(), mz, mz),
+        }
+    }
+}
+
+impl std::fmt::Debug for CalibrationModel {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalibrationModel::None => write!(fmt, "CalibrationModel::None"),
+            CalibrationModel::LtqFt { a, b } => {
+                write!(fmt, "CalibrationModel::LtqFt {{ a: {}, b: {} }}", a, b)
+            }
+            CalibrationModel::OrbitrapPoly(params) => {
+                write!(fmt, "CalibrationModel::OrbitrapPoly({:?})", params)
+            }
+            CalibrationModel::Custom(_) => write!(fmt, "CalibrationModel::Custom(..)"),
+        }
+    }
+}
+
+/// Numerically invert `f` around `seed` via a few Newton's-method steps,
+/// solving `f(x) = target` for `x`. Used for calibration forms with no
+/// closed-form inverse.
+fn newton_invert(f: &dyn Fn(f64) -> f64, target: f64, seed: f64) -> f64 {
+    let mut x = seed;
+    for _ in 0..20 {
+        let step = if x != 0.0 { x * 1e-6 } else { 1e-6 };
+        let computed = f(x);
+        let derivative = (f(x + step) - computed) / step;
+        if derivative.abs() < 1e-12 {
+            break;
+        }
+        x -= (computed - target) / derivative;
+    }
+    x
+}
+
+impl ScanEvent {
+    /// The frequency↔m/z [`CalibrationModel`] this event's
+    /// `conversion_params` describe.
+    pub fn calibration_model(&self) -> CalibrationModel {
+        CalibrationModel::from_conversion_params(&self.conversion_params)
+    }
+}
+
 impl std::fmt::Display for AnalyzerType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -630,4 +1012,300 @@ mod tests {
         // m/z = 100.0 / (1e6/1e6 + 0) = 100.0
         assert!((mz - 100.0).abs() < 1e-6);
     }
+
+    #[test]
+    fn test_mz_to_frequency_no_params_is_identity() {
+        assert_eq!(mz_to_frequency(500.0, &[]), 500.0);
+    }
+
+    #[test]
+    fn test_mz_to_frequency_ltq_ft_round_trip() {
+        let params = [100.0, 0.0, 0.0, 0.0];
+        let freq = 1e6;
+        let mz = frequency_to_mz(freq, &params);
+        let recovered = mz_to_frequency(mz, &params);
+        assert!((recovered - freq).abs() < 1e-6);
+    }
+
+    /// Build a minimal scan-event stream (v31 layout: 41-byte preamble, no
+    /// reactions, no name string) with `n` events, each distinguishable by
+    /// its `ms_level` byte (`i + 1`), for exercising `parse_scan_events` and
+    /// `parse_scan_events_iter` against the same bytes. The stream starts at
+    /// byte offset 4, after four padding bytes, since a `scan_params_addr`
+    /// of 0 means "no scan event stream".
+    fn build_scan_events_stream(n: u32) -> Vec<u8> {
+        let mut buf = vec![0u8; 4];
+        buf.extend_from_slice(&n.to_le_bytes());
+        for i in 0..n {
+            let mut preamble = vec![0u8; 41];
+            preamble[6] = (i + 1) as u8; // ms_level byte
+            buf.extend_from_slice(&preamble);
+            buf.extend_from_slice(&0u32.to_le_bytes()); // n_precursors
+            buf.extend_from_slice(&0u32.to_le_bytes()); // mass ranges
+            buf.extend_from_slice(&0u32.to_le_bytes()); // mass calibrators
+            buf.extend_from_slice(&0u32.to_le_bytes()); // source fragmentations
+            buf.extend_from_slice(&0u32.to_le_bytes()); // source frag mass ranges
+        }
+        buf
+    }
+
+    #[test]
+    fn test_scan_event_iter_matches_eager_parse() {
+        let data = build_scan_events_stream(4);
+        let eager = parse_scan_events(&data, 4, 31).unwrap();
+        let streamed: Vec<ScanEvent> = parse_scan_events_iter(&data, 4, 31)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(eager.len(), streamed.len());
+        for (a, b) in eager.iter().zip(streamed.iter()) {
+            assert_eq!(
+                ms_level_discriminant(&a.preamble.ms_level),
+                ms_level_discriminant(&b.preamble.ms_level)
+            );
+        }
+    }
+
+    fn ms_level_discriminant(level: &MsLevel) -> u8 {
+        match level {
+            MsLevel::Ms1 => 1,
+            MsLevel::Ms2 => 2,
+            MsLevel::Ms3 => 3,
+            MsLevel::Other(n) => *n,
+        }
+    }
+
+    #[test]
+    fn test_scan_event_iter_size_hint_and_stops_after_count() {
+        let data = build_scan_events_stream(3);
+        let mut iter = parse_scan_events_iter(&data, 4, 31).unwrap();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert!(iter.next().is_some());
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        assert!(iter.next().is_some());
+        assert!(iter.next().is_some());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_scan_event_iter_empty_addr_yields_nothing() {
+        let mut iter = parse_scan_events_iter(&[], 0, 31).unwrap();
+        assert!(iter.next().is_none());
+    }
+
+    fn reaction_with_activation(activation_bits: u32) -> Reaction {
+        Reaction {
+            precursor_mz: 500.0,
+            isolation_width: 2.0,
+            collision_energy: 25.0,
+            collision_energy_valid: 1 | (activation_bits << 1),
+            precursor_range_valid: false,
+            first_precursor_mass: 0.0,
+            last_precursor_mass: 0.0,
+            isolation_width_offset: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_activations_lists_each_reaction_in_order() {
+        let event = ScanEvent {
+            preamble: parse_preamble(&vec![0u8; 80]),
+            reactions: vec![reaction_with_activation(4), reaction_with_activation(5)], // ETD, HCD
+            conversion_params: vec![],
+        };
+        assert_eq!(event.activations(), vec![ActivationType::Etd, ActivationType::Hcd]);
+        assert_eq!(event.combined_activation(), "ETD+HCD");
+    }
+
+    #[test]
+    fn test_combined_activation_single_reaction() {
+        let event = ScanEvent {
+            preamble: parse_preamble(&vec![0u8; 80]),
+            reactions: vec![reaction_with_activation(5)], // HCD
+            conversion_params: vec![],
+        };
+        assert_eq!(event.combined_activation(), "HCD");
+    }
+
+    #[test]
+    fn test_combined_activation_no_reactions_is_empty() {
+        let event = ScanEvent {
+            preamble: parse_preamble(&vec![0u8; 80]),
+            reactions: vec![],
+            conversion_params: vec![],
+        };
+        assert_eq!(event.combined_activation(), "");
+    }
+
+    #[test]
+    fn test_parse_scan_event_sets_preamble_activation_from_first_reaction() {
+        let preamble_size = version::scan_event_preamble_size(31);
+        let mut buf = vec![0u8; preamble_size];
+        buf[6] = 2; // MS2
+        buf.extend_from_slice(&2u32.to_le_bytes()); // n_precursors
+
+        let mut first = reaction_with_activation(4); // ETD
+        first.collision_energy_valid = 1 | (4 << 1);
+        let mut second = reaction_with_activation(5); // HCD (supplemental)
+        second.collision_energy_valid = 1 | (5 << 1);
+        for rxn in [&first, &second] {
+            buf.extend_from_slice(&rxn.precursor_mz.to_le_bytes());
+            buf.extend_from_slice(&rxn.isolation_width.to_le_bytes());
+            buf.extend_from_slice(&rxn.collision_energy.to_le_bytes());
+            buf.extend_from_slice(&rxn.collision_energy_valid.to_le_bytes());
+            buf.extend_from_slice(&[0u8; 4]); // pad to the 32-byte v31 reaction size
+        }
+        buf.extend_from_slice(&0u32.to_le_bytes()); // mass ranges
+        buf.extend_from_slice(&0u32.to_le_bytes()); // mass calibrators
+        buf.extend_from_slice(&0u32.to_le_bytes()); // source fragmentations
+        buf.extend_from_slice(&0u32.to_le_bytes()); // source frag mass ranges
+
+        let (event, _end) = parse_scan_event(&buf, 0, 31).unwrap();
+        assert_eq!(event.preamble.activation, ActivationType::Etd);
+        assert_eq!(event.combined_activation(), "ETD+HCD");
+    }
+
+    #[test]
+    fn test_filter_string_ms2_dependent_hcd() {
+        let mut preamble = parse_preamble(&vec![0u8; 80]);
+        preamble.polarity = Polarity::Positive;
+        preamble.scan_mode = ScanMode::Profile;
+        preamble.ms_level = MsLevel::Ms2;
+        preamble.scan_type = ScanType::Full;
+        preamble.dependent = true;
+        preamble.ionization = IonizationType::Nsi;
+        preamble.analyzer = AnalyzerType::Ftms;
+
+        let event = ScanEvent {
+            preamble,
+            reactions: vec![Reaction {
+                precursor_mz: 445.12,
+                isolation_width: 2.0,
+                collision_energy: 27.0,
+                collision_energy_valid: 1 | (5 << 1), // HCD
+                precursor_range_valid: true,
+                first_precursor_mass: 100.0,
+                last_precursor_mass: 1500.0,
+                isolation_width_offset: 0.0,
+            }],
+            conversion_params: vec![],
+        };
+
+        assert_eq!(
+            event.filter_string(),
+            "FTMS + p NSI d Full ms2 445.12@hcd27.00 [100.0-1500.0]"
+        );
+    }
+
+    #[test]
+    fn test_filter_string_ms1_has_no_level_suffix_or_reactions() {
+        let mut preamble = parse_preamble(&vec![0u8; 80]);
+        preamble.polarity = Polarity::Positive;
+        preamble.scan_mode = ScanMode::Profile;
+        preamble.ms_level = MsLevel::Ms1;
+        preamble.scan_type = ScanType::Full;
+        preamble.dependent = false;
+        preamble.ionization = IonizationType::Nsi;
+        preamble.analyzer = AnalyzerType::Ftms;
+
+        let event = ScanEvent {
+            preamble,
+            reactions: vec![],
+            conversion_params: vec![],
+        };
+
+        assert_eq!(event.filter_string(), "FTMS + p NSI Full ms");
+    }
+
+    #[test]
+    fn test_filter_string_chains_supplemental_activation_on_same_precursor() {
+        let mut preamble = parse_preamble(&vec![0u8; 80]);
+        preamble.polarity = Polarity::Positive;
+        preamble.scan_mode = ScanMode::Centroid;
+        preamble.ms_level = MsLevel::Ms2;
+        preamble.scan_type = ScanType::Full;
+        preamble.dependent = true;
+        preamble.ionization = IonizationType::Nsi;
+        preamble.analyzer = AnalyzerType::Ftms;
+
+        let event = ScanEvent {
+            preamble,
+            reactions: vec![
+                reaction_with_activation(4), // ETD
+                {
+                    let mut r = reaction_with_activation(5); // HCD supplemental
+                    r.precursor_mz = 500.0;
+                    r
+                },
+            ],
+            conversion_params: vec![],
+        };
+
+        assert_eq!(
+            event.filter_string(),
+            "FTMS + c NSI d Full ms2 500.00@etd25.00@hcd25.00"
+        );
+    }
+
+    #[test]
+    fn test_mz_to_frequency_orbitrap_round_trip() {
+        let params = [4e13, -1e8, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let freq = 200_000.0;
+        let mz = frequency_to_mz(freq, &params);
+        let recovered = mz_to_frequency(mz, &params);
+        assert!((recovered - freq).abs() / freq < 1e-4);
+    }
+
+    #[test]
+    fn test_calibration_model_from_params_picks_variant() {
+        assert!(matches!(CalibrationModel::from_conversion_params(&[]), CalibrationModel::None));
+        assert!(matches!(
+            CalibrationModel::from_conversion_params(&[1.0, 2.0, 3.0, 4.0]),
+            CalibrationModel::LtqFt { .. }
+        ));
+        assert!(matches!(
+            CalibrationModel::from_conversion_params(&[0.0; 7]),
+            CalibrationModel::OrbitrapPoly(_)
+        ));
+    }
+
+    #[test]
+    fn test_calibration_model_ltq_ft_round_trips() {
+        let model = CalibrationModel::from_conversion_params(&[100.0, 0.0, 0.0, 0.0]);
+        let freq = 1e6;
+        let mz = model.to_mz(freq);
+        assert!((mz - 100.0).abs() < 1e-6);
+        let recovered = model.to_frequency(mz);
+        assert!((recovered - freq).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_calibration_model_orbitrap_round_trips() {
+        let params = [4e13, -1e8, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let model = CalibrationModel::from_conversion_params(&params);
+        let freq = 200_000.0;
+        let mz = model.to_mz(freq);
+        let recovered = model.to_frequency(mz);
+        assert!((recovered - freq).abs() / freq < 1e-4);
+    }
+
+    #[test]
+    fn test_calibration_model_custom_round_trips_via_newton() {
+        let model = CalibrationModel::Custom(Box::new(|freq: f64| freq * 2.0 + 1.0));
+        let mz = model.to_mz(100.0); // 201.0
+        assert!((mz - 201.0).abs() < 1e-9);
+        let recovered = model.to_frequency(mz);
+        assert!((recovered - 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_scan_event_calibration_model_uses_conversion_params() {
+        let event = ScanEvent {
+            preamble: parse_preamble(&vec![0u8; 80]),
+            reactions: vec![],
+            conversion_params: vec![100.0, 0.0, 0.0, 0.0],
+        };
+        assert!(matches!(event.calibration_model(), CalibrationModel::LtqFt { .. }));
+    }
 }
@@ -5,12 +5,41 @@
 
 use crate::types::{MsLevel, Polarity};
 
-/// Precursor info extracted from a filter string.
+/// Known ionization source tokens that can appear in a filter string.
+const KNOWN_SOURCES: &[&str] = &["NSI", "ESI", "APCI", "MALDI", "EI", "CI", "FAB", "TSP", "APPI"];
+
+/// One activation stage applied to a precursor (supports supplemental
+/// activation, e.g. EThcD's `@etd25.00@hcd20.00`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterActivation {
+    pub activation: String,
+    pub collision_energy: f64,
+}
+
+/// One isolation/activation stage in a (possibly multi-stage, e.g. MS3)
+/// precursor chain.
 #[derive(Debug, Clone)]
 pub struct FilterPrecursor {
     pub mz: f64,
-    pub activation: String,
-    pub collision_energy: f64,
+    /// All activation stages applied to this isolation, in filter order
+    /// (more than one for supplemental activation).
+    pub activations: Vec<FilterActivation>,
+}
+
+impl FilterPrecursor {
+    /// Convenience accessor for callers that only care about one activation:
+    /// the last stage applied (e.g. the supplemental activation in EThcD).
+    pub fn activation(&self) -> &str {
+        self.activations
+            .last()
+            .map(|a| a.activation.as_str())
+            .unwrap_or("")
+    }
+
+    /// Convenience accessor: collision energy of the last activation stage.
+    pub fn collision_energy(&self) -> f64 {
+        self.activations.last().map(|a| a.collision_energy).unwrap_or(0.0)
+    }
 }
 
 /// Parsed scan filter.
@@ -21,10 +50,30 @@ pub struct ScanFilter {
     pub analyzer: String,
     pub scan_mode: String,
     pub mass_range: Option<(f64, f64)>,
-    pub precursor: Option<FilterPrecursor>,
+    /// The full precursor isolation chain in order, e.g. for MS3 this holds
+    /// both the MS1->MS2 and MS2->MS3 isolation stages.
+    pub precursors: Vec<FilterPrecursor>,
+    /// Centroid (`c`) or profile (`p`) data type token.
+    pub data_type: Option<String>,
+    /// Ionization source, e.g. "NSI", "ESI", "APCI", "MALDI".
+    pub source: Option<String>,
+    /// Whether the `d` (data-dependent) flag is present.
+    pub is_data_dependent: bool,
+    /// FAIMS compensation voltage from a `cv=-45.00` token, if present.
+    pub compensation_voltage: Option<f64>,
+    /// Whether the `msx` (multiplexed) flag is present.
+    pub is_multiplexed: bool,
     pub raw_string: String,
 }
 
+impl ScanFilter {
+    /// Convenience accessor for backward compatibility: the direct
+    /// (last-stage) precursor, e.g. the MS2->MS3 isolation for an MS3 filter.
+    pub fn precursor(&self) -> Option<&FilterPrecursor> {
+        self.precursors.last()
+    }
+}
+
 /// Parse a Thermo scan filter string.
 pub fn parse_filter(filter: &str) -> ScanFilter {
     let polarity = if filter.contains(" + ") {
@@ -64,65 +113,91 @@ pub fn parse_filter(filter: &str) -> ScanFilter {
 
     let mass_range = parse_mass_range(filter);
 
-    let precursor = if matches!(ms_level, MsLevel::Ms2 | MsLevel::Ms3) {
-        parse_precursor_from_filter(filter)
+    let precursors = if matches!(ms_level, MsLevel::Ms2 | MsLevel::Ms3) {
+        parse_precursor_chain(filter)
     } else {
-        None
+        Vec::new()
     };
 
+    let tokens: Vec<&str> = filter.split_whitespace().collect();
+    let data_type = tokens
+        .iter()
+        .find(|&&t| t == "c" || t == "p")
+        .map(|t| t.to_string());
+    let source = tokens
+        .iter()
+        .find(|&&t| KNOWN_SOURCES.contains(&t))
+        .map(|t| t.to_string());
+    let is_data_dependent = tokens.iter().any(|&t| t == "d");
+    let is_multiplexed = tokens.iter().any(|&t| t.eq_ignore_ascii_case("msx"));
+    let compensation_voltage = tokens
+        .iter()
+        .find(|t| t.starts_with("cv="))
+        .and_then(|t| t[3..].parse::<f64>().ok());
+
     ScanFilter {
         ms_level,
         polarity,
         analyzer,
         scan_mode,
         mass_range,
-        precursor,
+        precursors,
+        data_type,
+        source,
+        is_data_dependent,
+        compensation_voltage,
+        is_multiplexed,
         raw_string: filter.to_string(),
     }
 }
 
-/// Extract precursor m/z, activation type, and collision energy from a filter string.
+/// Extract the full precursor isolation chain from a filter string.
 ///
-/// Parses patterns like "524.2648@hcd28.00" from filter strings such as:
-/// "FTMS + c NSI d Full ms2 524.2648@hcd28.00 [100.0000-1060.0000]"
-fn parse_precursor_from_filter(filter: &str) -> Option<FilterPrecursor> {
-    let at_pos = filter.rfind('@')?;
-
-    // Extract precursor m/z: scan backwards from '@' for the number
-    let before_at = &filter[..at_pos];
-    let mz_start = before_at
-        .rfind(|c: char| !c.is_ascii_digit() && c != '.')
-        .map(|i| i + 1)
-        .unwrap_or(0);
-    let mz_str = before_at[mz_start..].trim();
-    if mz_str.is_empty() {
-        return None;
-    }
-    let precursor_mz: f64 = mz_str.parse().ok()?;
-
-    // Extract activation type (alphabetic chars after '@')
-    let after_at = &filter[at_pos + 1..];
-    let type_end = after_at
-        .find(|c: char| c.is_ascii_digit() || c == '.')
-        .unwrap_or(after_at.len());
-    let activation = after_at[..type_end].to_lowercase();
-
-    // Collision energy follows the activation type
-    let ce_str = &after_at[type_end..];
-    let ce_end = ce_str
-        .find(|c: char| !c.is_ascii_digit() && c != '.')
-        .unwrap_or(ce_str.len());
-    let collision_energy: f64 = if ce_end > 0 {
-        ce_str[..ce_end].parse().unwrap_or(0.0)
-    } else {
-        0.0
+/// Parses patterns like "524.26@hcd28.00 300.15@hcd35.00" (one token per
+/// isolation stage, in order) from filter strings such as:
+/// "FTMS + c NSI d Full ms3 524.26@hcd28.00 300.15@hcd35.00 [100.0000-1060.0000]"
+///
+/// A single isolation stage may carry more than one activation (supplemental
+/// activation), e.g. "524.2648@etd25.00@hcd20.00".
+fn parse_precursor_chain(filter: &str) -> Vec<FilterPrecursor> {
+    let body = match filter.find('[') {
+        Some(bracket) => &filter[..bracket],
+        None => filter,
     };
 
-    Some(FilterPrecursor {
-        mz: precursor_mz,
-        activation,
-        collision_energy,
-    })
+    let mut precursors = Vec::new();
+    for token in body.split_whitespace() {
+        if !token.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+
+        let mut parts = token.split('@');
+        let mz_str = parts.next().unwrap_or("");
+        let mz: f64 = match mz_str.parse() {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        let mut activations = Vec::new();
+        for part in parts {
+            let type_end = part
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(part.len());
+            let activation = part[..type_end].to_lowercase();
+            let ce_str = &part[type_end..];
+            let collision_energy: f64 = ce_str.parse().unwrap_or(0.0);
+            activations.push(FilterActivation {
+                activation,
+                collision_energy,
+            });
+        }
+
+        if !activations.is_empty() {
+            precursors.push(FilterPrecursor { mz, activations });
+        }
+    }
+
+    precursors
 }
 
 fn parse_mass_range(filter: &str) -> Option<(f64, f64)> {
@@ -151,7 +226,10 @@ mod tests {
         assert_eq!(filter.analyzer, "FTMS");
         assert_eq!(filter.scan_mode, "Full");
         assert_eq!(filter.mass_range, Some((200.0, 2000.0)));
-        assert!(filter.precursor.is_none());
+        assert!(filter.precursor().is_none());
+        assert_eq!(filter.data_type.as_deref(), Some("p"));
+        assert_eq!(filter.source.as_deref(), Some("NSI"));
+        assert!(!filter.is_data_dependent);
     }
 
     #[test]
@@ -166,32 +244,61 @@ mod tests {
             parse_filter("FTMS + c NSI d Full ms2 524.2648@hcd28.00 [100.0000-1060.0000]");
         assert!(matches!(filter.ms_level, MsLevel::Ms2));
         assert_eq!(filter.mass_range, Some((100.0, 1060.0)));
-        let precursor = filter.precursor.as_ref().unwrap();
+        assert_eq!(filter.data_type.as_deref(), Some("c"));
+        assert!(filter.is_data_dependent);
+        let precursor = filter.precursor().unwrap();
         assert!((precursor.mz - 524.2648).abs() < 1e-4);
-        assert_eq!(precursor.activation, "hcd");
-        assert!((precursor.collision_energy - 28.0).abs() < 0.01);
+        assert_eq!(precursor.activation(), "hcd");
+        assert!((precursor.collision_energy() - 28.0).abs() < 0.01);
     }
 
     #[test]
     fn test_parse_ms2_cid() {
         let filter = parse_filter("ITMS + c NSI d Full ms2 445.120@cid35.00 [120.00-900.00]");
         assert!(matches!(filter.ms_level, MsLevel::Ms2));
-        let precursor = filter.precursor.as_ref().unwrap();
+        let precursor = filter.precursor().unwrap();
         assert!((precursor.mz - 445.12).abs() < 1e-4);
-        assert_eq!(precursor.activation, "cid");
-        assert!((precursor.collision_energy - 35.0).abs() < 0.01);
+        assert_eq!(precursor.activation(), "cid");
+        assert!((precursor.collision_energy() - 35.0).abs() < 0.01);
     }
 
     #[test]
-    fn test_parse_ms3() {
+    fn test_parse_ms3_full_chain() {
         let filter = parse_filter(
             "ITMS + c NSI d Full ms3 524.26@hcd28.00 300.15@hcd35.00 [100.00-600.00]",
         );
         assert!(matches!(filter.ms_level, MsLevel::Ms3));
-        // rfind('@') gets the last precursor (300.15), which is the direct MS3 precursor
-        let precursor = filter.precursor.as_ref().unwrap();
+        assert_eq!(filter.precursors.len(), 2);
+        assert!((filter.precursors[0].mz - 524.26).abs() < 0.01);
+        assert!((filter.precursors[1].mz - 300.15).abs() < 0.01);
+
+        // Backward-compatible accessor: last stage (direct MS3 precursor).
+        let precursor = filter.precursor().unwrap();
         assert!((precursor.mz - 300.15).abs() < 0.01);
-        assert_eq!(precursor.activation, "hcd");
-        assert!((precursor.collision_energy - 35.0).abs() < 0.01);
+        assert_eq!(precursor.activation(), "hcd");
+        assert!((precursor.collision_energy() - 35.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_supplemental_activation() {
+        let filter = parse_filter("FTMS + c NSI d Full ms2 524.2648@etd25.00@hcd20.00 [100.0000-1060.0000]");
+        let precursor = filter.precursor().unwrap();
+        assert_eq!(precursor.activations.len(), 2);
+        assert_eq!(precursor.activations[0].activation, "etd");
+        assert!((precursor.activations[0].collision_energy - 25.0).abs() < 0.01);
+        assert_eq!(precursor.activations[1].activation, "hcd");
+        assert!((precursor.activations[1].collision_energy - 20.0).abs() < 0.01);
+        // Backward-compat accessors return the last stage.
+        assert_eq!(precursor.activation(), "hcd");
+        assert!((precursor.collision_energy() - 20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_faims_cv_and_msx() {
+        let filter = parse_filter(
+            "FTMS + p NSI msx cv=-45.00 d Full ms2 524.26@hcd28.00 [100.00-1060.00]",
+        );
+        assert_eq!(filter.compensation_voltage, Some(-45.0));
+        assert!(filter.is_multiplexed);
     }
 }
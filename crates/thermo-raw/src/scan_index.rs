@@ -27,7 +27,8 @@
 //! | 80     | i32    | CycleNumber                        | --     | --    | ✓        |
 //! | 84     | --     | (4 bytes struct alignment padding)  | --     | --    | ✓        |
 
-use crate::io_utils::BinaryReader;
+use crate::io_utils::{bounded_vec_with_capacity, BinaryReader, FromReader};
+use std::io::{Read, Seek};
 use crate::version;
 use crate::RawError;
 
@@ -119,22 +120,20 @@ fn is_valid_stride(data: &[u8], offset: u64, n_scans: u32, stride: usize) -> boo
     true
 }
 
-/// Parse the entire scan index from the data stream.
-///
-/// `data` is the full file data. `offset` is the absolute address of the scan index.
-/// Returns one `ScanIndexEntry` per scan.
-pub fn parse_scan_index(
-    data: &[u8],
-    offset: u64,
-    version: u32,
-    n_scans: u32,
-) -> Result<Vec<ScanIndexEntry>, RawError> {
-    let entry_size = detect_entry_size(data, offset, n_scans, version);
-    let has_64bit_offset = entry_size >= 80;
-    let mut reader = BinaryReader::at_offset(data, offset);
-    let mut entries = Vec::with_capacity(n_scans as usize);
+/// Context parameterizing how a [`ScanIndexEntry`] is decoded: the RAW file
+/// version plus the entry size actually detected on disk (which can diverge
+/// from the version's documented size, see [`detect_entry_size`]).
+pub struct ScanIndexCtx {
+    pub version: u32,
+    pub entry_size: usize,
+}
 
-    for _ in 0..n_scans {
+impl FromReader for ScanIndexEntry {
+    type Ctx = ScanIndexCtx;
+
+    fn read<R: Read + Seek>(reader: &mut BinaryReader<R>, ctx: &ScanIndexCtx) -> Result<Self, RawError> {
+        let entry_size = ctx.entry_size;
+        let has_64bit_offset = entry_size >= 80;
         let entry_start = reader.position();
 
         // Offset 0: DataOffset32Bit (v<65) or DataSize (v65+)
@@ -186,13 +185,20 @@ pub fn parse_scan_index(
             (offset_or_size as u64, 0u32, 0i32)
         };
 
-        // Ensure we consumed exactly entry_size bytes
+        // Every branch above must consume exactly `entry_size` bytes; a
+        // mismatch means the layout for this (version, entry_size) pair is
+        // wrong rather than something to silently paper over.
         let expected_end = entry_start + entry_size as u64;
         if reader.position() != expected_end {
-            reader.set_position(expected_end);
+            return Err(RawError::CorruptedData(format!(
+                "ScanIndexEntry::read (v{}): consumed {} bytes, expected entry_size {}",
+                ctx.version,
+                reader.position() - entry_start,
+                entry_size
+            )));
         }
 
-        entries.push(ScanIndexEntry {
+        Ok(ScanIndexEntry {
             offset: scan_offset,
             trailer_offset,
             scan_event,
@@ -208,8 +214,277 @@ pub fn parse_scan_index(
             low_mz,
             high_mz,
             cycle_number,
-        });
+        })
+    }
+}
+
+/// One rule violated by a specific scan in the index.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexIssue {
+    /// `rt` decreased from the previous scan.
+    NonMonotonicRt {
+        scan_number: i32,
+        rt: f64,
+        prev_rt: f64,
+    },
+    /// `scan_number` was not exactly one more than the previous entry's.
+    NonContiguousScanNumber { scan_number: i32, prev: i32 },
+    /// The scan's `[offset, offset + data_size)` range falls outside the file.
+    OffsetOutOfBounds {
+        scan_number: i32,
+        offset: u64,
+        data_size: u32,
+        data_len: u64,
+    },
+    /// The scan's data range overlaps the following scan's, once sorted by offset.
+    OverlappingRanges {
+        scan_number: i32,
+        next_scan_number: i32,
+        offset: u64,
+        end: u64,
+        next_offset: u64,
+    },
+    /// `base_peak_mz` fell outside `[low_mz, high_mz]`.
+    BasePeakOutOfRange {
+        scan_number: i32,
+        base_peak_mz: f64,
+        low_mz: f64,
+        high_mz: f64,
+    },
+    /// A non-empty scan (`number_packets > 0` expected) reported zero packets.
+    ZeroPackets { scan_number: i32 },
+}
+
+/// Structured corruption report produced by [`validate_scan_index`].
+#[derive(Debug, Clone, Default)]
+pub struct IndexReport {
+    pub issues: Vec<IndexIssue>,
+}
+
+impl IndexReport {
+    /// Whether the index passed every check.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Validate a parsed scan index for internal consistency, producing a
+/// structured report rather than a single pass/fail bit.
+///
+/// Checks performed:
+/// 1. `rt` is non-decreasing across scans.
+/// 2. `scan_number` is contiguous (each entry is one more than the last).
+/// 3. Every `[offset, offset + data_size)` range lies within `data_len` and,
+///    once sorted by offset, none overlap the next entry's range (only
+///    checked when `data_size` is populated, i.e. v65+ entries).
+/// 4. `base_peak_mz` falls inside `[low_mz, high_mz]` (checked only for scans
+///    with `tic > 0` and `low_mz < high_mz`).
+/// 5. `number_packets > 0` for every scan with `tic > 0`; a scan with no ion
+///    current is treated as empty and not flagged.
+pub fn validate_scan_index(entries: &[ScanIndexEntry], data_len: u64) -> IndexReport {
+    let mut issues = Vec::new();
+
+    let mut prev_rt: Option<f64> = None;
+    let mut prev_scan_number: Option<i32> = None;
+
+    for entry in entries {
+        if let Some(prev) = prev_rt {
+            if entry.rt < prev {
+                issues.push(IndexIssue::NonMonotonicRt {
+                    scan_number: entry.scan_number,
+                    rt: entry.rt,
+                    prev_rt: prev,
+                });
+            }
+        }
+        prev_rt = Some(entry.rt);
+
+        if let Some(prev) = prev_scan_number {
+            if entry.scan_number != prev + 1 {
+                issues.push(IndexIssue::NonContiguousScanNumber {
+                    scan_number: entry.scan_number,
+                    prev,
+                });
+            }
+        }
+        prev_scan_number = Some(entry.scan_number);
+
+        if entry.data_size > 0 {
+            let end = entry.offset + entry.data_size as u64;
+            if end > data_len {
+                issues.push(IndexIssue::OffsetOutOfBounds {
+                    scan_number: entry.scan_number,
+                    offset: entry.offset,
+                    data_size: entry.data_size,
+                    data_len,
+                });
+            }
+        }
+
+        if !(entry.low_mz..=entry.high_mz).contains(&entry.base_peak_mz)
+            && entry.tic > 0.0
+            && entry.low_mz < entry.high_mz
+        {
+            issues.push(IndexIssue::BasePeakOutOfRange {
+                scan_number: entry.scan_number,
+                base_peak_mz: entry.base_peak_mz,
+                low_mz: entry.low_mz,
+                high_mz: entry.high_mz,
+            });
+        }
+
+        if entry.number_packets <= 0 && entry.tic > 0.0 {
+            issues.push(IndexIssue::ZeroPackets {
+                scan_number: entry.scan_number,
+            });
+        }
+    }
+
+    // Overlap check: only meaningful when data_size is populated (v65+).
+    if entries.iter().any(|e| e.data_size > 0) {
+        let mut by_offset: Vec<&ScanIndexEntry> = entries.iter().filter(|e| e.data_size > 0).collect();
+        by_offset.sort_by_key(|e| e.offset);
+        for pair in by_offset.windows(2) {
+            let (cur, next) = (pair[0], pair[1]);
+            let end = cur.offset + cur.data_size as u64;
+            if end > next.offset {
+                issues.push(IndexIssue::OverlappingRanges {
+                    scan_number: cur.scan_number,
+                    next_scan_number: next.scan_number,
+                    offset: cur.offset,
+                    end,
+                    next_offset: next.offset,
+                });
+            }
+        }
+    }
+
+    IndexReport { issues }
+}
+
+/// Parse the entire scan index from the data stream.
+///
+/// `data` is the full file data. `offset` is the absolute address of the scan index.
+/// Returns one `ScanIndexEntry` per scan.
+pub fn parse_scan_index(
+    data: &[u8],
+    offset: u64,
+    version: u32,
+    n_scans: u32,
+) -> Result<Vec<ScanIndexEntry>, RawError> {
+    let entry_size = detect_entry_size(data, offset, n_scans, version);
+    let ctx = ScanIndexCtx {
+        version,
+        entry_size,
+    };
+    let mut reader = BinaryReader::at_offset(data, offset);
+    let mut entries =
+        bounded_vec_with_capacity(n_scans, entry_size, offset, data.len() as u64, "scan index")?;
+
+    // Confine each entry to its own `entry_size`-byte window: an overrun in
+    // `ScanIndexEntry::read` hits `CorruptedData` at the record boundary
+    // instead of silently consuming bytes belonging to the next entry.
+    for _ in 0..n_scans {
+        let mut entry_reader = reader.take(entry_size)?;
+        entries.push(ScanIndexEntry::read(&mut entry_reader, &ctx)?);
     }
 
     Ok(entries)
 }
+
+#[cfg(test)]
+mod validation_tests {
+    use super::*;
+
+    fn entry(scan_number: i32, rt: f64, offset: u64, data_size: u32) -> ScanIndexEntry {
+        ScanIndexEntry {
+            offset,
+            trailer_offset: 0,
+            scan_event: 0,
+            scan_segment: 0,
+            scan_number,
+            packet_type: 0,
+            number_packets: 1,
+            data_size,
+            rt,
+            tic: 1.0,
+            base_peak_intensity: 0.0,
+            base_peak_mz: 500.0,
+            low_mz: 200.0,
+            high_mz: 2000.0,
+            cycle_number: 0,
+        }
+    }
+
+    #[test]
+    fn clean_index_is_ok() {
+        let entries = vec![entry(1, 0.0, 0, 100), entry(2, 0.1, 100, 100)];
+        let report = validate_scan_index(&entries, 200);
+        assert!(report.is_ok());
+    }
+
+    #[test]
+    fn detects_non_monotonic_rt() {
+        let entries = vec![entry(1, 1.0, 0, 100), entry(2, 0.5, 100, 100)];
+        let report = validate_scan_index(&entries, 200);
+        assert!(matches!(
+            report.issues[0],
+            IndexIssue::NonMonotonicRt { scan_number: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn detects_non_contiguous_scan_number() {
+        let entries = vec![entry(1, 0.0, 0, 100), entry(3, 0.1, 100, 100)];
+        let report = validate_scan_index(&entries, 200);
+        assert!(matches!(
+            report.issues[0],
+            IndexIssue::NonContiguousScanNumber {
+                scan_number: 3,
+                prev: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn detects_offset_out_of_bounds() {
+        let entries = vec![entry(1, 0.0, 0, 300)];
+        let report = validate_scan_index(&entries, 200);
+        assert!(matches!(
+            report.issues[0],
+            IndexIssue::OffsetOutOfBounds { scan_number: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn detects_overlapping_ranges() {
+        let entries = vec![entry(1, 0.0, 0, 150), entry(2, 0.1, 100, 100)];
+        let report = validate_scan_index(&entries, 300);
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| matches!(i, IndexIssue::OverlappingRanges { .. })));
+    }
+
+    #[test]
+    fn detects_base_peak_out_of_range() {
+        let mut bad = entry(1, 0.0, 0, 100);
+        bad.base_peak_mz = 5000.0;
+        let report = validate_scan_index(&[bad], 200);
+        assert!(matches!(
+            report.issues[0],
+            IndexIssue::BasePeakOutOfRange { scan_number: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn detects_zero_packets() {
+        let mut bad = entry(1, 0.0, 0, 100);
+        bad.number_packets = 0;
+        let report = validate_scan_index(&[bad], 200);
+        assert!(matches!(
+            report.issues[0],
+            IndexIssue::ZeroPackets { scan_number: 1 }
+        ));
+    }
+}
@@ -0,0 +1,218 @@
+//! Random-access abstraction over where scan payload bytes live.
+//!
+//! [`parse_scan_index`](crate::scan_index::parse_scan_index) resolves the
+//! offset/size of every scan up front, but until now callers had to hand that
+//! index around together with the *entire* file as a `&[u8]` slice just to
+//! read one scan's bytes. `ScanSource` decouples the two: the index is
+//! parsed once, and individual scan payloads are resolved on demand through
+//! whichever backing store the caller has (an in-memory slice, a
+//! memory-mapped file, or a plain `Read + Seek` handle). This is what makes
+//! constant-memory iteration over large acquisitions possible.
+
+use crate::scan_index::ScanIndexEntry;
+use crate::RawError;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io::{Read, Seek, SeekFrom};
+
+/// Resolves scan index entries and their raw packet bytes.
+///
+/// Implementations are expected to be cheap to query repeatedly; callers
+/// decode one scan at a time via [`ScanSource::scan_bytes`] rather than
+/// holding the whole file in memory.
+pub trait ScanSource {
+    /// Number of scans covered by the index.
+    fn n_scans(&self) -> u32;
+
+    /// Look up the index entry for `scan` (1-based, matching RAW file scan numbers).
+    fn index_entry(&self, scan: u32) -> Result<&ScanIndexEntry, RawError>;
+
+    /// Raw `ScanDataPacket` bytes for `scan`, starting at its index-reported
+    /// offset. The packet header is self-describing, so the returned slice
+    /// may run past the end of the packet; callers only read what the
+    /// packet header says to read.
+    fn scan_bytes(&self, scan: u32) -> Result<Cow<'_, [u8]>, RawError>;
+}
+
+/// Converts a scan number into a 0-based index into the index entries,
+/// given the first scan number; scans outside the index are an error.
+fn resolve_idx(first_scan: u32, scan: u32, n_entries: usize) -> Result<usize, RawError> {
+    let idx = scan
+        .checked_sub(first_scan)
+        .ok_or(RawError::ScanOutOfRange(scan))? as usize;
+    if idx >= n_entries {
+        return Err(RawError::ScanOutOfRange(scan));
+    }
+    Ok(idx)
+}
+
+/// `ScanSource` backed by an in-memory (or memory-mapped, via `Deref<Target
+/// = [u8]>`) byte slice. This is the original, eager behavior: the whole
+/// file is resident, but individual scan lookups are still O(1).
+pub struct SliceScanSource<'a> {
+    entries: &'a [ScanIndexEntry],
+    data: &'a [u8],
+    data_addr: u64,
+    first_scan: u32,
+}
+
+impl<'a> SliceScanSource<'a> {
+    pub fn new(
+        entries: &'a [ScanIndexEntry],
+        data: &'a [u8],
+        data_addr: u64,
+        first_scan: u32,
+    ) -> Self {
+        Self {
+            entries,
+            data,
+            data_addr,
+            first_scan,
+        }
+    }
+}
+
+impl<'a> ScanSource for SliceScanSource<'a> {
+    fn n_scans(&self) -> u32 {
+        self.entries.len() as u32
+    }
+
+    fn index_entry(&self, scan: u32) -> Result<&ScanIndexEntry, RawError> {
+        let idx = resolve_idx(self.first_scan, scan, self.entries.len())?;
+        Ok(&self.entries[idx])
+    }
+
+    fn scan_bytes(&self, scan: u32) -> Result<Cow<'_, [u8]>, RawError> {
+        let entry = self.index_entry(scan)?;
+        let start = (self.data_addr + entry.offset) as usize;
+        if start > self.data.len() {
+            return Err(RawError::CorruptedData(format!(
+                "scan {} packet offset {} past end of file ({} bytes)",
+                scan,
+                start,
+                self.data.len()
+            )));
+        }
+        Ok(Cow::Borrowed(&self.data[start..]))
+    }
+}
+
+/// `ScanSource` backed by a `Read + Seek` handle. The scan index is still
+/// parsed up front (it's small relative to scan data), but every
+/// `scan_bytes` call seeks and reads only that scan's packet, so the file is
+/// never loaded in full.
+pub struct StreamScanSource<R: Read + Seek> {
+    entries: Vec<ScanIndexEntry>,
+    reader: RefCell<R>,
+    data_addr: u64,
+    first_scan: u32,
+}
+
+impl<R: Read + Seek> StreamScanSource<R> {
+    pub fn new(entries: Vec<ScanIndexEntry>, reader: R, data_addr: u64, first_scan: u32) -> Self {
+        Self {
+            entries,
+            reader: RefCell::new(reader),
+            data_addr,
+            first_scan,
+        }
+    }
+
+    /// Number of bytes to read for a scan's packet: up to the next scan's
+    /// offset (sorted index order), or to end-of-file for the last scan.
+    fn packet_len(&self, idx: usize) -> Option<u64> {
+        let next = self.entries.get(idx + 1)?;
+        let cur = &self.entries[idx];
+        next.offset.checked_sub(cur.offset)
+    }
+}
+
+impl<R: Read + Seek> ScanSource for StreamScanSource<R> {
+    fn n_scans(&self) -> u32 {
+        self.entries.len() as u32
+    }
+
+    fn index_entry(&self, scan: u32) -> Result<&ScanIndexEntry, RawError> {
+        let idx = resolve_idx(self.first_scan, scan, self.entries.len())?;
+        Ok(&self.entries[idx])
+    }
+
+    fn scan_bytes(&self, scan: u32) -> Result<Cow<'_, [u8]>, RawError> {
+        let idx = resolve_idx(self.first_scan, scan, self.entries.len())?;
+        let entry = &self.entries[idx];
+        let abs_offset = self.data_addr + entry.offset;
+
+        let mut reader = self.reader.borrow_mut();
+        reader.seek(SeekFrom::Start(abs_offset))?;
+
+        let mut buf = match self.packet_len(idx) {
+            Some(len) => vec![0u8; len as usize],
+            // Last scan in the index: read to end-of-stream.
+            None => {
+                let mut buf = Vec::new();
+                reader.read_to_end(&mut buf)?;
+                return Ok(Cow::Owned(buf));
+            }
+        };
+        reader.read_exact(&mut buf)?;
+        Ok(Cow::Owned(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn dummy_entry(offset: u64) -> ScanIndexEntry {
+        ScanIndexEntry {
+            offset,
+            trailer_offset: 0,
+            scan_event: 0,
+            scan_segment: 0,
+            scan_number: 0,
+            packet_type: 0,
+            number_packets: 0,
+            data_size: 0,
+            rt: 0.0,
+            tic: 0.0,
+            base_peak_intensity: 0.0,
+            base_peak_mz: 0.0,
+            low_mz: 0.0,
+            high_mz: 0.0,
+            cycle_number: 0,
+        }
+    }
+
+    #[test]
+    fn slice_source_reads_from_offset() {
+        let entries = vec![dummy_entry(0), dummy_entry(4)];
+        let data: Vec<u8> = vec![0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF];
+        let source = SliceScanSource::new(&entries, &data, 0, 1);
+        assert_eq!(source.n_scans(), 2);
+        assert_eq!(&*source.scan_bytes(2).unwrap(), &[0xEE, 0xFF]);
+    }
+
+    #[test]
+    fn stream_source_reads_bounded_packet() {
+        let entries = vec![dummy_entry(0), dummy_entry(3), dummy_entry(5)];
+        let data: Vec<u8> = vec![1, 2, 3, 4, 5, 6, 7];
+        let cursor = Cursor::new(data);
+        let source = StreamScanSource::new(entries, cursor, 0, 1);
+
+        assert_eq!(&*source.scan_bytes(1).unwrap(), &[1, 2, 3]);
+        assert_eq!(&*source.scan_bytes(2).unwrap(), &[4, 5]);
+        assert_eq!(&*source.scan_bytes(3).unwrap(), &[6, 7]);
+    }
+
+    #[test]
+    fn out_of_range_scan_is_an_error() {
+        let entries = vec![dummy_entry(0)];
+        let data: Vec<u8> = vec![0; 4];
+        let source = SliceScanSource::new(&entries, &data, 0, 1);
+        assert!(matches!(
+            source.scan_bytes(99),
+            Err(RawError::ScanOutOfRange(99))
+        ));
+    }
+}
@@ -1,7 +1,22 @@
+//! Pure data-model types, usable in `no_std` + `alloc` contexts.
+//!
+//! This module has no dependency on `std`, `rayon`, or `quick-xml` — only on
+//! `alloc`'s `Vec`/`String` (always available, since `std` re-exports them)
+//! and, optionally, `serde`. Everything that touches the filesystem, mmap,
+//! or parallel decoding (`raw_file`, `batch`, `validation`, ...) lives
+//! elsewhere in the crate, behind the `std` feature.
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// Mass spectrometry polarity.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Polarity {
     Positive,
     Negative,
@@ -9,7 +24,8 @@ pub enum Polarity {
 }
 
 /// MS scan level.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MsLevel {
     Ms1,
     Ms2,
@@ -18,7 +34,8 @@ pub enum MsLevel {
 }
 
 /// A single scan with all associated data.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Scan {
     pub scan_number: u32,
     /// Retention time in minutes.
@@ -34,28 +51,67 @@ pub struct Scan {
     pub profile_intensity: Option<Vec<f64>>,
     pub precursor: Option<PrecursorInfo>,
     pub filter_string: Option<String>,
+    /// Ion mobility drift time in milliseconds, where the instrument reports one
+    /// (e.g. Bruker timsTOF). `None` for instruments without an IM dimension.
+    pub ion_mobility: Option<f64>,
+    /// FAIMS compensation voltage in volts, parsed from the Thermo filter string
+    /// (`cv=...` token) or trailer extra values.
+    pub compensation_voltage: Option<f64>,
 }
 
 /// MS2+ precursor ion information.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PrecursorInfo {
     pub mz: f64,
     pub charge: Option<i32>,
     pub isolation_width: Option<f64>,
     pub activation_type: Option<String>,
     pub collision_energy: Option<f64>,
+    /// FAIMS compensation voltage in effect when this precursor was isolated.
+    pub compensation_voltage: Option<f64>,
 }
 
 /// A chromatogram (TIC, BPC, XIC, etc.).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Chromatogram {
     /// Retention times in minutes.
     pub rt: Vec<f64>,
     pub intensity: Vec<f64>,
 }
 
+/// Bootstrap-quantified extracted-ion-chromatogram peak area, as returned by
+/// `RawFile::xic_ms1_quant`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct XicQuantification {
+    /// Trapezoidal-integrated peak area over the chromatogram's full window.
+    pub area: f64,
+    /// Standard deviation of the area across bootstrap resamples (`0.0` if
+    /// there weren't enough points to bootstrap).
+    pub area_std: f64,
+    /// Lower bound (2.5th percentile) of the bootstrap confidence interval.
+    pub ci_low: f64,
+    /// Upper bound (97.5th percentile) of the bootstrap confidence interval.
+    pub ci_high: f64,
+    /// Retention time (minutes) of the chromatogram's most intense point.
+    pub rt_apex: f64,
+}
+
+/// A single transition's chromatogram from a targeted (SRM/MRM/PRM)
+/// acquisition: summed product-ion intensity vs. retention time for all
+/// scans sharing one isolation-window target m/z.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TransitionChromatogram {
+    pub precursor_mz: f64,
+    pub chromatogram: Chromatogram,
+}
+
 /// File-level metadata.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FileMetadata {
     pub creation_date: String,
     pub instrument_model: String,
@@ -67,7 +123,8 @@ pub struct FileMetadata {
 }
 
 /// Acquisition type classification based on MS2 scan event properties.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AcquisitionType {
     Ms1Only,
     Dda,
@@ -76,7 +133,8 @@ pub enum AcquisitionType {
 }
 
 /// A unique DIA isolation window derived from MS2 scan events.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct IsolationWindow {
     pub center_mz: f64,
     pub isolation_width: f64,
@@ -87,7 +145,8 @@ pub struct IsolationWindow {
 }
 
 /// Lightweight MS2 scan metadata derived from ScanIndex + ScanEvent (no scan data decode).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Ms2ScanInfo {
     pub scan_number: u32,
     pub rt: f64,
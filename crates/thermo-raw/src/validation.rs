@@ -1,12 +1,15 @@
 //! Ground truth validation framework.
 //!
-//! Loads JSON exported by C# GroundTruthExporter and compares against
-//! Rust parser output.
+//! Compares Rust parser output against a trusted ground truth, which can come
+//! from either JSON exported by the C# `GroundTruthExporter` or (via
+//! [`GroundTruthSource`]) any other source that can answer the same two
+//! questions: "what scans exist" and "what does scan N look like".
 
 use crate::raw_file::RawFile;
+use crate::types::MsLevel;
 use crate::RawError;
-use serde::Deserialize;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,6 +22,9 @@ pub struct GroundTruthScanIndex {
     pub base_peak_mz: f64,
     pub base_peak_intensity: f64,
     pub filter_string: String,
+    /// FAIMS compensation voltage, when the ground truth exporter reports one.
+    #[serde(default)]
+    pub compensation_voltage: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,19 +39,48 @@ pub struct GroundTruthScanData {
     pub profile_intensity: Option<Vec<f64>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ValidationResult {
     pub scan_number: u32,
+    pub ms_level: MsLevel,
     pub passed: bool,
     pub mz_max_error_ppm: f64,
     pub mz_mean_error_ppm: f64,
+    /// Signed mean ppm error (parsed - truth, not absolute); a non-zero
+    /// distribution-wide average here indicates a calibration offset rather
+    /// than scattered noise. `0.0` when there's no truth m/z data to compare.
+    pub mz_mean_signed_error_ppm: f64,
     pub intensity_max_relative_error: f64,
     pub rt_error_seconds: f64,
     pub peak_count_match: bool,
+    /// Absolute difference between parsed and ground-truth compensation voltage,
+    /// `None` when either side doesn't report one (e.g. non-FAIMS runs).
+    pub compensation_voltage_error: Option<f64>,
     pub errors: Vec<String>,
 }
 
-#[derive(Debug)]
+/// Summary statistics over a set of per-scan [`ValidationResult`]s: the
+/// median/p95/p99 of the two headline error metrics, plus a signed mean ppm
+/// error to surface calibration bias that a median/percentile would hide.
+///
+/// Percentiles use nearest-rank over the finite samples; scans with an
+/// `f64::INFINITY` error (peak count mismatch) are excluded from the math
+/// here but are still counted as failures in [`FileValidationReport`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ErrorDistribution {
+    /// Number of scans in this group, including those whose non-finite
+    /// errors are left out of the percentiles and the signed mean.
+    pub scan_count: u32,
+    pub mz_error_median_ppm: f64,
+    pub mz_error_p95_ppm: f64,
+    pub mz_error_p99_ppm: f64,
+    pub mz_mean_signed_error_ppm: f64,
+    pub intensity_error_median: f64,
+    pub intensity_error_p95: f64,
+    pub intensity_error_p99: f64,
+}
+
+#[derive(Debug, Serialize)]
 pub struct FileValidationReport {
     pub total_scans: u32,
     pub passed_scans: u32,
@@ -53,9 +88,113 @@ pub struct FileValidationReport {
     pub pass_rate: f64,
     pub worst_mz_error_ppm: f64,
     pub worst_intensity_error: f64,
+    /// Error distribution across every validated scan.
+    pub overall_stats: ErrorDistribution,
+    /// Error distribution restricted to MS1 scans.
+    pub ms1_stats: ErrorDistribution,
+    /// Error distribution restricted to MS2+ scans.
+    pub ms2_plus_stats: ErrorDistribution,
     pub failures: Vec<ValidationResult>,
 }
 
+impl FileValidationReport {
+    /// Serialize the full report (including per-scan failures) as JSON, for
+    /// archiving alongside a CI run.
+    pub fn to_json(&self) -> Result<String, RawError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| RawError::CorruptedData(format!("failed to serialize validation report: {e}")))
+    }
+
+    /// Serialize the file-level summary (not the per-scan failures) as a
+    /// single CSV row with a header, so repeated runs across many files can
+    /// be concatenated and diffed in CI.
+    pub fn to_csv(&self) -> String {
+        let header = "total_scans,passed_scans,failed_scans,pass_rate,worst_mz_error_ppm,worst_intensity_error,\
+mz_error_median_ppm,mz_error_p95_ppm,mz_error_p99_ppm,mz_mean_signed_error_ppm,\
+intensity_error_median,intensity_error_p95,intensity_error_p99,\
+ms1_mz_error_median_ppm,ms1_mz_mean_signed_error_ppm,ms1_intensity_error_median,\
+ms2_plus_mz_error_median_ppm,ms2_plus_mz_mean_signed_error_ppm,ms2_plus_intensity_error_median\n";
+        let row = format!(
+            "{},{},{},{:.6},{:.4},{:.6},{:.4},{:.4},{:.4},{:.4},{:.6},{:.6},{:.6},{:.4},{:.4},{:.6},{:.4},{:.4},{:.6}\n",
+            self.total_scans,
+            self.passed_scans,
+            self.failed_scans,
+            self.pass_rate,
+            self.worst_mz_error_ppm,
+            self.worst_intensity_error,
+            self.overall_stats.mz_error_median_ppm,
+            self.overall_stats.mz_error_p95_ppm,
+            self.overall_stats.mz_error_p99_ppm,
+            self.overall_stats.mz_mean_signed_error_ppm,
+            self.overall_stats.intensity_error_median,
+            self.overall_stats.intensity_error_p95,
+            self.overall_stats.intensity_error_p99,
+            self.ms1_stats.mz_error_median_ppm,
+            self.ms1_stats.mz_mean_signed_error_ppm,
+            self.ms1_stats.intensity_error_median,
+            self.ms2_plus_stats.mz_error_median_ppm,
+            self.ms2_plus_stats.mz_mean_signed_error_ppm,
+            self.ms2_plus_stats.intensity_error_median,
+        );
+        format!("{header}{row}")
+    }
+}
+
+/// Nearest-rank percentile over `sorted`, which must already be in
+/// ascending order. Returns `0.0` for an empty slice.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    let idx = rank.saturating_sub(1).min(sorted.len() - 1);
+    sorted[idx]
+}
+
+/// Build an [`ErrorDistribution`] from the `mz_max_error_ppm`/
+/// `mz_mean_signed_error_ppm`/`intensity_max_relative_error` of each result
+/// in `results`, skipping non-finite values (length-mismatch sentinels).
+/// The signed mean also skips values of exactly `0.0` (what a result
+/// carries when it had no m/z data to compare), so a scan whose signed
+/// error is truly zero does not count toward it either.
+fn build_distribution(results: &[&ValidationResult]) -> ErrorDistribution {
+    let mut mz_errors: Vec<f64> = results
+        .iter()
+        .map(|r| r.mz_max_error_ppm)
+        .filter(|v| v.is_finite())
+        .collect();
+    mz_errors.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mut intensity_errors: Vec<f64> = results
+        .iter()
+        .map(|r| r.intensity_max_relative_error)
+        .filter(|v| v.is_finite())
+        .collect();
+    intensity_errors.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let signed_errors: Vec<f64> = results
+        .iter()
+        .map(|r| r.mz_mean_signed_error_ppm)
+        .filter(|v| v.is_finite() && *v != 0.0)
+        .collect();
+    let mz_mean_signed_error_ppm = if signed_errors.is_empty() {
+        0.0
+    } else {
+        signed_errors.iter().sum::<f64>() / signed_errors.len() as f64
+    };
+
+    ErrorDistribution {
+        scan_count: results.len() as u32,
+        mz_error_median_ppm: percentile(&mz_errors, 50.0),
+        mz_error_p95_ppm: percentile(&mz_errors, 95.0),
+        mz_error_p99_ppm: percentile(&mz_errors, 99.0),
+        mz_mean_signed_error_ppm,
+        intensity_error_median: percentile(&intensity_errors, 50.0),
+        intensity_error_p95: percentile(&intensity_errors, 95.0),
+        intensity_error_p99: percentile(&intensity_errors, 99.0),
+    }
+}
+
 /// Acceptance criteria thresholds.
 pub struct ValidationCriteria {
     /// Maximum allowed m/z error in ppm (default: 0.1).
@@ -64,6 +203,8 @@ pub struct ValidationCriteria {
     pub intensity_rel_tolerance: f64,
     /// Maximum allowed RT error in minutes (default: 0.001).
     pub rt_tolerance_minutes: f64,
+    /// Maximum allowed FAIMS compensation voltage error in volts (default: 0.01).
+    pub compensation_voltage_tolerance: f64,
 }
 
 impl Default for ValidationCriteria {
@@ -72,10 +213,50 @@ impl Default for ValidationCriteria {
             mz_tolerance_ppm: 0.1,
             intensity_rel_tolerance: 1e-6,
             rt_tolerance_minutes: 0.001,
+            compensation_voltage_tolerance: 0.01,
+        }
+    }
+}
+
+/// A source of ground-truth scan data to validate against.
+///
+/// Implemented by [`JsonGroundTruthSource`] (the original C#
+/// `GroundTruthExporter` export) and by `thermo-raw-mzml`'s
+/// `MzmlGroundTruthSource`, which reads the same information out of an
+/// existing reference mzML file instead. [`validate_file`] is generic over
+/// this trait so either can be passed in.
+pub trait GroundTruthSource {
+    /// The full per-scan index (one entry per scan the ground truth covers).
+    fn scan_index(&self) -> Result<Vec<GroundTruthScanIndex>, RawError>;
+    /// Detailed peak data for a single scan, looked up by scan number.
+    fn scan_data(&self, scan_number: u32) -> Result<GroundTruthScanData, RawError>;
+}
+
+/// Ground truth loaded from a directory of JSON files produced by the C#
+/// `GroundTruthExporter` (a `scan_index.json` plus one `scans/scan_NNNNN.json`
+/// per scan).
+pub struct JsonGroundTruthSource {
+    truth_dir: PathBuf,
+}
+
+impl JsonGroundTruthSource {
+    pub fn new(truth_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            truth_dir: truth_dir.into(),
         }
     }
 }
 
+impl GroundTruthSource for JsonGroundTruthSource {
+    fn scan_index(&self) -> Result<Vec<GroundTruthScanIndex>, RawError> {
+        load_scan_index(&self.truth_dir)
+    }
+
+    fn scan_data(&self, scan_number: u32) -> Result<GroundTruthScanData, RawError> {
+        load_scan_data(&self.truth_dir, scan_number)
+    }
+}
+
 /// Load the scan index ground truth from a directory.
 pub fn load_scan_index(truth_dir: &Path) -> Result<Vec<GroundTruthScanIndex>, RawError> {
     let path = truth_dir.join("scan_index.json");
@@ -182,10 +363,10 @@ pub fn validate_intensity_arrays(
 }
 
 /// Validate a single scan against ground truth.
-fn validate_single_scan(
+fn validate_single_scan<S: GroundTruthSource>(
     raw: &RawFile,
     truth_index: &GroundTruthScanIndex,
-    truth_dir: &Path,
+    source: &S,
     criteria: &ValidationCriteria,
 ) -> ValidationResult {
     let scan_number = truth_index.scan_number;
@@ -197,12 +378,15 @@ fn validate_single_scan(
         Err(e) => {
             return ValidationResult {
                 scan_number,
+                ms_level: ms_level_from_u8(truth_index.ms_level),
                 passed: false,
                 mz_max_error_ppm: f64::INFINITY,
                 mz_mean_error_ppm: f64::INFINITY,
+                mz_mean_signed_error_ppm: 0.0,
                 intensity_max_relative_error: f64::INFINITY,
                 rt_error_seconds: f64::INFINITY,
                 peak_count_match: false,
+                compensation_voltage_error: None,
                 errors: vec![format!("Failed to read scan: {}", e)],
             };
         }
@@ -221,11 +405,12 @@ fn validate_single_scan(
     // Default values for m/z and intensity validation
     let mut mz_max_error_ppm = 0.0;
     let mut mz_mean_error_ppm = 0.0;
+    let mut mz_mean_signed_error_ppm = 0.0;
     let mut intensity_max_error = 0.0;
     let mut peak_count_match = true;
 
     // Load per-scan truth data if available
-    let scan_truth = load_scan_data(truth_dir, scan_number).ok();
+    let scan_truth = source.scan_data(scan_number).ok();
     if let Some(ref truth_data) = scan_truth {
         // Validate centroid m/z
         if let Some(ref truth_mz) = truth_data.centroid_mz {
@@ -234,6 +419,7 @@ fn validate_single_scan(
                 validate_mz_arrays(&scan.centroid_mz, truth_mz, criteria.mz_tolerance_ppm);
             mz_max_error_ppm = max_ppm;
             mz_mean_error_ppm = mean_ppm;
+            mz_mean_signed_error_ppm = mean_signed_ppm_error(&scan.centroid_mz, truth_mz);
             errors.extend(mz_errors);
         }
 
@@ -246,40 +432,91 @@ fn validate_single_scan(
         }
     }
 
+    // Validate compensation voltage, when both sides report one.
+    let compensation_voltage_error =
+        match (scan.compensation_voltage, truth_index.compensation_voltage) {
+            (Some(parsed), Some(truth)) => {
+                let diff = (parsed - truth).abs();
+                if diff > criteria.compensation_voltage_tolerance {
+                    errors.push(format!(
+                        "Compensation voltage error: parsed={:.3} truth={:.3} diff={:.3} V",
+                        parsed, truth, diff
+                    ));
+                }
+                Some(diff)
+            }
+            _ => None,
+        };
+
     let passed = errors.is_empty();
 
     ValidationResult {
         scan_number,
+        ms_level: scan.ms_level,
         passed,
         mz_max_error_ppm,
         mz_mean_error_ppm,
+        mz_mean_signed_error_ppm,
         intensity_max_relative_error: intensity_max_error,
         rt_error_seconds,
         peak_count_match,
+        compensation_voltage_error,
         errors,
     }
 }
 
+/// Map the ground-truth export's numeric ms level back to [`MsLevel`]
+/// (mirrors `thermo-raw-mzml`'s own level<->number mapping).
+fn ms_level_from_u8(level: u8) -> MsLevel {
+    match level {
+        1 => MsLevel::Ms1,
+        2 => MsLevel::Ms2,
+        3 => MsLevel::Ms3,
+        other => MsLevel::Other(other),
+    }
+}
+
+/// Mean of the *signed* per-peak ppm error (parsed - truth), used to surface
+/// a systematic calibration offset that the unsigned mean in
+/// [`validate_mz_arrays`] would average away. Returns `0.0` on a length
+/// mismatch (already reported separately via `peak_count_match`).
+fn mean_signed_ppm_error(parsed: &[f64], truth: &[f64]) -> f64 {
+    if parsed.len() != truth.len() || truth.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = parsed
+        .iter()
+        .zip(truth.iter())
+        .map(|(p, t)| if *t != 0.0 { (p - t) / t * 1e6 } else { 0.0 })
+        .sum();
+    sum / truth.len() as f64
+}
+
 /// Validate an entire RAW file against ground truth data.
 ///
+/// Generic over [`GroundTruthSource`] so callers can validate against either
+/// the C# `GroundTruthExporter` JSON export ([`JsonGroundTruthSource`]) or
+/// any other source of ground truth, such as `thermo-raw-mzml`'s
+/// `MzmlGroundTruthSource`.
+///
 /// Returns a FileValidationReport with per-scan results and aggregate statistics.
-pub fn validate_file(
+pub fn validate_file<S: GroundTruthSource>(
     raw: &RawFile,
-    truth_dir: &Path,
+    source: &S,
     criteria: &ValidationCriteria,
 ) -> Result<FileValidationReport, RawError> {
-    let truth_index = load_scan_index(truth_dir)?;
+    let truth_index = source.scan_index()?;
 
     let mut total_scans = 0u32;
     let mut passed_scans = 0u32;
     let mut failed_scans = 0u32;
     let mut worst_mz = 0.0f64;
     let mut worst_intensity = 0.0f64;
-    let mut failures = Vec::new();
+    let mut results = Vec::new();
 
     for truth in &truth_index {
         total_scans += 1;
-        let result = validate_single_scan(raw, truth, truth_dir, criteria);
+        let result = validate_single_scan(raw, truth, source, criteria);
 
         worst_mz = worst_mz.max(result.mz_max_error_ppm);
         worst_intensity = worst_intensity.max(result.intensity_max_relative_error);
@@ -288,8 +525,8 @@ pub fn validate_file(
             passed_scans += 1;
         } else {
             failed_scans += 1;
-            failures.push(result);
         }
+        results.push(result);
     }
 
     let pass_rate = if total_scans > 0 {
@@ -298,6 +535,23 @@ pub fn validate_file(
         1.0
     };
 
+    let all_refs: Vec<&ValidationResult> = results.iter().collect();
+    let overall_stats = build_distribution(&all_refs);
+    let ms1_refs: Vec<&ValidationResult> = all_refs
+        .iter()
+        .copied()
+        .filter(|r| matches!(r.ms_level, MsLevel::Ms1))
+        .collect();
+    let ms2_plus_refs: Vec<&ValidationResult> = all_refs
+        .iter()
+        .copied()
+        .filter(|r| !matches!(r.ms_level, MsLevel::Ms1))
+        .collect();
+    let ms1_stats = build_distribution(&ms1_refs);
+    let ms2_plus_stats = build_distribution(&ms2_plus_refs);
+
+    let failures = results.into_iter().filter(|r| !r.passed).collect();
+
     Ok(FileValidationReport {
         total_scans,
         passed_scans,
@@ -305,6 +559,9 @@ pub fn validate_file(
         pass_rate,
         worst_mz_error_ppm: worst_mz,
         worst_intensity_error: worst_intensity,
+        overall_stats,
+        ms1_stats,
+        ms2_plus_stats,
         failures,
     })
 }
@@ -369,4 +626,58 @@ mod tests {
         // relative error = 0.01/1000 = 1e-5
         assert!(max_err > 1e-6);
     }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let values = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        assert_eq!(percentile(&values, 50.0), 5.0);
+        assert_eq!(percentile(&values, 95.0), 10.0);
+        assert_eq!(percentile(&[], 50.0), 0.0);
+    }
+
+    #[test]
+    fn test_mean_signed_ppm_error_detects_calibration_offset() {
+        let truth = vec![100.0, 200.0, 300.0];
+        // Every peak parsed 1 ppm high: a systematic offset, not noise.
+        let parsed: Vec<f64> = truth.iter().map(|t| t * (1.0 + 1e-6)).collect();
+        let signed = mean_signed_ppm_error(&parsed, &truth);
+        assert!((signed - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_build_distribution_excludes_infinite_samples() {
+        let ok = ValidationResult {
+            scan_number: 1,
+            ms_level: MsLevel::Ms1,
+            passed: true,
+            mz_max_error_ppm: 1.0,
+            mz_mean_error_ppm: 0.5,
+            mz_mean_signed_error_ppm: 0.2,
+            intensity_max_relative_error: 1e-7,
+            rt_error_seconds: 0.0,
+            peak_count_match: true,
+            compensation_voltage_error: None,
+            errors: vec![],
+        };
+        let mismatched = ValidationResult {
+            scan_number: 2,
+            ms_level: MsLevel::Ms1,
+            passed: false,
+            mz_max_error_ppm: f64::INFINITY,
+            mz_mean_error_ppm: f64::INFINITY,
+            mz_mean_signed_error_ppm: 0.0,
+            intensity_max_relative_error: f64::INFINITY,
+            rt_error_seconds: 0.0,
+            peak_count_match: false,
+            compensation_voltage_error: None,
+            errors: vec!["Peak count mismatch".to_string()],
+        };
+        let refs = vec![&ok, &mismatched];
+        let dist = build_distribution(&refs);
+        // Both scans count toward scan_count...
+        assert_eq!(dist.scan_count, 2);
+        // ...but the infinite sample is excluded from the percentile math.
+        assert_eq!(dist.mz_error_median_ppm, 1.0);
+        assert!((dist.mz_mean_signed_error_ppm - 0.2).abs() < 1e-9);
+    }
 }
@@ -1,6 +1,6 @@
 //! RAW file version detection and handling.
 //!
-//! Thermo RAW files have version numbers typically in the range v57-v66.
+//! Thermo RAW files have version numbers typically in the range v31-v66.
 //! The version determines the exact layout of internal structures.
 //!
 //! Key version boundaries (from decompiled ThermoFisher.CommonCore.RawFileReader):
@@ -11,9 +11,16 @@
 //!   ScanEvent gains Name field, Reaction gains precursor mass range
 //! - v66: RunHeader gains InstrumentType field,
 //!   Reaction gains IsolationWidthOffset (48→56 bytes)
+//!
+//! Pre-v57 files (down to v31) use smaller, older struct variants for the
+//! same records -- [`scan_event_preamble_size`] and [`reaction_size`] pick
+//! the right one per version so the same field-offset readers
+//! (`scan_event::parse_preamble`, `scan_event::parse_reaction`) work
+//! unchanged: they're already length-gated and fall back to defaults for
+//! fields a smaller struct doesn't have.
 
 /// Minimum supported RAW file version.
-pub const MIN_SUPPORTED_VERSION: u32 = 57;
+pub const MIN_SUPPORTED_VERSION: u32 = 31;
 /// Maximum supported RAW file version.
 pub const MAX_SUPPORTED_VERSION: u32 = 66;
 
@@ -53,10 +60,10 @@ pub fn uses_64bit_addresses(version: u32) -> bool {
 /// - v63-64: ScanEventInfoStruct63 (128 bytes) - adds SupplementalActivation, CompensationVoltage
 /// - v62: ScanEventInfoStruct62 (120 bytes) - adds PulsedQ, ETD, HCD dissociation
 /// - v54-61: ScanEventInfoStruct54 (80 bytes) - adds MassAnalyzer, ECD, MPD, etc.
-/// - v51-53: ScanEventInfoStruct51
-/// - v48-50: ScanEventInfoStruct50
-/// - v31-47: ScanEventInfoStruct3
-/// - v<30: ScanEventInfoStruct2
+/// - v51-53: ScanEventInfoStruct51 (56 bytes)
+/// - v48-50: ScanEventInfoStruct50 (48 bytes)
+/// - v31-47: ScanEventInfoStruct3 (41 bytes)
+/// - v<31:   ScanEventInfoStruct2 (41 bytes, same fixed prefix as Struct3)
 pub fn scan_event_preamble_size(version: u32) -> usize {
     if version >= 65 {
         132
@@ -64,8 +71,12 @@ pub fn scan_event_preamble_size(version: u32) -> usize {
         128
     } else if version >= 62 {
         120
-    } else if version >= 57 {
+    } else if version >= 54 {
         80
+    } else if version >= 51 {
+        56
+    } else if version >= 48 {
+        48
     } else {
         41
     }
@@ -3,12 +3,15 @@
 //! These tests construct minimal binary buffers that mimic real RAW file structures
 //! and verify that the parsing pipeline handles them correctly.
 
+use thermo_raw::io_utils::BinaryReader;
+use thermo_raw::scan_data;
 use thermo_raw::scan_data_centroid;
 use thermo_raw::scan_data_profile;
 use thermo_raw::scan_event::{
     frequency_to_mz, ActivationType, AnalyzerType, IonizationType, ScanMode, ScanType,
 };
 use thermo_raw::scan_filter;
+use thermo_raw::types::{MsLevel, Polarity, Scan};
 use thermo_raw::validation;
 
 /// Build a minimal centroid data buffer: count + (f32 mz, f32 intensity) pairs.
@@ -47,7 +50,7 @@ fn test_centroid_decode_roundtrip() {
         (500.123, 12345.6),
     ];
     let data = build_centroid_data(&peaks);
-    let (mz, intensity) = scan_data_centroid::decode_centroid(&data, 0).unwrap();
+    let (mz, intensity) = scan_data_centroid::decode_centroid(&data[..], 0).unwrap();
 
     assert_eq!(mz.len(), 4);
     assert_eq!(intensity.len(), 4);
@@ -72,7 +75,7 @@ fn test_centroid_decode_roundtrip() {
 #[test]
 fn test_centroid_decode_empty() {
     let data = 0u32.to_le_bytes().to_vec();
-    let (mz, intensity) = scan_data_centroid::decode_centroid(&data, 0).unwrap();
+    let (mz, intensity) = scan_data_centroid::decode_centroid(&data[..], 0).unwrap();
     assert!(mz.is_empty());
     assert!(intensity.is_empty());
 }
@@ -84,7 +87,7 @@ fn test_profile_decode_roundtrip() {
     let signals: Vec<f32> = (0..100).map(|i| (i as f32) * 10.0).collect();
     let data = build_profile_data(first_value, step, 0, &signals);
 
-    let (mz, intensity) = scan_data_profile::decode_profile(&data, 0, 0).unwrap();
+    let (mz, intensity) = scan_data_profile::decode_profile(&data[..], 0, 0).unwrap();
 
     assert_eq!(mz.len(), 100);
     assert_eq!(intensity.len(), 100);
@@ -129,7 +132,7 @@ fn test_profile_decode_with_fudge() {
         data.extend_from_slice(&s.to_le_bytes());
     }
 
-    let (mz, intensity) = scan_data_profile::decode_profile(&data, 0, 1).unwrap();
+    let (mz, intensity) = scan_data_profile::decode_profile(&data[..], 0, 1).unwrap();
     assert_eq!(mz.len(), 3);
 
     // mz[0] = 400.0 + 10 * 0.005 = 400.05
@@ -139,6 +142,65 @@ fn test_profile_decode_with_fudge() {
     assert!((intensity[0] - 100.0).abs() < 1e-3);
 }
 
+#[test]
+fn test_encode_scan_legacy_roundtrip() {
+    let scan = Scan {
+        scan_number: 42,
+        rt: 1.23,
+        ms_level: MsLevel::Ms1,
+        polarity: Polarity::Positive,
+        tic: 0.0,
+        base_peak_mz: 0.0,
+        base_peak_intensity: 0.0,
+        centroid_mz: vec![100.5, 200.75, 300.25],
+        centroid_intensity: vec![1000.0, 2500.0, 500.0],
+        profile_mz: Some((0..50).map(|i| 200.0 + i as f64 * 0.01).collect()),
+        profile_intensity: Some((0..50).map(|i| i as f64 * 10.0).collect()),
+        precursor: None,
+        filter_string: None,
+        ion_mobility: None,
+        compensation_voltage: None,
+    };
+
+    let bytes = scan_data::encode_scan_legacy(&scan);
+
+    let header_bytes = &bytes[..scan_data::PacketHeader::SIZE];
+    let header = scan_data::PacketHeader::parse(&mut BinaryReader::new(header_bytes)).unwrap();
+    assert_eq!(header.layout, 0);
+
+    let profile_start = scan_data::PacketHeader::SIZE as u64;
+    let (profile_mz, profile_intensity) =
+        scan_data_profile::decode_profile(&bytes[..], profile_start, header.layout).unwrap();
+    let expected_profile_mz = scan.profile_mz.as_ref().unwrap();
+    assert_eq!(profile_mz.len(), expected_profile_mz.len());
+    // `step` is recovered as mz[1] - mz[0], so later bins can differ from
+    // the input grid by floating-point rounding.
+    for (got, want) in profile_mz.iter().zip(expected_profile_mz) {
+        assert!((got - want).abs() < 1e-9);
+    }
+    assert_eq!(profile_intensity, *scan.profile_intensity.as_ref().unwrap());
+
+    let peak_start = profile_start + header.profile_size as u64 * 4;
+    let (centroid_mz, centroid_intensity) =
+        scan_data_centroid::decode_centroid(&bytes[..], peak_start).unwrap();
+    assert_eq!(centroid_mz.len(), 3);
+    for (i, (&expected_mz, &expected_int)) in scan
+        .centroid_mz
+        .iter()
+        .zip(scan.centroid_intensity.iter())
+        .enumerate()
+    {
+        assert!((centroid_mz[i] - expected_mz).abs() < 1e-3);
+        assert!((centroid_intensity[i] - expected_int).abs() < 1e-1);
+    }
+
+    // low_mz/high_mz span both arrays: lowest is the first centroid peak
+    // (100.5), highest is the last centroid peak (300.25), which beats the
+    // profile's own top bin (200.0 + 49 * 0.01 = 200.49).
+    assert!((header.low_mz - 100.5).abs() < 1e-3);
+    assert!((header.high_mz - 300.25).abs() < 1e-3);
+}
+
 #[test]
 fn test_scan_filter_complex_ms2() {
     let filter =
@@ -147,10 +209,10 @@ fn test_scan_filter_complex_ms2() {
     assert_eq!(filter.polarity, thermo_raw::Polarity::Positive);
     assert_eq!(filter.analyzer, "FTMS");
 
-    let p = filter.precursor.unwrap();
+    let p = filter.precursor().unwrap();
     assert!((p.mz - 524.2648).abs() < 1e-4);
-    assert_eq!(p.activation, "hcd");
-    assert!((p.collision_energy - 28.0).abs() < 0.01);
+    assert_eq!(p.activation(), "hcd");
+    assert!((p.collision_energy() - 28.0).abs() < 0.01);
 
     let (low, high) = filter.mass_range.unwrap();
     assert!((low - 100.0).abs() < 0.01);
@@ -273,8 +335,11 @@ fn test_serde_roundtrip_scan() {
             isolation_width: Some(1.5),
             activation_type: Some("HCD".to_string()),
             collision_energy: Some(28.0),
+            compensation_voltage: None,
         }),
         filter_string: Some("FTMS + c NSI d Full ms2 524.2648@hcd28.00".to_string()),
+        ion_mobility: None,
+        compensation_voltage: None,
     };
 
     let json = serde_json::to_string(&scan).unwrap();
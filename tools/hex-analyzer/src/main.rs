@@ -1,6 +1,10 @@
 use clap::{Parser, Subcommand};
+use flate2::read::{DeflateDecoder, ZlibDecoder};
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(
@@ -23,6 +27,12 @@ enum Commands {
         /// Limit search range: offset:length
         #[arg(long)]
         range: Option<String>,
+        /// Also search inside zlib-compressed streams found in the file.
+        #[arg(long)]
+        decompress: bool,
+        /// Minimum inflated size (bytes) for a candidate stream to be trusted, when --decompress is set.
+        #[arg(long, default_value_t = DEFAULT_MIN_INFLATED_SIZE)]
+        min_inflated_size: usize,
     },
 
     /// Search for an f32 value in a file.
@@ -31,13 +41,34 @@ enum Commands {
         value: f32,
         #[arg(long, default_value = "1e-5")]
         tolerance: f32,
+        /// Also search inside zlib-compressed streams found in the file.
+        #[arg(long)]
+        decompress: bool,
+        #[arg(long, default_value_t = DEFAULT_MIN_INFLATED_SIZE)]
+        min_inflated_size: usize,
     },
 
     /// Search for a u32 value in a file.
-    SearchU32 { file: PathBuf, value: u32 },
+    SearchU32 {
+        file: PathBuf,
+        value: u32,
+        /// Also search inside zlib-compressed streams found in the file.
+        #[arg(long)]
+        decompress: bool,
+        #[arg(long, default_value_t = DEFAULT_MIN_INFLATED_SIZE)]
+        min_inflated_size: usize,
+    },
 
     /// Search for a UTF-16LE string in a file.
-    SearchUtf16 { file: PathBuf, pattern: String },
+    SearchUtf16 {
+        file: PathBuf,
+        pattern: String,
+        /// Also search inside zlib-compressed streams found in the file.
+        #[arg(long)]
+        decompress: bool,
+        #[arg(long, default_value_t = DEFAULT_MIN_INFLATED_SIZE)]
+        min_inflated_size: usize,
+    },
 
     /// Hex dump a region of a file.
     Dump {
@@ -69,6 +100,29 @@ enum Commands {
         max_diffs: usize,
     },
 
+    /// List zlib/deflate-compressed streams embedded in a file, without
+    /// searching them -- useful to see what `--decompress` would cover.
+    ScanCompressed {
+        file: PathBuf,
+        #[arg(long, default_value_t = DEFAULT_MIN_INFLATED_SIZE)]
+        min_inflated_size: usize,
+        /// Also probe for headerless (bare) deflate streams. Expensive: with
+        /// no magic byte to anchor on, this attempts an inflate at every
+        /// remaining offset instead of just the `0x78 01/9C/DA` candidates.
+        #[arg(long)]
+        include_raw_deflate: bool,
+    },
+
+    /// Slide a window across a file, classifying each by byte entropy and
+    /// structure, to show where in a multi-GB RAW file to point
+    /// `SearchF64`/`SearchUtf16`/etc. instead of searching blindly.
+    Profile {
+        file: PathBuf,
+        /// Window size in bytes.
+        #[arg(long, default_value_t = PROFILE_WINDOW_SIZE)]
+        window: usize,
+    },
+
     /// Given ground truth JSON, auto-locate fields in the binary.
     AutoLocate {
         /// RAW file path.
@@ -76,6 +130,686 @@ enum Commands {
         /// Ground truth directory (with scan_index.json, metadata.json, etc.).
         truth_dir: PathBuf,
     },
+
+    /// Confirm an `AutoLocate` candidate holds across the whole run, rather
+    /// than just the handful of scans `AutoLocate` itself probes, and emit a
+    /// `layout.json` manifest on success.
+    AutoLocateVerify {
+        /// RAW file path.
+        raw_file: PathBuf,
+        /// Ground truth directory (with scan_index.json).
+        truth_dir: PathBuf,
+        /// One candidate field to verify, as `name:base_offset:stride:type`,
+        /// e.g. `rt:4096:128:f64`. `name` must be a key present on every
+        /// entry in scan_index.json (`rt`, `tic`, ...). Repeatable.
+        #[arg(long = "field")]
+        fields: Vec<String>,
+        /// Maximum absolute residual (or relative to the ground-truth
+        /// magnitude, whichever is larger) for a scan to count as matched.
+        #[arg(long, default_value = "1e-6")]
+        tolerance: f64,
+        /// Manifest output path. Only written if every requested field
+        /// matched on every scan.
+        #[arg(long, default_value = "layout.json")]
+        out: PathBuf,
+    },
+
+    /// Locate known structures in a file using a signature database, scoring
+    /// each anchor occurrence by how many of its typed fields check out.
+    ScanSignatures {
+        file: PathBuf,
+        /// JSON signature database -- see [`SignatureDb`].
+        sig_db: PathBuf,
+        /// Only print matches whose score (fraction of fields that
+        /// validated) is at least this.
+        #[arg(long, default_value = "0.5")]
+        min_score: f64,
+    },
+
+    /// Emit a draft signature from one confirmed match: read `anchor_len`
+    /// bytes at `offset` as the anchor pattern, and record each `--field`'s
+    /// current decoded value as its expected value. The draft is printed to
+    /// stdout (or written to `--out`) for hand-editing -- e.g. turning some
+    /// anchor bytes into `??` wildcards, or dropping `expected` so a field is
+    /// only extracted, not verified.
+    Learn {
+        file: PathBuf,
+        #[arg(long)]
+        offset: usize,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        anchor_len: usize,
+        /// One field per occurrence, as `relative_offset:type` or
+        /// `relative_offset:utf16:len`, e.g. `8:f64` or `32:utf16:64`.
+        #[arg(long = "field")]
+        fields: Vec<String>,
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+/// Size of each window [`scan_file`] hands to a search function. Chosen to
+/// keep peak memory bounded regardless of the source file's size -- a
+/// multi-GB Thermo RAW file is paged in by the OS 16 MiB at a time instead
+/// of being read into one giant `Vec<u8>`.
+const WINDOW_SIZE: usize = 16 * 1024 * 1024;
+
+/// Read-only view of a file backed by `memmap2`, so every subcommand below
+/// can treat a multi-gigabyte RAW file as a `&[u8]` without copying it into
+/// memory first.
+struct Scanner {
+    mmap: Mmap,
+}
+
+impl Scanner {
+    fn open(path: &Path) -> std::io::Result<Self> {
+        let file = fs::File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self { mmap })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.mmap[..]
+    }
+
+    fn len(&self) -> usize {
+        self.mmap.len()
+    }
+}
+
+/// Split `total_len` bytes into `window_size`-byte windows, each overlapping
+/// the previous one by `overlap` bytes, as `(start, end)` byte ranges.
+///
+/// The overlap exists so a fixed-width match (f64/u32/UTF-16 pattern, ...)
+/// that straddles a window boundary is still fully contained in at least
+/// one window -- callers pass `overlap = width - 1` for a `width`-byte
+/// match. The final window is shrunk to end exactly at `total_len` rather
+/// than stepping past it.
+fn window_ranges(total_len: usize, window_size: usize, overlap: usize) -> Vec<(usize, usize)> {
+    if total_len == 0 {
+        return Vec::new();
+    }
+    let step = window_size.saturating_sub(overlap).max(1);
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + window_size).min(total_len);
+        ranges.push((start, end));
+        if end == total_len {
+            break;
+        }
+        start += step;
+    }
+    ranges
+}
+
+/// Run `search_window` over `scanner` one [`WINDOW_SIZE`] window at a time,
+/// joining the results into file-absolute offsets. `min_width` is the byte
+/// width of whatever `search_window` looks for (8 for f64, 4 for u32, the
+/// encoded length for a UTF-16 pattern, ...) -- it sets the overlap between
+/// windows so a match straddling a window boundary is never missed.
+///
+/// `search_window` only reports matches fully contained in the window it's
+/// given, so a window only ever reports local offsets up to
+/// `window.len() - min_width`. Since consecutive windows step by exactly
+/// `window_size - (min_width - 1)`, that's precisely the number of start
+/// positions between one window's start and the next -- so the absolute
+/// offset ranges reported by consecutive windows are adjacent, never
+/// overlapping, and no deduping is needed.
+fn scan_file<T>(
+    scanner: &Scanner,
+    min_width: usize,
+    search_window: impl Fn(&[u8]) -> Vec<(usize, T)>,
+) -> Vec<(usize, T)> {
+    scan_file_with_window_size(scanner, min_width, WINDOW_SIZE, search_window)
+}
+
+/// [`scan_file`] parameterized over the window size, so tests can exercise
+/// the multi-window path with a small window instead of [`WINDOW_SIZE`].
+fn scan_file_with_window_size<T>(
+    scanner: &Scanner,
+    min_width: usize,
+    window_size: usize,
+    search_window: impl Fn(&[u8]) -> Vec<(usize, T)>,
+) -> Vec<(usize, T)> {
+    let overlap = min_width.saturating_sub(1);
+    let window_size = window_size.max(min_width);
+    let data = scanner.as_slice();
+
+    let mut hits = Vec::new();
+    for (start, end) in window_ranges(data.len(), window_size, overlap) {
+        for (local_offset, value) in search_window(&data[start..end]) {
+            hits.push((start + local_offset, value));
+        }
+    }
+    hits
+}
+
+/// [`scan_file`] for search functions that only report an offset (no
+/// matched value worth carrying along, e.g. [`search_u32`]/[`search_utf16le`]).
+fn scan_file_offsets(
+    scanner: &Scanner,
+    min_width: usize,
+    search_window: impl Fn(&[u8]) -> Vec<usize>,
+) -> Vec<usize> {
+    scan_file(scanner, min_width, |w| {
+        search_window(w).into_iter().map(|o| (o, ())).collect()
+    })
+    .into_iter()
+    .map(|(o, ())| o)
+    .collect()
+}
+
+/// Default floor on inflated size for a candidate compressed stream to be
+/// accepted by [`find_compressed_streams`] -- trying to inflate a bare
+/// `0x78 01` byte pair elsewhere in the file (not an actual zlib stream)
+/// typically either errors immediately or produces only a handful of
+/// garbage bytes, so anything shorter than this is assumed to be noise
+/// rather than an actual embedded spectrum.
+const DEFAULT_MIN_INFLATED_SIZE: usize = 64;
+
+/// Whether a [`CompressedRegion`] was anchored on a zlib header or found as
+/// a headerless deflate stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompressedKind {
+    Zlib,
+    RawDeflate,
+}
+
+/// A zlib/deflate-compressed region found by [`find_compressed_streams`],
+/// already inflated so callers can search its contents directly.
+struct CompressedRegion {
+    /// Offset of the stream's first byte (the zlib header, or the first
+    /// deflate byte for a bare stream) in the original file.
+    compressed_offset: usize,
+    /// Number of compressed bytes the decoder actually consumed -- used to
+    /// advance the scan past this stream instead of rescanning its own
+    /// bytes as further candidates.
+    compressed_len: usize,
+    inflated: Vec<u8>,
+    kind: CompressedKind,
+}
+
+/// Attempt to inflate `data[offset..]` as `kind`, accepting the result only
+/// if it decodes cleanly and produces at least `min_inflated_size` bytes --
+/// the "contiguous run, plausible length" guard against false-positive
+/// stream starts.
+fn try_inflate_at(
+    data: &[u8],
+    offset: usize,
+    min_inflated_size: usize,
+    kind: CompressedKind,
+) -> Option<CompressedRegion> {
+    let mut inflated = Vec::new();
+    let (consumed, ok) = match kind {
+        CompressedKind::Zlib => {
+            let mut decoder = ZlibDecoder::new(&data[offset..]);
+            let ok = decoder.read_to_end(&mut inflated).is_ok();
+            (decoder.total_in() as usize, ok)
+        }
+        CompressedKind::RawDeflate => {
+            let mut decoder = DeflateDecoder::new(&data[offset..]);
+            let ok = decoder.read_to_end(&mut inflated).is_ok();
+            (decoder.total_in() as usize, ok)
+        }
+    };
+
+    if !ok || consumed == 0 || inflated.len() < min_inflated_size {
+        return None;
+    }
+
+    Some(CompressedRegion {
+        compressed_offset: offset,
+        compressed_len: consumed,
+        inflated,
+        kind,
+    })
+}
+
+/// Walk `scanner` looking for zlib stream starts (`0x78` followed by
+/// `0x01`/`0x9C`/`0xDA`, the common compression-level second bytes), and --
+/// if `include_raw_deflate` is set -- headerless deflate streams at every
+/// other offset. Each accepted stream's compressed bytes are skipped over
+/// rather than rescanned, so a valid stream is never reported twice.
+///
+/// `include_raw_deflate` defaults to off: with no magic byte to anchor on,
+/// a bare-deflate probe has to attempt an inflate at essentially every
+/// remaining byte offset, which is far slower than the zlib-anchored scan
+/// above and produces more false-accepts for short `min_inflated_size`
+/// values.
+fn find_compressed_streams(
+    scanner: &Scanner,
+    min_inflated_size: usize,
+    include_raw_deflate: bool,
+) -> Vec<CompressedRegion> {
+    let data = scanner.as_slice();
+    let mut regions = Vec::new();
+    let mut i = 0;
+    while i + 1 < data.len() {
+        if data[i] == 0x78 && matches!(data[i + 1], 0x01 | 0x9C | 0xDA) {
+            if let Some(region) = try_inflate_at(data, i, min_inflated_size, CompressedKind::Zlib) {
+                i += region.compressed_len.max(1);
+                regions.push(region);
+                continue;
+            }
+        }
+        if include_raw_deflate {
+            if let Some(region) =
+                try_inflate_at(data, i, min_inflated_size, CompressedKind::RawDeflate)
+            {
+                i += region.compressed_len.max(1);
+                regions.push(region);
+                continue;
+            }
+        }
+        i += 1;
+    }
+    regions
+}
+
+/// Where a search hit was found: a plain file offset, or -- when
+/// `--decompress` uncovered it inside a compressed stream -- the stream's
+/// own file offset together with the hit's offset inside the inflated
+/// bytes, as the request asks for.
+enum HitLocation {
+    Plain(usize),
+    Compressed {
+        compressed_stream_offset: usize,
+        inflated_inner_offset: usize,
+    },
+}
+
+impl std::fmt::Display for HitLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HitLocation::Plain(offset) => write!(f, "0x{:08X}", offset),
+            HitLocation::Compressed {
+                compressed_stream_offset,
+                inflated_inner_offset,
+            } => write!(
+                f,
+                "stream@0x{:08X} + inflated offset 0x{:X}",
+                compressed_stream_offset, inflated_inner_offset
+            ),
+        }
+    }
+}
+
+/// Run a value-returning search function over every region's inflated
+/// bytes, tagging each hit with its `(compressed_stream_offset,
+/// inflated_inner_offset)` location.
+fn decompressed_hits<T>(
+    regions: &[CompressedRegion],
+    search: impl Fn(&[u8]) -> Vec<(usize, T)>,
+) -> Vec<(HitLocation, T)> {
+    let mut hits = Vec::new();
+    for region in regions {
+        for (local_offset, value) in search(&region.inflated) {
+            hits.push((
+                HitLocation::Compressed {
+                    compressed_stream_offset: region.compressed_offset,
+                    inflated_inner_offset: local_offset,
+                },
+                value,
+            ));
+        }
+    }
+    hits
+}
+
+/// [`decompressed_hits`] for offset-only search functions.
+fn decompressed_offsets(
+    regions: &[CompressedRegion],
+    search: impl Fn(&[u8]) -> Vec<usize>,
+) -> Vec<HitLocation> {
+    decompressed_hits(regions, |w| search(w).into_iter().map(|o| (o, ())).collect())
+        .into_iter()
+        .map(|(loc, ())| loc)
+        .collect()
+}
+
+/// A database of known structures to recognize in a RAW file, loaded from
+/// JSON. Ported from the signature-matching concept used by decomp-toolkit:
+/// an anchor byte pattern (with optional wildcard bytes) that's cheap to
+/// scan for, followed by a handful of typed fields at fixed offsets from the
+/// anchor whose values (if known) confirm the match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignatureDb {
+    signatures: Vec<Signature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Signature {
+    name: String,
+    /// Anchor bytes, each a two hex digit token (`"4D"`) or `"??"` for a
+    /// byte that can be anything.
+    anchor: Vec<String>,
+    fields: Vec<SignatureField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignatureField {
+    /// Byte offset of this field relative to the anchor's first byte.
+    relative_offset: i64,
+    #[serde(rename = "type")]
+    field_type: FieldType,
+    /// What this field must decode to for it to count toward the match
+    /// score. `None` means the field is only extracted and reported, not
+    /// verified.
+    #[serde(default)]
+    expected: Option<ExpectedValue>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FieldType {
+    F64,
+    F32,
+    U32,
+    I32,
+    /// UTF-16LE string of `len` code units.
+    Utf16 { len: usize },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ExpectedValue {
+    Range { min: f64, max: f64 },
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+enum DecodedValue {
+    F64(f64),
+    F32(f32),
+    U32(u32),
+    I32(i32),
+    Text(String),
+}
+
+impl std::fmt::Display for DecodedValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodedValue::F64(v) => write!(f, "{:.6}", v),
+            DecodedValue::F32(v) => write!(f, "{:.6}", v),
+            DecodedValue::U32(v) => write!(f, "{}", v),
+            DecodedValue::I32(v) => write!(f, "{}", v),
+            DecodedValue::Text(v) => write!(f, "{:?}", v),
+        }
+    }
+}
+
+/// One byte of a [`Signature`]'s anchor pattern: a fixed value or a
+/// wildcard that matches anything.
+#[derive(Debug, Clone, Copy)]
+enum AnchorByte {
+    Exact(u8),
+    Wildcard,
+}
+
+fn parse_anchor(anchor: &[String]) -> Result<Vec<AnchorByte>, String> {
+    anchor
+        .iter()
+        .map(|tok| {
+            if tok == "??" {
+                Ok(AnchorByte::Wildcard)
+            } else {
+                u8::from_str_radix(tok, 16)
+                    .map(AnchorByte::Exact)
+                    .map_err(|_| format!("invalid anchor byte token {:?}", tok))
+            }
+        })
+        .collect()
+}
+
+fn search_anchor(data: &[u8], anchor: &[AnchorByte]) -> Vec<usize> {
+    let mut hits = Vec::new();
+    if anchor.is_empty() || data.len() < anchor.len() {
+        return hits;
+    }
+    for i in 0..=data.len() - anchor.len() {
+        let matched = anchor.iter().enumerate().all(|(j, b)| match b {
+            AnchorByte::Exact(expected) => data[i + j] == *expected,
+            AnchorByte::Wildcard => true,
+        });
+        if matched {
+            hits.push(i);
+        }
+    }
+    hits
+}
+
+/// Decode one field at `anchor_offset + field.relative_offset`, or `None` if
+/// that range falls outside `data`.
+fn decode_field(data: &[u8], anchor_offset: usize, field: &SignatureField) -> Option<DecodedValue> {
+    let abs = anchor_offset as i64 + field.relative_offset;
+    if abs < 0 {
+        return None;
+    }
+    let abs = abs as usize;
+
+    match field.field_type {
+        FieldType::F64 => {
+            let bytes = data.get(abs..abs + 8)?;
+            Some(DecodedValue::F64(f64::from_le_bytes(
+                bytes.try_into().unwrap(),
+            )))
+        }
+        FieldType::F32 => {
+            let bytes = data.get(abs..abs + 4)?;
+            Some(DecodedValue::F32(f32::from_le_bytes(
+                bytes.try_into().unwrap(),
+            )))
+        }
+        FieldType::U32 => {
+            let bytes = data.get(abs..abs + 4)?;
+            Some(DecodedValue::U32(u32::from_le_bytes(
+                bytes.try_into().unwrap(),
+            )))
+        }
+        FieldType::I32 => {
+            let bytes = data.get(abs..abs + 4)?;
+            Some(DecodedValue::I32(i32::from_le_bytes(
+                bytes.try_into().unwrap(),
+            )))
+        }
+        FieldType::Utf16 { len } => {
+            let bytes = data.get(abs..abs + len * 2)?;
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            Some(DecodedValue::Text(String::from_utf16_lossy(&units)))
+        }
+    }
+}
+
+/// Whether `value` satisfies `expected`, within a relative tolerance for
+/// numeric comparisons (mirrors [`search_f64`]/[`search_f32`]'s tolerance
+/// handling).
+fn field_satisfies(value: &DecodedValue, expected: &ExpectedValue) -> bool {
+    let as_f64 = match value {
+        DecodedValue::F64(v) => Some(*v),
+        DecodedValue::F32(v) => Some(*v as f64),
+        DecodedValue::U32(v) => Some(*v as f64),
+        DecodedValue::I32(v) => Some(*v as f64),
+        DecodedValue::Text(_) => None,
+    };
+
+    match (expected, value) {
+        (ExpectedValue::Text(expected), DecodedValue::Text(actual)) => actual == expected,
+        (ExpectedValue::Number(target), _) => {
+            as_f64.is_some_and(|v| (v - target).abs() <= 1e-6_f64.max(target.abs() * 1e-6))
+        }
+        (ExpectedValue::Range { min, max }, _) => as_f64.is_some_and(|v| v >= *min && v <= *max),
+        _ => false,
+    }
+}
+
+/// One anchor occurrence scored against a [`Signature`]'s fields.
+struct SignatureMatch {
+    offset: usize,
+    /// Fraction of fields (in `[0.0, 1.0]`) that decoded and either have no
+    /// `expected` value (decode-only) or satisfy it; a field whose range falls
+    /// outside the data never counts. A signature with no fields at all
+    /// always scores 1.0.
+    score: f64,
+    fields: Vec<(SignatureField, Option<DecodedValue>)>,
+}
+
+fn score_signature_matches(data: &[u8], signature: &Signature, anchor: &[AnchorByte]) -> Vec<SignatureMatch> {
+    search_anchor(data, anchor)
+        .into_iter()
+        .map(|offset| {
+            let fields: Vec<(SignatureField, Option<DecodedValue>)> = signature
+                .fields
+                .iter()
+                .map(|field| (field.clone(), decode_field(data, offset, field)))
+                .collect();
+
+            let score = if fields.is_empty() {
+                1.0
+            } else {
+                let satisfied = fields
+                    .iter()
+                    .filter(|(field, decoded)| match (&field.expected, decoded) {
+                        (None, Some(_)) => true,
+                        (None, None) => false,
+                        (Some(expected), Some(value)) => field_satisfies(value, expected),
+                        (Some(_), None) => false,
+                    })
+                    .count();
+                satisfied as f64 / fields.len() as f64
+            };
+
+            SignatureMatch {
+                offset,
+                score,
+                fields,
+            }
+        })
+        .collect()
+}
+
+/// A candidate field layout to verify: `name:base_offset:stride:type`, as
+/// parsed from an [`AutoLocateVerify`](Commands::AutoLocateVerify) `--field`.
+struct CandidateField {
+    name: String,
+    base_offset: usize,
+    stride: usize,
+    field_type: FieldType,
+}
+
+fn parse_candidate_field(spec: &str) -> CandidateField {
+    let parts: Vec<&str> = spec.split(':').collect();
+    let field_type = match parts.get(3).copied() {
+        Some("f64") | None => FieldType::F64,
+        Some("f32") => FieldType::F32,
+        Some("u32") => FieldType::U32,
+        Some("i32") => FieldType::I32,
+        Some(other) => panic!("unsupported field type {:?} in --field {:?}", other, spec),
+    };
+    CandidateField {
+        name: parts[0].to_string(),
+        base_offset: parts[1].parse().expect("invalid base_offset"),
+        stride: parts[2].parse().expect("invalid stride"),
+        field_type,
+    }
+}
+
+/// Result of checking one [`CandidateField`] against every scan's
+/// ground-truth value.
+struct FieldVerification {
+    n_scans: usize,
+    n_matched: usize,
+    max_abs_residual: f64,
+    first_failing_scan: Option<usize>,
+}
+
+/// Known units for the field names `AutoLocate` discovers ground truth for.
+/// `None` when `name` isn't one we recognize -- the manifest just omits
+/// units for those rather than guessing.
+fn field_units(name: &str) -> Option<&'static str> {
+    match name {
+        "rt" => Some("minutes"),
+        "tic" => Some("intensity counts"),
+        _ => None,
+    }
+}
+
+/// Read `candidate.field_type` at `base_offset + i*stride` for every scan
+/// `i` and compare it against `scans[i][candidate.name]`.
+fn verify_candidate_field(
+    data: &[u8],
+    scans: &[serde_json::Value],
+    candidate: &CandidateField,
+    tolerance: f64,
+) -> FieldVerification {
+    let dummy_field = SignatureField {
+        relative_offset: 0,
+        field_type: candidate.field_type,
+        expected: None,
+    };
+
+    let mut n_matched = 0;
+    let mut max_abs_residual = 0.0_f64;
+    let mut first_failing_scan = None;
+
+    for (i, scan) in scans.iter().enumerate() {
+        let Some(truth) = scan[candidate.name.as_str()].as_f64() else {
+            first_failing_scan.get_or_insert(i);
+            continue;
+        };
+        let abs_offset = candidate.base_offset + i * candidate.stride;
+        let decoded = decode_field(data, abs_offset, &dummy_field);
+        let actual = match decoded {
+            Some(DecodedValue::F64(v)) => v,
+            Some(DecodedValue::F32(v)) => v as f64,
+            Some(DecodedValue::U32(v)) => v as f64,
+            Some(DecodedValue::I32(v)) => v as f64,
+            _ => {
+                first_failing_scan.get_or_insert(i);
+                continue;
+            }
+        };
+
+        let residual = (actual - truth).abs();
+        max_abs_residual = max_abs_residual.max(residual);
+        if residual <= tolerance.max(truth.abs() * tolerance) {
+            n_matched += 1;
+        } else {
+            first_failing_scan.get_or_insert(i);
+        }
+    }
+
+    FieldVerification {
+        n_scans: scans.len(),
+        n_matched,
+        max_abs_residual,
+        first_failing_scan,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct LayoutField {
+    name: String,
+    base_offset: usize,
+    stride: usize,
+    #[serde(rename = "type")]
+    field_type: FieldType,
+    units: Option<&'static str>,
+}
+
+/// Manifest written by `AutoLocateVerify` on success, so a downstream parser
+/// can read fields directly at their learned offsets instead of re-running
+/// the discovery heuristics.
+#[derive(Debug, Clone, Serialize)]
+struct LayoutManifest {
+    raw_file: String,
+    n_scans: usize,
+    fields: Vec<LayoutField>,
 }
 
 fn search_f64(data: &[u8], target: f64, tolerance: f64) -> Vec<(usize, f64)> {
@@ -187,8 +921,9 @@ fn hex_dump(data: &[u8], offset: usize, length: usize, interpret: bool) {
     }
 }
 
-fn detect_stride(data: &[u8], values: &[f64], tolerance: f64) -> Vec<(usize, usize, usize)> {
-    let first_hits = search_f64(data, values[0], tolerance);
+fn detect_stride(scanner: &Scanner, values: &[f64], tolerance: f64) -> Vec<(usize, usize, usize)> {
+    let first_hits = scan_file(scanner, 8, |w| search_f64(w, values[0], tolerance));
+    let data = scanner.as_slice();
     let mut results = Vec::new();
 
     for (hit_offset, _) in &first_hits {
@@ -218,6 +953,160 @@ fn detect_stride(data: &[u8], values: &[f64], tolerance: f64) -> Vec<(usize, usi
     results
 }
 
+/// Default window size for [`profile_file`] -- small enough to localize
+/// region boundaries (header vs. string table vs. scan data) without the
+/// per-window entropy/lane checks costing much over a multi-GB file.
+const PROFILE_WINDOW_SIZE: usize = 4096;
+
+/// What a [`profile_file`] window looks like, from least to most "random".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WindowClass {
+    /// Entropy near zero -- padding or a run of a constant byte.
+    Padding,
+    /// Moderate entropy with most 2-byte lanes looking like UTF-16LE ASCII
+    /// (a `0x00` high byte paired with a printable or null low byte).
+    Utf16Text,
+    /// High entropy, but most 8-byte lanes decode to a finite f64 in a
+    /// plausible magnitude range -- an array of f64/f32 values, not noise.
+    NumericArray,
+    /// Entropy near the 8.0 bit/byte ceiling with no numeric-lane or text
+    /// structure -- compressed or encrypted bytes.
+    CompressedOrEncrypted,
+    /// Doesn't clearly fit any of the above.
+    Mixed,
+}
+
+impl std::fmt::Display for WindowClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            WindowClass::Padding => "padding",
+            WindowClass::Utf16Text => "utf16_text",
+            WindowClass::NumericArray => "numeric_array",
+            WindowClass::CompressedOrEncrypted => "compressed_or_encrypted",
+            WindowClass::Mixed => "mixed",
+        })
+    }
+}
+
+/// Shannon entropy in bits/byte over `window`'s 256-symbol histogram.
+fn shannon_entropy(window: &[u8]) -> f64 {
+    if window.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0u32; 256];
+    for &b in window {
+        counts[b as usize] += 1;
+    }
+    let len = window.len() as f64;
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Fraction of non-overlapping 2-byte lanes that look like UTF-16LE ASCII:
+/// a zero high byte paired with a printable (or null-terminator) low byte.
+fn utf16_text_lane_fraction(window: &[u8]) -> f64 {
+    if window.len() < 2 {
+        return 0.0;
+    }
+    let lanes = window.chunks_exact(2);
+    let total = lanes.len();
+    let matched = lanes
+        .filter(|lane| lane[1] == 0 && (lane[0] == 0 || (0x20..0x7F).contains(&lane[0])))
+        .count();
+    matched as f64 / total as f64
+}
+
+/// Fraction of non-overlapping 8-byte lanes that decode to a finite,
+/// plausibly-scaled f64 -- the signature of an array of mass/intensity
+/// values rather than compressed or random bytes.
+fn numeric_lane_fraction(window: &[u8]) -> f64 {
+    if window.len() < 8 {
+        return 0.0;
+    }
+    let lanes = window.chunks_exact(8);
+    let total = lanes.len();
+    let matched = lanes
+        .filter(|lane| {
+            let v = f64::from_le_bytes(lane.try_into().unwrap());
+            v.is_finite() && v != 0.0 && v.abs() > 1e-300 && v.abs() < 1e15
+        })
+        .count();
+    matched as f64 / total as f64
+}
+
+/// Entropy threshold below which a window is [`WindowClass::Padding`].
+const PADDING_ENTROPY_THRESHOLD: f64 = 1.0;
+/// Entropy threshold above which a window is considered
+/// [`WindowClass::CompressedOrEncrypted`], absent numeric/text structure.
+const HIGH_ENTROPY_THRESHOLD: f64 = 7.5;
+/// Minimum lane-match fraction to call a window UTF-16 text or a numeric
+/// array, rather than requiring every lane to match exactly.
+const LANE_FRACTION_THRESHOLD: f64 = 0.6;
+
+fn classify_window(window: &[u8]) -> (WindowClass, f64) {
+    let entropy = shannon_entropy(window);
+    let class = if entropy < PADDING_ENTROPY_THRESHOLD {
+        WindowClass::Padding
+    } else if utf16_text_lane_fraction(window) >= LANE_FRACTION_THRESHOLD {
+        WindowClass::Utf16Text
+    } else if numeric_lane_fraction(window) >= LANE_FRACTION_THRESHOLD {
+        WindowClass::NumericArray
+    } else if entropy >= HIGH_ENTROPY_THRESHOLD {
+        WindowClass::CompressedOrEncrypted
+    } else {
+        WindowClass::Mixed
+    };
+    (class, entropy)
+}
+
+/// One contiguous run of windows that all classified the same way, with the
+/// mean entropy across that run.
+struct ProfileRegion {
+    start: usize,
+    end: usize,
+    class: WindowClass,
+    mean_entropy: f64,
+}
+
+/// Slide a `window_size`-byte window across `scanner`, classify each, and
+/// merge consecutive same-class windows into [`ProfileRegion`]s.
+fn profile_file(scanner: &Scanner, window_size: usize) -> Vec<ProfileRegion> {
+    let data = scanner.as_slice();
+    let window_size = window_size.max(1);
+
+    let mut regions: Vec<ProfileRegion> = Vec::new();
+    let mut start = 0;
+    while start < data.len() {
+        let end = (start + window_size).min(data.len());
+        let (class, entropy) = classify_window(&data[start..end]);
+
+        match regions.last_mut() {
+            Some(last) if last.class == class && last.end == start => {
+                let prev_len = (last.end - last.start) as f64;
+                let this_len = (end - start) as f64;
+                last.mean_entropy = (last.mean_entropy * prev_len + entropy * this_len)
+                    / (prev_len + this_len);
+                last.end = end;
+            }
+            _ => regions.push(ProfileRegion {
+                start,
+                end,
+                class,
+                mean_entropy: entropy,
+            }),
+        }
+
+        start = end;
+    }
+    regions
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -227,18 +1116,31 @@ fn main() {
             value,
             tolerance,
             range,
+            decompress,
+            min_inflated_size,
         } => {
-            let data = fs::read(&file).expect("Failed to read file");
-            let (search_data, base_offset) = if let Some(r) = range {
+            let scanner = Scanner::open(&file).expect("Failed to open file");
+            let mut hits: Vec<(HitLocation, f64)> = if let Some(r) = range {
                 let parts: Vec<usize> = r.split(':').map(|s| s.parse().unwrap()).collect();
                 let start = parts[0];
-                let len = parts.get(1).copied().unwrap_or(data.len() - start);
-                let end = (start + len).min(data.len());
-                (&data[start..end], start)
+                let len = parts.get(1).copied().unwrap_or(scanner.len() - start);
+                let end = (start + len).min(scanner.len());
+                search_f64(&scanner.as_slice()[start..end], value, tolerance)
+                    .into_iter()
+                    .map(|(o, v)| (HitLocation::Plain(o + start), v))
+                    .collect()
             } else {
-                (&data[..], 0)
+                scan_file(&scanner, 8, |w| search_f64(w, value, tolerance))
+                    .into_iter()
+                    .map(|(o, v)| (HitLocation::Plain(o), v))
+                    .collect()
             };
-            let hits = search_f64(search_data, value, tolerance);
+            if decompress {
+                let regions = find_compressed_streams(&scanner, min_inflated_size, false);
+                hits.extend(decompressed_hits(&regions, |w| {
+                    search_f64(w, value, tolerance)
+                }));
+            }
             println!(
                 "Searching for f64 {:.10} (+/-{}) in {}:",
                 value,
@@ -246,13 +1148,8 @@ fn main() {
                 file.display()
             );
             println!("Found {} hits:", hits.len());
-            for (offset, val) in &hits {
-                println!(
-                    "  offset 0x{:08X} ({:>10}): {:.15}",
-                    offset + base_offset,
-                    offset + base_offset,
-                    val
-                );
+            for (loc, val) in &hits {
+                println!("  offset {}: {:.15}", loc, val);
             }
         }
 
@@ -260,30 +1157,94 @@ fn main() {
             file,
             value,
             tolerance,
+            decompress,
+            min_inflated_size,
         } => {
-            let data = fs::read(&file).expect("Failed to read file");
-            let hits = search_f32(&data, value, tolerance);
+            let scanner = Scanner::open(&file).expect("Failed to open file");
+            let mut hits: Vec<(HitLocation, f32)> = scan_file(&scanner, 4, |w| {
+                search_f32(w, value, tolerance)
+            })
+            .into_iter()
+            .map(|(o, v)| (HitLocation::Plain(o), v))
+            .collect();
+            if decompress {
+                let regions = find_compressed_streams(&scanner, min_inflated_size, false);
+                hits.extend(decompressed_hits(&regions, |w| {
+                    search_f32(w, value, tolerance)
+                }));
+            }
             println!("Found {} hits for f32 {:.6}:", hits.len(), value);
-            for (offset, val) in &hits {
-                println!("  offset 0x{:08X}: {:.10}", offset, val);
+            for (loc, val) in &hits {
+                println!("  offset {}: {:.10}", loc, val);
             }
         }
 
-        Commands::SearchU32 { file, value } => {
-            let data = fs::read(&file).expect("Failed to read file");
-            let hits = search_u32(&data, value);
+        Commands::SearchU32 {
+            file,
+            value,
+            decompress,
+            min_inflated_size,
+        } => {
+            let scanner = Scanner::open(&file).expect("Failed to open file");
+            let mut hits: Vec<HitLocation> = scan_file_offsets(&scanner, 4, |w| search_u32(w, value))
+                .into_iter()
+                .map(HitLocation::Plain)
+                .collect();
+            if decompress {
+                let regions = find_compressed_streams(&scanner, min_inflated_size, false);
+                hits.extend(decompressed_offsets(&regions, |w| search_u32(w, value)));
+            }
             println!("Found {} hits for u32 {}:", hits.len(), value);
-            for offset in &hits {
-                println!("  offset 0x{:08X} ({:>10})", offset, offset);
+            for loc in &hits {
+                println!("  offset {}", loc);
             }
         }
 
-        Commands::SearchUtf16 { file, pattern } => {
-            let data = fs::read(&file).expect("Failed to read file");
-            let hits = search_utf16le(&data, &pattern);
+        Commands::SearchUtf16 {
+            file,
+            pattern,
+            decompress,
+            min_inflated_size,
+        } => {
+            let scanner = Scanner::open(&file).expect("Failed to open file");
+            let encoded_len = pattern.encode_utf16().count() * 2;
+            let mut hits: Vec<HitLocation> =
+                scan_file_offsets(&scanner, encoded_len.max(1), |w| search_utf16le(w, &pattern))
+                    .into_iter()
+                    .map(HitLocation::Plain)
+                    .collect();
+            if decompress {
+                let regions = find_compressed_streams(&scanner, min_inflated_size, false);
+                hits.extend(decompressed_offsets(&regions, |w| {
+                    search_utf16le(w, &pattern)
+                }));
+            }
             println!("Found {} hits for UTF-16LE \"{}\":", hits.len(), pattern);
-            for offset in &hits {
-                println!("  offset 0x{:08X}", offset);
+            for loc in &hits {
+                println!("  offset {}", loc);
+            }
+        }
+
+        Commands::ScanCompressed {
+            file,
+            min_inflated_size,
+            include_raw_deflate,
+        } => {
+            let scanner = Scanner::open(&file).expect("Failed to open file");
+            let regions = find_compressed_streams(&scanner, min_inflated_size, include_raw_deflate);
+            println!(
+                "Found {} compressed stream(s) in {}:",
+                regions.len(),
+                file.display()
+            );
+            for region in &regions {
+                println!(
+                    "  {:?} stream at 0x{:08X}: {} compressed bytes -> {} inflated bytes",
+                    region.kind,
+                    region.compressed_offset,
+                    region.compressed_len,
+                    region.inflated.len()
+                );
             }
         }
 
@@ -293,8 +1254,8 @@ fn main() {
             length,
             interpret,
         } => {
-            let data = fs::read(&file).expect("Failed to read file");
-            hex_dump(&data, offset, length, interpret);
+            let scanner = Scanner::open(&file).expect("Failed to open file");
+            hex_dump(scanner.as_slice(), offset, length, interpret);
         }
 
         Commands::DetectStride {
@@ -302,13 +1263,13 @@ fn main() {
             values,
             tolerance,
         } => {
-            let data = fs::read(&file).expect("Failed to read file");
+            let scanner = Scanner::open(&file).expect("Failed to open file");
             let vals: Vec<f64> = values
                 .split(',')
                 .map(|s| s.trim().parse().unwrap())
                 .collect();
             println!("Detecting stride for {} values: {:?}", vals.len(), vals);
-            let results = detect_stride(&data, &vals, tolerance);
+            let results = detect_stride(&scanner, &vals, tolerance);
             if results.is_empty() {
                 println!("No stride pattern found.");
             } else {
@@ -327,8 +1288,10 @@ fn main() {
             file_b,
             max_diffs,
         } => {
-            let a = fs::read(&file_a).expect("Failed to read file A");
-            let b = fs::read(&file_b).expect("Failed to read file B");
+            let scanner_a = Scanner::open(&file_a).expect("Failed to open file A");
+            let scanner_b = Scanner::open(&file_b).expect("Failed to open file B");
+            let a = scanner_a.as_slice();
+            let b = scanner_b.as_slice();
             let min_len = a.len().min(b.len());
             println!("File A: {} ({} bytes)", file_a.display(), a.len());
             println!("File B: {} ({} bytes)", file_b.display(), b.len());
@@ -377,11 +1340,217 @@ fn main() {
             }
         }
 
+        Commands::ScanSignatures {
+            file,
+            sig_db,
+            min_score,
+        } => {
+            let scanner = Scanner::open(&file).expect("Failed to open file");
+            let data = scanner.as_slice();
+            let db_str = fs::read_to_string(&sig_db).expect("Failed to read signature database");
+            let db: SignatureDb =
+                serde_json::from_str(&db_str).expect("Failed to parse signature database");
+
+            for signature in &db.signatures {
+                let anchor = match parse_anchor(&signature.anchor) {
+                    Ok(a) => a,
+                    Err(e) => {
+                        eprintln!("Signature {:?}: {}", signature.name, e);
+                        continue;
+                    }
+                };
+                let mut matches = score_signature_matches(data, signature, &anchor);
+                matches.retain(|m| m.score >= min_score);
+                matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+                println!(
+                    "=== {} ({} match(es) >= {:.2}) ===",
+                    signature.name,
+                    matches.len(),
+                    min_score
+                );
+                for m in &matches {
+                    println!("  offset 0x{:08X}  score {:.2}", m.offset, m.score);
+                    for (field, decoded) in &m.fields {
+                        match decoded {
+                            Some(value) => println!(
+                                "    +{}: {:?} = {}",
+                                field.relative_offset, field.field_type, value
+                            ),
+                            None => println!(
+                                "    +{}: {:?} = <out of range>",
+                                field.relative_offset, field.field_type
+                            ),
+                        }
+                    }
+                }
+            }
+        }
+
+        Commands::Learn {
+            file,
+            offset,
+            name,
+            anchor_len,
+            fields,
+            out,
+        } => {
+            let scanner = Scanner::open(&file).expect("Failed to open file");
+            let data = scanner.as_slice();
+            let anchor_bytes = data
+                .get(offset..offset + anchor_len)
+                .expect("anchor range is out of bounds");
+            let anchor: Vec<String> = anchor_bytes.iter().map(|b| format!("{:02X}", b)).collect();
+
+            let mut signature_fields = Vec::new();
+            for spec in &fields {
+                let parts: Vec<&str> = spec.split(':').collect();
+                let relative_offset: i64 = parts[0].parse().expect("invalid relative_offset");
+                let field_type = match parts[1] {
+                    "f64" => FieldType::F64,
+                    "f32" => FieldType::F32,
+                    "u32" => FieldType::U32,
+                    "i32" => FieldType::I32,
+                    "utf16" => FieldType::Utf16 {
+                        len: parts[2].parse().expect("invalid utf16 len"),
+                    },
+                    other => panic!("unknown field type {:?} in --field {:?}", other, spec),
+                };
+                let mut field = SignatureField {
+                    relative_offset,
+                    field_type,
+                    expected: None,
+                };
+                field.expected = decode_field(data, offset, &field).map(|value| match value {
+                    DecodedValue::F64(v) => ExpectedValue::Number(v),
+                    DecodedValue::F32(v) => ExpectedValue::Number(v as f64),
+                    DecodedValue::U32(v) => ExpectedValue::Number(v as f64),
+                    DecodedValue::I32(v) => ExpectedValue::Number(v as f64),
+                    DecodedValue::Text(v) => ExpectedValue::Text(v),
+                });
+                signature_fields.push(field);
+            }
+
+            let signature = Signature {
+                name,
+                anchor,
+                fields: signature_fields,
+            };
+            let draft =
+                serde_json::to_string_pretty(&signature).expect("Failed to serialize draft");
+
+            match out {
+                Some(path) => {
+                    fs::write(&path, &draft).expect("Failed to write draft signature");
+                    println!("Wrote draft signature to {}", path.display());
+                }
+                None => println!("{}", draft),
+            }
+        }
+
+        Commands::Profile { file, window } => {
+            let scanner = Scanner::open(&file).expect("Failed to open file");
+            let regions = profile_file(&scanner, window);
+
+            println!(
+                "Profiled {} in {}-byte windows -- {} region(s):",
+                file.display(),
+                window,
+                regions.len()
+            );
+            for region in &regions {
+                println!(
+                    "  0x{:08X}-0x{:08X} ({} bytes)  {}  entropy={:.2}",
+                    region.start,
+                    region.end,
+                    region.end - region.start,
+                    region.class,
+                    region.mean_entropy
+                );
+                match region.class {
+                    WindowClass::NumericArray => println!(
+                        "    suggest: --range {}:{}",
+                        region.start,
+                        region.end - region.start
+                    ),
+                    WindowClass::Utf16Text => println!(
+                        "    suggest: search-utf16 over 0x{:08X}-0x{:08X}",
+                        region.start, region.end
+                    ),
+                    _ => {}
+                }
+            }
+        }
+
+        Commands::AutoLocateVerify {
+            raw_file,
+            truth_dir,
+            fields,
+            tolerance,
+            out,
+        } => {
+            let scanner = Scanner::open(&raw_file).expect("Failed to open RAW file");
+            let data = scanner.as_slice();
+
+            let index_path = truth_dir.join("scan_index.json");
+            let index_str =
+                fs::read_to_string(&index_path).expect("Failed to read scan_index.json");
+            let index: serde_json::Value = serde_json::from_str(&index_str).unwrap();
+            let scans = index.as_array().unwrap();
+
+            let candidates: Vec<CandidateField> =
+                fields.iter().map(|s| parse_candidate_field(s)).collect();
+
+            let mut all_matched = true;
+            let mut layout_fields = Vec::new();
+
+            for candidate in &candidates {
+                let result = verify_candidate_field(data, scans, candidate, tolerance);
+                println!(
+                    "{}: {}/{} scans matched, max_abs_residual={:.9}{}",
+                    candidate.name,
+                    result.n_matched,
+                    result.n_scans,
+                    result.max_abs_residual,
+                    match result.first_failing_scan {
+                        Some(i) => format!(", first failing scan: {}", i),
+                        None => String::new(),
+                    }
+                );
+
+                if result.n_matched != result.n_scans {
+                    all_matched = false;
+                } else {
+                    layout_fields.push(LayoutField {
+                        name: candidate.name.clone(),
+                        base_offset: candidate.base_offset,
+                        stride: candidate.stride,
+                        field_type: candidate.field_type,
+                        units: field_units(&candidate.name),
+                    });
+                }
+            }
+
+            if all_matched && !candidates.is_empty() {
+                let manifest = LayoutManifest {
+                    raw_file: raw_file.display().to_string(),
+                    n_scans: scans.len(),
+                    fields: layout_fields,
+                };
+                let manifest_json =
+                    serde_json::to_string_pretty(&manifest).expect("Failed to serialize layout");
+                fs::write(&out, &manifest_json).expect("Failed to write layout manifest");
+                println!("All fields verified -- wrote layout manifest to {}", out.display());
+            } else {
+                println!("Not all fields verified cleanly -- no layout manifest written.");
+            }
+        }
+
         Commands::AutoLocate {
             raw_file,
             truth_dir,
         } => {
-            let data = fs::read(&raw_file).expect("Failed to read RAW file");
+            let scanner = Scanner::open(&raw_file).expect("Failed to open RAW file");
 
             // Load scan_index.json
             let index_path = truth_dir.join("scan_index.json");
@@ -405,7 +1574,7 @@ fn main() {
 
             // 1. RT stride detection
             println!("\n--- Retention Time Stride Detection ---");
-            let stride_results = detect_stride(&data, &rts, 1e-9);
+            let stride_results = detect_stride(&scanner, &rts, 1e-9);
             for (offset, stride, matched) in &stride_results {
                 println!(
                     "  RT field at offset 0x{:08X}, stride {} bytes, {} matched",
@@ -417,7 +1586,7 @@ fn main() {
             let first_tic = scans.first().and_then(|s| s["tic"].as_f64());
             if let Some(tic) = first_tic {
                 println!("\n--- TIC Search (first scan: {:.2}) ---", tic);
-                let hits = search_f64(&data, tic, tic * 1e-6);
+                let hits = scan_file(&scanner, 8, |w| search_f64(w, tic, tic * 1e-6));
                 for (offset, val) in hits.iter().take(10) {
                     println!("  hit at 0x{:08X}: {:.6}", offset, val);
                 }
@@ -426,7 +1595,7 @@ fn main() {
             // 3. Scan count
             let n_scans = scans.len() as u32;
             println!("\n--- Scan Count ({}) ---", n_scans);
-            let hits = search_u32(&data, n_scans);
+            let hits = scan_file_offsets(&scanner, 4, |w| search_u32(w, n_scans));
             for offset in hits.iter().take(20) {
                 println!("  hit at 0x{:08X}", offset);
             }
@@ -439,7 +1608,10 @@ fn main() {
                     if let Some(val) = meta[key].as_str() {
                         if !val.is_empty() {
                             println!("\n--- UTF-16LE search: {} = \"{}\" ---", key, val);
-                            let hits = search_utf16le(&data, val);
+                            let encoded_len = val.encode_utf16().count() * 2;
+                            let hits = scan_file_offsets(&scanner, encoded_len.max(1), |w| {
+                                search_utf16le(w, val)
+                            });
                             for offset in hits.iter().take(5) {
                                 println!("  hit at 0x{:08X}", offset);
                             }
@@ -450,3 +1622,78 @@ fn main() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn write_temp_file(bytes: &[u8]) -> PathBuf {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "hex-analyzer-test-{}-{}.bin",
+            std::process::id(),
+            n
+        ));
+        let mut file = fs::File::create(&path).unwrap();
+        file.write_all(bytes).unwrap();
+        path
+    }
+
+    /// A match planted so its bytes straddle the boundary between window 0
+    /// and window 1 must still be found -- regression test for the bug
+    /// where the overlap-dedup logic discarded exactly these hits (see
+    /// `scan_file`'s doc comment).
+    #[test]
+    fn scan_file_finds_match_straddling_window_boundary() {
+        let window_size = 16;
+        let width = 8; // f64
+        let target = 12345.6789_f64;
+
+        // Window 0 is bytes [0, 16). window_ranges' overlap is width-1 = 7,
+        // so window 1 starts at 16-7 = 9: [9, 25). A match fully contained
+        // in window 1 but not window 0 must start at local offset >= 9 in
+        // window 0's terms, i.e. file offset in [9, 16) won't fully fit in
+        // window 0 if it extends past 16. Planting the 8-byte match at
+        // offset 10 means it spans [10, 18) -- not fully inside window 0
+        // ([0, 16)), and only fully inside window 1 ([9, 25)).
+        let mut data = vec![0xABu8; 64];
+        data[10..18].copy_from_slice(&target.to_le_bytes());
+
+        let path = write_temp_file(&data);
+        let scanner = Scanner::open(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        let hits = scan_file_with_window_size(&scanner, width, window_size, |w| {
+            search_f64(w, target, 1e-9)
+        });
+
+        assert_eq!(hits.len(), 1, "expected exactly one hit, got {:?}", hits);
+        assert_eq!(hits[0].0, 10);
+    }
+
+    #[test]
+    fn scan_file_does_not_duplicate_matches_away_from_boundaries() {
+        let window_size = 16;
+        let width = 8;
+        let target = 42.0_f64;
+
+        // A match squarely inside window 0 and nowhere near the overlap
+        // region should be reported exactly once, not duplicated by window 1.
+        let mut data = vec![0x00u8; 64];
+        data[0..8].copy_from_slice(&target.to_le_bytes());
+
+        let path = write_temp_file(&data);
+        let scanner = Scanner::open(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        let hits = scan_file_with_window_size(&scanner, width, window_size, |w| {
+            search_f64(w, target, 1e-9)
+        });
+
+        assert_eq!(hits.len(), 1, "expected exactly one hit, got {:?}", hits);
+        assert_eq!(hits[0].0, 0);
+    }
+}